@@ -0,0 +1,121 @@
+//! Allocations and time of the text extraction helpers, compared to the
+//! naive extraction through `Element::text`, and of the failed lookups
+//! through the helpers returning an `Option`.
+//!
+//! Run with `cargo bench --bench extraction`.
+
+use minidom::Element;
+use minidom_ext::{AttributeElementExt, OnlyChildElementExt, TextElementExt};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const ITERATIONS: usize = 100_000;
+
+fn measure<F>(name: &str, mut f: F)
+where
+    F: FnMut(),
+{
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    println!(
+        "{:<40} {:>8.1} ns/iter {:>6.2} allocations/iter",
+        name,
+        elapsed.as_nanos() as f64 / ITERATIONS as f64,
+        allocations as f64 / ITERATIONS as f64
+    );
+}
+
+fn main() {
+    let single: Element = "<Distance> 1234 </Distance>".parse().unwrap();
+    let split: Element = "<Distance>12<!-- split -->34</Distance>".parse().unwrap();
+    let list: Element = "<Stops>1 2 3 4 5 6 7 8</Stops>".parse().unwrap();
+
+    for (name, element) in &[
+        ("single text node", &single),
+        ("several text nodes", &split),
+    ] {
+        measure(&format!("Element::text ({})", name), || {
+            let value: u32 = element.text().trim().parse().unwrap();
+            black_box(value);
+        });
+        measure(&format!("try_text ({})", name), || {
+            let value: u32 = element.try_text().unwrap();
+            black_box(value);
+        });
+    }
+    measure("Element::text (list)", || {
+        let values: Vec<u32> = list
+            .text()
+            .split_ascii_whitespace()
+            .map(|token| token.parse().unwrap())
+            .collect();
+        black_box(values);
+    });
+    measure("try_text_list (list)", || {
+        let values: Vec<u32> = list.try_text_list().unwrap();
+        black_box(values);
+    });
+
+    let line: Element = r#"<Line id="1" name="A"><Name>A</Name><Name>B</Name></Line>"#
+        .parse()
+        .unwrap();
+    measure("attribute (missing)", || {
+        black_box(line.attribute::<u32>("Id"));
+    });
+    measure("attribute (invalid)", || {
+        black_box(line.attribute::<u32>("name"));
+    });
+    measure("only_child (missing)", || {
+        black_box(line.only_child("Names"));
+    });
+    measure("only_child (duplicated)", || {
+        black_box(line.only_child("Name"));
+    });
+    measure("try_only_child (missing)", || {
+        black_box(line.try_only_child("Names").is_err());
+    });
+
+    const NETEX: &str = "http://www.netex.org.uk/netex";
+    let xml: &'static str = r#"<root xmlns:n="http://www.netex.org.uk/netex">
+            <n:Line n:id="1"><Name n:lang="fr">A</Name></n:Line>
+        </root>"#;
+    let root: Element = xml.parse().unwrap();
+    let line = root.children().next().unwrap();
+    let name = line.children().next().unwrap();
+    measure("attribute_ns (prefix of the element)", || {
+        black_box(line.attribute_ns::<u32>(NETEX, "id"));
+    });
+    measure("attribute_ns (inherited prefix)", || {
+        black_box(name.attribute_ns::<String>(NETEX, "lang"));
+    });
+    measure("attribute_ns (missing)", || {
+        black_box(line.attribute_ns::<u32>(NETEX, "version"));
+    });
+}
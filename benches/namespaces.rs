@@ -0,0 +1,106 @@
+//! Time of the namespace-aware lookups on a deep document declaring a
+//! namespace at each level, with plain URIs and with interned `Ns` handles.
+//!
+//! Run with `cargo bench --bench namespaces`.
+
+use minidom::Element;
+use minidom_ext::{AttributeElementExt, Ns, QName};
+use std::{collections::HashMap, hint::black_box, time::Instant};
+
+const ITERATIONS: usize = 100_000;
+const DEPTH: usize = 40;
+
+fn measure<F>(name: &str, mut f: F)
+where
+    F: FnMut(),
+{
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{:<40} {:>8.1} ns/iter",
+        name,
+        elapsed.as_nanos() as f64 / ITERATIONS as f64
+    );
+}
+
+fn uri(level: usize) -> String {
+    format!("http://www.example.com/schemas/level/{:04}", level)
+}
+
+/// A chain of `DEPTH` elements, each declaring its own prefix, the leaf
+/// having an attribute in the namespace of each level.
+fn deep_document() -> Element {
+    let mut xml = String::new();
+    for level in 0..DEPTH {
+        xml.push_str(&format!("<n{0}:e xmlns:n{0}=\"{1}\">", level, uri(level)));
+    }
+    xml.push_str("<leaf");
+    for level in 0..DEPTH {
+        xml.push_str(&format!(" n{}:a=\"{}\"", level, level));
+    }
+    xml.push_str(" />");
+    for level in (0..DEPTH).rev() {
+        xml.push_str(&format!("</n{}:e>", level));
+    }
+    xml.parse().unwrap()
+}
+
+fn main() {
+    let root = deep_document();
+    let mut leaf = &root;
+    while let Some(child) = leaf.children().next() {
+        leaf = child;
+    }
+    let first = uri(0);
+    let last = uri(DEPTH - 1);
+    let (first_ns, last_ns) = (Ns::from(first.as_str()), Ns::from(last.as_str()));
+
+    measure("Ns::from (interning)", || {
+        black_box(Ns::from(last.as_str()));
+    });
+    measure("attribute_ns (&str, outer namespace)", || {
+        black_box(leaf.attribute_ns::<u32>(first.as_str(), "a"));
+    });
+    measure("attribute_ns (&Ns, outer namespace)", || {
+        black_box(leaf.attribute_ns::<u32>(&first_ns, "a"));
+    });
+    measure("attribute_ns (&str, inner namespace)", || {
+        black_box(leaf.attribute_ns::<u32>(last.as_str(), "a"));
+    });
+    measure("attribute_ns (&Ns, inner namespace)", || {
+        black_box(leaf.attribute_ns::<u32>(&last_ns, "a"));
+    });
+
+    let qname = QName {
+        namespace: Some(last.clone()),
+        local: "e".to_owned(),
+    };
+    measure("QName::is (&str)", || {
+        black_box(qname.is(last.as_str(), "e"));
+    });
+    measure("QName::is (&Ns)", || {
+        black_box(qname.is(&last_ns, "e"));
+    });
+
+    let by_uri: HashMap<String, usize> = (0..DEPTH).map(|level| (uri(level), level)).collect();
+    let by_ns: HashMap<Ns, usize> = (0..DEPTH)
+        .map(|level| (Ns::from(uri(level)), level))
+        .collect();
+    measure("HashMap<String, _>::get", || {
+        black_box(by_uri.get(last.as_str()));
+    });
+    measure("HashMap<Ns, _>::get", || {
+        black_box(by_ns.get(&last_ns));
+    });
+    let other = uri(DEPTH);
+    let other_ns = Ns::from(other.as_str());
+    measure("str == str (same length)", || {
+        black_box(black_box(last.as_str()) == black_box(other.as_str()));
+    });
+    measure("Ns == Ns (same length)", || {
+        black_box(black_box(&last_ns) == black_box(&other_ns));
+    });
+}
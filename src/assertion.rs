@@ -0,0 +1,247 @@
+use crate::{ElementCursor, Finding, Selector, Severity};
+use minidom::Element;
+use std::{collections::HashSet, ops::Not};
+
+/// A condition on an element, tested by an [`Assertion`].
+///
+/// [`Assertion`]: struct.Assertion.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Test {
+    /// The element has the attribute
+    Attribute(String),
+    /// The attribute of the element has the given value
+    AttributeEquals(String, String),
+    /// The selector selects at least one element from the element
+    Select(Selector),
+    /// The condition is false
+    Not(Box<Test>),
+    /// Both conditions are true
+    And(Box<Test>, Box<Test>),
+    /// At least one of the conditions is true
+    Or(Box<Test>, Box<Test>),
+    /// The second condition is true if the first one is
+    Implies(Box<Test>, Box<Test>),
+}
+
+impl Test {
+    /// The element has the attribute.
+    pub fn attribute(name: &str) -> Self {
+        Test::Attribute(name.to_owned())
+    }
+
+    /// The attribute of the element has the given value.
+    pub fn attribute_equals(name: &str, value: &str) -> Self {
+        Test::AttributeEquals(name.to_owned(), value.to_owned())
+    }
+
+    /// The selector selects at least one element from the element.
+    pub fn select(selector: Selector) -> Self {
+        Test::Select(selector)
+    }
+
+    /// Both conditions are true.
+    pub fn and(self, other: Test) -> Self {
+        Test::And(Box::new(self), Box::new(other))
+    }
+
+    /// At least one of the conditions is true.
+    pub fn or(self, other: Test) -> Self {
+        Test::Or(Box::new(self), Box::new(other))
+    }
+
+    /// The other condition is true if this one is.
+    pub fn implies(self, other: Test) -> Self {
+        Test::Implies(Box::new(self), Box::new(other))
+    }
+
+    /// Evaluate the condition on an element.
+    pub fn eval(&self, element: &Element) -> bool {
+        match self {
+            Test::Attribute(name) => element.attr(name).is_some(),
+            Test::AttributeEquals(name, value) => element.attr(name) == Some(value.as_str()),
+            Test::Select(selector) => selector.select_first(element).is_some(),
+            Test::Not(test) => !test.eval(element),
+            Test::And(a, b) => a.eval(element) && b.eval(element),
+            Test::Or(a, b) => a.eval(element) || b.eval(element),
+            Test::Implies(a, b) => !a.eval(element) || b.eval(element),
+        }
+    }
+}
+
+impl Not for Test {
+    type Output = Test;
+
+    fn not(self) -> Test {
+        Test::Not(Box::new(self))
+    }
+}
+
+/// A business rule, in the spirit of Schematron: the elements selected by
+/// the context selector (evaluated from the root) must pass the test.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assertion {
+    /// Identifier of the rule, reported by the findings
+    pub rule: String,
+    /// Selects the elements to test, from the root
+    pub context: Selector,
+    /// The condition the elements must fulfill
+    pub test: Test,
+    /// Message of the findings
+    pub message: String,
+    /// Severity of the findings
+    pub severity: Severity,
+}
+
+impl Assertion {
+    /// Create an assertion whose violations are errors.
+    pub fn new(rule: &str, context: Selector, test: Test, message: &str) -> Self {
+        Assertion {
+            rule: rule.to_owned(),
+            context,
+            test,
+            message: message.to_owned(),
+            severity: Severity::Error,
+        }
+    }
+
+    /// Change the severity of the violations.
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+}
+
+/// A set of [`Assertion`]s expressing cross-element business rules, checked
+/// with the [`Selector`] engine.
+///
+/// [`check`] returns the violations as [`Finding`]s, in document order,
+/// located by their breadcrumb (see [`ElementCursor::breadcrumb`]).
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{Assertion, Assertions, Test};
+///
+/// let assertions = Assertions::new().with_assertion(Assertion::new(
+///     "arrival-time",
+///     "//Call".parse().unwrap(),
+///     Test::attribute("departureTime").implies(Test::attribute("arrivalTime")),
+///     "A call with a departure time must have an arrival time",
+/// ));
+/// let xml: &'static str = r#"<Journey><calls>
+///         <Call departureTime="08:00" arrivalTime="07:59" />
+///         <Call departureTime="08:10" />
+///         <Call arrivalTime="08:20" />
+///     </calls></Journey>"#;
+/// let root: Element = xml.parse().unwrap();
+/// let violations: Vec<String> = assertions
+///     .check(&root)
+///     .iter()
+///     .map(ToString::to_string)
+///     .collect();
+/// assert_eq!(
+///     vec!["error[arrival-time] Journey > calls[1] > Call[2]: A call with a departure time must have an arrival time"],
+///     violations
+/// );
+/// ```
+///
+/// [`Assertion`]: struct.Assertion.html
+/// [`check`]: #method.check
+/// [`ElementCursor::breadcrumb`]: struct.ElementCursor.html#method.breadcrumb
+/// [`Finding`]: struct.Finding.html
+/// [`Selector`]: struct.Selector.html
+#[derive(Debug, Clone, Default)]
+pub struct Assertions {
+    assertions: Vec<Assertion>,
+}
+
+impl Assertions {
+    /// Create an empty set of assertions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an assertion.
+    pub fn with_assertion(mut self, assertion: Assertion) -> Self {
+        self.assertions.push(assertion);
+        self
+    }
+
+    /// Check the assertions on a tree and return all the violations.
+    pub fn check(&self, root: &Element) -> Vec<Finding> {
+        let failures: Vec<HashSet<*const Element>> = self
+            .assertions
+            .iter()
+            .map(|assertion| {
+                assertion
+                    .context
+                    .select(root)
+                    .into_iter()
+                    .filter(|element| !assertion.test.eval(element))
+                    .map(|element| element as *const Element)
+                    .collect()
+            })
+            .collect();
+        let mut findings = Vec::new();
+        if failures.iter().any(|failures| !failures.is_empty()) {
+            self.locate(&ElementCursor::new(root), &failures, &mut findings);
+        }
+        findings
+    }
+
+    fn locate(
+        &self,
+        cursor: &ElementCursor<'_>,
+        failures: &[HashSet<*const Element>],
+        findings: &mut Vec<Finding>,
+    ) {
+        let element: *const Element = cursor.element();
+        for (assertion, failures) in self.assertions.iter().zip(failures) {
+            if failures.contains(&element) {
+                findings.push(Finding::new(
+                    &assertion.rule,
+                    assertion.severity,
+                    &cursor.breadcrumb(),
+                    &assertion.message,
+                ));
+            }
+        }
+        for child in cursor.children() {
+            self.locate(&child, failures, findings);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn combined_tests() {
+        let root: Element = r#"<root><Line mode="bus"><Name /></Line><Line mode="tram" /></root>"#
+            .parse()
+            .unwrap();
+        let mut lines = root.children();
+        let (bus, tram) = (lines.next().unwrap(), lines.next().unwrap());
+        let named = Test::select("Name".parse().unwrap());
+        let test = Test::attribute_equals("mode", "bus").and(named.clone());
+        assert!(test.eval(bus));
+        assert!(!test.eval(tram));
+        assert!((!named.clone()).eval(tram));
+        assert!(Test::attribute("id").or(named).eval(bus));
+
+        let assertions = Assertions::new().with_assertion(
+            Assertion::new(
+                "named-bus",
+                "Line".parse().unwrap(),
+                test,
+                "Not a named bus",
+            )
+            .with_severity(Severity::Warning),
+        );
+        let findings = assertions.check(&root);
+        assert_eq!(1, findings.len());
+        assert_eq!("root > Line[2]", findings[0].path);
+        assert_eq!(Severity::Warning, findings[0].severity);
+    }
+}
@@ -1,33 +1,389 @@
-use crate::Error;
+use crate::{
+    duration::ExactDuration,
+    name::clark_name,
+    namespace::{split_qname, PrefixResolver},
+    suggest::attribute_not_found,
+    util::parse_value,
+    AttrValue, ControlCharacters, Error, HexBinary, List, ParseOptions, XsBoolean, XsEnumeration,
+};
 use minidom::Element;
-use std::str::FromStr;
+use std::time::Duration;
 
 /// Get an attribute from an element.
 pub trait AttributeElementExt {
     /// Try to get an attribute from its name and return a [`Result`].
     ///
     /// The type of the return value is chosen by the caller, as long as this
-    /// type can be parsed from `&str`.
+    /// type implements [`AttrValue`], which is the case of any type
+    /// implementing `FromStr`.
     ///
+    /// [`AttrValue`]: trait.AttrValue.html
     /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
     fn try_attribute<F>(&self, attr_name: &str) -> Result<F, Error>
     where
-        F: FromStr,
-        F::Err: std::error::Error + Send + Sync + 'static;
+        F: AttrValue;
 
     /// Get an attribute from its name if present and return a [`Option`].
     ///
     /// The type of the return value is chosen by the caller, as long as this
-    /// type can be parsed from `&str`.
+    /// type implements [`AttrValue`].
     ///
+    /// [`AttrValue`]: trait.AttrValue.html
     /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
     fn attribute<F>(&self, attr_name: &str) -> Option<F>
     where
-        F: FromStr,
-        F::Err: std::error::Error + Send + Sync + 'static,
+        F: AttrValue,
     {
         self.try_attribute(attr_name).ok()
     }
+
+    /// Try to get an attribute from its name, rejecting or removing the
+    /// control characters of the value depending on `policy`, and return a
+    /// [`Result`].
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::{AttributeElementExt, ControlCharacters};
+    ///
+    /// let xml: &'static str = r#"<Line name="Line&#7; 1" />"#;
+    /// let line: Element = xml.parse().unwrap();
+    /// let name: String = line
+    ///     .try_attribute_sanitized("name", ControlCharacters::Scrub)
+    ///     .unwrap();
+    /// assert_eq!("Line 1", name);
+    /// let error = line
+    ///     .try_attribute_sanitized::<String>("name", ControlCharacters::Reject)
+    ///     .unwrap_err();
+    /// assert_eq!(
+    ///     "Forbidden control character U+0007 at byte 4 of attribute 'name' of element 'Line'",
+    ///     error.to_string()
+    /// );
+    /// ```
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_attribute_sanitized<F>(
+        &self,
+        attr_name: &str,
+        policy: ControlCharacters,
+    ) -> Result<F, Error>
+    where
+        F: AttrValue,
+    {
+        let options = ParseOptions::new().with_control_characters(policy);
+        self.try_attribute_with(attr_name, &options)
+    }
+
+    /// Try to get an attribute from its name, prepared by `options` before
+    /// being parsed, and return a [`Result`].
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::{AttributeElementExt, Normalizer};
+    ///
+    /// let xml: &'static str = r#"<Line code=" 42 " />"#;
+    /// let line: Element = xml.parse().unwrap();
+    /// let code: u32 = line
+    ///     .try_attribute_with("code", &Normalizer::new().trim().into())
+    ///     .unwrap();
+    /// assert_eq!(42, code);
+    /// ```
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_attribute_with<F>(&self, attr_name: &str, options: &ParseOptions) -> Result<F, Error>
+    where
+        F: AttrValue;
+
+    /// Get an attribute from its name, prepared by `options` before being
+    /// parsed, if present and valid and return a [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn attribute_with<F>(&self, attr_name: &str, options: &ParseOptions) -> Option<F>
+    where
+        F: AttrValue,
+    {
+        self.try_attribute_with(attr_name, options).ok()
+    }
+
+    /// Try to get a namespaced attribute from its namespace and local name
+    /// and return a [`Result`].
+    ///
+    /// The prefix used in the document does not matter, only the namespace it
+    /// is bound to. Well-known namespaces are available as constants, see
+    /// [`XSI_NS`] and [`XML_NS`], and can be kept as [`Ns`] handles.
+    ///
+    /// [`Ns`]: struct.Ns.html
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`XML_NS`]: constant.XML_NS.html
+    /// [`XSI_NS`]: constant.XSI_NS.html
+    fn try_attribute_ns<F>(&self, ns: impl AsRef<str>, attr_name: &str) -> Result<F, Error>
+    where
+        F: AttrValue;
+
+    /// Get a namespaced attribute from its namespace and local name if
+    /// present and return a [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn attribute_ns<F>(&self, ns: impl AsRef<str>, attr_name: &str) -> Option<F>
+    where
+        F: AttrValue,
+    {
+        self.try_attribute_ns(ns, attr_name).ok()
+    }
+
+    /// Try to get an `xs:boolean` attribute (`true`, `false`, `1` or `0`)
+    /// and return a [`Result`].
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::AttributeElementExt;
+    ///
+    /// let xml: &'static str = r#"<Quay accessible="1" />"#;
+    /// let quay: Element = xml.parse().unwrap();
+    /// assert!(quay.try_attribute_bool("accessible").unwrap());
+    /// ```
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_attribute_bool(&self, attr_name: &str) -> Result<bool, Error> {
+        self.try_attribute::<XsBoolean>(attr_name).map(bool::from)
+    }
+
+    /// Get an `xs:boolean` attribute if present and valid and return a
+    /// [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn attribute_bool(&self, attr_name: &str) -> Option<bool> {
+        self.attribute::<XsBoolean>(attr_name).map(bool::from)
+    }
+
+    /// Try to get an `xs:duration` attribute (like `PT1H30M`) as an exact
+    /// [`Duration`] and return a [`Result`].
+    ///
+    /// Negative durations and durations with years or months are an error,
+    /// see [`XsDuration`] to handle them.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::AttributeElementExt;
+    /// use std::time::Duration;
+    ///
+    /// let xml: &'static str = r#"<Headway interval="PT7M30S" />"#;
+    /// let headway: Element = xml.parse().unwrap();
+    /// let interval = headway.try_attribute_duration("interval").unwrap();
+    /// assert_eq!(Duration::from_secs(450), interval);
+    /// ```
+    ///
+    /// [`Duration`]: https://doc.rust-lang.org/std/time/struct.Duration.html
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`XsDuration`]: struct.XsDuration.html
+    fn try_attribute_duration(&self, attr_name: &str) -> Result<Duration, Error> {
+        self.try_attribute::<ExactDuration>(attr_name)
+            .map(|duration| duration.0)
+    }
+
+    /// Get an `xs:duration` attribute as an exact [`Duration`] if present and
+    /// valid and return a [`Option`].
+    ///
+    /// [`Duration`]: https://doc.rust-lang.org/std/time/struct.Duration.html
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn attribute_duration(&self, attr_name: &str) -> Option<Duration> {
+        self.attribute::<ExactDuration>(attr_name)
+            .map(|duration| duration.0)
+    }
+
+    /// Try to get an `xs:hexBinary` attribute and return a [`Result`].
+    ///
+    /// The source of the [`ParseError`] is a [`HexError`] giving the position
+    /// of the first invalid character.
+    ///
+    /// [`HexError`]: enum.HexError.html
+    /// [`ParseError`]: enum.Error.html#variant.ParseError
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_attribute_hex(&self, attr_name: &str) -> Result<Vec<u8>, Error> {
+        self.try_attribute::<HexBinary>(attr_name)
+            .map(|binary| binary.0)
+    }
+
+    /// Get an `xs:hexBinary` attribute if present and valid and return a
+    /// [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn attribute_hex(&self, attr_name: &str) -> Option<Vec<u8>> {
+        self.attribute::<HexBinary>(attr_name)
+            .map(|binary| binary.0)
+    }
+
+    /// Try to get an attribute made of whitespace-separated values (like
+    /// `xs:list`) and return a [`Result`].
+    ///
+    /// The source of the [`ParseError`] is a [`ListError`] giving the index
+    /// of the failing token.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::AttributeElementExt;
+    ///
+    /// let xml: &'static str = r#"<LineString points="2.35 48.85  2.29 48.86" />"#;
+    /// let line: Element = xml.parse().unwrap();
+    /// let points: Vec<f64> = line.try_attribute_list("points").unwrap();
+    /// assert_eq!(vec![2.35, 48.85, 2.29, 48.86], points);
+    /// ```
+    ///
+    /// [`ListError`]: struct.ListError.html
+    /// [`ParseError`]: enum.Error.html#variant.ParseError
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_attribute_list<T>(&self, attr_name: &str) -> Result<Vec<T>, Error>
+    where
+        T: AttrValue,
+    {
+        self.try_attribute::<List<T>>(attr_name).map(|list| list.0)
+    }
+
+    /// Get an attribute made of whitespace-separated values if present and
+    /// valid and return a [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn attribute_list<T>(&self, attr_name: &str) -> Option<Vec<T>>
+    where
+        T: AttrValue,
+    {
+        self.attribute::<List<T>>(attr_name).map(|list| list.0)
+    }
+
+    /// Try to get an attribute whose value must be one of `allowed`, parsed
+    /// into `T` (like a `FromStr` enum), and return a [`Result`].
+    ///
+    /// Returns an [`UnexpectedValue`] error listing the allowed values if
+    /// the value is not allowed.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::AttributeElementExt;
+    ///
+    /// let xml: &'static str = r#"<Line mode="bus" status="retired" />"#;
+    /// let line: Element = xml.parse().unwrap();
+    /// let mode: String = line.try_attribute_enum("mode", &["bus", "tram"]).unwrap();
+    /// assert_eq!("bus", mode);
+    /// let error = line
+    ///     .try_attribute_enum::<String>("status", &["active", "planned"])
+    ///     .unwrap_err();
+    /// assert_eq!(
+    ///     "Value 'retired' of attribute 'status' in element 'Line' is not one of: active, planned",
+    ///     error.to_string()
+    /// );
+    /// ```
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`UnexpectedValue`]: enum.Error.html#variant.UnexpectedValue
+    fn try_attribute_enum<T>(&self, attr_name: &str, allowed: &[&str]) -> Result<T, Error>
+    where
+        T: AttrValue;
+
+    /// Get an attribute whose value is one of `allowed` if present and valid
+    /// and return a [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn attribute_enum<T>(&self, attr_name: &str, allowed: &[&str]) -> Option<T>
+    where
+        T: AttrValue,
+    {
+        self.try_attribute_enum(attr_name, allowed).ok()
+    }
+
+    /// Try to get an attribute whose value must be one of the
+    /// [`XsEnumeration::VALUES`] of `T`, and return a [`Result`].
+    ///
+    /// Like [`try_attribute_enum`], without repeating the allowed values at
+    /// each call.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::{AttributeElementExt, XsEnumeration};
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct Mode(String);
+    ///
+    /// impl std::str::FromStr for Mode {
+    ///     type Err = std::convert::Infallible;
+    ///
+    ///     fn from_str(s: &str) -> Result<Self, Self::Err> {
+    ///         Ok(Mode(s.to_owned()))
+    ///     }
+    /// }
+    ///
+    /// impl XsEnumeration for Mode {
+    ///     const VALUES: &'static [&'static str] = &["bus", "tram"];
+    /// }
+    ///
+    /// let line: Element = r#"<Line mode="metro" />"#.parse().unwrap();
+    /// assert_eq!(
+    ///     "Value 'metro' of attribute 'mode' in element 'Line' is not one of: bus, tram",
+    ///     line.try_attribute_variant::<Mode>("mode").unwrap_err().to_string()
+    /// );
+    /// ```
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`try_attribute_enum`]: #tymethod.try_attribute_enum
+    /// [`XsEnumeration::VALUES`]: trait.XsEnumeration.html#associatedconstant.VALUES
+    fn try_attribute_variant<T>(&self, attr_name: &str) -> Result<T, Error>
+    where
+        T: XsEnumeration,
+    {
+        self.try_attribute_enum(attr_name, T::VALUES)
+    }
+
+    /// Get an attribute whose value is one of the [`XsEnumeration::VALUES`]
+    /// of `T` if present and valid and return a [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    /// [`XsEnumeration::VALUES`]: trait.XsEnumeration.html#associatedconstant.VALUES
+    fn attribute_variant<T>(&self, attr_name: &str) -> Option<T>
+    where
+        T: XsEnumeration,
+    {
+        self.try_attribute_variant(attr_name).ok()
+    }
+}
+
+/// Find a namespaced attribute, returning its qualified name and its value.
+pub(crate) fn find_attribute_ns<'a>(
+    element: &'a Element,
+    ns: &str,
+    attr_name: &str,
+) -> Option<(&'a str, &'a str)> {
+    let mut resolver = PrefixResolver::new(element);
+    element.attrs().find(|(key, _)| match split_qname(key) {
+        // Compared in place, without resolving the namespace into a `String`
+        (Some(prefix), local_name)
+            if local_name == attr_name && element.prefix() == Some(prefix) =>
+        {
+            element.has_ns(ns)
+        }
+        (Some(prefix), local_name) if local_name == attr_name => {
+            resolver.resolve(Some(prefix)).as_deref() == Some(ns)
+        }
+        _ => false,
+    })
+}
+
+/// Parse an attribute whose value must be one of `allowed`.
+pub(crate) fn parse_enum<T>(
+    element: &Element,
+    attr_name: &str,
+    value: &str,
+    allowed: &[&str],
+) -> Result<T, Error>
+where
+    T: AttrValue,
+{
+    if !allowed.contains(&value) {
+        return Err(Error::UnexpectedValue {
+            element_name: element.name().to_owned(),
+            attribute_name: attr_name.to_owned(),
+            value: value.to_owned(),
+            allowed: allowed.iter().map(|value| (*value).to_owned()).collect(),
+        });
+    }
+    parse_value(element, attr_name, value)
 }
 
 impl AttributeElementExt for Element {
@@ -49,18 +405,76 @@ impl AttributeElementExt for Element {
     /// [`Element`]: ../minidom/element/struct.Element.html
     fn try_attribute<F>(&self, attr_name: &str) -> Result<F, Error>
     where
-        F: FromStr,
-        F::Err: std::error::Error + Send + Sync + 'static,
+        F: AttrValue,
+    {
+        let value = self
+            .attr(attr_name)
+            .ok_or_else(|| attribute_not_found(self, attr_name))?;
+        parse_value(self, attr_name, value)
+    }
+
+    fn attribute<F>(&self, attr_name: &str) -> Option<F>
+    where
+        F: AttrValue,
+    {
+        self.attr(attr_name)
+            .and_then(|value| F::parse_attr(value).ok())
+    }
+
+    fn try_attribute_with<F>(&self, attr_name: &str, options: &ParseOptions) -> Result<F, Error>
+    where
+        F: AttrValue,
     {
-        let value = self.attr(attr_name).ok_or_else(|| {
-            Error::AttributeNotFound(self.name().to_owned(), attr_name.to_owned())
+        let value = self
+            .attr(attr_name)
+            .ok_or_else(|| attribute_not_found(self, attr_name))?;
+        let value = options.prepare(value, || {
+            format!("attribute '{}' of element '{}'", attr_name, self.name())
         })?;
-        value.parse().map_err(|e: F::Err| Error::ParseError {
-            element_name: self.name().to_owned(),
-            attribute_name: attr_name.to_owned(),
-            value: value.to_owned(),
-            source: e.into(),
-        })
+        parse_value(self, attr_name, &value)
+    }
+
+    /// Namespaced attributes are matched on their namespace and local name,
+    /// whatever the prefix they are written with.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::{AttributeElementExt, XSI_NS};
+    ///
+    /// let xml: &'static str = r#"<root xmlns:i="http://www.w3.org/2001/XMLSchema-instance">
+    ///         <child i:type="StopPlace" />
+    ///     </root>"#;
+    /// let root: Element = xml.parse().unwrap();
+    /// let child = root.children().next().unwrap();
+    /// let xsi_type: String = child.try_attribute_ns(XSI_NS, "type").unwrap();
+    /// assert_eq!("StopPlace", xsi_type);
+    /// ```
+    fn try_attribute_ns<F>(&self, ns: impl AsRef<str>, attr_name: &str) -> Result<F, Error>
+    where
+        F: AttrValue,
+    {
+        let ns = ns.as_ref();
+        let (qualified_name, value) = find_attribute_ns(self, ns, attr_name)
+            .ok_or_else(|| attribute_not_found(self, &clark_name(Some(ns), attr_name)))?;
+        parse_value(self, qualified_name, value)
+    }
+
+    fn attribute_ns<F>(&self, ns: impl AsRef<str>, attr_name: &str) -> Option<F>
+    where
+        F: AttrValue,
+    {
+        find_attribute_ns(self, ns.as_ref(), attr_name)
+            .and_then(|(_, value)| F::parse_attr(value).ok())
+    }
+
+    fn try_attribute_enum<T>(&self, attr_name: &str, allowed: &[&str]) -> Result<T, Error>
+    where
+        T: AttrValue,
+    {
+        let value = self
+            .attr(attr_name)
+            .ok_or_else(|| attribute_not_found(self, attr_name))?;
+        parse_enum(self, attr_name, value, allowed)
     }
 }
 
@@ -87,4 +501,61 @@ mod tests {
         let error = root.try_attribute::<f64>("id").unwrap_err();
         assert_eq!("Failed to parse and convert the value \'root:1\' of attribute \'id\' in element \'root\'", format!("{}", error));
     }
+
+    #[test]
+    fn namespaced_attribute() {
+        let xml: &'static str =
+            r#"<root xmlns:a="urn:a" xmlns:b="urn:b" a:id="1" b:id="2" id="3" />"#;
+        let root: Element = xml.parse().unwrap();
+        assert_eq!(2, root.try_attribute_ns::<u64>("urn:b", "id").unwrap());
+        assert_eq!(1, root.try_attribute_ns::<u64>("urn:a", "id").unwrap());
+    }
+
+    #[test]
+    fn no_namespaced_attribute() {
+        let xml: &'static str = r#"<root xmlns:a="urn:a" id="3" />"#;
+        let root: Element = xml.parse().unwrap();
+        let error = root.try_attribute_ns::<u64>("urn:a", "id").unwrap_err();
+        assert_eq!(
+            "Failed to find attribute \'{urn:a}id\' in element \'root\' (available: id)",
+            format!("{}", error)
+        );
+    }
+
+    #[test]
+    fn enumerated_attribute() {
+        #[derive(Debug, PartialEq)]
+        enum Mode {
+            Bus,
+            Tram,
+        }
+
+        impl std::str::FromStr for Mode {
+            type Err = std::fmt::Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    "bus" => Ok(Mode::Bus),
+                    "tram" => Ok(Mode::Tram),
+                    _ => Err(std::fmt::Error),
+                }
+            }
+        }
+
+        let root: Element = r#"<root a="tram" b="metro" />"#.parse().unwrap();
+        let allowed = ["bus", "tram"];
+        assert_eq!(Mode::Tram, root.try_attribute_enum("a", &allowed).unwrap());
+        assert_eq!(Mode::Tram, root.attribute_enum("a", &allowed).unwrap());
+        assert_eq!(None, root.attribute_enum::<Mode>("b", &allowed));
+        let error = root.try_attribute_enum::<Mode>("b", &allowed).unwrap_err();
+        assert_eq!(crate::ErrorKind::UnexpectedValue, error.kind());
+        assert_eq!("MDX0022", error.code());
+
+        impl XsEnumeration for Mode {
+            const VALUES: &'static [&'static str] = &["bus", "tram"];
+        }
+
+        assert_eq!(Mode::Tram, root.try_attribute_variant("a").unwrap());
+        assert_eq!(None, root.attribute_variant::<Mode>("b"));
+    }
 }
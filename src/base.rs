@@ -0,0 +1,259 @@
+use crate::{scratch::with_text, suggest::attribute_not_found, ElementCursor, Error};
+use minidom::Element;
+use url::Url;
+
+const XML_BASE: &str = "xml:base";
+
+fn parse_error(
+    element: &Element,
+    attribute_name: &str,
+    value: &str,
+    source: url::ParseError,
+) -> Error {
+    Error::ParseError {
+        element_name: element.name().to_owned(),
+        attribute_name: attribute_name.to_owned(),
+        value: value.to_owned(),
+        source: source.into(),
+    }
+}
+
+fn resolve(base: Option<Url>, value: &str) -> Result<Url, url::ParseError> {
+    match base {
+        Some(base) => base.join(value),
+        None => Url::parse(value),
+    }
+}
+
+/// Parse URL attributes and texts into [`Url`], optionally resolved against a
+/// base.
+///
+/// [`Url`]: ../url/struct.Url.html
+pub trait UrlElementExt {
+    /// Try to get a URL attribute and return a [`Result`].
+    ///
+    /// When a `base` is given, relative URLs are resolved against it,
+    /// otherwise the URL must be absolute. See
+    /// [`ElementCursor::try_resolve_attribute_uri`] to resolve against the
+    /// `xml:base` declared in the document.
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`ElementCursor::try_resolve_attribute_uri`]: struct.ElementCursor.html#method.try_resolve_attribute_uri
+    fn try_attribute_url(&self, attr_name: &str, base: Option<&Url>) -> Result<Url, Error>;
+
+    /// Get a URL attribute if present and valid and return an [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn attribute_url(&self, attr_name: &str, base: Option<&Url>) -> Option<Url> {
+        self.try_attribute_url(attr_name, base).ok()
+    }
+
+    /// Try to parse the text of the element as a URL, optionally resolved
+    /// against `base`, and return a [`Result`].
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_text_url(&self, base: Option<&Url>) -> Result<Url, Error>;
+
+    /// Parse the text of the element as a URL, optionally resolved against
+    /// `base`, if valid and return an [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn text_url(&self, base: Option<&Url>) -> Option<Url> {
+        self.try_text_url(base).ok()
+    }
+}
+
+impl UrlElementExt for Element {
+    /// Implementation of [`UrlElementExt`] for [`Element`] trims the value
+    /// before parsing it, the reason of a failure being the source of the
+    /// returned [`ParseError`] or [`TextParseError`].
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::UrlElementExt;
+    /// use url::Url;
+    ///
+    /// let xml: &'static str = r#"<Link href="stops/gare.xml" />"#;
+    /// let link: Element = xml.parse().unwrap();
+    /// let base = Url::parse("https://example.com/data/").unwrap();
+    /// let url = link.try_attribute_url("href", Some(&base)).unwrap();
+    /// assert_eq!("https://example.com/data/stops/gare.xml", url.as_str());
+    /// let error = link.try_attribute_url("href", None).unwrap_err();
+    /// assert_eq!(
+    ///     "Failed to parse and convert the value 'stops/gare.xml' of attribute 'href' in element 'Link'",
+    ///     error.to_string()
+    /// );
+    /// ```
+    ///
+    /// [`UrlElementExt`]: trait.UrlElementExt.html
+    /// [`Element`]: ../minidom/element/struct.Element.html
+    /// [`ParseError`]: enum.Error.html#variant.ParseError
+    /// [`TextParseError`]: enum.Error.html#variant.TextParseError
+    fn try_attribute_url(&self, attr_name: &str, base: Option<&Url>) -> Result<Url, Error> {
+        let value = self
+            .attr(attr_name)
+            .ok_or_else(|| attribute_not_found(self, attr_name))?;
+        resolve(base.cloned(), value.trim()).map_err(|e| parse_error(self, attr_name, value, e))
+    }
+
+    fn try_text_url(&self, base: Option<&Url>) -> Result<Url, Error> {
+        with_text(self, |text| {
+            let value = text.trim();
+            resolve(base.cloned(), value).map_err(|e| Error::TextParseError {
+                element_name: self.name().to_owned(),
+                value: value.to_owned(),
+                source: e.into(),
+            })
+        })
+    }
+}
+
+impl<'a> ElementCursor<'a> {
+    /// Try to get the base URI of the current element and return a
+    /// [`Result`].
+    ///
+    /// The base URI is built from the `xml:base` attributes of the root down
+    /// to the current element, each one being resolved against the previous
+    /// one. `Ok(None)` is returned when no `xml:base` is declared.
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    pub fn try_base_uri(&self) -> Result<Option<Url>, Error> {
+        let mut ancestors: Vec<&Element> = self.ancestors_or_self().collect();
+        ancestors.reverse();
+        let mut base: Option<Url> = None;
+        for element in ancestors {
+            if let Some(value) = element.attr(XML_BASE) {
+                let uri = resolve(base.take(), value)
+                    .map_err(|e| parse_error(element, XML_BASE, value, e))?;
+                base = Some(uri);
+            }
+        }
+        Ok(base)
+    }
+
+    /// Try to get a URI attribute of the current element, resolved against
+    /// the inherited `xml:base`, and return a [`Result`].
+    ///
+    /// Relative URIs without any absolute `xml:base` are an error.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::ElementCursor;
+    ///
+    /// let xml: &'static str = r#"<root xml:base="https://example.com/data/">
+    ///         <frame xml:base="stops/"><Link href="gare.xml" /></frame>
+    ///     </root>"#;
+    /// let root: Element = xml.parse().unwrap();
+    /// let link = ElementCursor::new(&root)
+    ///     .children()
+    ///     .next()
+    ///     .unwrap()
+    ///     .children()
+    ///     .next()
+    ///     .unwrap();
+    /// let uri = link.try_resolve_attribute_uri("href").unwrap();
+    /// assert_eq!("https://example.com/data/stops/gare.xml", uri.as_str());
+    /// ```
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    pub fn try_resolve_attribute_uri(&self, attr_name: &str) -> Result<Url, Error> {
+        let element = self.element();
+        let value = element
+            .attr(attr_name)
+            .ok_or_else(|| attribute_not_found(element, attr_name))?;
+        resolve(self.try_base_uri()?, value).map_err(|e| parse_error(element, attr_name, value, e))
+    }
+
+    /// Get a URI attribute of the current element, resolved against the
+    /// inherited `xml:base`, if present and valid and return an [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    pub fn resolve_attribute_uri(&self, attr_name: &str) -> Option<Url> {
+        self.try_resolve_attribute_uri(attr_name).ok()
+    }
+
+    /// Try to get the text of the current element as a URI, resolved against
+    /// the inherited `xml:base`, and return a [`Result`].
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    pub fn try_resolve_text_uri(&self) -> Result<Url, Error> {
+        let element = self.element();
+        let base = self.try_base_uri()?;
+        with_text(element, |text| {
+            let value = text.trim();
+            resolve(base, value).map_err(|e| Error::TextParseError {
+                element_name: element.name().to_owned(),
+                value: value.to_owned(),
+                source: e.into(),
+            })
+        })
+    }
+
+    /// Get the text of the current element as a URI, resolved against the
+    /// inherited `xml:base`, if valid and return an [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    pub fn resolve_text_uri(&self) -> Option<Url> {
+        self.try_resolve_text_uri().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn nested_bases() {
+        let xml: &'static str = r#"<root xml:base="http://example.com/a/b/">
+                <frame xml:base="../c/"><Uri>d.xml</Uri></frame>
+                <frame xml:base="https://other.org/"><Uri>/e.xml</Uri></frame>
+            </root>"#;
+        let root: Element = xml.parse().unwrap();
+        let cursor = ElementCursor::new(&root);
+        let uris: Vec<String> = cursor
+            .children()
+            .map(|frame| {
+                let uri = frame.children().next().unwrap();
+                uri.try_resolve_text_uri().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(
+            vec!["http://example.com/a/c/d.xml", "https://other.org/e.xml"],
+            uris
+        );
+    }
+
+    #[test]
+    fn explicit_base() {
+        let xml: &'static str = r#"<Link href=" https://other.org/a.xml ">../b.xml</Link>"#;
+        let link: Element = xml.parse().unwrap();
+        let base = Url::parse("http://example.com/a/b/").unwrap();
+        assert_eq!(
+            "https://other.org/a.xml",
+            link.try_attribute_url("href", Some(&base))
+                .unwrap()
+                .as_str()
+        );
+        assert_eq!(
+            "http://example.com/a/b.xml",
+            link.try_text_url(Some(&base)).unwrap().as_str()
+        );
+        assert_eq!(None, link.text_url(None));
+        assert_eq!(None, link.attribute_url("missing", Some(&base)));
+    }
+
+    #[test]
+    fn relative_without_base() {
+        let xml: &'static str = r#"<root><Link href="stops.xml" /></root>"#;
+        let root: Element = xml.parse().unwrap();
+        let cursor = ElementCursor::new(&root);
+        let link = cursor.children().next().unwrap();
+        assert_eq!(None, link.try_base_uri().unwrap());
+        let error = link.try_resolve_attribute_uri("href").unwrap_err();
+        assert_eq!(
+            "Failed to parse and convert the value \'stops.xml\' of attribute \'href\' in element \'Link\'",
+            format!("{}", error)
+        );
+    }
+}
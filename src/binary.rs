@@ -0,0 +1,96 @@
+use crate::{AttributeElementExt, Error, TextElementExt};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use minidom::Element;
+use std::str::FromStr;
+
+/// Binary content encoded with `xs:base64Binary`, where whitespaces are
+/// allowed between the characters.
+struct Base64(Vec<u8>);
+
+impl FromStr for Base64 {
+    type Err = base64::DecodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let encoded: String = s.split_ascii_whitespace().collect();
+        STANDARD.decode(encoded).map(Base64)
+    }
+}
+
+/// Decode binary content encoded with `xs:base64Binary`.
+pub trait Base64ElementExt {
+    /// Try to decode a base64 attribute and return a [`Result`].
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_attribute_base64(&self, attr_name: &str) -> Result<Vec<u8>, Error>;
+
+    /// Decode a base64 attribute if present and valid and return an
+    /// [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn attribute_base64(&self, attr_name: &str) -> Option<Vec<u8>> {
+        self.try_attribute_base64(attr_name).ok()
+    }
+
+    /// Try to decode the base64 text of the element and return a
+    /// [`Result`].
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_text_base64(&self) -> Result<Vec<u8>, Error>;
+
+    /// Decode the base64 text of the element if valid and return an
+    /// [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn text_base64(&self) -> Option<Vec<u8>> {
+        self.try_text_base64().ok()
+    }
+}
+
+impl Base64ElementExt for Element {
+    /// Implementation of [`Base64ElementExt`] for [`Element`] decodes
+    /// embedded binary payloads, the decoding error being the source of the
+    /// returned [`ParseError`] or [`TextParseError`].
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::Base64ElementExt;
+    ///
+    /// let xml: &'static str = r#"<Signature>
+    ///         SGVsbG8g
+    ///         d29ybGQ=
+    ///     </Signature>"#;
+    /// let signature: Element = xml.parse().unwrap();
+    /// assert_eq!(b"Hello world".to_vec(), signature.try_text_base64().unwrap());
+    /// ```
+    ///
+    /// [`Base64ElementExt`]: trait.Base64ElementExt.html
+    /// [`Element`]: ../minidom/element/struct.Element.html
+    /// [`ParseError`]: enum.Error.html#variant.ParseError
+    /// [`TextParseError`]: enum.Error.html#variant.TextParseError
+    fn try_attribute_base64(&self, attr_name: &str) -> Result<Vec<u8>, Error> {
+        self.try_attribute::<Base64>(attr_name)
+            .map(|binary| binary.0)
+    }
+
+    fn try_text_base64(&self) -> Result<Vec<u8>, Error> {
+        self.try_text::<Base64>().map(|binary| binary.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn invalid_attribute() {
+        let xml: &'static str = r#"<Data payload="AAE=" checksum="A*==" />"#;
+        let data: Element = xml.parse().unwrap();
+        assert_eq!(Some(vec![0, 1]), data.attribute_base64("payload"));
+        let error = data.try_attribute_base64("checksum").unwrap_err();
+        assert_eq!(
+            "Failed to parse and convert the value \'A*==\' of attribute \'checksum\' in element \'Data\'",
+            format!("{}", error)
+        );
+    }
+}
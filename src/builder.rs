@@ -0,0 +1,130 @@
+use minidom::{element::ElementBuilder, Element};
+use std::fmt::Display;
+
+/// Turn a value into an [`Element`].
+///
+/// This is the counterpart of [`FromElement`], used by
+/// [`ElementBuilderExt`] to append typed values as children.
+///
+/// [`Element`]: ../minidom/element/struct.Element.html
+/// [`ElementBuilderExt`]: trait.ElementBuilderExt.html
+/// [`FromElement`]: trait.FromElement.html
+pub trait IntoElement {
+    /// Build the element representing the value.
+    fn into_element(self) -> Element;
+}
+
+impl IntoElement for Element {
+    fn into_element(self) -> Element {
+        self
+    }
+}
+
+impl IntoElement for ElementBuilder {
+    fn into_element(self) -> Element {
+        self.build()
+    }
+}
+
+/// Append typed values to a `minidom` [`ElementBuilder`], without
+/// intermediate `String`s nor `if let` blocks for the optional ones.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{ElementBuilderExt, IntoElement};
+///
+/// struct Stop {
+///     id: u32,
+///     name: Option<String>,
+/// }
+///
+/// impl IntoElement for &Stop {
+///     fn into_element(self) -> Element {
+///         Element::builder("Stop")
+///             .attr_value("id", self.id)
+///             .opt_attr("name", self.name.as_ref())
+///             .build()
+///     }
+/// }
+///
+/// let stops = vec![
+///     Stop { id: 1, name: Some("Nation".to_owned()) },
+///     Stop { id: 2, name: None },
+/// ];
+/// let route = Element::builder("Route")
+///     .attr_value("length", 2.5)
+///     .child_from(Element::builder("Name").append("M1"))
+///     .children_from(&stops)
+///     .build();
+/// assert_eq!(
+///     r#"<Route length="2.5"><Name>M1</Name><Stop id="1" name="Nation"/><Stop id="2"/></Route>"#,
+///     String::from(&route)
+/// );
+/// ```
+///
+/// [`ElementBuilder`]: ../minidom/element/struct.ElementBuilder.html
+pub trait ElementBuilderExt {
+    /// Set the attribute `name` to the [`Display`] representation of `value`.
+    ///
+    /// [`Display`]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+    fn attr_value<T: Display>(self, name: &str, value: T) -> Self;
+
+    /// Set the attribute `name` like [`attr_value`] if `value` is `Some`.
+    ///
+    /// [`attr_value`]: #tymethod.attr_value
+    fn opt_attr<T: Display>(self, name: &str, value: Option<T>) -> Self;
+
+    /// Append the element built from `child`.
+    fn child_from<T: IntoElement>(self, child: T) -> Self;
+
+    /// Append the elements built from each of `children`.
+    fn children_from<I>(self, children: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: IntoElement;
+}
+
+impl ElementBuilderExt for ElementBuilder {
+    fn attr_value<T: Display>(self, name: &str, value: T) -> Self {
+        self.attr(name, value.to_string())
+    }
+
+    fn opt_attr<T: Display>(self, name: &str, value: Option<T>) -> Self {
+        match value {
+            Some(value) => self.attr_value(name, value),
+            None => self,
+        }
+    }
+
+    fn child_from<T: IntoElement>(self, child: T) -> Self {
+        self.append(child.into_element())
+    }
+
+    fn children_from<I>(self, children: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: IntoElement,
+    {
+        self.append_all(children.into_iter().map(IntoElement::into_element))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn children_namespace() {
+        let line = Element::builder("Line")
+            .ns("urn:netex")
+            .opt_attr("id", Option::<u32>::None)
+            .children_from(vec![Element::builder("Name"), Element::builder("Code")])
+            .build();
+        assert_eq!(
+            r#"<Line xmlns="urn:netex"><Name/><Code/></Line>"#,
+            String::from(&line)
+        );
+        assert!(line.children().all(|child| child.has_ns("urn:netex")));
+    }
+}
@@ -0,0 +1,409 @@
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+/// Error returned when parsing one of the calendar types fails.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum CalendarError {
+    /// The day of week is not recognized.
+    #[error("Unknown day of week '{0}'")]
+    UnknownDay(String),
+    /// The bitmask doesn't have exactly 7 characters made of `0` and `1`.
+    #[error("Invalid day of week bitmask '{0}' (expected 7 characters '0' or '1')")]
+    InvalidBitmask(String),
+    /// The date doesn't follow the `YYYY-MM-DD` format or doesn't exist.
+    #[error("Invalid date '{0}' (expected 'YYYY-MM-DD')")]
+    InvalidDate(String),
+    /// The date range doesn't follow the `start/end` format.
+    #[error("Invalid date range '{0}' (expected 'YYYY-MM-DD/YYYY-MM-DD')")]
+    InvalidDateRange(String),
+    /// The end of the date range is before its start.
+    #[error("Date range '{0}' ends before it starts")]
+    ReversedDateRange(String),
+}
+
+/// A day of the week.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Weekday {
+    /// Monday
+    Monday,
+    /// Tuesday
+    Tuesday,
+    /// Wednesday
+    Wednesday,
+    /// Thursday
+    Thursday,
+    /// Friday
+    Friday,
+    /// Saturday
+    Saturday,
+    /// Sunday
+    Sunday,
+}
+
+impl Weekday {
+    /// All the days of the week, starting on Monday.
+    pub const ALL: [Weekday; 7] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+
+    fn bit(self) -> u8 {
+        1 << (self as u8)
+    }
+}
+
+impl FromStr for Weekday {
+    type Err = CalendarError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Weekday::ALL
+            .iter()
+            .copied()
+            .find(|day| format!("{:?}", day).eq_ignore_ascii_case(s))
+            .ok_or_else(|| CalendarError::UnknownDay(s.to_owned()))
+    }
+}
+
+/// A set of days of the week, stored as a bitmask.
+///
+/// It can be parsed from a whitespace-separated list of days (`Monday
+/// Tuesday`, including the `Weekdays`, `Weekend` and `Everyday` shortcuts), or
+/// from a 7 characters bitmask starting on Monday (`1111100`).
+///
+/// ```
+/// use minidom_ext::{DaysOfWeek, Weekday};
+///
+/// let days: DaysOfWeek = "Monday Weekend".parse().unwrap();
+/// assert!(days.contains(Weekday::Monday));
+/// assert!(days.contains(Weekday::Sunday));
+/// assert!(!days.contains(Weekday::Friday));
+/// assert_eq!(days, "1000011".parse().unwrap());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct DaysOfWeek(u8);
+
+impl DaysOfWeek {
+    /// No day at all.
+    pub const NONE: DaysOfWeek = DaysOfWeek(0);
+    /// From Monday to Friday.
+    pub const WEEKDAYS: DaysOfWeek = DaysOfWeek(0b0011111);
+    /// Saturday and Sunday.
+    pub const WEEKEND: DaysOfWeek = DaysOfWeek(0b1100000);
+    /// Every day of the week.
+    pub const EVERYDAY: DaysOfWeek = DaysOfWeek(0b1111111);
+
+    /// Check if the day is part of the set.
+    pub fn contains(self, day: Weekday) -> bool {
+        self.0 & day.bit() != 0
+    }
+
+    /// Add a day to the set.
+    pub fn insert(&mut self, day: Weekday) {
+        self.0 |= day.bit();
+    }
+
+    /// Check if the set is empty.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Iterate over the days of the set, starting on Monday.
+    pub fn iter(self) -> impl Iterator<Item = Weekday> {
+        Weekday::ALL
+            .iter()
+            .copied()
+            .filter(move |day| self.contains(*day))
+    }
+
+    /// Get the raw bitmask, Monday being the least significant bit.
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+}
+
+impl FromStr for DaysOfWeek {
+    type Err = CalendarError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if !s.is_empty() && s.chars().all(|c| c == '0' || c == '1') {
+            if s.len() != 7 {
+                return Err(CalendarError::InvalidBitmask(s.to_owned()));
+            }
+            let bits = s
+                .chars()
+                .enumerate()
+                .filter(|(_, c)| *c == '1')
+                .fold(0, |bits, (index, _)| bits | 1 << index);
+            return Ok(DaysOfWeek(bits));
+        }
+        let mut days = DaysOfWeek::NONE;
+        for token in s.split_whitespace() {
+            days.0 |= match token.to_ascii_lowercase().as_str() {
+                "weekdays" => DaysOfWeek::WEEKDAYS.0,
+                "weekend" => DaysOfWeek::WEEKEND.0,
+                "everyday" => DaysOfWeek::EVERYDAY.0,
+                _ => token.parse::<Weekday>()?.bit(),
+            };
+        }
+        Ok(days)
+    }
+}
+
+impl fmt::Display for DaysOfWeek {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for day in Weekday::ALL.iter() {
+            write!(f, "{}", if self.contains(*day) { '1' } else { '0' })?;
+        }
+        Ok(())
+    }
+}
+
+/// A calendar date, in the proleptic Gregorian calendar.
+///
+/// Parsed from the `YYYY-MM-DD` format (as in `xs:date` without timezone).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl Date {
+    /// Create a date, returning `None` if the date doesn't exist.
+    pub fn from_ymd(year: i32, month: u8, day: u8) -> Option<Date> {
+        if (1..=12).contains(&month) && day >= 1 && day <= days_in_month(year, month) {
+            Some(Date { year, month, day })
+        } else {
+            None
+        }
+    }
+
+    /// Year of the date.
+    pub fn year(self) -> i32 {
+        self.year
+    }
+
+    /// Month of the date, from 1 to 12.
+    pub fn month(self) -> u8 {
+        self.month
+    }
+
+    /// Day of the month, from 1 to 31.
+    pub fn day(self) -> u8 {
+        self.day
+    }
+
+    /// Number of days since 1970-01-01 (negative before).
+    pub fn days_since_epoch(self) -> i64 {
+        // Algorithm from http://howardhinnant.github.io/date_algorithms.html
+        let year = i64::from(self.year) - if self.month <= 2 { 1 } else { 0 };
+        let era = if year >= 0 { year } else { year - 399 } / 400;
+        let year_of_era = year - era * 400;
+        let month = i64::from(self.month);
+        let day_of_year =
+            (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(self.day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    /// Day of the week of the date.
+    pub fn weekday(self) -> Weekday {
+        // 1970-01-01 was a Thursday
+        Weekday::ALL[(self.days_since_epoch() + 3).rem_euclid(7) as usize]
+    }
+}
+
+impl FromStr for Date {
+    type Err = CalendarError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || CalendarError::InvalidDate(s.to_owned());
+        let (sign, unsigned) = match s.strip_prefix('-') {
+            Some(unsigned) => (-1, unsigned),
+            None => (1, s),
+        };
+        let mut parts = unsigned.splitn(3, '-');
+        let mut next_part = |min_len: usize| {
+            parts
+                .next()
+                .filter(|part| part.len() >= min_len && part.bytes().all(|b| b.is_ascii_digit()))
+                .ok_or_else(error)
+        };
+        let year: i32 = next_part(4)?.parse().map_err(|_| error())?;
+        let month = next_part(2)?;
+        let day = next_part(2)?;
+        if month.len() != 2 || day.len() != 2 {
+            return Err(error());
+        }
+        Date::from_ymd(
+            sign * year,
+            month.parse().map_err(|_| error())?,
+            day.parse().map_err(|_| error())?,
+        )
+        .ok_or_else(error)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// An inclusive range of dates, parsed from `start/end` (ISO 8601 interval
+/// notation).
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{AttributeElementExt, DateRange};
+///
+/// let xml: &'static str = r#"<root validity="2024-01-01/2024-06-30" />"#;
+/// let root: Element = xml.parse().unwrap();
+/// let validity: DateRange = root.try_attribute("validity").unwrap();
+/// assert_eq!(2024, validity.start().year());
+/// assert_eq!(182, validity.len());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DateRange {
+    start: Date,
+    end: Date,
+}
+
+impl DateRange {
+    /// Create a date range, returning `None` if `end` is before `start`.
+    pub fn new(start: Date, end: Date) -> Option<DateRange> {
+        if start <= end {
+            Some(DateRange { start, end })
+        } else {
+            None
+        }
+    }
+
+    /// First day of the range.
+    pub fn start(self) -> Date {
+        self.start
+    }
+
+    /// Last day of the range (included).
+    pub fn end(self) -> Date {
+        self.end
+    }
+
+    /// Check if the date is within the range.
+    pub fn contains(self, date: Date) -> bool {
+        self.start <= date && date <= self.end
+    }
+
+    /// Number of days in the range.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(self) -> u64 {
+        (self.end.days_since_epoch() - self.start.days_since_epoch() + 1) as u64
+    }
+}
+
+impl FromStr for DateRange {
+    type Err = CalendarError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().splitn(2, '/');
+        let (start, end) = match (parts.next(), parts.next()) {
+            (Some(start), Some(end)) => (start.trim().parse()?, end.trim().parse()?),
+            _ => return Err(CalendarError::InvalidDateRange(s.to_owned())),
+        };
+        DateRange::new(start, end).ok_or_else(|| CalendarError::ReversedDateRange(s.to_owned()))
+    }
+}
+
+impl fmt::Display for DateRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.start, self.end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn days_of_week_from_names() {
+        let days: DaysOfWeek = "Monday tuesday  Friday".parse().unwrap();
+        assert_eq!(
+            vec![Weekday::Monday, Weekday::Tuesday, Weekday::Friday],
+            days.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(DaysOfWeek::WEEKDAYS, "Weekdays".parse().unwrap());
+        assert_eq!(
+            "1111111",
+            "Weekdays Weekend"
+                .parse::<DaysOfWeek>()
+                .unwrap()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn days_of_week_from_bitmask() {
+        let days: DaysOfWeek = "1111100".parse().unwrap();
+        assert_eq!(DaysOfWeek::WEEKDAYS, days);
+        assert_eq!("1111100", days.to_string());
+    }
+
+    #[test]
+    fn invalid_days_of_week() {
+        assert_eq!(
+            CalendarError::UnknownDay("Funday".to_owned()),
+            "Monday Funday".parse::<DaysOfWeek>().unwrap_err()
+        );
+        assert_eq!(
+            CalendarError::InvalidBitmask("11111".to_owned()),
+            "11111".parse::<DaysOfWeek>().unwrap_err()
+        );
+    }
+
+    #[test]
+    fn parse_date() {
+        let date: Date = "2024-02-29".parse().unwrap();
+        assert_eq!((2024, 2, 29), (date.year(), date.month(), date.day()));
+        assert_eq!(Weekday::Thursday, date.weekday());
+        assert_eq!("2024-02-29", date.to_string());
+        assert!("2023-02-29".parse::<Date>().is_err());
+        assert!("2023-2-28".parse::<Date>().is_err());
+        assert!("2023-02-28T00:00:00".parse::<Date>().is_err());
+    }
+
+    #[test]
+    fn parse_date_range() {
+        let range: DateRange = "2024-01-01/2024-06-30".parse().unwrap();
+        assert!(range.contains("2024-03-15".parse().unwrap()));
+        assert!(!range.contains("2024-07-01".parse().unwrap()));
+        assert_eq!(182, range.len());
+        assert_eq!(
+            CalendarError::ReversedDateRange("2024-06-30/2024-01-01".to_owned()),
+            "2024-06-30/2024-01-01".parse::<DateRange>().unwrap_err()
+        );
+        assert_eq!(
+            CalendarError::InvalidDateRange("2024-06-30".to_owned()),
+            "2024-06-30".parse::<DateRange>().unwrap_err()
+        );
+    }
+}
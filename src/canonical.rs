@@ -0,0 +1,320 @@
+//! Canonical serialization of a subtree, inspired by Exclusive XML
+//! Canonicalization: only the namespace declarations visibly used by an
+//! element are written, attributes and declarations are sorted, comments
+//! are dropped and empty elements are written with a start and an end tag.
+//!
+//! Two subtrees with the same canonical serialization are the same for any
+//! consumer, whatever the prefixes declared by their ancestors or the order
+//! of their attributes.
+
+use crate::namespace::{lookup_prefix, own_declarations, split_qname};
+use minidom::{Element, Node};
+use std::collections::BTreeMap;
+
+type Bindings = BTreeMap<Option<String>, String>;
+
+/// Where the canonical serialization is written.
+pub(crate) trait Output {
+    fn push(&mut self, c: char);
+    fn push_str(&mut self, s: &str);
+}
+
+impl Output for String {
+    fn push(&mut self, c: char) {
+        String::push(self, c);
+    }
+
+    fn push_str(&mut self, s: &str) {
+        String::push_str(self, s);
+    }
+}
+
+/// 64 bits FNV-1a hash of the canonical serialization, computed without
+/// holding the serialization in memory.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Output for Fnv1a {
+    fn push(&mut self, c: char) {
+        self.push_str(c.encode_utf8(&mut [0; 4]));
+    }
+
+    fn push_str(&mut self, s: &str) {
+        for byte in s.bytes() {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+}
+
+struct Canonicalizer<'a> {
+    root: &'a Element,
+    /// Bindings declared by the ancestors of the root, resolved on demand
+    inherited: Bindings,
+    /// Whether the texts made only of whitespaces are dropped, except under
+    /// `xml:space="preserve"`
+    strip_whitespace: bool,
+}
+
+impl Canonicalizer<'_> {
+    fn resolve(&mut self, bindings: &Bindings, prefix: Option<&str>) -> String {
+        let key = prefix.map(ToOwned::to_owned);
+        if let Some(namespace) = bindings.get(&key) {
+            return namespace.clone();
+        }
+        let root = self.root;
+        self.inherited
+            .entry(key)
+            .or_insert_with(|| lookup_prefix(root, prefix).unwrap_or_default())
+            .clone()
+    }
+
+    fn write(
+        &mut self,
+        element: &Element,
+        bindings: &Bindings,
+        rendered: &Bindings,
+        preserve: bool,
+        output: &mut dyn Output,
+    ) {
+        let preserve = match element.attr("xml:space") {
+            Some(space) => space == "preserve",
+            None => preserve,
+        };
+        let mut bindings = bindings.clone();
+        bindings.extend(own_declarations(element));
+
+        let mut used = vec![element.prefix()];
+        for (name, _) in element.attrs() {
+            if let (Some(prefix), _) = split_qname(name) {
+                if prefix != "xml" {
+                    used.push(Some(prefix));
+                }
+            }
+        }
+        let mut rendered = rendered.clone();
+        let mut declarations = Bindings::new();
+        for prefix in used {
+            let namespace = self.resolve(&bindings, prefix);
+            let key = prefix.map(ToOwned::to_owned);
+            let current = rendered.get(&key).map(String::as_str).unwrap_or("");
+            if current != namespace {
+                rendered.insert(key.clone(), namespace.clone());
+                declarations.insert(key, namespace);
+            }
+        }
+
+        output.push('<');
+        push_qname(output, element);
+        for (prefix, namespace) in &declarations {
+            match prefix {
+                Some(prefix) => push_attribute(output, &format!("xmlns:{}", prefix), namespace),
+                None => push_attribute(output, "xmlns", namespace),
+            }
+        }
+        let mut attributes: Vec<(&str, &str)> = element.attrs().collect();
+        attributes.sort_unstable();
+        for (name, value) in attributes {
+            push_attribute(output, name, value);
+        }
+        output.push('>');
+        for node in element.nodes() {
+            match node {
+                Node::Element(child) => self.write(child, &bindings, &rendered, preserve, output),
+                Node::Text(text)
+                    if self.strip_whitespace && !preserve && text.trim().is_empty() => {}
+                Node::Text(text) => push_text(output, text),
+                Node::Comment(_) => {}
+            }
+        }
+        output.push_str("</");
+        push_qname(output, element);
+        output.push('>');
+    }
+}
+
+fn push_qname(output: &mut dyn Output, element: &Element) {
+    if let Some(prefix) = element.prefix() {
+        output.push_str(prefix);
+        output.push(':');
+    }
+    output.push_str(element.name());
+}
+
+fn push_text(output: &mut dyn Output, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '\r' => output.push_str("&#xD;"),
+            c => output.push(c),
+        }
+    }
+}
+
+fn push_attribute(output: &mut dyn Output, name: &str, value: &str) {
+    output.push(' ');
+    output.push_str(name);
+    output.push_str("=\"");
+    for c in value.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '"' => output.push_str("&quot;"),
+            '\t' => output.push_str("&#x9;"),
+            '\n' => output.push_str("&#xA;"),
+            '\r' => output.push_str("&#xD;"),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+}
+
+/// Write the canonical serialization of `element` and its descendants,
+/// dropping the texts made only of whitespaces if `strip_whitespace`.
+pub(crate) fn write_canonical(element: &Element, strip_whitespace: bool, output: &mut dyn Output) {
+    let mut canonicalizer = Canonicalizer {
+        root: element,
+        inherited: Bindings::new(),
+        strip_whitespace,
+    };
+    canonicalizer.write(element, &Bindings::new(), &Bindings::new(), false, output);
+}
+
+/// Serialize elements in a canonical form.
+pub trait CanonicalElementExt {
+    /// Serialize the element and its descendants into a deterministic
+    /// representation, for hashing, caching or diff-friendly storage.
+    ///
+    /// The output is UTF-8, inspired by Exclusive XML Canonicalization:
+    /// - attributes are sorted by qualified name
+    /// - an element only declares the namespaces it visibly uses (in its
+    ///   name or its attributes) and its closest ancestor in the output
+    ///   doesn't already declare, sorted by prefix
+    /// - comments are dropped, empty elements have a start and an end tag
+    /// - the texts made only of whitespaces (like indentation) are dropped,
+    ///   except under `xml:space="preserve"`
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::CanonicalElementExt;
+    ///
+    /// let xml: &'static str = r#"<root xmlns:n="urn:netex" xmlns:unused="urn:unused">
+    ///         <n:Line version="1" id="L1">
+    ///             <!-- generated -->
+    ///             <n:Name>Metro 1</n:Name>
+    ///         </n:Line>
+    ///     </root>"#;
+    /// let root: Element = xml.parse().unwrap();
+    /// let line = root.children().next().unwrap();
+    /// assert_eq!(
+    ///     r#"<n:Line xmlns:n="urn:netex" id="L1" version="1"><n:Name>Metro 1</n:Name></n:Line>"#,
+    ///     String::from_utf8(line.canonicalize()).unwrap()
+    /// );
+    /// ```
+    fn canonicalize(&self) -> Vec<u8>;
+
+    /// Hash the canonical form of the element (see [`canonicalize`]) with
+    /// 64 bits FNV-1a, to deduplicate subtrees or detect their changes
+    /// cheaply: the canonical form is hashed while the tree is walked, neither
+    /// it nor a copy of the tree being held in memory.
+    ///
+    /// The hash is stable across runs and platforms, so it can be stored,
+    /// but it is not cryptographic: use `digest` (feature `sha2`), which hashes
+    /// the same canonical form, to check the integrity of untrusted data.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::CanonicalElementExt;
+    ///
+    /// let first: Element = r#"<ServiceFrame version="1" id="SF1">
+    ///         <lines><Line id="L1" /></lines>
+    ///     </ServiceFrame>"#
+    ///     .parse()
+    ///     .unwrap();
+    /// let second: Element = r#"<ServiceFrame id="SF1" version="1"><lines><Line id="L1"/></lines></ServiceFrame>"#
+    ///     .parse()
+    ///     .unwrap();
+    /// assert_eq!(first.structural_hash(), second.structural_hash());
+    /// let changed: Element = r#"<ServiceFrame id="SF1" version="2" />"#.parse().unwrap();
+    /// assert_ne!(first.structural_hash(), changed.structural_hash());
+    /// ```
+    ///
+    /// [`canonicalize`]: #tymethod.canonicalize
+    fn structural_hash(&self) -> u64;
+}
+
+impl CanonicalElementExt for Element {
+    fn canonicalize(&self) -> Vec<u8> {
+        let mut output = String::new();
+        write_canonical(self, true, &mut output);
+        output.into_bytes()
+    }
+
+    fn structural_hash(&self) -> u64 {
+        let mut hasher = Fnv1a::new();
+        write_canonical(self, true, &mut hasher);
+        hasher.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn canonical(element: &Element) -> String {
+        let mut output = String::new();
+        write_canonical(element, false, &mut output);
+        output
+    }
+
+    #[test]
+    fn visibly_used_namespaces() {
+        let xml: &'static str = r#"<root xmlns="urn:default" xmlns:a="urn:a" xmlns:unused="urn:unused">
+                <a:frame b="2" a:a="1"><!-- comment --><child/></a:frame>
+            </root>"#;
+        let root: Element = xml.parse().unwrap();
+        let frame = root.children().next().unwrap();
+        assert_eq!(
+            r#"<a:frame xmlns:a="urn:a" a:a="1" b="2"><child xmlns="urn:default"></child></a:frame>"#,
+            canonical(frame)
+        );
+    }
+
+    #[test]
+    fn independent_of_attribute_order() {
+        let first: Element = r#"<a x="1" y="&#10;&lt;"/>"#.parse().unwrap();
+        let second: Element = r#"<a y="&#10;&lt;" x="1"></a>"#.parse().unwrap();
+        assert_eq!(canonical(&first), canonical(&second));
+        assert_eq!(r#"<a x="1" y="&#xA;&lt;"></a>"#, canonical(&first));
+    }
+
+    #[test]
+    fn stable_hash() {
+        let mut hasher = Fnv1a::new();
+        hasher.push_str("<a></a>");
+        let a: Element = "<a/>".parse().unwrap();
+        assert_eq!(hasher.0, a.structural_hash());
+        // FNV-1a 64 test vector
+        let mut hasher = Fnv1a::new();
+        hasher.push('a');
+        assert_eq!(0xaf63_dc4c_8601_ec8c, hasher.0);
+    }
+
+    #[test]
+    fn preserved_whitespaces() {
+        let xml: &'static str = r#"<a> <b xml:space="preserve"> <c> </c> </b> <d>x </d></a>"#;
+        let a: Element = xml.parse().unwrap();
+        assert_eq!(
+            r#"<a><b xml:space="preserve"> <c> </c> </b><d>x </d></a>"#,
+            String::from_utf8(a.canonicalize()).unwrap()
+        );
+    }
+}
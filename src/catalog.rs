@@ -0,0 +1,212 @@
+use crate::{AttributeElementExt, ElementCursor, Error};
+use minidom::Element;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+use url::Url;
+
+/// Namespace of OASIS XML catalogs.
+pub const CATALOG_NS: &str = "urn:oasis:names:tc:entity:xmlns:xml:catalog";
+
+/// Map the URIs referenced by documents to local files, like an OASIS XML
+/// catalog, so that pipelines can run offline.
+///
+/// A URI is first looked up among the exact mappings (`uri` entries of a
+/// catalog), then among the prefix rewrites (`rewriteURI` entries), the
+/// longest prefix winning. Unmapped `file:` URIs are used as is.
+///
+/// ```
+/// use minidom_ext::Catalog;
+/// use std::path::PathBuf;
+/// use url::Url;
+///
+/// let catalog = Catalog::new()
+///     .with_uri("https://example.com/lines.xml", "/data/lines.xml")
+///     .with_rewrite("https://example.com/stops/", "/data/stops/");
+/// let uri = Url::parse("https://example.com/stops/gare.xml").unwrap();
+/// assert_eq!(Some(PathBuf::from("/data/stops/gare.xml")), catalog.resolve(&uri));
+/// let uri = Url::parse("https://other.org/a.xml").unwrap();
+/// assert_eq!(
+///     "No catalog entry for URI 'https://other.org/a.xml'",
+///     catalog.try_resolve(&uri).unwrap_err().to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    uris: HashMap<String, PathBuf>,
+    rewrites: Vec<(String, PathBuf)>,
+}
+
+impl Catalog {
+    /// Create an empty catalog.
+    pub fn new() -> Self {
+        Catalog::default()
+    }
+
+    /// Map a URI to a local file.
+    pub fn with_uri<P: Into<PathBuf>>(mut self, uri: &str, path: P) -> Self {
+        self.uris.insert(uri.to_owned(), path.into());
+        self
+    }
+
+    /// Map all the URIs starting with `uri_prefix` to the local files
+    /// starting with `path_prefix`.
+    pub fn with_rewrite<P: Into<PathBuf>>(mut self, uri_prefix: &str, path_prefix: P) -> Self {
+        self.rewrites
+            .push((uri_prefix.to_owned(), path_prefix.into()));
+        self
+    }
+
+    /// Read the `uri` and `rewriteURI` entries of a catalog (in the
+    /// [`CATALOG_NS`] namespace), relative paths being resolved against
+    /// `base_dir`.
+    ///
+    /// [`CATALOG_NS`]: constant.CATALOG_NS.html
+    pub fn from_catalog_element(catalog: &Element, base_dir: &Path) -> Result<Self, Error> {
+        let mut result = Catalog::new();
+        for entry in catalog.children().filter(|entry| entry.has_ns(CATALOG_NS)) {
+            match entry.name() {
+                "uri" => {
+                    let name: String = entry.try_attribute("name")?;
+                    let path: String = entry.try_attribute("uri")?;
+                    result = result.with_uri(&name, base_dir.join(path));
+                }
+                "rewriteURI" => {
+                    let prefix: String = entry.try_attribute("uriStartString")?;
+                    let path: String = entry.try_attribute("rewritePrefix")?;
+                    result = result.with_rewrite(&prefix, base_dir.join(path));
+                }
+                _ => {}
+            }
+        }
+        Ok(result)
+    }
+
+    /// Read a catalog file, relative paths being resolved against the
+    /// directory of the file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let catalog = read_document(path).map_err(|source| Error::InvalidDocument { source })?;
+        let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+        Catalog::from_catalog_element(&catalog, base_dir)
+    }
+
+    /// The local file a URI is mapped to, if any.
+    pub fn resolve(&self, uri: &Url) -> Option<PathBuf> {
+        let uri_str = uri.as_str();
+        if let Some(path) = self.uris.get(uri_str) {
+            return Some(path.clone());
+        }
+        let rewrite = self
+            .rewrites
+            .iter()
+            .filter(|(prefix, _)| uri_str.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len());
+        match rewrite {
+            Some((prefix, path_prefix)) => {
+                let mut path = path_prefix.as_os_str().to_owned();
+                path.push(&uri_str[prefix.len()..]);
+                Some(PathBuf::from(path))
+            }
+            None if uri.scheme() == "file" => uri.to_file_path().ok(),
+            None => None,
+        }
+    }
+
+    /// Try to get the local file a URI is mapped to, and return an
+    /// [`UnmappedUri`] error if there is none.
+    ///
+    /// [`UnmappedUri`]: enum.Error.html#variant.UnmappedUri
+    pub fn try_resolve(&self, uri: &Url) -> Result<PathBuf, Error> {
+        self.resolve(uri).ok_or_else(|| Error::UnmappedUri {
+            uri: uri.to_string(),
+        })
+    }
+
+    /// Read and parse the document a URI is mapped to.
+    ///
+    /// An error reading or parsing the local file is returned as an
+    /// [`UnreadableResource`] error naming both the URI and the file.
+    ///
+    /// [`UnreadableResource`]: enum.Error.html#variant.UnreadableResource
+    pub fn try_open(&self, uri: &Url) -> Result<Element, Error> {
+        let path = self.try_resolve(uri)?;
+        read_document(&path).map_err(|source| Error::UnreadableResource {
+            uri: uri.to_string(),
+            path,
+            source,
+        })
+    }
+}
+
+fn read_document(path: &Path) -> Result<Element, minidom::Error> {
+    fs::read_to_string(path)?.parse()
+}
+
+impl<'a> ElementCursor<'a> {
+    /// Try to read the document referenced by a URI attribute of the current
+    /// element (resolved against the inherited `xml:base`, see
+    /// [`try_resolve_attribute_uri`]) from its local copy in `catalog`.
+    ///
+    /// [`try_resolve_attribute_uri`]: #method.try_resolve_attribute_uri
+    pub fn try_open_attribute_uri(
+        &self,
+        attr_name: &str,
+        catalog: &Catalog,
+    ) -> Result<Element, Error> {
+        catalog.try_open(&self.try_resolve_attribute_uri(attr_name)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn open_mapped_documents() {
+        let dir = std::env::temp_dir().join(format!("minidom_ext_catalog_{}", std::process::id()));
+        fs::create_dir_all(dir.join("stops")).unwrap();
+        fs::write(dir.join("stops/gare.xml"), r#"<StopPlace id="gare" />"#).unwrap();
+        fs::write(
+            dir.join("catalog.xml"),
+            format!(
+                r#"<catalog xmlns="{}">
+                    <rewriteURI uriStartString="https://example.com/stops/" rewritePrefix="stops/" />
+                    <uri name="https://example.com/lines.xml" uri="lines.xml" />
+                </catalog>"#,
+                CATALOG_NS
+            ),
+        )
+        .unwrap();
+        let catalog = Catalog::load(dir.join("catalog.xml")).unwrap();
+
+        let xml: &'static str = r#"<root xml:base="https://example.com/">
+                <Link href="stops/gare.xml" /><Link href="lines.xml" />
+            </root>"#;
+        let root: Element = xml.parse().unwrap();
+        let cursor = ElementCursor::new(&root);
+        let mut links = cursor.children();
+        let stop = links
+            .next()
+            .unwrap()
+            .try_open_attribute_uri("href", &catalog)
+            .unwrap();
+        assert_eq!("gare", stop.attr("id").unwrap());
+        let error = links
+            .next()
+            .unwrap()
+            .try_open_attribute_uri("href", &catalog)
+            .unwrap_err();
+        assert_eq!(ErrorKind::UnreadableResource, error.kind());
+        let expected = format!(
+            "Failed to read resource 'https://example.com/lines.xml' from '{}'",
+            dir.join("lines.xml").display()
+        );
+        assert!(error.to_string().starts_with(&expected));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
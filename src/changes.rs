@@ -0,0 +1,334 @@
+use minidom::{Element, Node};
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap, BTreeSet, HashMap},
+    hash::{Hash, Hasher},
+};
+
+/// Keys of the elements which changed between two versions of a document, see
+/// [`changed_elements`].
+///
+/// [`changed_elements`]: fn.changed_elements.html
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ElementChanges {
+    /// Keys only found in the new document, sorted
+    pub added: Vec<String>,
+    /// Keys only found in the old document, sorted
+    pub removed: Vec<String>,
+    /// Keys found in both documents but whose elements differ, sorted
+    pub modified: Vec<String>,
+}
+
+impl ElementChanges {
+    /// Check if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+fn index_by_key<'a>(
+    element: &'a Element,
+    key_attr: &str,
+    index: &mut BTreeMap<&'a str, &'a Element>,
+) {
+    if let Some(key) = element.attr(key_attr) {
+        index.entry(key).or_insert(element);
+    }
+    for child in element.children() {
+        index_by_key(child, key_attr, index);
+    }
+}
+
+/// Compare two versions of a document and return the keys of the elements
+/// which were added, removed or modified.
+///
+/// Every element (at any depth) having the `key_attr` attribute is a record.
+/// A record is modified if anything differs in its whole subtree (including
+/// nested records). If a key is duplicated in a document, only its first
+/// occurrence is considered.
+///
+/// This is designed for services reloading a reference dataset periodically
+/// and updating only the affected records.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::changed_elements;
+///
+/// let old: Element = r#"<root><Line id="1" name="A" /><Line id="2" /></root>"#.parse().unwrap();
+/// let new: Element = r#"<root><Line id="1" name="B" /><Line id="3" /></root>"#.parse().unwrap();
+/// let changes = changed_elements(&old, &new, "id");
+/// assert_eq!(vec!["3"], changes.added);
+/// assert_eq!(vec!["2"], changes.removed);
+/// assert_eq!(vec!["1"], changes.modified);
+/// ```
+pub fn changed_elements(old_tree: &Element, new_tree: &Element, key_attr: &str) -> ElementChanges {
+    let mut old_index = BTreeMap::new();
+    index_by_key(old_tree, key_attr, &mut old_index);
+    let mut new_index = BTreeMap::new();
+    index_by_key(new_tree, key_attr, &mut new_index);
+    let mut changes = ElementChanges::default();
+    for (key, old_element) in &old_index {
+        match new_index.get(key) {
+            None => changes.removed.push((*key).to_owned()),
+            Some(new_element) if new_element != old_element => {
+                changes.modified.push((*key).to_owned())
+            }
+            Some(_) => {}
+        }
+    }
+    changes.added = new_index
+        .keys()
+        .filter(|key| !old_index.contains_key(*key))
+        .map(|key| (*key).to_owned())
+        .collect();
+    changes
+}
+
+/// An attribute whose value differs between two versions of an entity, see
+/// [`compare_entities`].
+///
+/// [`compare_entities`]: fn.compare_entities.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributeChange {
+    /// Name of the attribute
+    pub name: String,
+    /// Value in the old version, `None` if the attribute was added
+    pub old: Option<String>,
+    /// Value in the new version, `None` if the attribute was removed
+    pub new: Option<String>,
+}
+
+/// Children with the same name added or removed between two versions of an
+/// entity, see [`compare_entities`].
+///
+/// [`compare_entities`]: fn.compare_entities.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChildChanges<'a> {
+    /// Name of the children
+    pub name: String,
+    /// Children only found in the new version, in document order
+    pub added: Vec<&'a Element>,
+    /// Children only found in the old version, in document order
+    pub removed: Vec<&'a Element>,
+}
+
+/// Differences between two versions of the same entity, see
+/// [`compare_entities`].
+///
+/// [`compare_entities`]: fn.compare_entities.html
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EntityDiff<'a> {
+    /// Attributes added, removed or modified, sorted by name
+    pub attributes: Vec<AttributeChange>,
+    /// Children added or removed, grouped by name and sorted by name
+    pub children: Vec<ChildChanges<'a>>,
+}
+
+impl EntityDiff<'_> {
+    /// Check if both versions are the same.
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty() && self.children.is_empty()
+    }
+}
+
+fn hash_element<H: Hasher>(element: &Element, state: &mut H) {
+    element.name().hash(state);
+    element.ns().hash(state);
+    for (name, value) in element.attrs() {
+        name.hash(state);
+        value.hash(state);
+    }
+    for node in element.nodes() {
+        match node {
+            Node::Element(child) => {
+                state.write_u8(0);
+                hash_element(child, state);
+            }
+            Node::Text(text) => {
+                state.write_u8(1);
+                text.hash(state);
+            }
+            Node::Comment(comment) => {
+                state.write_u8(2);
+                comment.hash(state);
+            }
+        }
+    }
+}
+
+fn element_hash(element: &Element) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hash_element(element, &mut hasher);
+    hasher.finish()
+}
+
+/// Remove from `candidates` the elements equal to one of `others`, each
+/// element of `others` matching at most one candidate.
+///
+/// Elements are matched by hash first, so only the elements with the same
+/// hash are compared.
+fn unmatched<'a>(candidates: &[&'a Element], others: &[&'a Element]) -> Vec<&'a Element> {
+    let mut by_hash: HashMap<u64, Vec<&Element>> = HashMap::new();
+    for other in others {
+        by_hash.entry(element_hash(other)).or_default().push(other);
+    }
+    candidates
+        .iter()
+        .filter(|candidate| {
+            let same_hash = match by_hash.get_mut(&element_hash(candidate)) {
+                Some(same_hash) => same_hash,
+                None => return true,
+            };
+            match same_hash.iter().position(|other| other == *candidate) {
+                Some(position) => {
+                    same_hash.swap_remove(position);
+                    false
+                }
+                None => true,
+            }
+        })
+        .copied()
+        .collect()
+}
+
+/// Compare two deliveries of the same entity (usually with the same
+/// identifier) and return the attributes which changed and the children
+/// which were added or removed.
+///
+/// The attributes whose name is in `ignore` (like a version number or a
+/// modification date) are not compared. Children are grouped by name and
+/// compared as a whole: a modified child is reported as removed from the old
+/// version and added to the new one. Both lists are sorted by name, so
+/// reports are stable from one run to the other.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::compare_entities;
+///
+/// let old: Element = r#"<Line id="1" version="1" name="A"><Stop ref="S1" /><Stop ref="S2" /></Line>"#
+///     .parse()
+///     .unwrap();
+/// let new: Element = r#"<Line id="1" version="2" color="red"><Stop ref="S1" /><Stop ref="S3" /></Line>"#
+///     .parse()
+///     .unwrap();
+/// let diff = compare_entities(&old, &new, &["version"]);
+/// let attributes: Vec<_> = diff.attributes.iter().map(|c| c.name.as_str()).collect();
+/// assert_eq!(vec!["color", "name"], attributes);
+/// assert_eq!("Stop", diff.children[0].name);
+/// assert_eq!(Some("S3"), diff.children[0].added[0].attr("ref"));
+/// assert_eq!(Some("S2"), diff.children[0].removed[0].attr("ref"));
+/// ```
+pub fn compare_entities<'a>(old: &'a Element, new: &'a Element, ignore: &[&str]) -> EntityDiff<'a> {
+    let names: BTreeSet<&str> = old
+        .attrs()
+        .chain(new.attrs())
+        .map(|(name, _)| name)
+        .collect();
+    let attributes = names
+        .iter()
+        .filter(|name| !ignore.contains(name))
+        .filter_map(|name| {
+            let (old_value, new_value) = (old.attr(name), new.attr(name));
+            if old_value == new_value {
+                return None;
+            }
+            Some(AttributeChange {
+                name: (*name).to_owned(),
+                old: old_value.map(ToOwned::to_owned),
+                new: new_value.map(ToOwned::to_owned),
+            })
+        })
+        .collect();
+
+    let mut groups: BTreeMap<&str, (Vec<&Element>, Vec<&Element>)> = BTreeMap::new();
+    for child in old.children() {
+        groups.entry(child.name()).or_default().0.push(child);
+    }
+    for child in new.children() {
+        groups.entry(child.name()).or_default().1.push(child);
+    }
+    let children = groups
+        .into_iter()
+        .filter_map(|(name, (old_children, new_children))| {
+            let added = unmatched(&new_children, &old_children);
+            let removed = unmatched(&old_children, &new_children);
+            if added.is_empty() && removed.is_empty() {
+                return None;
+            }
+            Some(ChildChanges {
+                name: name.to_owned(),
+                added,
+                removed,
+            })
+        })
+        .collect();
+    EntityDiff {
+        attributes,
+        children,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn nested_records() {
+        let old: Element = r#"<root><Line id="L1"><Route id="R1" /></Line></root>"#
+            .parse()
+            .unwrap();
+        let new: Element = r#"<root><Line id="L1"><Route id="R1" name="x" /></Line></root>"#
+            .parse()
+            .unwrap();
+        let changes = changed_elements(&old, &new, "id");
+        assert_eq!(vec!["L1", "R1"], changes.modified);
+        assert!(changes.added.is_empty() && changes.removed.is_empty());
+    }
+
+    #[test]
+    fn no_changes() {
+        let old: Element = r#"<root><Line id="L1" /></root>"#.parse().unwrap();
+        assert!(changed_elements(&old, &old.clone(), "id").is_empty());
+    }
+
+    #[test]
+    fn entity_diff() {
+        let old: Element = r#"<Line a="1" b="2"><Stop /><Stop /><Route id="R1" /></Line>"#
+            .parse()
+            .unwrap();
+        let new: Element = r#"<Line b="3" c="4"><Route id="R1" /><Stop /><Route id="R2" /></Line>"#
+            .parse()
+            .unwrap();
+        let diff = compare_entities(&old, &new, &[]);
+        assert_eq!(
+            vec![
+                AttributeChange {
+                    name: "a".to_owned(),
+                    old: Some("1".to_owned()),
+                    new: None,
+                },
+                AttributeChange {
+                    name: "b".to_owned(),
+                    old: Some("2".to_owned()),
+                    new: Some("3".to_owned()),
+                },
+                AttributeChange {
+                    name: "c".to_owned(),
+                    old: None,
+                    new: Some("4".to_owned()),
+                },
+            ],
+            diff.attributes
+        );
+        let children: Vec<_> = diff
+            .children
+            .iter()
+            .map(|c| (c.name.as_str(), c.added.len(), c.removed.len()))
+            .collect();
+        assert_eq!(vec![("Route", 1, 0), ("Stop", 0, 1)], children);
+        assert!(compare_entities(&old, &old, &[]).is_empty());
+        let diff = compare_entities(&old, &new, &["a", "b", "c"]);
+        assert!(diff.attributes.is_empty());
+        assert_eq!(2, diff.children.len());
+    }
+}
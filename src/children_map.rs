@@ -0,0 +1,116 @@
+use crate::{AttrValue, AttributeElementExt, ElemResultExt, Error, FromElement};
+use minidom::Element;
+use std::collections::BTreeMap;
+
+/// Build collections of typed values out of the children of an element.
+pub trait ChildrenMapElementExt {
+    /// Try to parse all the children with the given name into a map, keyed by
+    /// the value of their `key_attr` attribute.
+    ///
+    /// The errors of a child are wrapped in a [`WithPath`] error naming the
+    /// child by its key (like `Line[@id='42']`) or, when the key itself cannot
+    /// be read, by its position (like `Line[3]`). Two children with the same
+    /// key are rejected with a [`DuplicateKey`] error.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::{AttributeElementExt, ChildrenMapElementExt, Error, FromElement};
+    /// use std::collections::BTreeMap;
+    ///
+    /// #[derive(Debug)]
+    /// struct Line {
+    ///     name: String,
+    /// }
+    ///
+    /// impl FromElement for Line {
+    ///     fn from_element(element: &Element) -> Result<Self, Error> {
+    ///         Ok(Line {
+    ///             name: element.try_attribute("name")?,
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let xml: &'static str = r#"<root>
+    ///         <Line id="2" name="B" />
+    ///         <Line id="1" name="A" />
+    ///         <Line id="3" />
+    ///     </root>"#;
+    /// let root: Element = xml.parse().unwrap();
+    /// let error = root.try_children_map::<u32, Line>("Line", "id").unwrap_err();
+    /// assert_eq!(
+    ///     "In Line[@id='3']: Failed to find attribute 'name' in element 'Line' (available: id)",
+    ///     error.to_string()
+    /// );
+    /// let lines: BTreeMap<String, Element> = root.try_children_map("Line", "id").unwrap();
+    /// assert_eq!(vec!["1", "2", "3"], lines.keys().map(String::as_str).collect::<Vec<_>>());
+    /// ```
+    ///
+    /// [`DuplicateKey`]: enum.Error.html#variant.DuplicateKey
+    /// [`WithPath`]: enum.Error.html#variant.WithPath
+    fn try_children_map<K, V>(&self, name: &str, key_attr: &str) -> Result<BTreeMap<K, V>, Error>
+    where
+        K: AttrValue + Ord,
+        V: FromElement;
+}
+
+impl ChildrenMapElementExt for Element {
+    fn try_children_map<K, V>(&self, name: &str, key_attr: &str) -> Result<BTreeMap<K, V>, Error>
+    where
+        K: AttrValue + Ord,
+        V: FromElement,
+    {
+        let mut map = BTreeMap::new();
+        for (index, child) in self
+            .children()
+            .filter(|child| child.name() == name)
+            .enumerate()
+        {
+            let position = format!("{}[{}]", name, index + 1);
+            let key: K = child.try_attribute(key_attr).ctx_path(position)?;
+            let raw_key = child.attr(key_attr).unwrap_or_default();
+            if map.contains_key(&key) {
+                return Err(Error::DuplicateKey {
+                    element_name: self.name().to_owned(),
+                    child_name: name.to_owned(),
+                    attribute_name: key_attr.to_owned(),
+                    key: raw_key.to_owned(),
+                });
+            }
+            let value = V::from_element(child)
+                .ctx_path(format!("{}[@{}='{}']", name, key_attr, raw_key))?;
+            map.insert(key, value);
+        }
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn invalid_keys() {
+        let xml: &'static str = r#"<root><Line id="1" /><Line id="x" /><Line id="01" /></root>"#;
+        let root: Element = xml.parse().unwrap();
+        let error = root
+            .try_children_map::<u32, Element>("Line", "id")
+            .unwrap_err();
+        assert_eq!(
+            "In Line[2]: Failed to parse and convert the value 'x' of attribute 'id' in element 'Line'",
+            error.to_string()
+        );
+        let root: Element = r#"<root><Line id="1" /><Line id="01" /></root>"#.parse().unwrap();
+        let error = root
+            .try_children_map::<u32, Element>("Line", "id")
+            .unwrap_err();
+        assert_eq!(ErrorKind::DuplicateKey, error.kind());
+        assert_eq!(
+            "Duplicate key '01' of attribute 'id' for children with name 'Line' in Element 'root'",
+            error.to_string()
+        );
+        let empty = root.try_children_map::<u32, Element>("Stop", "id").unwrap();
+        assert!(empty.is_empty());
+    }
+}
@@ -0,0 +1,413 @@
+use crate::{AttributeElementExt, Error, TextElementExt};
+use minidom::Element;
+use std::{borrow::Cow, fmt, str::FromStr};
+
+/// Error returned when a contact field is lexically invalid.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum ContactError {
+    /// The value is not a valid email address.
+    #[error("Invalid email address '{0}'")]
+    InvalidEmail(String),
+    /// The value is not a valid phone number.
+    #[error("Invalid phone number '{0}'")]
+    InvalidPhone(String),
+    /// The value is not a valid absolute URI.
+    #[error("Invalid URI '{0}'")]
+    InvalidUri(String),
+}
+
+/// An email address, lexically validated (`local@domain.tld`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Email(String);
+
+impl Email {
+    /// The email address as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+fn is_valid_domain(domain: &str) -> bool {
+    let labels: Vec<&str> = domain.split('.').collect();
+    labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+        })
+        && labels
+            .last()
+            .map(|tld| tld.len() >= 2 && tld.chars().all(char::is_alphabetic))
+            .unwrap_or(false)
+}
+
+impl FromStr for Email {
+    type Err = ContactError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ContactError::InvalidEmail(s.to_owned());
+        let index = s.rfind('@').ok_or_else(error)?;
+        let (local, domain) = (&s[..index], &s[index + 1..]);
+        let valid_local = !local.is_empty()
+            && local.len() <= 64
+            && !local.starts_with('.')
+            && !local.ends_with('.')
+            && !local.contains("..")
+            && local
+                .chars()
+                .all(|c| c.is_alphanumeric() || "!#$%&'*+/=?^_`{|}~.-".contains(c));
+        if valid_local && is_valid_domain(domain) {
+            Ok(Email(s.to_owned()))
+        } else {
+            Err(error())
+        }
+    }
+}
+
+impl fmt::Display for Email {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A phone number, lexically validated: an optional leading `+`, then
+/// between 3 and 15 digits, possibly separated by spaces, dots, hyphens or
+/// parentheses.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PhoneNumber(String);
+
+impl PhoneNumber {
+    /// The phone number as written in the document.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The phone number without separators, nor the trunk prefix written
+    /// in parentheses after the country code (`+33 (0)1 23 45 67 89` gives
+    /// `+33123456789`).
+    pub fn normalized(&self) -> String {
+        let number = if self.0.starts_with('+') {
+            Cow::Owned(self.0.replacen("(0)", "", 1))
+        } else {
+            Cow::Borrowed(self.0.as_str())
+        };
+        number
+            .chars()
+            .filter(|c| c.is_ascii_digit() || *c == '+')
+            .collect()
+    }
+}
+
+impl FromStr for PhoneNumber {
+    type Err = ContactError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = s.strip_prefix('+').unwrap_or(s);
+        let digits = number.chars().filter(char::is_ascii_digit).count();
+        let valid = (3..=15).contains(&digits)
+            && number.starts_with(|c: char| c.is_ascii_digit() || c == '(')
+            && number
+                .chars()
+                .all(|c| c.is_ascii_digit() || " .-()".contains(c));
+        if valid {
+            Ok(PhoneNumber(s.to_owned()))
+        } else {
+            Err(ContactError::InvalidPhone(s.to_owned()))
+        }
+    }
+}
+
+impl fmt::Display for PhoneNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// An absolute URI, strictly validated against the RFC 3986 syntax: a scheme,
+/// only allowed characters, well-formed percent-encodings, and a non-empty
+/// authority for `http` and `https`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StrictUri(String);
+
+impl StrictUri {
+    /// The URI as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The scheme of the URI (`https`, `mailto`, ...).
+    pub fn scheme(&self) -> &str {
+        &self.0[..self.0.find(':').unwrap_or(0)]
+    }
+}
+
+fn is_valid_scheme(scheme: &str) -> bool {
+    scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+}
+
+fn has_valid_characters(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        let b = bytes[index];
+        if b == b'%' {
+            let hex = bytes.get(index + 1..index + 3);
+            if !hex
+                .map(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+                .unwrap_or(false)
+            {
+                return false;
+            }
+            index += 3;
+            continue;
+        }
+        if !(b.is_ascii_alphanumeric() || b"-._~:/?#[]@!$&'()*+,;=".contains(&b)) {
+            return false;
+        }
+        index += 1;
+    }
+    true
+}
+
+impl FromStr for StrictUri {
+    type Err = ContactError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ContactError::InvalidUri(s.to_owned());
+        let index = s.find(':').ok_or_else(error)?;
+        let (scheme, rest) = (&s[..index], &s[index + 1..]);
+        if !is_valid_scheme(scheme) || rest.is_empty() || !has_valid_characters(rest) {
+            return Err(error());
+        }
+        if scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https") {
+            let authority = rest
+                .strip_prefix("//")
+                .and_then(|rest| rest.split(['/', '?', '#']).next())
+                .ok_or_else(error)?;
+            if authority.is_empty() {
+                return Err(error());
+            }
+        }
+        Ok(StrictUri(s.to_owned()))
+    }
+}
+
+impl fmt::Display for StrictUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Validate contact details (emails, phone numbers and URIs).
+///
+/// These are lexical checks only: they catch typos and garbage values, not
+/// unreachable addresses.
+pub trait ContactElementExt {
+    /// Try to get the text of the element as an [`Email`] and return a
+    /// [`Result`].
+    ///
+    /// [`Email`]: struct.Email.html
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_text_email(&self) -> Result<Email, Error>;
+
+    /// Get the text of the element as an [`Email`] if valid and return an
+    /// [`Option`].
+    ///
+    /// [`Email`]: struct.Email.html
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn text_email(&self) -> Option<Email> {
+        self.try_text_email().ok()
+    }
+
+    /// Try to get an attribute as an [`Email`] and return a [`Result`].
+    ///
+    /// [`Email`]: struct.Email.html
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_attribute_email(&self, attr_name: &str) -> Result<Email, Error>;
+
+    /// Get an attribute as an [`Email`] if present and valid and return an
+    /// [`Option`].
+    ///
+    /// [`Email`]: struct.Email.html
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn attribute_email(&self, attr_name: &str) -> Option<Email> {
+        self.try_attribute_email(attr_name).ok()
+    }
+
+    /// Try to get the text of the element as a [`PhoneNumber`] and return a
+    /// [`Result`].
+    ///
+    /// [`PhoneNumber`]: struct.PhoneNumber.html
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_text_phone(&self) -> Result<PhoneNumber, Error>;
+
+    /// Get the text of the element as a [`PhoneNumber`] if valid and return an
+    /// [`Option`].
+    ///
+    /// [`PhoneNumber`]: struct.PhoneNumber.html
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn text_phone(&self) -> Option<PhoneNumber> {
+        self.try_text_phone().ok()
+    }
+
+    /// Try to get an attribute as a [`PhoneNumber`] and return a [`Result`].
+    ///
+    /// [`PhoneNumber`]: struct.PhoneNumber.html
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_attribute_phone(&self, attr_name: &str) -> Result<PhoneNumber, Error>;
+
+    /// Get an attribute as a [`PhoneNumber`] if present and valid and return an
+    /// [`Option`].
+    ///
+    /// [`PhoneNumber`]: struct.PhoneNumber.html
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn attribute_phone(&self, attr_name: &str) -> Option<PhoneNumber> {
+        self.try_attribute_phone(attr_name).ok()
+    }
+
+    /// Try to get the text of the element as a [`StrictUri`] and return a
+    /// [`Result`].
+    ///
+    /// [`StrictUri`]: struct.StrictUri.html
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_text_uri_strict(&self) -> Result<StrictUri, Error>;
+
+    /// Get the text of the element as a [`StrictUri`] if valid and return an
+    /// [`Option`].
+    ///
+    /// [`StrictUri`]: struct.StrictUri.html
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn text_uri_strict(&self) -> Option<StrictUri> {
+        self.try_text_uri_strict().ok()
+    }
+
+    /// Try to get an attribute as a [`StrictUri`] and return a [`Result`].
+    ///
+    /// [`StrictUri`]: struct.StrictUri.html
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_attribute_uri_strict(&self, attr_name: &str) -> Result<StrictUri, Error>;
+
+    /// Get an attribute as a [`StrictUri`] if present and valid and return an
+    /// [`Option`].
+    ///
+    /// [`StrictUri`]: struct.StrictUri.html
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn attribute_uri_strict(&self, attr_name: &str) -> Option<StrictUri> {
+        self.try_attribute_uri_strict(attr_name).ok()
+    }
+}
+
+impl ContactElementExt for Element {
+    /// Implementation of [`ContactElementExt`] for [`Element`] reports invalid
+    /// values as parsing errors.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::ContactElementExt;
+    ///
+    /// let xml: &'static str = r#"<ContactDetails url="https://example.com/contact">
+    ///         <Email>contact@example.com</Email>
+    ///     </ContactDetails>"#;
+    /// let contact: Element = xml.parse().unwrap();
+    /// let url = contact.try_attribute_uri_strict("url").unwrap();
+    /// assert_eq!("https", url.scheme());
+    /// let email = contact.children().next().unwrap().try_text_email().unwrap();
+    /// assert_eq!("contact@example.com", email.as_str());
+    /// ```
+    ///
+    /// [`ContactElementExt`]: trait.ContactElementExt.html
+    /// [`Element`]: ../minidom/element/struct.Element.html
+    fn try_text_email(&self) -> Result<Email, Error> {
+        self.try_text()
+    }
+
+    fn try_attribute_email(&self, attr_name: &str) -> Result<Email, Error> {
+        self.try_attribute(attr_name)
+    }
+
+    fn try_text_phone(&self) -> Result<PhoneNumber, Error> {
+        self.try_text()
+    }
+
+    fn try_attribute_phone(&self, attr_name: &str) -> Result<PhoneNumber, Error> {
+        self.try_attribute(attr_name)
+    }
+
+    fn try_text_uri_strict(&self) -> Result<StrictUri, Error> {
+        self.try_text()
+    }
+
+    fn try_attribute_uri_strict(&self, attr_name: &str) -> Result<StrictUri, Error> {
+        self.try_attribute(attr_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn emails() {
+        assert!("first.last+tag@sub.example.org".parse::<Email>().is_ok());
+        assert!("first..last@example.org".parse::<Email>().is_err());
+        assert!("first.last@example".parse::<Email>().is_err());
+        assert!("first last@example.org".parse::<Email>().is_err());
+        assert!("@example.org".parse::<Email>().is_err());
+    }
+
+    #[test]
+    fn phone_numbers() {
+        let phone: PhoneNumber = "+33 (0)1 23.45-67-89".parse().unwrap();
+        assert_eq!("+33123456789", phone.normalized());
+        let phone: PhoneNumber = "(0)1 23 45 67 89".parse().unwrap();
+        assert_eq!("0123456789", phone.normalized());
+        assert!("112".parse::<PhoneNumber>().is_ok());
+        assert!("12".parse::<PhoneNumber>().is_err());
+        assert!("+33 1 23 45 67 89 ext 3".parse::<PhoneNumber>().is_err());
+    }
+
+    #[test]
+    fn strict_uris() {
+        assert!("mailto:contact@example.com".parse::<StrictUri>().is_ok());
+        assert!("https://example.com/a%20b?q=1#top"
+            .parse::<StrictUri>()
+            .is_ok());
+        assert!("https:///path".parse::<StrictUri>().is_err());
+        assert!("https://example.com/a b".parse::<StrictUri>().is_err());
+        assert!("https://example.com/%zz".parse::<StrictUri>().is_err());
+        assert!("www.example.com".parse::<StrictUri>().is_err());
+    }
+
+    #[test]
+    fn invalid_email_text() {
+        let xml: &'static str = r#"<Email>contact at example.com</Email>"#;
+        let email: Element = xml.parse().unwrap();
+        let error = email.try_text_email().unwrap_err();
+        assert_eq!(
+            "Failed to parse and convert the text \'contact at example.com\' of element \'Email\'",
+            format!("{}", error)
+        );
+    }
+
+    #[test]
+    fn optional_getters() {
+        let xml: &'static str = r#"<Contact phone="+33 1 23 45 67 89" email="x"><Url>https://example.com</Url></Contact>"#;
+        let contact: Element = xml.parse().unwrap();
+        assert_eq!(
+            "+33123456789",
+            contact.attribute_phone("phone").unwrap().normalized()
+        );
+        assert!(contact.attribute_email("email").is_none());
+        assert!(contact.attribute_uri_strict("url").is_none());
+        let url = contact.children().next().unwrap();
+        assert_eq!("https", url.text_uri_strict().unwrap().scheme());
+        assert!(url.text_phone().is_none());
+        assert!(url.text_email().is_none());
+    }
+}
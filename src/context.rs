@@ -0,0 +1,99 @@
+use crate::Error;
+use minidom::Element;
+
+/// Prepend a segment to the path of an error, merging it with the path of an
+/// error which was already wrapped.
+fn with_path(error: Error, segment: &str) -> Error {
+    match error {
+        Error::WithPath { path, source } => Error::WithPath {
+            path: format!("{} > {}", segment, path),
+            source,
+        },
+        error => Error::WithPath {
+            path: segment.to_owned(),
+            source: Box::new(error),
+        },
+    }
+}
+
+/// Attach the chain of parent elements to the errors of nested extraction
+/// code, like `anyhow::Context` but keeping the typed [`Error`].
+///
+/// Each level wraps the error of the deeper calls in a [`WithPath`] error, the
+/// paths of the successive levels being merged into a single one.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{AttributeElementExt, ElemResultExt, Error, OnlyChildElementExt};
+///
+/// fn line_id(line: &Element) -> Result<u32, Error> {
+///     line.try_attribute("id").ctx(line)
+/// }
+///
+/// fn frame_line_id(frame: &Element) -> Result<u32, Error> {
+///     line_id(frame.try_only_child("Line")?).ctx(frame)
+/// }
+///
+/// let xml: &'static str = r#"<root><frame><Line id="x" /></frame></root>"#;
+/// let root: Element = xml.parse().unwrap();
+/// let frame = root.children().next().unwrap();
+/// let error = frame_line_id(frame).ctx(&root).unwrap_err();
+/// assert_eq!(
+///     "In root > frame > Line: Failed to parse and convert the value 'x' of attribute 'id' in element 'Line'",
+///     error.to_string()
+/// );
+/// ```
+///
+/// [`Error`]: enum.Error.html
+/// [`WithPath`]: enum.Error.html#variant.WithPath
+pub trait ElemResultExt<T> {
+    /// Wrap an error with the name of the element it was raised in.
+    fn ctx(self, element: &Element) -> Result<T, Error>;
+
+    /// Wrap an error with a path segment, like `frame[2]` or the
+    /// [`breadcrumb`] of a cursor.
+    ///
+    /// [`breadcrumb`]: struct.ElementCursor.html#method.breadcrumb
+    fn ctx_path<S>(self, path: S) -> Result<T, Error>
+    where
+        S: AsRef<str>;
+}
+
+impl<T> ElemResultExt<T> for Result<T, Error> {
+    fn ctx(self, element: &Element) -> Result<T, Error> {
+        self.map_err(|error| with_path(error, element.name()))
+    }
+
+    fn ctx_path<S>(self, path: S) -> Result<T, Error>
+    where
+        S: AsRef<str>,
+    {
+        self.map_err(|error| with_path(error, path.as_ref()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn merged_paths() {
+        let root: Element = r#"<root><frame /></root>"#.parse().unwrap();
+        let frame = root.children().next().unwrap();
+        let result: Result<(), Error> = Err(Error::NilElement {
+            element_name: "Line".to_owned(),
+        });
+        let error = result.ctx(frame).ctx(&root).unwrap_err();
+        match &error {
+            Error::WithPath { path, source } => {
+                assert_eq!("root > frame", path);
+                assert!(matches!(**source, Error::NilElement { .. }));
+            }
+            error => panic!("unexpected error {:?}", error),
+        }
+        assert_eq!("In root > frame: Element 'Line' is nil", error.to_string());
+        let result: Result<u32, Error> = Ok(42);
+        assert_eq!(42, result.ctx_path("root").unwrap());
+    }
+}
@@ -0,0 +1,327 @@
+use crate::{suggest::attribute_not_found, AttrValue, AttributeElementExt, Error};
+use minidom::Element;
+
+/// Step of a path: the name of an element and its position among the sibling
+/// elements with the same name, starting at 1.
+pub(crate) fn path_step(name: &str, position: usize) -> String {
+    format!("{}[{}]", name, position)
+}
+
+/// A position in a tree which remembers the way from the root.
+///
+/// `minidom::Element` has no link to its parent, so a cursor keeps the whole
+/// chain of ancestors while navigating down the tree, which makes it possible
+/// to look back at them.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::ElementCursor;
+///
+/// let xml: &'static str = r#"<root><frame version="2"><Line /></frame></root>"#;
+/// let root: Element = xml.parse().unwrap();
+/// let line = ElementCursor::new(&root)
+///     .children()
+///     .next()
+///     .unwrap()
+///     .children()
+///     .next()
+///     .unwrap();
+/// assert_eq!("Line", line.element().name());
+/// assert_eq!(Some("2"), line.parent().unwrap().element().attr("version"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ElementCursor<'a> {
+    /// From the root to the current element (included)
+    stack: Vec<&'a Element>,
+}
+
+impl<'a> ElementCursor<'a> {
+    /// Create a cursor on the root of a tree.
+    pub fn new(root: &'a Element) -> Self {
+        ElementCursor { stack: vec![root] }
+    }
+
+    /// The element the cursor is on.
+    pub fn element(&self) -> &'a Element {
+        self.stack[self.stack.len() - 1]
+    }
+
+    /// The root element of the tree.
+    pub fn root(&self) -> &'a Element {
+        self.stack[0]
+    }
+
+    /// Depth of the current element, the root being at depth 0.
+    pub fn depth(&self) -> usize {
+        self.stack.len() - 1
+    }
+
+    /// Steps from the root to the current element: the name of the root,
+    /// then the [`path_step`] of each descendant.
+    fn steps(&self) -> Vec<String> {
+        let mut steps = vec![self.root().name().to_owned()];
+        for pair in self.stack.windows(2) {
+            let (parent, child) = (pair[0], pair[1]);
+            let position = parent
+                .children()
+                .take_while(|sibling| !std::ptr::eq(*sibling, child))
+                .filter(|sibling| sibling.name() == child.name())
+                .count();
+            steps.push(path_step(child.name(), position + 1));
+        }
+        steps
+    }
+
+    /// Path of the current element, like `/root/frame[1]/Line[2]`, where the
+    /// positions count the siblings with the same name, starting at 1.
+    ///
+    /// This is the format of the paths reported by the transformations,
+    /// repairs and differences of trees.
+    pub fn path(&self) -> String {
+        format!("/{}", self.steps().join("/"))
+    }
+
+    /// Human readable variant of the [`path`] of the current element, like
+    /// `PublicationDelivery > dataObjects[1] > CompositeFrame[3] > frames[1]`,
+    /// for error messages.
+    ///
+    /// [`path`]: #method.path
+    pub fn breadcrumb(&self) -> String {
+        self.steps().join(" > ")
+    }
+
+    /// Call a helper on the current element, an error being wrapped in a
+    /// [`WithPath`] error reporting the [`breadcrumb`] of the element.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::{AttributeElementExt, ElementCursor};
+    ///
+    /// let xml: &'static str = r#"<PublicationDelivery>
+    ///         <CompositeFrame><Line id="1" /></CompositeFrame>
+    ///         <CompositeFrame><Line id="x" /></CompositeFrame>
+    ///     </PublicationDelivery>"#;
+    /// let root: Element = xml.parse().unwrap();
+    /// let line = ElementCursor::new(&root)
+    ///     .children()
+    ///     .nth(1)
+    ///     .unwrap()
+    ///     .children()
+    ///     .next()
+    ///     .unwrap();
+    /// let error = line
+    ///     .try_with(|line| line.try_attribute::<u32>("id"))
+    ///     .unwrap_err();
+    /// assert_eq!(
+    ///     "In PublicationDelivery > CompositeFrame[2] > Line[1]: Failed to parse and convert the value 'x' of attribute 'id' in element 'Line'",
+    ///     error.to_string()
+    /// );
+    /// ```
+    ///
+    /// [`WithPath`]: enum.Error.html#variant.WithPath
+    /// [`breadcrumb`]: #method.breadcrumb
+    pub fn try_with<T, F>(&self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&'a Element) -> Result<T, Error>,
+    {
+        f(self.element()).map_err(|error| Error::WithPath {
+            path: self.breadcrumb(),
+            source: Box::new(error),
+        })
+    }
+
+    /// Move to a child element.
+    ///
+    /// The child should be one of the children of the current element, which
+    /// is not checked.
+    pub fn child(&self, child: &'a Element) -> Self {
+        let mut stack = self.stack.clone();
+        stack.push(child);
+        ElementCursor { stack }
+    }
+
+    /// Cursors on each child element of the current element.
+    pub fn children(&self) -> impl Iterator<Item = ElementCursor<'a>> + '_ {
+        self.element()
+            .children()
+            .map(move |child| self.child(child))
+    }
+
+    /// Cursor on the parent of the current element, if not the root.
+    pub fn parent(&self) -> Option<Self> {
+        if self.stack.len() > 1 {
+            Some(ElementCursor {
+                stack: self.stack[..self.stack.len() - 1].to_vec(),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Cursors on the other children of the parent of the current element, in
+    /// document order. The root has no siblings.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::ElementCursor;
+    ///
+    /// let xml: &'static str = r#"<Line><Name /><Mode /><Color /></Line>"#;
+    /// let root: Element = xml.parse().unwrap();
+    /// let mode = ElementCursor::new(&root).children().nth(1).unwrap();
+    /// let siblings: Vec<&str> = mode.siblings().map(|sibling| sibling.element().name()).collect();
+    /// assert_eq!(vec!["Name", "Color"], siblings);
+    /// assert_eq!("/Line/Mode[1]", mode.path());
+    /// ```
+    pub fn siblings(&self) -> impl Iterator<Item = ElementCursor<'a>> + '_ {
+        let element = self.element();
+        let parent = self.parent();
+        let stack = &self.stack[..self.stack.len() - 1];
+        parent
+            .into_iter()
+            .flat_map(|parent| parent.element().children())
+            .filter(move |sibling| !std::ptr::eq(*sibling, element))
+            .map(move |sibling| {
+                let mut stack = stack.to_vec();
+                stack.push(sibling);
+                ElementCursor { stack }
+            })
+    }
+
+    /// Iterate over the ancestors of the current element, from its parent up
+    /// to the root.
+    pub fn ancestors(&self) -> impl Iterator<Item = &'a Element> + '_ {
+        self.stack[..self.stack.len() - 1].iter().rev().copied()
+    }
+
+    /// Iterate over the current element then all its ancestors, up to the
+    /// root.
+    pub fn ancestors_or_self(&self) -> impl Iterator<Item = &'a Element> + '_ {
+        self.stack.iter().rev().copied()
+    }
+
+    /// Try to get an attribute from the current element or, if missing, from
+    /// the closest ancestor having it, and return a [`Result`].
+    ///
+    /// Returns an [`AttributeNotFound`] error (about the current element) if
+    /// neither the element nor its ancestors have the attribute, and a
+    /// [`ParseError`] (about the element holding the value) if the value
+    /// can't be parsed.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::ElementCursor;
+    ///
+    /// let xml: &'static str = r#"<frame version="1">
+    ///         <Line id="1" />
+    ///         <Line id="2" version="3" />
+    ///     </frame>"#;
+    /// let root: Element = xml.parse().unwrap();
+    /// let versions: Vec<u32> = ElementCursor::new(&root)
+    ///     .children()
+    ///     .map(|line| line.try_attribute_inherited("version").unwrap())
+    ///     .collect();
+    /// assert_eq!(vec![1, 3], versions);
+    /// ```
+    ///
+    /// [`AttributeNotFound`]: enum.Error.html#variant.AttributeNotFound
+    /// [`ParseError`]: enum.Error.html#variant.ParseError
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    pub fn try_attribute_inherited<F>(&self, attr_name: &str) -> Result<F, Error>
+    where
+        F: AttrValue,
+    {
+        match self
+            .ancestors_or_self()
+            .find(|element| element.attr(attr_name).is_some())
+        {
+            Some(element) => element.try_attribute(attr_name),
+            None => Err(attribute_not_found(self.element(), attr_name)),
+        }
+    }
+
+    /// Get an attribute from the current element or its closest ancestor
+    /// having it, if present and valid, and return an [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    pub fn attribute_inherited<F>(&self, attr_name: &str) -> Option<F>
+    where
+        F: AttrValue,
+    {
+        self.try_attribute_inherited(attr_name).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn navigate() {
+        let xml: &'static str = r#"<a><b><c /></b></a>"#;
+        let root: Element = xml.parse().unwrap();
+        let cursor = ElementCursor::new(&root);
+        assert!(cursor.parent().is_none());
+        let c = cursor.children().next().unwrap().children().next().unwrap();
+        assert_eq!(2, c.depth());
+        assert_eq!(
+            vec!["b", "a"],
+            c.ancestors().map(Element::name).collect::<Vec<_>>()
+        );
+        assert_eq!("a", c.root().name());
+        assert_eq!("/a/b[1]/c[1]", c.path());
+        assert_eq!(0, c.siblings().count());
+        assert_eq!(0, cursor.siblings().count());
+        assert_eq!("a > b[1] > c[1]", c.breadcrumb());
+    }
+
+    #[test]
+    fn breadcrumb_positions() {
+        let xml: &'static str = r#"<a><b /><c><d /></c><b><d /><d /></b></a>"#;
+        let root: Element = xml.parse().unwrap();
+        let breadcrumbs: Vec<String> = ElementCursor::new(&root)
+            .children()
+            .flat_map(|child| {
+                let mut cursors = vec![child.clone()];
+                cursors.extend(child.children());
+                cursors
+            })
+            .map(|cursor| cursor.breadcrumb())
+            .collect();
+        assert_eq!(
+            vec![
+                "a > b[1]",
+                "a > c[1]",
+                "a > c[1] > d[1]",
+                "a > b[2]",
+                "a > b[2] > d[1]",
+                "a > b[2] > d[2]"
+            ],
+            breadcrumbs
+        );
+    }
+
+    #[test]
+    fn inherited_attributes() {
+        let xml: &'static str = r#"<a version="x"><b><c /></b></a>"#;
+        let root: Element = xml.parse().unwrap();
+        let c = ElementCursor::new(&root)
+            .children()
+            .next()
+            .unwrap()
+            .children()
+            .next()
+            .unwrap();
+        assert_eq!(Some("x".to_owned()), c.attribute_inherited("version"));
+        let error = c.try_attribute_inherited::<u32>("version").unwrap_err();
+        assert_eq!(
+            "Failed to parse and convert the value 'x' of attribute 'version' in element 'a'",
+            format!("{}", error)
+        );
+        let error = c.try_attribute_inherited::<u32>("id").unwrap_err();
+        assert_eq!(
+            "Failed to find attribute 'id' in element 'c'",
+            format!("{}", error)
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use crate::{AttributeElementExt, Error, TextElementExt, XsFormat};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone};
+use minidom::Element;
+use std::{fmt, str::FromStr};
+
+/// Error returned when a value is not a valid `xs:dateTime` or `xs:date`.
+#[derive(Debug, thiserror::Error)]
+pub enum DateTimeError {
+    /// The date or the time is not valid.
+    #[error("Invalid date or time: {0}")]
+    Invalid(#[from] chrono::ParseError),
+    /// The timezone offset is not valid (`Z` or `+HH:MM`/`-HH:MM` expected).
+    #[error("Invalid timezone offset '{0}'")]
+    InvalidOffset(String),
+    /// The value has no timezone offset while one is required.
+    #[error("Missing timezone offset")]
+    MissingOffset,
+}
+
+/// Split the optional timezone offset (`Z`, `+HH:MM` or `-HH:MM`) at the end
+/// of a value whose part before the offset is at least `min_len` long.
+fn split_offset(value: &str, min_len: usize) -> Result<(&str, Option<FixedOffset>), DateTimeError> {
+    if let Some(value) = value.strip_suffix('Z') {
+        return Ok((value, FixedOffset::east_opt(0)));
+    }
+    let offset_start = value.len().saturating_sub(6);
+    let offset = match value.get(offset_start..) {
+        Some(offset) if offset_start >= min_len && offset.is_ascii() => offset,
+        _ => return Ok((value, None)),
+    };
+    if !(offset.starts_with('+') || offset.starts_with('-')) {
+        return Ok((value, None));
+    }
+    let invalid = || DateTimeError::InvalidOffset(offset.to_owned());
+    let hours: i32 = offset[1..3].parse().map_err(|_| invalid())?;
+    let minutes: i32 = offset[4..].parse().map_err(|_| invalid())?;
+    if &offset[3..4] != ":" || hours > 14 || minutes > 59 {
+        return Err(invalid());
+    }
+    let seconds = (hours * 60 + minutes) * 60;
+    let seconds = if offset.starts_with('-') {
+        -seconds
+    } else {
+        seconds
+    };
+    let offset = FixedOffset::east_opt(seconds).ok_or_else(invalid)?;
+    Ok((&value[..offset_start], Some(offset)))
+}
+
+/// An `xs:dateTime`, with an optional timezone offset.
+fn parse_datetime(value: &str) -> Result<(NaiveDateTime, Option<FixedOffset>), DateTimeError> {
+    let (datetime, offset) = split_offset(value.trim(), "YYYY-MM-DDThh:mm:ss".len())?;
+    let datetime = NaiveDateTime::parse_from_str(datetime, "%Y-%m-%dT%H:%M:%S%.f")?;
+    Ok((datetime, offset))
+}
+
+/// An `xs:dateTime` with a mandatory timezone offset.
+struct XsDateTime(DateTime<FixedOffset>);
+
+impl FromStr for XsDateTime {
+    type Err = DateTimeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (datetime, offset) = parse_datetime(s)?;
+        let offset = offset.ok_or(DateTimeError::MissingOffset)?;
+        Ok(XsDateTime(DateTime::from_naive_utc_and_offset(
+            datetime - offset,
+            offset,
+        )))
+    }
+}
+
+/// An `xs:dateTime` as written, its optional timezone offset being ignored.
+struct XsNaiveDateTime(NaiveDateTime);
+
+impl FromStr for XsNaiveDateTime {
+    type Err = DateTimeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_datetime(s).map(|(datetime, _)| XsNaiveDateTime(datetime))
+    }
+}
+
+/// An `xs:date`, its optional timezone offset being ignored.
+struct XsDate(NaiveDate);
+
+impl FromStr for XsDate {
+    type Err = DateTimeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (date, _) = split_offset(s.trim(), "YYYY-MM-DD".len())?;
+        Ok(XsDate(NaiveDate::parse_from_str(date, "%Y-%m-%d")?))
+    }
+}
+
+/// Dates and date-times are written like `xs:dateTime` and `xs:date`.
+///
+/// ```
+/// use chrono::{NaiveDate, TimeZone, Utc};
+/// use minidom::Element;
+/// use minidom_ext::{MutateElementExt, XsFormat};
+///
+/// let mut line = Element::bare("Line");
+/// let changed = Utc.with_ymd_and_hms(2024, 3, 1, 7, 30, 0).unwrap();
+/// line.set_attr_value("changed", XsFormat(changed));
+/// line.set_attr_value("created", XsFormat(changed.naive_utc()));
+/// line.set_attr_value("validFrom", XsFormat(NaiveDate::from_ymd_opt(2024, 3, 2).unwrap()));
+/// assert_eq!(Some("2024-03-01T07:30:00Z"), line.attr("changed"));
+/// assert_eq!(Some("2024-03-01T07:30:00"), line.attr("created"));
+/// assert_eq!(Some("2024-03-02"), line.attr("validFrom"));
+/// ```
+impl<Tz> fmt::Display for XsFormat<DateTime<Tz>>
+where
+    Tz: TimeZone,
+    Tz::Offset: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+    }
+}
+
+impl fmt::Display for XsFormat<NaiveDateTime> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.format("%Y-%m-%dT%H:%M:%S%.f"))
+    }
+}
+
+impl fmt::Display for XsFormat<NaiveDate> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.format("%Y-%m-%d"))
+    }
+}
+
+/// Parse `xs:dateTime` and `xs:date` values into `chrono` types.
+pub trait DateTimeElementExt {
+    /// Try to get an `xs:dateTime` attribute with a timezone offset (like
+    /// `2024-03-01T08:30:00+01:00` or `2024-03-01T07:30:00Z`) and return a
+    /// [`Result`].
+    ///
+    /// A value without offset is an error, see [`try_attribute_naive_datetime`]
+    /// for local date-times.
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`try_attribute_naive_datetime`]: #tymethod.try_attribute_naive_datetime
+    fn try_attribute_datetime(&self, attr_name: &str) -> Result<DateTime<FixedOffset>, Error>;
+
+    /// Try to get an `xs:dateTime` attribute as written (like
+    /// `2024-03-01T08:30:00`), ignoring its timezone offset if any, and
+    /// return a [`Result`].
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_attribute_naive_datetime(&self, attr_name: &str) -> Result<NaiveDateTime, Error>;
+
+    /// Try to get an `xs:date` attribute (like `2024-03-01`), ignoring its
+    /// timezone offset if any, and return a [`Result`].
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_attribute_date(&self, attr_name: &str) -> Result<NaiveDate, Error>;
+
+    /// Try to parse the text of the element as an `xs:dateTime` with a
+    /// timezone offset and return a [`Result`].
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_text_datetime(&self) -> Result<DateTime<FixedOffset>, Error>;
+
+    /// Try to parse the text of the element as an `xs:dateTime` as written,
+    /// ignoring its timezone offset if any, and return a [`Result`].
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_text_naive_datetime(&self) -> Result<NaiveDateTime, Error>;
+
+    /// Try to parse the text of the element as an `xs:date`, ignoring its
+    /// timezone offset if any, and return a [`Result`].
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_text_date(&self) -> Result<NaiveDate, Error>;
+}
+
+impl DateTimeElementExt for Element {
+    /// Implementation of [`DateTimeElementExt`] for [`Element`] parses the
+    /// lexical representation of XML Schema, the offending value being part
+    /// of the returned [`ParseError`] or [`TextParseError`].
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::DateTimeElementExt;
+    ///
+    /// let xml: &'static str = r#"<Call departureTime="2024-03-01T08:30:00.5+01:00" />"#;
+    /// let call: Element = xml.parse().unwrap();
+    /// let departure = call.try_attribute_datetime("departureTime").unwrap();
+    /// assert_eq!("2024-03-01T08:30:00.500+01:00", departure.to_rfc3339());
+    /// let error = call.try_attribute_date("departureTime").unwrap_err();
+    /// assert_eq!(
+    ///     "Failed to parse and convert the value '2024-03-01T08:30:00.5+01:00' of attribute 'departureTime' in element 'Call'",
+    ///     error.to_string()
+    /// );
+    /// ```
+    ///
+    /// [`DateTimeElementExt`]: trait.DateTimeElementExt.html
+    /// [`Element`]: ../minidom/element/struct.Element.html
+    /// [`ParseError`]: enum.Error.html#variant.ParseError
+    /// [`TextParseError`]: enum.Error.html#variant.TextParseError
+    fn try_attribute_datetime(&self, attr_name: &str) -> Result<DateTime<FixedOffset>, Error> {
+        self.try_attribute::<XsDateTime>(attr_name)
+            .map(|datetime| datetime.0)
+    }
+
+    fn try_attribute_naive_datetime(&self, attr_name: &str) -> Result<NaiveDateTime, Error> {
+        self.try_attribute::<XsNaiveDateTime>(attr_name)
+            .map(|datetime| datetime.0)
+    }
+
+    fn try_attribute_date(&self, attr_name: &str) -> Result<NaiveDate, Error> {
+        self.try_attribute::<XsDate>(attr_name).map(|date| date.0)
+    }
+
+    fn try_text_datetime(&self) -> Result<DateTime<FixedOffset>, Error> {
+        self.try_text::<XsDateTime>().map(|datetime| datetime.0)
+    }
+
+    fn try_text_naive_datetime(&self) -> Result<NaiveDateTime, Error> {
+        self.try_text::<XsNaiveDateTime>()
+            .map(|datetime| datetime.0)
+    }
+
+    fn try_text_date(&self) -> Result<NaiveDate, Error> {
+        self.try_text::<XsDate>().map(|date| date.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn offsets() {
+        let (_, offset) = split_offset("2024-03-01T08:30:00-05:30", 19).unwrap();
+        assert_eq!(FixedOffset::west_opt(5 * 3600 + 1800), offset);
+        let (datetime, offset) = split_offset("2024-03-01T08:30:00", 19).unwrap();
+        assert_eq!(("2024-03-01T08:30:00", None), (datetime, offset));
+        let (date, offset) = split_offset("2024-03-01Z", 10).unwrap();
+        assert_eq!(("2024-03-01", FixedOffset::east_opt(0)), (date, offset));
+        let error = split_offset("2024-03-01+25:00", 10).unwrap_err();
+        assert_eq!("Invalid timezone offset '+25:00'", format!("{}", error));
+    }
+
+    #[test]
+    fn texts() {
+        let xml: &'static str = r#"<root>
+                <a>2024-03-01T08:30:00</a>
+                <b>2024-02-30</b>
+            </root>"#;
+        let root: Element = xml.parse().unwrap();
+        let mut children = root.children();
+        let a = children.next().unwrap();
+        assert_eq!(
+            NaiveDate::from_ymd_opt(2024, 3, 1)
+                .unwrap()
+                .and_hms_opt(8, 30, 0)
+                .unwrap(),
+            a.try_text_naive_datetime().unwrap()
+        );
+        match a.try_text_datetime().unwrap_err() {
+            Error::TextParseError { source, .. } => {
+                assert_eq!("Missing timezone offset", source.to_string())
+            }
+            e => panic!("unexpected error {:?}", e),
+        }
+        assert!(children.next().unwrap().try_text_date().is_err());
+        assert!("2024-03-01T08:30:0é".parse::<XsNaiveDateTime>().is_err());
+    }
+}
@@ -0,0 +1,138 @@
+use crate::{AttributeElementExt, Error, TextElementExt};
+use minidom::Element;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+/// An `xs:decimal`, normalized before being parsed: surrounding whitespaces
+/// and a leading `+` are removed, and a missing digit before or after the
+/// decimal separator (`.5`, `5.`) is accepted.
+struct XsDecimal(Decimal);
+
+impl FromStr for XsDecimal {
+    type Err = rust_decimal::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim();
+        let (sign, value) = match value.strip_prefix('-') {
+            Some(value) => ("-", value),
+            None => ("", value.strip_prefix('+').unwrap_or(value)),
+        };
+        if !value.bytes().all(|b| b.is_ascii_digit() || b == b'.') {
+            // Let `rust_decimal` report the error
+            return Decimal::from_str_exact(s).map(XsDecimal);
+        }
+        let value = value.strip_suffix('.').unwrap_or(value);
+        let normalized = if value.starts_with('.') {
+            format!("{}0{}", sign, value)
+        } else {
+            format!("{}{}", sign, value)
+        };
+        Decimal::from_str_exact(&normalized).map(XsDecimal)
+    }
+}
+
+/// Parse `xs:decimal` values into exact [`Decimal`], to be used for amounts
+/// which must not be approximated by `f64`.
+///
+/// `try_attribute::<Decimal>` also works but is strict about the lexical
+/// form, whereas the methods of this trait accept the variations found in
+/// the wild.
+///
+/// [`Decimal`]: ../rust_decimal/struct.Decimal.html
+pub trait DecimalElementExt {
+    /// Try to get an `xs:decimal` attribute (like `+12.50` or `.5`) and
+    /// return a [`Result`].
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_attribute_decimal(&self, attr_name: &str) -> Result<Decimal, Error>;
+
+    /// Get an `xs:decimal` attribute if present and valid and return an
+    /// [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn attribute_decimal(&self, attr_name: &str) -> Option<Decimal> {
+        self.try_attribute_decimal(attr_name).ok()
+    }
+
+    /// Try to parse the text of the element as an `xs:decimal` and return a
+    /// [`Result`].
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_text_decimal(&self) -> Result<Decimal, Error>;
+
+    /// Parse the text of the element as an `xs:decimal` if valid and return
+    /// an [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn text_decimal(&self) -> Option<Decimal> {
+        self.try_text_decimal().ok()
+    }
+}
+
+impl DecimalElementExt for Element {
+    /// Implementation of [`DecimalElementExt`] for [`Element`] keeps the
+    /// scale of the value as written, without rounding.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::DecimalElementExt;
+    ///
+    /// let xml: &'static str = r#"<Fare amount="+12.50" discount=".1">0.30</Fare>"#;
+    /// let fare: Element = xml.parse().unwrap();
+    /// let amount = fare.try_attribute_decimal("amount").unwrap();
+    /// assert_eq!("12.50", amount.to_string());
+    /// let discount = fare.try_attribute_decimal("discount").unwrap();
+    /// assert_eq!("0.1", discount.to_string());
+    /// let tax = fare.try_text_decimal().unwrap();
+    /// assert_eq!("0.40", (tax + discount).to_string());
+    /// ```
+    ///
+    /// [`DecimalElementExt`]: trait.DecimalElementExt.html
+    /// [`Element`]: ../minidom/element/struct.Element.html
+    fn try_attribute_decimal(&self, attr_name: &str) -> Result<Decimal, Error> {
+        self.try_attribute::<XsDecimal>(attr_name)
+            .map(|decimal| decimal.0)
+    }
+
+    fn try_text_decimal(&self) -> Result<Decimal, Error> {
+        self.try_text::<XsDecimal>().map(|decimal| decimal.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn normalization() {
+        for (value, expected) in &[
+            ("+12.50", "12.50"),
+            (" -.5 ", "-0.5"),
+            ("5.", "5"),
+            ("-0", "0"),
+            ("0.000000000000000000001", "0.000000000000000000001"),
+        ] {
+            assert_eq!(
+                *expected,
+                value.parse::<XsDecimal>().unwrap().0.to_string(),
+                "{}",
+                value
+            );
+        }
+        for value in &["", "+", "+-1", "--1", "1e3", "1,5", "."] {
+            assert!(value.parse::<XsDecimal>().is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn attribute_error() {
+        let element: Element = r#"<Fare amount="12,50" />"#.parse().unwrap();
+        let error = element.try_attribute_decimal("amount").unwrap_err();
+        assert_eq!(
+            "Failed to parse and convert the value '12,50' of attribute 'amount' in element 'Fare'",
+            format!("{}", error)
+        );
+        assert_eq!(None, element.attribute_decimal("missing"));
+    }
+}
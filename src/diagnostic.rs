@@ -0,0 +1,430 @@
+use crate::{Error, ErrorKind, Position};
+use std::{fmt, iter};
+
+impl ErrorKind {
+    /// Stable identifier of the kind of error, like `MDX0003`, to aggregate
+    /// errors without relying on their message.
+    ///
+    /// Codes are never reused nor changed, new kinds of error get new codes.
+    pub fn code(self) -> &'static str {
+        match self {
+            ErrorKind::AttributeNotFound => "MDX0001",
+            ErrorKind::NoChildrenFound => "MDX0002",
+            ErrorKind::NoChildren => "MDX0003",
+            ErrorKind::MultipleChildrenFound => "MDX0004",
+            ErrorKind::MultipleChildren => "MDX0005",
+            ErrorKind::UnexpectedElement => "MDX0006",
+            ErrorKind::ParseError => "MDX0007",
+            ErrorKind::NoParserFound => "MDX0008",
+            ErrorKind::NilElement => "MDX0009",
+            ErrorKind::InvalidSelector => "MDX0010",
+            ErrorKind::UnknownId => "MDX0011",
+            ErrorKind::DanglingReference => "MDX0012",
+            ErrorKind::InvalidDocument => "MDX0013",
+            ErrorKind::UnexpectedLeadingContent => "MDX0014",
+            ErrorKind::ControlCharacter => "MDX0015",
+            ErrorKind::TextParseError => "MDX0016",
+            ErrorKind::UnexpectedExtension => "MDX0017",
+            ErrorKind::DuplicateKey => "MDX0018",
+            ErrorKind::UnmappedUri => "MDX0019",
+            ErrorKind::UnreadableResource => "MDX0020",
+            ErrorKind::ForbiddenChild => "MDX0021",
+            ErrorKind::UnexpectedValue => "MDX0022",
+            ErrorKind::UnknownChild => "MDX0023",
+            ErrorKind::MergeConflict => "MDX0024",
+            ErrorKind::AmbiguousReference => "MDX0025",
+            ErrorKind::Omitted => "MDX0026",
+        }
+    }
+}
+
+impl Error {
+    /// Stable identifier of the error, see [`ErrorKind::code`].
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::OnlyChildElementExt;
+    ///
+    /// let root: Element = r#"<root />"#.parse().unwrap();
+    /// assert_eq!("MDX0003", root.try_only_child("Line").unwrap_err().code());
+    /// ```
+    ///
+    /// [`ErrorKind::code`]: enum.ErrorKind.html#method.code
+    pub fn code(&self) -> &'static str {
+        self.kind().code()
+    }
+
+    /// The error without its [`WithPath`], [`WithPosition`] and
+    /// [`WithSourceName`] wrappers.
+    ///
+    /// [`WithPath`]: #variant.WithPath
+    /// [`WithPosition`]: #variant.WithPosition
+    /// [`WithSourceName`]: #variant.WithSourceName
+    pub fn root_cause(&self) -> &Error {
+        self.unwrapped()
+            .last()
+            .expect("the error itself is always yielded")
+    }
+
+    /// The error, then the errors wrapped by its [`WithPath`],
+    /// [`WithPosition`] and [`WithSourceName`] wrappers, down to its root
+    /// cause.
+    ///
+    /// [`WithPath`]: #variant.WithPath
+    /// [`WithPosition`]: #variant.WithPosition
+    /// [`WithSourceName`]: #variant.WithSourceName
+    fn unwrapped(&self) -> impl Iterator<Item = &Error> {
+        iter::successors(Some(self), |error| match error {
+            Error::WithPath { source, .. }
+            | Error::WithPosition { source, .. }
+            | Error::WithSourceName { source, .. } => Some(source),
+            _ => None,
+        })
+    }
+}
+
+/// A machine-readable report of an [`Error`], to aggregate and chart the
+/// rejection reasons of a data pipeline.
+///
+/// With the `serde` feature, a diagnostic can be serialized (as a struct
+/// named `Diagnostic`, the position being flattened into `line` and
+/// `column`).
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{AttributeElementExt, Diagnostic, ElemResultExt};
+///
+/// let line: Element = r#"<Line id="x" />"#.parse().unwrap();
+/// let error = line.try_attribute::<u32>("id").ctx(&line).unwrap_err();
+/// let diagnostic = Diagnostic::from(&error);
+/// assert_eq!("MDX0007", diagnostic.code);
+/// assert_eq!(Some("Line"), diagnostic.path.as_deref());
+/// assert_eq!(Some("id"), diagnostic.attribute.as_deref());
+/// ```
+///
+/// [`Error`]: enum.Error.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Stable identifier of the error, see [`Error::code`]
+    ///
+    /// [`Error::code`]: enum.Error.html#method.code
+    pub code: &'static str,
+    /// Message of the error, without its path nor its position
+    pub message: String,
+    /// Name of the source of the document, if known
+    pub source_name: Option<String>,
+    /// Path of the element, if known
+    pub path: Option<String>,
+    /// Position of the element in the source of the document, if known
+    pub position: Option<Position>,
+    /// Name of the element the error is about, if any
+    pub element: Option<String>,
+    /// Name of the attribute the error is about, if any
+    pub attribute: Option<String>,
+}
+
+impl From<&Error> for Diagnostic {
+    fn from(error: &Error) -> Self {
+        // The outermost wrappers are the most precise ones
+        let source_name = error.unwrapped().find_map(|error| match error {
+            Error::WithSourceName { source_name, .. } => Some(source_name.clone()),
+            _ => None,
+        });
+        let path = error.unwrapped().find_map(|error| match error {
+            Error::WithPath { path, .. } => Some(path.clone()),
+            _ => None,
+        });
+        let position = error.unwrapped().find_map(|error| match error {
+            Error::WithPosition { position, .. } => Some(*position),
+            _ => None,
+        });
+        let cause = error.root_cause();
+        let (element, attribute) = match cause {
+            Error::AttributeNotFound {
+                element_name,
+                attribute_name,
+                ..
+            }
+            | Error::ParseError {
+                element_name,
+                attribute_name,
+                ..
+            }
+            | Error::UnexpectedValue {
+                element_name,
+                attribute_name,
+                ..
+            } => (Some(element_name), Some(attribute_name)),
+            Error::MergeConflict {
+                element_name,
+                attribute_name,
+                ..
+            } => (Some(element_name), attribute_name.as_ref()),
+            Error::NoChildrenFound { element_name }
+            | Error::NoChildren { element_name, .. }
+            | Error::MultipleChildrenFound { element_name, .. }
+            | Error::MultipleChildren { element_name, .. }
+            | Error::NoParserFound { element_name, .. }
+            | Error::NilElement { element_name }
+            | Error::TextParseError { element_name, .. }
+            | Error::UnexpectedExtension { element_name, .. }
+            | Error::DuplicateKey { element_name, .. }
+            | Error::ForbiddenChild { element_name, .. }
+            | Error::UnknownChild { element_name, .. }
+            | Error::DanglingReference {
+                from_element: element_name,
+                ..
+            }
+            | Error::AmbiguousReference {
+                from_element: element_name,
+                ..
+            }
+            | Error::UnexpectedElement {
+                found: element_name,
+                ..
+            } => (Some(element_name), None),
+            _ => (None, None),
+        };
+        Diagnostic {
+            code: cause.code(),
+            message: cause.to_string(),
+            source_name,
+            path,
+            position,
+            element: element.cloned(),
+            attribute: attribute.cloned(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}]", self.code)?;
+        if let Some(source_name) = &self.source_name {
+            write!(f, " {}:", source_name)?;
+        }
+        if let Some(position) = &self.position {
+            write!(f, " {}:", position)?;
+        }
+        if let Some(path) = &self.path {
+            write!(f, " {}:", path)?;
+        }
+        write!(f, " {}", self.message)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Diagnostic {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("Diagnostic", 8)?;
+        state.serialize_field("code", self.code)?;
+        state.serialize_field("message", &self.message)?;
+        state.serialize_field("source_name", &self.source_name)?;
+        state.serialize_field("path", &self.path)?;
+        state.serialize_field("line", &self.position.map(|p| p.line))?;
+        state.serialize_field("column", &self.position.map(|p| p.column))?;
+        state.serialize_field("element", &self.element)?;
+        state.serialize_field("attribute", &self.attribute)?;
+        state.end()
+    }
+}
+
+/// A flat report of an [`Error`], to emit validation results as JSON lines
+/// (serializable with the `serde` feature, as a struct named `ErrorReport`).
+///
+/// The value is the offending value of the document, if the error is about
+/// one (an unparsable attribute or text, an unknown identifier, a dangling
+/// reference or a duplicate key).
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{AttributeElementExt, ElemResultExt, ErrorReport};
+///
+/// let line: Element = r#"<Line id="x" />"#.parse().unwrap();
+/// let error = line.try_attribute::<u32>("id").ctx(&line).unwrap_err();
+/// let report = ErrorReport::from(&error);
+/// assert_eq!(Some("Line"), report.path.as_deref());
+/// assert_eq!("MDX0007", report.code);
+/// assert_eq!(Some("x"), report.value.as_deref());
+/// ```
+///
+/// [`Error`]: enum.Error.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorReport {
+    /// Path of the element, if known
+    pub path: Option<String>,
+    /// Stable identifier of the error, see [`Error::code`]
+    ///
+    /// [`Error::code`]: enum.Error.html#method.code
+    pub code: &'static str,
+    /// Message of the error, without its path nor its position
+    pub message: String,
+    /// The offending value, if any
+    pub value: Option<String>,
+}
+
+impl From<&Error> for ErrorReport {
+    fn from(error: &Error) -> Self {
+        let value = match error.root_cause() {
+            Error::ParseError { value, .. }
+            | Error::UnexpectedValue { value, .. }
+            | Error::MergeConflict {
+                other_value: value, ..
+            }
+            | Error::TextParseError { value, .. }
+            | Error::UnknownId { id: value, .. }
+            | Error::DanglingReference {
+                ref_value: value, ..
+            }
+            | Error::AmbiguousReference {
+                ref_value: value, ..
+            }
+            | Error::DuplicateKey { key: value, .. } => Some(value.clone()),
+            _ => None,
+        };
+        let diagnostic = Diagnostic::from(error);
+        ErrorReport {
+            path: diagnostic.path,
+            code: diagnostic.code,
+            message: diagnostic.message,
+            value,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ErrorReport {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("ErrorReport", 4)?;
+        state.serialize_field("path", &self.path)?;
+        state.serialize_field("code", self.code)?;
+        state.serialize_field("message", &self.message)?;
+        state.serialize_field("value", &self.value)?;
+        state.end()
+    }
+}
+
+/// With the `miette` feature, errors can be rendered as reports, the code
+/// being the [stable code] of the error and the help the suggested or
+/// available names. Errors located in a [`PositionedDocument`] are labelled at
+/// the start tag of the element, to be rendered with the source of the
+/// document (see `miette::Report::with_source_code`).
+///
+/// [`PositionedDocument`]: struct.PositionedDocument.html
+/// [stable code]: enum.Error.html#method.code
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for Error {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        Some(Box::new(Error::code(self)))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        let (suggestion, available) = match self.root_cause() {
+            Error::AttributeNotFound {
+                suggestion,
+                available,
+                ..
+            }
+            | Error::NoChildren {
+                suggestion,
+                available,
+                ..
+            } => (suggestion, available),
+            _ => return None,
+        };
+        match suggestion {
+            Some(suggestion) => Some(Box::new(format!("did you mean '{}'?", suggestion))),
+            None if !available.is_empty() => {
+                Some(Box::new(format!("available: {}", available.join(", "))))
+            }
+            None => None,
+        }
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        let diagnostic = Diagnostic::from(self);
+        let position = diagnostic.position?;
+        let length = diagnostic.element.as_ref().map_or(1, |name| name.len() + 1);
+        let label = match &diagnostic.attribute {
+            Some(attribute) => format!("attribute '{}' of this element", attribute),
+            None => "this element".to_owned(),
+        };
+        Some(Box::new(std::iter::once(
+            miette::LabeledSpan::new_primary_with_span(Some(label), (position.offset, length)),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ElemResultExt, OnlyChildElementExt, PositionedDocument};
+    use pretty_assertions::assert_eq;
+
+    fn located_error() -> Error {
+        let document = PositionedDocument::parse("<root>\n<frame/></root>").unwrap();
+        let frame = document.root().children().next().unwrap();
+        document
+            .try_with(frame, |frame| frame.try_only_child("Line").ctx(frame))
+            .unwrap_err()
+    }
+
+    #[test]
+    fn diagnostic() {
+        let diagnostic = Diagnostic::from(&located_error());
+        assert_eq!("MDX0003", diagnostic.code);
+        assert_eq!(Some("frame"), diagnostic.element.as_deref());
+        assert_eq!(None, diagnostic.attribute);
+        assert_eq!(
+            "[MDX0003] line 2, column 1: frame: No children with name 'Line' in Element 'frame'",
+            diagnostic.to_string()
+        );
+    }
+
+    #[cfg(feature = "miette")]
+    #[test]
+    fn miette_report() {
+        use miette::Diagnostic as _;
+
+        let error = located_error();
+        assert_eq!(
+            "MDX0003",
+            miette::Diagnostic::code(&error).unwrap().to_string()
+        );
+        let labels: Vec<miette::LabeledSpan> = error.labels().unwrap().collect();
+        assert_eq!(7, labels[0].offset());
+        assert_eq!(6, labels[0].len());
+        assert_eq!(Some("this element"), labels[0].label());
+        let root: minidom::Element = "<root><Lines /></root>".parse().unwrap();
+        let error = root.try_only_child("Line").unwrap_err();
+        assert_eq!("did you mean 'Lines'?", error.help().unwrap().to_string());
+        assert!(error.labels().is_none());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize() {
+        let diagnostic = Diagnostic::from(&located_error());
+        assert_eq!(
+            r#"{"code":"MDX0003","message":"No children with name 'Line' in Element 'frame'","source_name":null,"path":"frame","line":2,"column":1,"element":"frame","attribute":null}"#,
+            serde_json::to_string(&diagnostic).unwrap()
+        );
+        let error = located_error();
+        let report = ErrorReport::from(&error);
+        assert_eq!(None, report.value);
+        assert_eq!(
+            r#"{"path":"frame","code":"MDX0003","message":"No children with name 'Line' in Element 'frame'","value":null}"#,
+            serde_json::to_string(&report).unwrap()
+        );
+    }
+}
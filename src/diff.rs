@@ -0,0 +1,443 @@
+use crate::cursor::path_step;
+use minidom::Element;
+use std::fmt;
+
+/// How [`diff_with`] pairs the children of two elements.
+///
+/// [`diff_with`]: fn.diff_with.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffOptions {
+    ordered: bool,
+    id_attributes: Vec<String>,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        DiffOptions {
+            ordered: true,
+            id_attributes: Vec::new(),
+        }
+    }
+}
+
+impl DiffOptions {
+    /// Create options pairing the children with the same name by position.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ignore the order of the children: the children identical in both
+    /// trees are paired first, wherever they are, and the others by position.
+    pub fn unordered(mut self) -> Self {
+        self.ordered = false;
+        self
+    }
+
+    /// Pair the children with the same name by the value of the attribute
+    /// (like `id`), the first declared attribute present on a child being
+    /// used. Children without any of these attributes are paired as usual.
+    pub fn with_id_attribute(mut self, attribute: &str) -> Self {
+        self.id_attributes.push(attribute.to_owned());
+        self
+    }
+
+    /// The identifier attribute of an element and its value, if any.
+    fn key<'a>(&'a self, element: &'a Element) -> Option<(&'a str, &'a str)> {
+        self.id_attributes.iter().find_map(|attribute| {
+            element
+                .attr(attribute)
+                .map(|value| (attribute.as_str(), value))
+        })
+    }
+}
+
+/// A difference between two trees, see [`diff`].
+///
+/// Paths are like `/root/Line[2]`, the positions counting the siblings with
+/// the same name (starting at 1), or like `/root/Line[@id='L1']` for the
+/// children paired by identifier. The path of a renamed or removed element
+/// and of a removed attribute is in the old tree, the others are in the new
+/// tree.
+///
+/// [`diff`]: fn.diff.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Difference {
+    /// The elements have different names
+    NameChanged {
+        /// Path of the element
+        path: String,
+        /// Old name
+        old: String,
+        /// New name
+        new: String,
+    },
+    /// An attribute was added
+    AttributeAdded {
+        /// Path of the element
+        path: String,
+        /// Name of the attribute
+        name: String,
+        /// New value
+        value: String,
+    },
+    /// An attribute was removed
+    AttributeRemoved {
+        /// Path of the element
+        path: String,
+        /// Name of the attribute
+        name: String,
+        /// Old value
+        value: String,
+    },
+    /// The value of an attribute changed
+    AttributeChanged {
+        /// Path of the element
+        path: String,
+        /// Name of the attribute
+        name: String,
+        /// Old value
+        old: String,
+        /// New value
+        new: String,
+    },
+    /// The text of the element (without leading and trailing whitespaces)
+    /// changed
+    TextChanged {
+        /// Path of the element
+        path: String,
+        /// Old text
+        old: String,
+        /// New text
+        new: String,
+    },
+    /// A child was added
+    ChildAdded {
+        /// Path of the child
+        path: String,
+    },
+    /// A child was removed
+    ChildRemoved {
+        /// Path of the child
+        path: String,
+    },
+}
+
+impl Difference {
+    /// Path of the element the difference is about.
+    pub fn path(&self) -> &str {
+        match self {
+            Difference::NameChanged { path, .. }
+            | Difference::AttributeAdded { path, .. }
+            | Difference::AttributeRemoved { path, .. }
+            | Difference::AttributeChanged { path, .. }
+            | Difference::TextChanged { path, .. }
+            | Difference::ChildAdded { path }
+            | Difference::ChildRemoved { path } => path,
+        }
+    }
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Difference::NameChanged { path, old, new } => {
+                write!(f, "{}: name changed from '{}' to '{}'", path, old, new)
+            }
+            Difference::AttributeAdded { path, name, value } => {
+                write!(f, "{}: attribute '{}' added ('{}')", path, name, value)
+            }
+            Difference::AttributeRemoved { path, name, value } => {
+                write!(f, "{}: attribute '{}' removed ('{}')", path, name, value)
+            }
+            Difference::AttributeChanged {
+                path,
+                name,
+                old,
+                new,
+            } => write!(
+                f,
+                "{}: attribute '{}' changed from '{}' to '{}'",
+                path, name, old, new
+            ),
+            Difference::TextChanged { path, old, new } => {
+                write!(f, "{}: text changed from '{}' to '{}'", path, old, new)
+            }
+            Difference::ChildAdded { path } => write!(f, "{}: added", path),
+            Difference::ChildRemoved { path } => write!(f, "{}: removed", path),
+        }
+    }
+}
+
+/// A child of one of the trees, with its path segment.
+struct Child<'a> {
+    element: &'a Element,
+    segment: String,
+}
+
+/// Children of both trees with the same name, paired or not.
+#[derive(Default)]
+struct Pairing<'a> {
+    pairs: Vec<(Child<'a>, Child<'a>)>,
+    removed: Vec<Child<'a>>,
+    added: Vec<Child<'a>>,
+}
+
+fn own_text(element: &Element) -> String {
+    element.texts().collect::<String>().trim().to_owned()
+}
+
+struct Differ<'o> {
+    options: &'o DiffOptions,
+    differences: Vec<Difference>,
+}
+
+impl Differ<'_> {
+    fn children<'a>(&self, element: &'a Element, name: &str) -> Vec<Child<'a>> {
+        element
+            .children()
+            .filter(|child| child.name() == name)
+            .enumerate()
+            .map(|(index, child)| Child {
+                element: child,
+                segment: match self.options.key(child) {
+                    Some((attribute, value)) => {
+                        format!("{}[@{}='{}']", name, attribute, value)
+                    }
+                    None => path_step(name, index + 1),
+                },
+            })
+            .collect()
+    }
+
+    fn pair<'a>(&self, old: Vec<Child<'a>>, new: Vec<Child<'a>>) -> Pairing<'a> {
+        let mut pairing = Pairing::default();
+        let mut new: Vec<Option<Child<'a>>> = new.into_iter().map(Some).collect();
+        let mut unpaired = Vec::new();
+        for old in old {
+            let key = self.options.key(old.element);
+            let found = new.iter().position(|new| match (new, key) {
+                (Some(new), Some(key)) => self.options.key(new.element) == Some(key),
+                (Some(new), None) => {
+                    !self.options.ordered
+                        && self.options.key(new.element).is_none()
+                        && new.element == old.element
+                }
+                (None, _) => false,
+            });
+            match (found, key) {
+                (Some(position), _) => {
+                    let new = new[position].take().expect("unpaired child");
+                    pairing.pairs.push((old, new));
+                }
+                (None, Some(_)) => pairing.removed.push(old),
+                (None, None) => unpaired.push(old),
+            }
+        }
+        let (mut added, new): (Vec<Child<'a>>, Vec<Child<'a>>) = new
+            .into_iter()
+            .flatten()
+            .partition(|new| self.options.key(new.element).is_some());
+        let mut new = new.into_iter();
+        for old in unpaired {
+            match new.next() {
+                Some(new) => pairing.pairs.push((old, new)),
+                None => pairing.removed.push(old),
+            }
+        }
+        added.extend(new);
+        pairing.added = added;
+        pairing
+    }
+
+    fn compare(&mut self, old: &Element, new: &Element, old_path: &str, new_path: &str) {
+        if old.name() != new.name() {
+            self.differences.push(Difference::NameChanged {
+                path: old_path.to_owned(),
+                old: old.name().to_owned(),
+                new: new.name().to_owned(),
+            });
+            return;
+        }
+        for (name, value) in old.attrs() {
+            match new.attr(name) {
+                None => self.differences.push(Difference::AttributeRemoved {
+                    path: old_path.to_owned(),
+                    name: name.to_owned(),
+                    value: value.to_owned(),
+                }),
+                Some(new_value) if new_value != value => {
+                    self.differences.push(Difference::AttributeChanged {
+                        path: new_path.to_owned(),
+                        name: name.to_owned(),
+                        old: value.to_owned(),
+                        new: new_value.to_owned(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        for (name, value) in new.attrs() {
+            if old.attr(name).is_none() {
+                self.differences.push(Difference::AttributeAdded {
+                    path: new_path.to_owned(),
+                    name: name.to_owned(),
+                    value: value.to_owned(),
+                });
+            }
+        }
+        let (old_text, new_text) = (own_text(old), own_text(new));
+        if old_text != new_text {
+            self.differences.push(Difference::TextChanged {
+                path: new_path.to_owned(),
+                old: old_text,
+                new: new_text,
+            });
+        }
+        let mut names: Vec<&str> = Vec::new();
+        for name in old.children().chain(new.children()).map(Element::name) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        for name in names {
+            let pairing = self.pair(self.children(old, name), self.children(new, name));
+            for removed in pairing.removed {
+                self.differences.push(Difference::ChildRemoved {
+                    path: format!("{}/{}", old_path, removed.segment),
+                });
+            }
+            for (old_child, new_child) in pairing.pairs {
+                if old_child.element != new_child.element {
+                    self.compare(
+                        old_child.element,
+                        new_child.element,
+                        &format!("{}/{}", old_path, old_child.segment),
+                        &format!("{}/{}", new_path, new_child.segment),
+                    );
+                }
+            }
+            for added in pairing.added {
+                self.differences.push(Difference::ChildAdded {
+                    path: format!("{}/{}", new_path, added.segment),
+                });
+            }
+        }
+    }
+}
+
+/// Compare two trees and return their differences: added, removed and
+/// changed attributes, changed texts, and added and removed children.
+///
+/// The children with the same name are paired by position, see [`diff_with`]
+/// to ignore their order or to pair them by identifier. Paired children are
+/// compared recursively. Namespaces, comments and the whitespaces around
+/// the texts are ignored.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::diff;
+///
+/// let old: Element = r#"<Line id="1" name="A"><Stop>S1</Stop><Stop>S2</Stop></Line>"#
+///     .parse()
+///     .unwrap();
+/// let new: Element = r#"<Line id="1" name="B" color="red"><Stop>S1</Stop></Line>"#
+///     .parse()
+///     .unwrap();
+/// let differences: Vec<String> = diff(&old, &new).iter().map(ToString::to_string).collect();
+/// assert_eq!(
+///     vec![
+///         "/Line: attribute 'name' changed from 'A' to 'B'",
+///         "/Line: attribute 'color' added ('red')",
+///         "/Line/Stop[2]: removed",
+///     ],
+///     differences
+/// );
+/// ```
+///
+/// [`diff_with`]: fn.diff_with.html
+pub fn diff(old: &Element, new: &Element) -> Vec<Difference> {
+    diff_with(old, new, &DiffOptions::new())
+}
+
+/// Compare two trees like [`diff`], pairing the children as configured by
+/// `options`.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{diff_with, DiffOptions};
+///
+/// let old: Element = r#"<lines><Line id="1" /><Line id="2" name="B" /></lines>"#
+///     .parse()
+///     .unwrap();
+/// let new: Element = r#"<lines><Line id="2" name="C" /><Line id="3" /></lines>"#
+///     .parse()
+///     .unwrap();
+/// let options = DiffOptions::new().with_id_attribute("id");
+/// let differences: Vec<String> = diff_with(&old, &new, &options)
+///     .iter()
+///     .map(ToString::to_string)
+///     .collect();
+/// assert_eq!(
+///     vec![
+///         "/lines/Line[@id='1']: removed",
+///         "/lines/Line[@id='2']: attribute 'name' changed from 'B' to 'C'",
+///         "/lines/Line[@id='3']: added",
+///     ],
+///     differences
+/// );
+/// ```
+///
+/// [`diff`]: fn.diff.html
+pub fn diff_with(old: &Element, new: &Element, options: &DiffOptions) -> Vec<Difference> {
+    let mut differ = Differ {
+        options,
+        differences: Vec::new(),
+    };
+    differ.compare(
+        old,
+        new,
+        &format!("/{}", old.name()),
+        &format!("/{}", new.name()),
+    );
+    differ.differences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn differences(old: &str, new: &str, options: &DiffOptions) -> Vec<String> {
+        let (old, new): (Element, Element) = (old.parse().unwrap(), new.parse().unwrap());
+        diff_with(&old, &new, options)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn ordering() {
+        let old = r#"<root><a>1</a><a>2</a><b /></root>"#;
+        let new = r#"<root><b /><a>2</a><a> 1 </a></root>"#;
+        assert_eq!(
+            vec![
+                "/root/a[1]: text changed from '1' to '2'",
+                "/root/a[2]: text changed from '2' to '1'",
+            ],
+            differences(old, new, &DiffOptions::new())
+        );
+        assert!(differences(old, new, &DiffOptions::new().unordered()).is_empty());
+        assert_eq!(
+            vec!["/root/a[1]: removed"],
+            differences(
+                old,
+                "<root><b /><a>2</a></root>",
+                &DiffOptions::new().unordered()
+            )
+        );
+        assert_eq!(
+            vec!["/root: name changed from 'root' to 'other'"],
+            differences(old, "<other />", &DiffOptions::new())
+        );
+    }
+}
@@ -0,0 +1,73 @@
+use crate::canonical::write_canonical;
+use minidom::Element;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+/// Hash algorithm used by [`digest`].
+///
+/// [`digest`]: fn.digest.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DigestAlgorithm {
+    /// SHA-256, 32 bytes digest
+    Sha256,
+    /// SHA-384, 48 bytes digest
+    Sha384,
+    /// SHA-512, 64 bytes digest
+    Sha512,
+}
+
+/// Compute the hash of the canonical serialization of a subtree.
+///
+/// The canonical serialization is the one of
+/// [`CanonicalElementExt::canonicalize`], which only depends on the content
+/// of the subtree: the order of the attributes, the prefixes declared but not
+/// used by the subtree, the comments, the indentation (the texts made only
+/// of whitespaces) and the way empty elements are written do not change the
+/// digest. This makes it possible to embed integrity checksums of
+/// individual frames of a delivery and to verify them after extraction.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{digest, DigestAlgorithm, HexBinary};
+///
+/// let xml: &'static str = r#"<delivery xmlns:unused="urn:unused">
+///         <frame version="1" id="f1">
+///             <Line/>
+///         </frame>
+///     </delivery>"#;
+/// let delivery: Element = xml.parse().unwrap();
+/// let frame = delivery.children().next().unwrap();
+/// let other: Element = r#"<frame id="f1" version="1"><Line></Line></frame>"#.parse().unwrap();
+/// let checksum = digest(frame, DigestAlgorithm::Sha256);
+/// assert_eq!(32, checksum.len());
+/// assert_eq!(checksum, digest(&other, DigestAlgorithm::Sha256));
+/// println!("{}", HexBinary(checksum));
+/// ```
+///
+/// [`CanonicalElementExt::canonicalize`]: trait.CanonicalElementExt.html#tymethod.canonicalize
+pub fn digest(element: &Element, algorithm: DigestAlgorithm) -> Vec<u8> {
+    let mut canonical = String::new();
+    write_canonical(element, true, &mut canonical);
+    match algorithm {
+        DigestAlgorithm::Sha256 => Sha256::digest(canonical.as_bytes()).to_vec(),
+        DigestAlgorithm::Sha384 => Sha384::digest(canonical.as_bytes()).to_vec(),
+        DigestAlgorithm::Sha512 => Sha512::digest(canonical.as_bytes()).to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HexBinary;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn known_digest() {
+        let element: Element = r#"<a/>"#.parse().unwrap();
+        // sha256sum of "<a></a>"
+        assert_eq!(
+            "A812A69BA6858A54CEFDB2FC3882E7CEB7D66AA1ED792562082872DD6ED4F921",
+            HexBinary(digest(&element, DigestAlgorithm::Sha256)).to_string()
+        );
+        assert_eq!(64, digest(&element, DigestAlgorithm::Sha512).len());
+    }
+}
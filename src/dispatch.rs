@@ -0,0 +1,307 @@
+use crate::{
+    materialize::detach, namespace::split_qname, AttributeElementExt, Error, ExtensionPolicy,
+    FromElement, XSI_NS,
+};
+use minidom::Element;
+use std::collections::HashMap;
+
+type Parser<T> = Box<dyn Fn(&Element) -> Result<T, Error>>;
+
+/// Select the parser of an element depending on its `xsi:type` or its name.
+///
+/// This is useful for schemas relying on abstract elements and substitution
+/// groups, where a child can be one of many concrete types. Parsers are
+/// registered for the local part of an `xsi:type` value (the prefix is
+/// ignored) or for an element name. The `xsi:type` has precedence over the
+/// element's name.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{AttributeElementExt, Dispatcher, Error, FromElement};
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Place {
+///     Stop(String),
+///     Parking(String),
+/// }
+///
+/// struct StopPlace(String);
+/// impl FromElement for StopPlace {
+///     fn from_element(element: &Element) -> Result<Self, Error> {
+///         element.try_attribute("id").map(StopPlace)
+///     }
+/// }
+/// impl From<StopPlace> for Place {
+///     fn from(stop: StopPlace) -> Self {
+///         Place::Stop(stop.0)
+///     }
+/// }
+///
+/// let dispatcher = Dispatcher::new()
+///     .with_type::<StopPlace>("StopPlace_VersionStructure")
+///     .with_name_fn("Parking", |e| e.try_attribute("id").map(Place::Parking));
+///
+/// let xml: &'static str = r#"<places xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+///         <Place id="1" xsi:type="StopPlace_VersionStructure" />
+///         <Parking id="2" />
+///     </places>"#;
+/// let root: Element = xml.parse().unwrap();
+/// let places = dispatcher.dispatch_children(&root).unwrap();
+/// assert_eq!(
+///     vec![Place::Stop("1".to_owned()), Place::Parking("2".to_owned())],
+///     places
+/// );
+/// ```
+pub struct Dispatcher<T> {
+    by_type: HashMap<String, Parser<T>>,
+    by_name: HashMap<String, Parser<T>>,
+    extensions: HashMap<String, ExtensionPolicy>,
+}
+
+impl<T> Default for Dispatcher<T> {
+    fn default() -> Self {
+        Dispatcher {
+            by_type: HashMap::new(),
+            by_name: HashMap::new(),
+            extensions: HashMap::new(),
+        }
+    }
+}
+
+impl<T> Dispatcher<T> {
+    /// Create a dispatcher without any parser.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the [`FromElement`] implementation of `U` for elements with
+    /// the given `xsi:type`.
+    ///
+    /// [`FromElement`]: trait.FromElement.html
+    pub fn with_type<U>(self, xsi_type: &str) -> Self
+    where
+        U: FromElement + Into<T>,
+    {
+        self.with_type_fn(xsi_type, |element| U::from_element(element).map(Into::into))
+    }
+
+    /// Register a parser for elements with the given `xsi:type`.
+    pub fn with_type_fn<F>(mut self, xsi_type: &str, parser: F) -> Self
+    where
+        F: Fn(&Element) -> Result<T, Error> + 'static,
+    {
+        self.by_type.insert(xsi_type.to_owned(), Box::new(parser));
+        self
+    }
+
+    /// Register the [`FromElement`] implementation of `U` for elements with
+    /// the given name.
+    ///
+    /// [`FromElement`]: trait.FromElement.html
+    pub fn with_name<U>(self, name: &str) -> Self
+    where
+        U: FromElement + Into<T>,
+    {
+        self.with_name_fn(name, |element| U::from_element(element).map(Into::into))
+    }
+
+    /// Register a parser for elements with the given name.
+    pub fn with_name_fn<F>(mut self, name: &str, parser: F) -> Self
+    where
+        F: Fn(&Element) -> Result<T, Error> + 'static,
+    {
+        self.by_name.insert(name.to_owned(), Box::new(parser));
+        self
+    }
+
+    /// Handle the elements with the given name (like `Extensions`) as
+    /// extension points:
+    /// - [`Preserve`]: they are collected verbatim by
+    ///   [`dispatch_children_with_extensions`], without being parsed
+    /// - [`Ignore`]: they are skipped by [`dispatch_children`]
+    /// - [`Strict`]: they are rejected with an [`UnexpectedExtension`] error
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::{AttributeElementExt, Dispatcher, ExtensionPolicy};
+    ///
+    /// let xml: &'static str = r#"<places>
+    ///         <Parking id="1" />
+    ///         <Extensions><Color>red</Color></Extensions>
+    ///     </places>"#;
+    /// let root: Element = xml.parse().unwrap();
+    /// let dispatcher = Dispatcher::new()
+    ///     .with_name_fn("Parking", |e| e.try_attribute::<String>("id"))
+    ///     .with_extension("Extensions", ExtensionPolicy::Ignore);
+    /// assert_eq!(vec!["1"], dispatcher.dispatch_children(&root).unwrap());
+    /// let dispatcher = dispatcher.with_extension("Extensions", ExtensionPolicy::Strict);
+    /// assert!(dispatcher.dispatch_children(&root).is_err());
+    /// ```
+    ///
+    /// [`Preserve`]: enum.ExtensionPolicy.html#variant.Preserve
+    /// [`Ignore`]: enum.ExtensionPolicy.html#variant.Ignore
+    /// [`Strict`]: enum.ExtensionPolicy.html#variant.Strict
+    /// [`dispatch_children`]: #method.dispatch_children
+    /// [`dispatch_children_with_extensions`]: #method.dispatch_children_with_extensions
+    /// [`UnexpectedExtension`]: enum.Error.html#variant.UnexpectedExtension
+    pub fn with_extension(mut self, name: &str, policy: ExtensionPolicy) -> Self {
+        self.extensions.insert(name.to_owned(), policy);
+        self
+    }
+
+    /// Check if a parser is registered for the element.
+    pub fn handles(&self, element: &Element) -> bool {
+        self.parser(element).is_ok()
+    }
+
+    fn parser(&self, element: &Element) -> Result<&Parser<T>, Error> {
+        // Most elements have no prefixed `type` attribute, which is checked
+        // before resolving any namespace
+        let has_type = element.attrs().any(|(name, _)| name.ends_with(":type"));
+        let xsi_type: Option<String> = if has_type {
+            element.attribute_ns(XSI_NS, "type")
+        } else {
+            None
+        };
+        if let Some(xsi_type) = &xsi_type {
+            let (_, local_type) = split_qname(xsi_type);
+            if let Some(parser) = self.by_type.get(local_type) {
+                return Ok(parser);
+            }
+        }
+        self.by_name
+            .get(element.name())
+            .ok_or_else(|| Error::NoParserFound {
+                element_name: element.name().to_owned(),
+                type_name: xsi_type.unwrap_or_else(|| element.name().to_owned()),
+            })
+    }
+
+    /// Parse the element with the parser registered for its `xsi:type` or its
+    /// name, or return a [`NoParserFound`] error.
+    ///
+    /// [`NoParserFound`]: enum.Error.html#variant.NoParserFound
+    pub fn dispatch(&self, element: &Element) -> Result<T, Error> {
+        self.parser(element).and_then(|parser| parser(element))
+    }
+
+    /// Parse all the children of the element, failing on the first child
+    /// without any registered parser or rejected as an extension (see
+    /// [`with_extension`]).
+    ///
+    /// The preserved extensions are skipped, see
+    /// [`dispatch_children_with_extensions`] to collect them.
+    ///
+    /// [`dispatch_children_with_extensions`]: #method.dispatch_children_with_extensions
+    /// [`with_extension`]: #method.with_extension
+    pub fn dispatch_children(&self, element: &Element) -> Result<Vec<T>, Error> {
+        self.dispatch_children_with_extensions(element)
+            .map(|(values, _)| values)
+    }
+
+    /// Parse all the children of the element like [`dispatch_children`],
+    /// and collect the extensions with the [`Preserve`] policy verbatim, as
+    /// detached elements, in document order.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::{AttributeElementExt, Dispatcher, ExtensionPolicy};
+    ///
+    /// let xml: &'static str = r#"<places>
+    ///         <Parking id="1" />
+    ///         <Extensions><Color>red</Color></Extensions>
+    ///     </places>"#;
+    /// let root: Element = xml.parse().unwrap();
+    /// let dispatcher = Dispatcher::new()
+    ///     .with_name_fn("Parking", |e| e.try_attribute::<String>("id"))
+    ///     .with_extension("Extensions", ExtensionPolicy::Preserve);
+    /// let (parkings, extensions) = dispatcher.dispatch_children_with_extensions(&root).unwrap();
+    /// assert_eq!(vec!["1"], parkings);
+    /// assert_eq!(
+    ///     "<Extensions><Color>red</Color></Extensions>",
+    ///     String::from(&extensions[0])
+    /// );
+    /// ```
+    ///
+    /// [`dispatch_children`]: #method.dispatch_children
+    /// [`Preserve`]: enum.ExtensionPolicy.html#variant.Preserve
+    pub fn dispatch_children_with_extensions(
+        &self,
+        element: &Element,
+    ) -> Result<(Vec<T>, Vec<Element>), Error> {
+        let mut values = Vec::new();
+        let mut extensions = Vec::new();
+        for child in element.children() {
+            match self.extensions.get(child.name()) {
+                Some(ExtensionPolicy::Ignore) => {}
+                Some(ExtensionPolicy::Strict) => {
+                    return Err(Error::UnexpectedExtension {
+                        element_name: element.name().to_owned(),
+                        extension_name: child.name().to_owned(),
+                    })
+                }
+                Some(ExtensionPolicy::Preserve) => extensions.push(detach(child)),
+                None => values.push(self.dispatch(child)?),
+            }
+        }
+        Ok((values, extensions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn prefixed_type() {
+        let xml: &'static str = r#"<root xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns:n="urn:netex">
+                <Place xsi:type="n:StopPlace" />
+            </root>"#;
+        let root: Element = xml.parse().unwrap();
+        let dispatcher = Dispatcher::new()
+            .with_type_fn("StopPlace", |_| Ok("stop"))
+            .with_name_fn("Place", |_| Ok("place"));
+        assert_eq!(vec!["stop"], dispatcher.dispatch_children(&root).unwrap());
+    }
+
+    #[test]
+    fn preserved_extension() {
+        let xml: &'static str =
+            r#"<root xmlns:x="urn:x"><Parking /><Extensions><x:Color /></Extensions></root>"#;
+        let root: Element = xml.parse().unwrap();
+        let dispatcher = Dispatcher::new()
+            .with_name_fn("Parking", |_| Ok("parking"))
+            .with_extension("Extensions", ExtensionPolicy::Preserve);
+        assert_eq!(
+            vec!["parking"],
+            dispatcher.dispatch_children(&root).unwrap()
+        );
+        let (values, extensions) = dispatcher.dispatch_children_with_extensions(&root).unwrap();
+        assert_eq!(vec!["parking"], values);
+        assert_eq!(
+            vec![r#"<Extensions xmlns:x="urn:x"><x:Color/></Extensions>"#],
+            extensions.iter().map(String::from).collect::<Vec<_>>()
+        );
+        let error = dispatcher
+            .with_extension("Extensions", ExtensionPolicy::Strict)
+            .dispatch_children(&root)
+            .unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedExtension, error.kind());
+    }
+
+    #[test]
+    fn no_parser() {
+        let xml: &'static str = r#"<root xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+                <Place xsi:type="Parking" />
+            </root>"#;
+        let root: Element = xml.parse().unwrap();
+        let dispatcher = Dispatcher::new().with_type_fn("StopPlace", |_| Ok(()));
+        let error = dispatcher.dispatch_children(&root).unwrap_err();
+        assert_eq!(
+            "No parser registered for element \'Place\' with type \'Parking\'",
+            format!("{}", error)
+        );
+    }
+}
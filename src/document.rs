@@ -0,0 +1,509 @@
+use crate::{
+    AttrValue, AttributeElementExt, ChildrenMapElementExt, Error, FromElement, OnlyChildElementExt,
+    ParseOptions, RequiredChildrenElementExt, TextElementExt,
+};
+use minidom::{
+    quick_xml::{events::Event, Reader},
+    Element,
+};
+use std::{collections::BTreeMap, fs, ops::Deref, path::Path};
+
+/// The XML declaration of a document (`<?xml version="1.0" ...?>`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmlDeclaration {
+    /// Version of XML
+    pub version: String,
+    /// Encoding of the document, if declared
+    pub encoding: Option<String>,
+    /// Whether the document is standalone, if declared
+    pub standalone: Option<bool>,
+}
+
+/// A node found outside of the root element of a [`Document`].
+///
+/// [`Document`]: struct.Document.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MiscNode {
+    /// A comment, without its delimiters
+    Comment(String),
+    /// A processing instruction (like `xml-stylesheet href="a.xsl"`),
+    /// without its delimiters
+    ProcessingInstruction(String),
+}
+
+/// A parsed document: the root element along with what `minidom` drops, the
+/// XML declaration and the comments and processing instructions around the
+/// root, and the name of its source.
+///
+/// The lookup traits ([`AttributeElementExt`], [`TextElementExt`],
+/// [`RequiredChildrenElementExt`] and [`ChildrenMapElementExt`]) are
+/// implemented for `Document` by delegating to its root, so code generic over
+/// them accepts a document, and APIs can take a single value instead of an
+/// element and its metadata. The methods of [`OnlyChildElementExt`], which
+/// return children of the type they are implemented for, are provided as
+/// inherent methods. Their errors are wrapped with [`locate`], to report the
+/// name of the source of the document. For everything else, a `Document`
+/// dereferences to its root [`Element`].
+///
+/// ```
+/// use minidom_ext::{AttributeElementExt, Document, MiscNode, OnlyChildElementExt};
+///
+/// let xml: &'static str = r#"<?xml version="1.0" encoding="UTF-8"?>
+///     <!-- Exported on 2020-01-01 -->
+///     <PublicationDelivery version="1.0"><dataObjects /></PublicationDelivery>"#;
+/// let document = Document::parse(xml).unwrap().with_source_name("lines.xml");
+/// assert_eq!("1.0", document.try_attribute::<String>("version").unwrap());
+/// assert!(document.try_only_child("dataObjects").is_ok());
+/// assert_eq!(Some("UTF-8"), document.declaration().unwrap().encoding.as_deref());
+/// assert_eq!(
+///     &[MiscNode::Comment(" Exported on 2020-01-01 ".to_owned())],
+///     document.prolog()
+/// );
+/// assert_eq!(Some("lines.xml"), document.source_name());
+/// ```
+///
+/// [`AttributeElementExt`]: trait.AttributeElementExt.html
+/// [`ChildrenMapElementExt`]: trait.ChildrenMapElementExt.html
+/// [`Element`]: ../minidom/element/struct.Element.html
+/// [`locate`]: #method.locate
+/// [`OnlyChildElementExt`]: trait.OnlyChildElementExt.html
+/// [`RequiredChildrenElementExt`]: trait.RequiredChildrenElementExt.html
+/// [`TextElementExt`]: trait.TextElementExt.html
+#[derive(Debug, Clone)]
+pub struct Document {
+    root: Element,
+    declaration: Option<XmlDeclaration>,
+    prolog: Vec<MiscNode>,
+    epilog: Vec<MiscNode>,
+    source_name: Option<String>,
+}
+
+fn decode(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+fn invalid(error: minidom::quick_xml::Error) -> Error {
+    Error::InvalidDocument {
+        source: minidom::Error::XmlError(error),
+    }
+}
+
+impl Document {
+    /// Parse a document.
+    pub fn parse(input: &str) -> Result<Self, Error> {
+        let root = input
+            .parse()
+            .map_err(|source| Error::InvalidDocument { source })?;
+        let mut document = Document {
+            root,
+            declaration: None,
+            prolog: Vec::new(),
+            epilog: Vec::new(),
+            source_name: None,
+        };
+        let mut reader = Reader::from_str(input);
+        let mut buffer = Vec::new();
+        let mut depth = 0;
+        let mut after_root = false;
+        loop {
+            let event = reader.read_event(&mut buffer).map_err(invalid)?;
+            let misc = match event {
+                Event::Decl(decl) if !after_root && depth == 0 => {
+                    let version = decl.version().map_err(invalid)?;
+                    let encoding = decl.encoding().transpose().map_err(invalid)?;
+                    let standalone = decl.standalone().transpose().map_err(invalid)?;
+                    document.declaration = Some(XmlDeclaration {
+                        version: decode(&version),
+                        encoding: encoding.map(|encoding| decode(&encoding)),
+                        standalone: standalone.map(|standalone| &*standalone == b"yes"),
+                    });
+                    None
+                }
+                Event::Start(_) => {
+                    depth += 1;
+                    None
+                }
+                Event::End(_) => {
+                    depth -= 1;
+                    after_root |= depth == 0;
+                    None
+                }
+                Event::Empty(_) => {
+                    after_root |= depth == 0;
+                    None
+                }
+                Event::Comment(text) if depth == 0 => Some(MiscNode::Comment(decode(&text))),
+                Event::PI(text) if depth == 0 => {
+                    Some(MiscNode::ProcessingInstruction(decode(&text)))
+                }
+                Event::Eof => return Ok(document),
+                _ => None,
+            };
+            match misc {
+                Some(misc) if after_root => document.epilog.push(misc),
+                Some(misc) => document.prolog.push(misc),
+                None => {}
+            }
+            buffer.clear();
+        }
+    }
+
+    /// Read and parse a document from a file, named after its path (which
+    /// is also reported by the errors reading the file).
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let source_name = path.as_ref().display().to_string();
+        fs::read_to_string(path)
+            .map_err(|error| Error::InvalidDocument {
+                source: error.into(),
+            })
+            .and_then(|input| Document::parse(&input))
+            .map(|document| document.with_source_name(&source_name))
+            .map_err(|error| Error::WithSourceName {
+                source_name,
+                source: Box::new(error),
+            })
+    }
+
+    /// Name the source of the document (a file path or a logical name).
+    pub fn with_source_name(mut self, source_name: &str) -> Self {
+        self.source_name = Some(source_name.to_owned());
+        self
+    }
+
+    /// The root element of the document.
+    pub fn root(&self) -> &Element {
+        &self.root
+    }
+
+    /// Give back the root element, dropping the metadata.
+    pub fn into_root(self) -> Element {
+        self.root
+    }
+
+    /// The XML declaration, if any.
+    pub fn declaration(&self) -> Option<&XmlDeclaration> {
+        self.declaration.as_ref()
+    }
+
+    /// The comments and processing instructions before the root element.
+    pub fn prolog(&self) -> &[MiscNode] {
+        &self.prolog
+    }
+
+    /// The comments and processing instructions after the root element.
+    pub fn epilog(&self) -> &[MiscNode] {
+        &self.epilog
+    }
+
+    /// The name of the source of the document, if known.
+    pub fn source_name(&self) -> Option<&str> {
+        self.source_name.as_deref()
+    }
+
+    /// Wrap an error in a [`WithSourceName`] error reporting the name of the
+    /// source of the document, the error being returned as is if the
+    /// document has no name.
+    ///
+    /// [`WithSourceName`]: enum.Error.html#variant.WithSourceName
+    pub fn locate(&self, error: Error) -> Error {
+        match &self.source_name {
+            Some(source_name) => Error::WithSourceName {
+                source_name: source_name.clone(),
+                source: Box::new(error),
+            },
+            None => error,
+        }
+    }
+
+    /// Get the only child of the root named `child_name`, see
+    /// [`OnlyChildElementExt::try_only_child`].
+    ///
+    /// [`OnlyChildElementExt::try_only_child`]: trait.OnlyChildElementExt.html#tymethod.try_only_child
+    pub fn try_only_child(&self, child_name: &str) -> Result<&Element, Error> {
+        self.root
+            .try_only_child(child_name)
+            .map_err(|error| self.locate(error))
+    }
+
+    /// Get the only child of the root matching `predicate`, see
+    /// [`OnlyChildElementExt::try_find_only_child`].
+    ///
+    /// [`OnlyChildElementExt::try_find_only_child`]: trait.OnlyChildElementExt.html#tymethod.try_find_only_child
+    pub fn try_find_only_child<'a, P>(&'a self, predicate: P) -> Result<&'a Element, Error>
+    where
+        P: Fn(&'a Element) -> bool,
+    {
+        self.root
+            .try_find_only_child(predicate)
+            .map_err(|error| self.locate(error))
+    }
+
+    fn locate_all(&self, errors: Vec<Error>) -> Vec<Error> {
+        errors.into_iter().map(|error| self.locate(error)).collect()
+    }
+
+    /// Call the extraction code of the document on its root, an error being
+    /// wrapped in a [`WithSourceName`] error reporting the name of the source
+    /// of the document.
+    ///
+    /// ```
+    /// use minidom_ext::{Document, ElemResultExt, OnlyChildElementExt};
+    ///
+    /// let xml: &'static str = r#"<StopPlace id="1204" />"#;
+    /// let document = Document::parse(xml).unwrap().with_source_name("stops_03.xml");
+    /// let error = document
+    ///     .try_with(|stop_place| {
+    ///         stop_place
+    ///             .try_only_child("Name")
+    ///             .ctx_path("StopPlace[1204]".to_owned())
+    ///     })
+    ///     .unwrap_err();
+    /// assert_eq!(
+    ///     "stops_03.xml: In StopPlace[1204]: No children with name 'Name' in Element 'StopPlace'",
+    ///     error.to_string()
+    /// );
+    /// ```
+    ///
+    /// [`WithSourceName`]: enum.Error.html#variant.WithSourceName
+    pub fn try_with<'a, T, F>(&'a self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&'a Element) -> Result<T, Error>,
+    {
+        f(&self.root).map_err(|error| self.locate(error))
+    }
+}
+
+impl AttributeElementExt for Document {
+    fn try_attribute<F>(&self, attr_name: &str) -> Result<F, Error>
+    where
+        F: AttrValue,
+    {
+        self.root
+            .try_attribute(attr_name)
+            .map_err(|error| self.locate(error))
+    }
+
+    fn attribute<F>(&self, attr_name: &str) -> Option<F>
+    where
+        F: AttrValue,
+    {
+        self.root.attribute(attr_name)
+    }
+
+    fn try_attribute_with<F>(&self, attr_name: &str, options: &ParseOptions) -> Result<F, Error>
+    where
+        F: AttrValue,
+    {
+        self.root
+            .try_attribute_with(attr_name, options)
+            .map_err(|error| self.locate(error))
+    }
+
+    fn try_attribute_ns<F>(&self, ns: impl AsRef<str>, attr_name: &str) -> Result<F, Error>
+    where
+        F: AttrValue,
+    {
+        self.root
+            .try_attribute_ns(ns, attr_name)
+            .map_err(|error| self.locate(error))
+    }
+
+    fn attribute_ns<F>(&self, ns: impl AsRef<str>, attr_name: &str) -> Option<F>
+    where
+        F: AttrValue,
+    {
+        self.root.attribute_ns(ns, attr_name)
+    }
+
+    fn try_attribute_enum<T>(&self, attr_name: &str, allowed: &[&str]) -> Result<T, Error>
+    where
+        T: AttrValue,
+    {
+        self.root
+            .try_attribute_enum(attr_name, allowed)
+            .map_err(|error| self.locate(error))
+    }
+}
+
+impl TextElementExt for Document {
+    fn try_text<F>(&self) -> Result<F, Error>
+    where
+        F: AttrValue,
+    {
+        self.root.try_text().map_err(|error| self.locate(error))
+    }
+
+    fn text_value<F>(&self) -> Option<F>
+    where
+        F: AttrValue,
+    {
+        self.root.text_value()
+    }
+
+    fn try_text_with<F>(&self, options: &ParseOptions) -> Result<F, Error>
+    where
+        F: AttrValue,
+    {
+        self.root
+            .try_text_with(options)
+            .map_err(|error| self.locate(error))
+    }
+
+    fn is_nil(&self) -> bool {
+        self.root.is_nil()
+    }
+
+    fn try_text_list<F>(&self) -> Result<Vec<F>, Error>
+    where
+        F: AttrValue,
+    {
+        self.root
+            .try_text_list()
+            .map_err(|error| self.locate(error))
+    }
+}
+
+impl RequiredChildrenElementExt for Document {
+    fn try_require_children(&self, names: &[&str]) -> Result<(), Vec<Error>> {
+        self.root
+            .try_require_children(names)
+            .map_err(|errors| self.locate_all(errors))
+    }
+
+    fn try_forbid_children(&self, names: &[&str]) -> Result<(), Vec<Error>> {
+        self.root
+            .try_forbid_children(names)
+            .map_err(|errors| self.locate_all(errors))
+    }
+
+    fn try_deny_unknown_children(&self, names: &[&str]) -> Result<(), Vec<Error>> {
+        self.root
+            .try_deny_unknown_children(names)
+            .map_err(|errors| self.locate_all(errors))
+    }
+}
+
+impl ChildrenMapElementExt for Document {
+    fn try_children_map<K, V>(&self, name: &str, key_attr: &str) -> Result<BTreeMap<K, V>, Error>
+    where
+        K: AttrValue + Ord,
+        V: FromElement,
+    {
+        self.root
+            .try_children_map(name, key_attr)
+            .map_err(|error| self.locate(error))
+    }
+}
+
+impl Deref for Document {
+    type Target = Element;
+
+    fn deref(&self) -> &Element {
+        &self.root
+    }
+}
+
+impl AsRef<Element> for Document {
+    fn as_ref(&self) -> &Element {
+        &self.root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Diagnostic, ErrorKind, TextElementExt, XsBoolean};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn misc_nodes() {
+        let xml: &'static str = r#"<?xml version="1.1" standalone="yes"?>
+            <?xml-stylesheet href="a.xsl"?>
+            <root><!-- inner --><?inner?><a>1</a></root>
+            <!-- end -->"#;
+        let document = Document::parse(xml).unwrap();
+        let expected = XmlDeclaration {
+            version: "1.1".to_owned(),
+            encoding: None,
+            standalone: Some(true),
+        };
+        assert_eq!(Some(&expected), document.declaration());
+        assert_eq!(
+            &[MiscNode::ProcessingInstruction(
+                r#"xml-stylesheet href="a.xsl""#.to_owned()
+            )],
+            document.prolog()
+        );
+        assert_eq!(&[MiscNode::Comment(" end ".to_owned())], document.epilog());
+        assert_eq!(None, document.source_name());
+        let a = document.children().next().unwrap();
+        assert_eq!(1, a.try_text::<u32>().unwrap());
+
+        let document = Document::parse("<root/><!-- end -->").unwrap();
+        assert_eq!(None, document.declaration());
+        assert_eq!(1, document.epilog().len());
+    }
+
+    #[test]
+    fn source_name_in_errors() {
+        let error = Document::open("missing/stops_03.xml").unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("missing/stops_03.xml: Failed to read the document"));
+        assert_eq!(ErrorKind::InvalidDocument, error.kind());
+        let document = Document::parse("<root/>").unwrap();
+        let error = document
+            .try_with(|root| root.try_text::<u32>())
+            .unwrap_err();
+        assert_eq!(ErrorKind::TextParseError, error.kind());
+        let error = document.with_source_name("a.xml").locate(error);
+        let diagnostic = Diagnostic::from(&error);
+        assert_eq!(Some("a.xml"), diagnostic.source_name.as_deref());
+        assert!(diagnostic.to_string().starts_with("[MDX0016] a.xml: "));
+    }
+
+    #[test]
+    fn generic_lookups() {
+        fn version<T: AttributeElementExt>(value: &T) -> Option<String> {
+            value.attribute("version")
+        }
+
+        let document = Document::parse(r#"<root version="1"><a/></root>"#).unwrap();
+        assert_eq!(Some("1".to_owned()), version(&document));
+        assert_eq!(Some("1".to_owned()), version(document.root()));
+        assert!(document.try_require_children(&["a"]).is_ok());
+        assert_eq!("a", document.try_only_child("a").unwrap().name());
+        let document = document.with_source_name("a.xml");
+        assert_eq!(
+            "a.xml: Failed to find attribute 'id' in element 'root' (available: version)",
+            document
+                .try_attribute::<String>("id")
+                .unwrap_err()
+                .to_string()
+        );
+        let errors = document.try_require_children(&["b"]).unwrap_err();
+        assert_eq!(
+            Some("a.xml"),
+            Diagnostic::from(&errors[0]).source_name.as_deref()
+        );
+        assert!(document
+            .try_only_child("b")
+            .unwrap_err()
+            .to_string()
+            .starts_with("a.xml: "));
+        assert_eq!(
+            XsBoolean(true),
+            document.try_attribute_variant("version").unwrap()
+        );
+        let document = Document::parse(r#"<root nil="yes"/>"#)
+            .unwrap()
+            .with_source_name("b.xml");
+        assert_eq!(
+            "b.xml: Value 'yes' of attribute 'nil' in element 'root' is not one of: true, false, 1, 0",
+            document
+                .try_attribute_variant::<XsBoolean>("nil")
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use std::{fmt, str::FromStr, time::Duration};
+
+/// Error returned when parsing an `xs:duration` fails.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum DurationError {
+    /// The value doesn't follow the `PnYnMnDTnHnMnS` format.
+    #[error("Invalid duration '{0}' (expected a format like 'P1DT2H30M')")]
+    InvalidDuration(String),
+    /// The duration has years or months, whose length varies, or is
+    /// negative, so it cannot be converted into an exact duration.
+    #[error("Duration '{0}' is not an exact positive duration")]
+    NotExact(String),
+}
+
+/// An ISO 8601 duration as defined by `xs:duration`, like `PT1H30M` or
+/// `-P1Y2M`.
+///
+/// Years and months are kept apart as their length depends on the date the
+/// duration is applied to. Use [`to_std`] to convert the duration into a
+/// [`Duration`] when it has no such component.
+///
+/// ```
+/// use minidom_ext::XsDuration;
+/// use std::time::Duration;
+///
+/// let headway: XsDuration = "PT1H30M".parse().unwrap();
+/// assert_eq!(Some(Duration::from_secs(5400)), headway.to_std());
+/// let validity: XsDuration = "P1Y6M".parse().unwrap();
+/// assert_eq!(18, validity.months);
+/// assert_eq!(None, validity.to_std());
+/// ```
+///
+/// [`Duration`]: https://doc.rust-lang.org/std/time/struct.Duration.html
+/// [`to_std`]: #method.to_std
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct XsDuration {
+    /// The duration is negative (`-P1D`)
+    pub negative: bool,
+    /// Number of months, including years (12 months each)
+    pub months: u64,
+    /// The exact part of the duration (days, hours, minutes and seconds)
+    pub time: Duration,
+}
+
+impl XsDuration {
+    /// Convert into an exact [`Duration`], if the duration is positive and
+    /// has no years or months.
+    ///
+    /// [`Duration`]: https://doc.rust-lang.org/std/time/struct.Duration.html
+    pub fn to_std(&self) -> Option<Duration> {
+        if self.months == 0 && (!self.negative || self.time == Duration::default()) {
+            Some(self.time)
+        } else {
+            None
+        }
+    }
+}
+
+/// Parse the fractional part of the seconds, truncated to nanoseconds.
+fn nanos(fraction: &str) -> Option<u32> {
+    if fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let digits: String = fraction
+        .chars()
+        .chain("000000000".chars())
+        .take(9)
+        .collect();
+    digits.parse().ok()
+}
+
+impl FromStr for XsDuration {
+    type Err = DurationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || DurationError::InvalidDuration(s.to_owned());
+        let value = s.trim();
+        let (negative, value) = match value.strip_prefix('-') {
+            Some(value) => (true, value),
+            None => (false, value),
+        };
+        let value = value.strip_prefix('P').ok_or_else(invalid)?;
+        let (date, time) = match value.find('T') {
+            Some(index) if index + 1 < value.len() => (&value[..index], Some(&value[index + 1..])),
+            Some(_) => return Err(invalid()),
+            None => (value, None),
+        };
+        if date.is_empty() && time.is_none() {
+            return Err(invalid());
+        }
+
+        let mut duration = XsDuration {
+            negative,
+            ..XsDuration::default()
+        };
+        let mut seconds: u64 = 0;
+        // Designators must appear in this order, each at most once
+        for (part, designators) in [(date, "YMD"), (time.unwrap_or(""), "HMS")] {
+            let mut rest = part;
+            let mut allowed = designators;
+            while !rest.is_empty() {
+                let end = rest
+                    .find(|c: char| !c.is_ascii_digit() && c != '.')
+                    .ok_or_else(invalid)?;
+                let designator = rest[end..].chars().next().ok_or_else(invalid)?;
+                let position = allowed.find(designator).ok_or_else(invalid)?;
+                allowed = &allowed[position + 1..];
+                let number = &rest[..end];
+                rest = &rest[end + 1..];
+                let (integer, fraction) = match number.find('.') {
+                    Some(index) if designator == 'S' && designators == "HMS" => (
+                        &number[..index],
+                        Some(nanos(&number[index + 1..]).ok_or_else(invalid)?),
+                    ),
+                    Some(_) => return Err(invalid()),
+                    None => (number, None),
+                };
+                let integer: u64 = integer.parse().map_err(|_| invalid())?;
+                let overflow = |value: Option<u64>| value.ok_or_else(invalid);
+                match (designators, designator) {
+                    ("YMD", 'Y') => duration.months = overflow(integer.checked_mul(12))?,
+                    ("YMD", 'M') => {
+                        duration.months = overflow(duration.months.checked_add(integer))?
+                    }
+                    (_, unit) => {
+                        let factor = match unit {
+                            'D' => 86_400,
+                            'H' => 3600,
+                            'M' => 60,
+                            _ => 1,
+                        };
+                        seconds = overflow(
+                            integer
+                                .checked_mul(factor)
+                                .and_then(|s| s.checked_add(seconds)),
+                        )?;
+                        if let Some(nanos) = fraction {
+                            duration.time = Duration::new(0, nanos);
+                        }
+                    }
+                }
+            }
+        }
+        duration.time += Duration::from_secs(seconds);
+        Ok(duration)
+    }
+}
+
+impl fmt::Display for XsDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negative {
+            write!(f, "-")?;
+        }
+        write!(f, "P")?;
+        let (years, months) = (self.months / 12, self.months % 12);
+        if years > 0 {
+            write!(f, "{}Y", years)?;
+        }
+        if months > 0 {
+            write!(f, "{}M", months)?;
+        }
+        let seconds = self.time.as_secs();
+        let (days, hours) = (seconds / 86_400, seconds % 86_400 / 3600);
+        let (minutes, seconds) = (seconds % 3600 / 60, seconds % 60);
+        let nanos = self.time.subsec_nanos();
+        if days > 0 {
+            write!(f, "{}D", days)?;
+        }
+        if hours > 0 || minutes > 0 || seconds > 0 || nanos > 0 {
+            write!(f, "T")?;
+            if hours > 0 {
+                write!(f, "{}H", hours)?;
+            }
+            if minutes > 0 {
+                write!(f, "{}M", minutes)?;
+            }
+            if nanos > 0 {
+                let fraction = format!("{:09}", nanos);
+                write!(f, "{}.{}S", seconds, fraction.trim_end_matches('0'))?;
+            } else if seconds > 0 {
+                write!(f, "{}S", seconds)?;
+            }
+        } else if self.months == 0 && days == 0 {
+            write!(f, "T0S")?;
+        }
+        Ok(())
+    }
+}
+
+/// An `xs:duration` which can be converted into an exact duration.
+pub(crate) struct ExactDuration(pub(crate) Duration);
+
+impl FromStr for ExactDuration {
+    type Err = DurationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<XsDuration>()?
+            .to_std()
+            .map(ExactDuration)
+            .ok_or_else(|| DurationError::NotExact(s.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse() {
+        let duration: XsDuration = "P1Y2M3DT4H5M6.7S".parse().unwrap();
+        assert_eq!(14, duration.months);
+        assert_eq!(
+            Duration::new(3 * 86_400 + 4 * 3600 + 5 * 60 + 6, 700_000_000),
+            duration.time
+        );
+        assert_eq!("P1Y2M3DT4H5M6.7S", duration.to_string());
+        let duration: XsDuration = "-PT0S".parse().unwrap();
+        assert_eq!(Some(Duration::default()), duration.to_std());
+        assert_eq!("PT0S", XsDuration::default().to_string());
+        assert_eq!(
+            "PT90M".parse::<XsDuration>().unwrap().to_string(),
+            "PT1H30M"
+        );
+    }
+
+    #[test]
+    fn invalid_durations() {
+        for duration in &[
+            "", "P", "PT", "1D", "P1H", "PT1D", "P1M1Y", "P1.5D", "P1DT", "PT1.S", "P-1D", "P1D1D",
+        ] {
+            assert!(duration.parse::<XsDuration>().is_err(), "{}", duration);
+        }
+        assert_eq!(
+            Err(DurationError::NotExact("-PT1M".to_owned())),
+            "-PT1M".parse::<ExactDuration>().map(|d| d.0)
+        );
+    }
+}
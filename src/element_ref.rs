@@ -0,0 +1,99 @@
+use crate::Error;
+use minidom::Element;
+use std::{fmt, marker::PhantomData, ops::Deref};
+
+/// A reference to an element whose name has been checked, tagged with a
+/// marker type.
+///
+/// Functions can require an `ElementRef<StopPlace>` instead of an
+/// [`Element`] to make sure, at the type level, that they receive an element
+/// already verified to be a `StopPlace`. The marker type is usually an empty
+/// type without any value. An `ElementRef` dereferences to the [`Element`].
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{ElementRef, OnlyChildElementExt};
+///
+/// enum StopPlace {}
+///
+/// fn stop_place_id(stop_place: ElementRef<StopPlace>) -> Option<String> {
+///     stop_place.attr("id").map(ToOwned::to_owned)
+/// }
+///
+/// let xml: &'static str = r#"<stopPlaces><StopPlace id="stop:1" /></stopPlaces>"#;
+/// let root: Element = xml.parse().unwrap();
+/// let stop_place = root.try_only_child_as::<StopPlace>("StopPlace").unwrap();
+/// assert_eq!(Some("stop:1".to_owned()), stop_place_id(stop_place));
+/// ```
+///
+/// [`Element`]: ../minidom/element/struct.Element.html
+pub struct ElementRef<'a, M> {
+    element: &'a Element,
+    marker: PhantomData<fn() -> M>,
+}
+
+impl<'a, M> ElementRef<'a, M> {
+    /// Check that the element has the given (local) name and tag it, or
+    /// return an [`UnexpectedElement`] error.
+    ///
+    /// [`UnexpectedElement`]: enum.Error.html#variant.UnexpectedElement
+    pub fn try_new(element: &'a Element, name: &str) -> Result<Self, Error> {
+        if element.name() == name {
+            Ok(ElementRef {
+                element,
+                marker: PhantomData,
+            })
+        } else {
+            Err(Error::UnexpectedElement {
+                expected: name.to_owned(),
+                found: element.name().to_owned(),
+            })
+        }
+    }
+
+    /// The tagged element, with the lifetime of the tree.
+    pub fn element(&self) -> &'a Element {
+        self.element
+    }
+}
+
+impl<M> Clone for ElementRef<'_, M> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M> Copy for ElementRef<'_, M> {}
+
+impl<M> fmt::Debug for ElementRef<'_, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ElementRef").field(self.element).finish()
+    }
+}
+
+impl<M> Deref for ElementRef<'_, M> {
+    type Target = Element;
+
+    fn deref(&self) -> &Element {
+        self.element
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    enum Line {}
+
+    #[test]
+    fn unexpected_name() {
+        let xml: &'static str = r#"<Route />"#;
+        let route: Element = xml.parse().unwrap();
+        let error = ElementRef::<Line>::try_new(&route, "Line").unwrap_err();
+        assert_eq!(
+            "Expected element 'Line' but found 'Route'",
+            format!("{}", error)
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{materialize::detach, Error};
+use minidom::Element;
+use std::collections::BTreeMap;
+
+/// What to do with the extension points of a schema (like `<Extensions>`
+/// children or `xs:any` content), whose content is not known in advance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExtensionPolicy {
+    /// Keep the extension verbatim, as a detached [`Element`]
+    ///
+    /// [`Element`]: ../minidom/element/struct.Element.html
+    Preserve,
+    /// Skip the extension
+    Ignore,
+    /// Reject the extension with an [`UnexpectedExtension`] error
+    ///
+    /// [`UnexpectedExtension`]: enum.Error.html#variant.UnexpectedExtension
+    Strict,
+}
+
+impl ExtensionPolicy {
+    /// Apply the policy on an extension element found in `parent`.
+    pub(crate) fn apply(
+        self,
+        parent: &Element,
+        extension: &Element,
+    ) -> Result<Option<Element>, Error> {
+        match self {
+            ExtensionPolicy::Preserve => Ok(Some(detach(extension))),
+            ExtensionPolicy::Ignore => Ok(None),
+            ExtensionPolicy::Strict => Err(Error::UnexpectedExtension {
+                element_name: parent.name().to_owned(),
+                extension_name: extension.name().to_owned(),
+            }),
+        }
+    }
+}
+
+/// The attributes and children of an element which are not modeled by an
+/// extraction, gathered by [`ExtensionElementExt::extras`] to write them back
+/// with [`apply_to`].
+///
+/// [`apply_to`]: #method.apply_to
+/// [`ExtensionElementExt::extras`]: trait.ExtensionElementExt.html#tymethod.extras
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Extras {
+    /// The unknown attributes, by name
+    pub attributes: BTreeMap<String, String>,
+    /// The unknown children, detached from their document, in document
+    /// order
+    pub children: Vec<Element>,
+}
+
+impl Extras {
+    /// Whether there are no unknown attributes nor children.
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty() && self.children.is_empty()
+    }
+
+    /// Write the unknown attributes and children back into an element, the
+    /// children being appended after the existing ones.
+    pub fn apply_to(&self, element: &mut Element) {
+        for (name, value) in &self.attributes {
+            element.set_attr(name.as_str(), value.as_str());
+        }
+        for child in &self.children {
+            element.append_child(child.clone());
+        }
+    }
+}
+
+/// Get the extension points of an element.
+pub trait ExtensionElementExt {
+    /// Try to get the first child with the given name, handled as an
+    /// extension depending on `policy`.
+    ///
+    /// Returns [`None`] if there is no such child or if it is ignored, and an
+    /// [`UnexpectedExtension`] error if it is rejected.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::{ExtensionElementExt, ExtensionPolicy};
+    ///
+    /// let xml: &'static str = r#"<Line xmlns="urn:netex" xmlns:x="urn:x">
+    ///         <Name>A</Name>
+    ///         <Extensions><x:color>red</x:color></Extensions>
+    ///     </Line>"#;
+    /// let line: Element = xml.parse().unwrap();
+    /// let extensions = line
+    ///     .try_extension("Extensions", ExtensionPolicy::Preserve)
+    ///     .unwrap()
+    ///     .unwrap();
+    /// assert_eq!(Some("urn:x".to_owned()), extensions.children().next().unwrap().ns());
+    /// assert!(line
+    ///     .try_extension("Extensions", ExtensionPolicy::Ignore)
+    ///     .unwrap()
+    ///     .is_none());
+    /// assert_eq!(
+    ///     "Unexpected extension 'Extensions' in element 'Line'",
+    ///     line.try_extension("Extensions", ExtensionPolicy::Strict)
+    ///         .unwrap_err()
+    ///         .to_string()
+    /// );
+    /// ```
+    ///
+    /// [`None`]:  https://doc.rust-lang.org/std/option/enum.Option.html#variant.None
+    /// [`UnexpectedExtension`]: enum.Error.html#variant.UnexpectedExtension
+    fn try_extension(&self, name: &str, policy: ExtensionPolicy) -> Result<Option<Element>, Error>;
+
+    /// Get the first child with the given name, handled as an extension
+    /// depending on `policy`, and return an [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn extension(&self, name: &str, policy: ExtensionPolicy) -> Option<Element> {
+        self.try_extension(name, policy).ok().flatten()
+    }
+
+    /// Gather the attributes and the children which are not in
+    /// `known_attributes` and `known_children`, so the content an extraction
+    /// doesn't model can be kept for a lossless round trip.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::ExtensionElementExt;
+    ///
+    /// let xml: &'static str = r#"<Line xmlns="urn:netex" id="1" color="red">
+    ///         <Name>A</Name>
+    ///         <Notes>Night service</Notes>
+    ///     </Line>"#;
+    /// let line: Element = xml.parse().unwrap();
+    /// let extras = line.extras(&["id"], &["Name"]);
+    /// assert_eq!(Some("red"), extras.attributes.get("color").map(String::as_str));
+    /// assert_eq!(vec!["Notes"], extras.children.iter().map(Element::name).collect::<Vec<_>>());
+    ///
+    /// let mut rebuilt = Element::builder("Line").ns("urn:netex").attr("id", "1").build();
+    /// extras.apply_to(&mut rebuilt);
+    /// assert_eq!(Some("red"), rebuilt.attr("color"));
+    /// assert!(rebuilt.has_child("Notes", "urn:netex"));
+    /// ```
+    fn extras(&self, known_attributes: &[&str], known_children: &[&str]) -> Extras;
+}
+
+impl ExtensionElementExt for Element {
+    fn try_extension(&self, name: &str, policy: ExtensionPolicy) -> Result<Option<Element>, Error> {
+        match self.children().find(|child| child.name() == name) {
+            Some(extension) => policy.apply(self, extension),
+            None => Ok(None),
+        }
+    }
+
+    fn extras(&self, known_attributes: &[&str], known_children: &[&str]) -> Extras {
+        Extras {
+            attributes: self
+                .attrs()
+                .filter(|(name, _)| !known_attributes.contains(name))
+                .map(|(name, value)| (name.to_owned(), value.to_owned()))
+                .collect(),
+            children: self
+                .children()
+                .filter(|child| !known_children.contains(&child.name()))
+                .map(detach)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn missing_extension() {
+        let line: Element = r#"<Line><Name>A</Name></Line>"#.parse().unwrap();
+        for policy in &[
+            ExtensionPolicy::Preserve,
+            ExtensionPolicy::Ignore,
+            ExtensionPolicy::Strict,
+        ] {
+            assert_eq!(None, line.try_extension("Extensions", *policy).unwrap());
+        }
+    }
+
+    #[test]
+    fn round_trip_extras() {
+        let xml: &'static str =
+            r#"<Line xmlns:x="urn:x" id="1" x:color="red"><Name>A</Name><x:Note>B</x:Note></Line>"#;
+        let line: Element = xml.parse().unwrap();
+        assert!(line
+            .extras(&["id", "x:color"], &["Name", "Note"])
+            .is_empty());
+        let extras = line.extras(&["id"], &["Name"]);
+        assert_eq!(1, extras.attributes.len());
+        assert_eq!(Some("urn:x".to_owned()), extras.children[0].ns());
+        let mut rebuilt = Element::bare("Line");
+        extras.apply_to(&mut rebuilt);
+        assert_eq!(Some("red"), rebuilt.attr("x:color"));
+        assert_eq!(1, rebuilt.children().count());
+    }
+}
@@ -0,0 +1,321 @@
+use crate::{Diagnostic, Error, Modification, Selector, Transform};
+use minidom::Element;
+use std::{collections::HashMap, fmt};
+
+/// How serious a [`Finding`] is.
+///
+/// [`Finding`]: struct.Finding.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Worth knowing, the data is fine
+    Info,
+    /// The data is usable but should be fixed
+    Warning,
+    /// The data is not usable
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Info => write!(f, "info"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A machine-applicable fix of a [`Finding`]: set an attribute of the
+/// elements matching a selector (relative to the root, like
+/// `frame[1]/Line[2]`).
+///
+/// [`Finding`]: struct.Finding.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    /// The elements to fix
+    pub selector: Selector,
+    /// Name of the attribute to set
+    pub attribute: String,
+    /// The new value of the attribute
+    pub value: String,
+}
+
+impl Edit {
+    /// Create a fix setting an attribute of the elements matching the
+    /// selector.
+    pub fn set_attribute(selector: Selector, attribute: &str, value: &str) -> Self {
+        Edit {
+            selector,
+            attribute: attribute.to_owned(),
+            value: value.to_owned(),
+        }
+    }
+
+    /// Apply the fix on the tree and return the modifications, see
+    /// [`Transform`].
+    ///
+    /// [`Transform`]: struct.Transform.html
+    pub fn apply(&self, root: &mut Element) -> Vec<Modification> {
+        Transform::new()
+            .set_attribute(self.selector.clone(), &self.attribute, &self.value)
+            .apply(root)
+    }
+}
+
+impl fmt::Display for Edit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "set {}/@{} to '{}'",
+            self.selector, self.attribute, self.value
+        )
+    }
+}
+
+/// An issue found by a validation rule, with an optional fix which tools can
+/// apply automatically, see [`Validator::report`].
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{Edit, Finding, Severity};
+///
+/// let mut root: Element = r#"<root><Line id="1" /></root>"#.parse().unwrap();
+/// let finding = Finding::new("missing-version", Severity::Warning, "root > Line", "No version")
+///     .with_fix(Edit::set_attribute("Line".parse().unwrap(), "version", "any"));
+/// assert_eq!(
+///     "warning[missing-version] root > Line: No version (fix: set Line/@version to 'any')",
+///     finding.to_string()
+/// );
+/// assert_eq!(1, finding.apply_fix(&mut root).len());
+/// assert_eq!(Some("any"), root.children().next().unwrap().attr("version"));
+/// ```
+///
+/// [`Validator::report`]: struct.Validator.html#method.report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// Identifier of the rule which raised the finding
+    pub rule: String,
+    /// How serious the finding is
+    pub severity: Severity,
+    /// Path of the element the finding is about, like `root > frame[2] > Line[1]`
+    pub path: String,
+    /// Human description of the issue
+    pub message: String,
+    /// Name of the attribute the finding is about, if any
+    pub attribute: Option<String>,
+    /// A fix of the issue, if it can be applied automatically
+    pub fix: Option<Edit>,
+}
+
+impl Finding {
+    /// Create a finding without any fix.
+    pub fn new(rule: &str, severity: Severity, path: &str, message: &str) -> Self {
+        Finding {
+            rule: rule.to_owned(),
+            severity,
+            path: path.to_owned(),
+            message: message.to_owned(),
+            attribute: None,
+            fix: None,
+        }
+    }
+
+    /// Tell which attribute of the element the finding is about.
+    pub fn with_attribute(mut self, attribute: &str) -> Self {
+        self.attribute = Some(attribute.to_owned());
+        self
+    }
+
+    /// Name of the element the finding is about, the last element of its
+    /// path.
+    pub fn element_name(&self) -> &str {
+        let last = self.path.rsplit(" > ").next().unwrap_or_default();
+        last.split('[').next().unwrap_or_default()
+    }
+
+    /// Suggest a fix of the issue.
+    pub fn with_fix(mut self, fix: Edit) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+
+    /// Apply the fix on the tree, if any, and return the modifications.
+    pub fn apply_fix(&self, root: &mut Element) -> Vec<Modification> {
+        self.fix
+            .as_ref()
+            .map(|fix| fix.apply(root))
+            .unwrap_or_default()
+    }
+}
+
+impl From<&Error> for Finding {
+    /// An error is a finding of [`Severity::Error`], raised by the rule named
+    /// after its [code], without any fix.
+    ///
+    /// [code]: enum.Error.html#method.code
+    /// [`Severity::Error`]: enum.Severity.html#variant.Error
+    fn from(error: &Error) -> Self {
+        let diagnostic = Diagnostic::from(error);
+        Finding {
+            rule: diagnostic.code.to_owned(),
+            severity: Severity::Error,
+            path: diagnostic.path.unwrap_or_default(),
+            message: diagnostic.message,
+            attribute: diagnostic.attribute,
+            fix: None,
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]", self.severity, self.rule)?;
+        if !self.path.is_empty() {
+            write!(f, " {}:", self.path)?;
+        }
+        write!(f, " {}", self.message)?;
+        if let Some(fix) = &self.fix {
+            write!(f, " (fix: {})", fix)?;
+        }
+        Ok(())
+    }
+}
+
+/// Identical [`Finding`]s (same rule, same element name and same
+/// attribute) grouped by [`aggregate_findings`].
+///
+/// [`aggregate_findings`]: fn.aggregate_findings.html
+/// [`Finding`]: struct.Finding.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FindingGroup {
+    /// Identifier of the rule which raised the findings
+    pub rule: String,
+    /// How serious the findings are (the most serious of the group)
+    pub severity: Severity,
+    /// Name of the element the findings are about
+    pub element_name: String,
+    /// Name of the attribute the findings are about, if any
+    pub attribute: Option<String>,
+    /// Message of the first finding
+    pub message: String,
+    /// Number of findings in the group
+    pub count: usize,
+    /// Paths of the first findings
+    pub examples: Vec<String>,
+}
+
+impl fmt::Display for FindingGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}] {}", self.severity, self.rule, self.element_name)?;
+        if let Some(attribute) = &self.attribute {
+            write!(f, "/@{}", attribute)?;
+        }
+        write!(f, ": {} (x{}", self.message, self.count)?;
+        if !self.examples.is_empty() {
+            write!(f, ", e.g. {}", self.examples.join(", "))?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Group the identical findings (same rule, same element name and same
+/// attribute) to make the reports of huge documents digestible, keeping the
+/// paths of the first `max_examples` findings of each group.
+///
+/// Groups are in the order of their first finding.
+///
+/// ```
+/// use minidom_ext::{aggregate_findings, Finding, Severity};
+///
+/// let findings: Vec<Finding> = (1..=1000)
+///     .map(|index| {
+///         let path = format!("root > Line[{}]", index);
+///         Finding::new("version", Severity::Warning, &path, "No version")
+///             .with_attribute("version")
+///     })
+///     .collect();
+/// let groups = aggregate_findings(&findings, 2);
+/// assert_eq!(1, groups.len());
+/// assert_eq!(
+///     "warning[version] Line/@version: No version (x1000, e.g. root > Line[1], root > Line[2])",
+///     groups[0].to_string()
+/// );
+/// ```
+pub fn aggregate_findings(findings: &[Finding], max_examples: usize) -> Vec<FindingGroup> {
+    let mut groups: Vec<FindingGroup> = Vec::new();
+    let mut indexes: HashMap<(&str, &str, Option<&str>), usize> = HashMap::new();
+    for finding in findings {
+        let key = (
+            finding.rule.as_str(),
+            finding.element_name(),
+            finding.attribute.as_deref(),
+        );
+        let index = *indexes.entry(key).or_insert_with(|| {
+            groups.push(FindingGroup {
+                rule: finding.rule.clone(),
+                severity: finding.severity,
+                element_name: finding.element_name().to_owned(),
+                attribute: finding.attribute.clone(),
+                message: finding.message.clone(),
+                count: 0,
+                examples: Vec::new(),
+            });
+            groups.len() - 1
+        });
+        let group = &mut groups[index];
+        group.count += 1;
+        group.severity = group.severity.max(finding.severity);
+        if group.examples.len() < max_examples {
+            group.examples.push(finding.path.clone());
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AttributeElementExt, ElemResultExt};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn findings_from_errors() {
+        let root: Element = r#"<root><Line /></root>"#.parse().unwrap();
+        let line = root.children().next().unwrap();
+        let error = line.try_attribute::<u32>("id").ctx(line).unwrap_err();
+        let finding = Finding::from(&error);
+        assert_eq!(Severity::Error, finding.severity);
+        assert_eq!(None, finding.fix);
+        assert_eq!(
+            "error[MDX0001] Line: Failed to find attribute 'id' in element 'Line'",
+            finding.to_string()
+        );
+        assert!(Severity::Warning < Severity::Error);
+        assert_eq!("Line", finding.element_name());
+        assert_eq!(Some("id"), finding.attribute.as_deref());
+    }
+
+    #[test]
+    fn aggregate() {
+        let findings = vec![
+            Finding::new("type", Severity::Warning, "root > Line[1]", "A").with_attribute("id"),
+            Finding::new("type", Severity::Error, "root > Stop", "B").with_attribute("id"),
+            Finding::new("type", Severity::Error, "root > Line[2]", "C").with_attribute("id"),
+            Finding::new("type", Severity::Info, "root > Line[3]", "D"),
+        ];
+        let groups = aggregate_findings(&findings, 1);
+        let summary: Vec<(&str, usize, Severity)> = groups
+            .iter()
+            .map(|group| (group.message.as_str(), group.count, group.severity))
+            .collect();
+        assert_eq!(
+            vec![
+                ("A", 2, Severity::Error),
+                ("B", 1, Severity::Error),
+                ("D", 1, Severity::Info)
+            ],
+            summary
+        );
+        assert_eq!(vec!["root > Line[1]"], groups[0].examples);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::Error;
+use minidom::{Element, Node};
+
+/// Parse `input` inside a synthetic element, to accept any number of top
+/// level elements.
+fn parse_wrapped(input: &str) -> Result<Element, Error> {
+    // The declarations of concatenated documents are ignored by `minidom`
+    // wherever they are
+    format!("<fragments>{}</fragments>", input)
+        .parse()
+        .map_err(|source| Error::InvalidDocument { source })
+}
+
+/// Parse a list of XML fragments which are not enclosed in a single root
+/// element, like the concatenated documents returned by some APIs.
+///
+/// The XML declarations at the beginning of each fragment are ignored, as
+/// well as the text and comments between the fragments. See
+/// [`parse_fragments_with_root`] to keep them.
+///
+/// ```
+/// use minidom_ext::parse_fragments;
+///
+/// let xml: &'static str = r#"<?xml version="1.0"?>
+///     <Line id="1"/>
+///     <?xml version="1.0"?>
+///     <Line id="2"><Name>Metro 2</Name></Line>"#;
+/// let lines = parse_fragments(xml).unwrap();
+/// assert_eq!(2, lines.len());
+/// assert_eq!(Some("2"), lines[1].attr("id"));
+/// ```
+///
+/// [`parse_fragments_with_root`]: fn.parse_fragments_with_root.html
+pub fn parse_fragments(input: &str) -> Result<Vec<Element>, Error> {
+    let wrapper = parse_wrapped(input)?;
+    Ok(wrapper.children().cloned().collect())
+}
+
+/// Parse a list of XML fragments into the children of a synthetic root
+/// element named `root_name`, without namespace.
+///
+/// Unlike [`parse_fragments`], the text and comments between the fragments
+/// are kept as nodes of the root element.
+///
+/// ```
+/// use minidom_ext::parse_fragments_with_root;
+///
+/// let xml: &'static str = r#"<Line id="1"/>and<Line id="2"/>"#;
+/// let root = parse_fragments_with_root(xml, "Lines").unwrap();
+/// assert_eq!("Lines", root.name());
+/// assert_eq!(2, root.children().count());
+/// assert_eq!("and", root.text());
+/// ```
+///
+/// [`parse_fragments`]: fn.parse_fragments.html
+pub fn parse_fragments_with_root(input: &str, root_name: &str) -> Result<Element, Error> {
+    let wrapper = parse_wrapped(input)?;
+    let nodes: Vec<Node> = wrapper.nodes().cloned().collect();
+    Ok(Element::builder(root_name).append_all(nodes).build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn namespaced_fragments() {
+        let xml: &'static str = r#"<a:Line xmlns:a="urn:a"/>
+            <!-- comment -->
+            <Stop xmlns="urn:b"><Name/></Stop>"#;
+        let fragments = parse_fragments(xml).unwrap();
+        assert_eq!(2, fragments.len());
+        assert!(fragments[0].is("Line", "urn:a"));
+        assert!(fragments[1].children().next().unwrap().is("Name", "urn:b"));
+        let root = parse_fragments_with_root(xml, "root").unwrap();
+        assert_eq!(2, root.children().count());
+        assert_eq!(None, root.ns());
+    }
+
+    #[test]
+    fn empty_and_invalid_input() {
+        assert!(parse_fragments("  ").unwrap().is_empty());
+        let error = parse_fragments("<a><b></a>").unwrap_err();
+        assert_eq!(
+            "Failed to read the document: XML error: Expecting </b> found </a>",
+            format!("{}", error)
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use crate::Error;
+use minidom::Element;
+
+/// Build a value from an [`Element`].
+///
+/// This is the counterpart of [`FromStr`] for whole elements, used by the
+/// helpers of this crate which build typed values out of children.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{AttributeElementExt, Error, FromElement};
+///
+/// struct Line {
+///     id: u64,
+/// }
+///
+/// impl FromElement for Line {
+///     fn from_element(element: &Element) -> Result<Self, Error> {
+///         Ok(Line {
+///             id: element.try_attribute("id")?,
+///         })
+///     }
+/// }
+///
+/// let xml: &'static str = r#"<Line id="42" />"#;
+/// let element: Element = xml.parse().unwrap();
+/// assert_eq!(42, Line::from_element(&element).unwrap().id);
+/// ```
+///
+/// [`Element`]: ../minidom/element/struct.Element.html
+/// [`FromStr`]: https://doc.rust-lang.org/std/str/trait.FromStr.html
+pub trait FromElement: Sized {
+    /// Build the value from the element.
+    fn from_element(element: &Element) -> Result<Self, Error>;
+}
+
+impl FromElement for Element {
+    fn from_element(element: &Element) -> Result<Self, Error> {
+        Ok(element.clone())
+    }
+}
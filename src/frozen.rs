@@ -0,0 +1,138 @@
+use crate::Selector;
+use minidom::Element;
+use std::{cell::RefCell, collections::HashMap};
+
+/// A parsed document which is never modified, and whose query results are
+/// memoized.
+///
+/// Results are cached per [`Selector`] (a compiled query), so repeated
+/// identical queries coming from different code paths are only evaluated
+/// once. The cache only holds the path to the selected elements (the
+/// position of each element among the child elements of its parent), not the
+/// elements themselves, and a cached query is answered by following these
+/// paths, without walking the rest of the tree.
+///
+/// `FrozenDocument` is neither `Send` nor `Sync`: `minidom::Element` relies
+/// on `Rc` internally, so a document can't be shared between threads anyway,
+/// and the cache uses a `RefCell` rather than a lock. It is safe to use from
+/// any number of places (including reentrant calls) within a thread.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{FrozenDocument, Selector};
+///
+/// let xml: &'static str = r#"<root><Line id="1" /><Line id="2" /></root>"#;
+/// let document = FrozenDocument::new(xml.parse().unwrap());
+/// let lines: Selector = "Line".parse().unwrap();
+/// assert_eq!(2, document.query(&lines).len());
+/// // The second query is answered from the cache
+/// assert_eq!(2, document.query(&lines).len());
+/// assert_eq!(1, document.cached_queries());
+/// ```
+///
+/// [`Selector`]: struct.Selector.html
+#[derive(Debug)]
+pub struct FrozenDocument {
+    root: Element,
+    /// Path from the root to the selected elements, by selector
+    cache: RefCell<HashMap<Selector, Vec<Vec<usize>>>>,
+}
+
+/// Record the path from the root to `element` and to its descendants for
+/// which `slots` has an entry.
+fn record_paths(
+    element: &Element,
+    path: &mut Vec<usize>,
+    slots: &HashMap<*const Element, usize>,
+    paths: &mut [Vec<usize>],
+) {
+    if let Some(slot) = slots.get(&(element as *const Element)) {
+        paths[*slot] = path.clone();
+    }
+    for (position, child) in element.children().enumerate() {
+        path.push(position);
+        record_paths(child, path, slots, paths);
+        path.pop();
+    }
+}
+
+impl FrozenDocument {
+    /// Freeze a document.
+    pub fn new(root: Element) -> Self {
+        FrozenDocument {
+            root,
+            cache: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// The root element of the document.
+    pub fn root(&self) -> &Element {
+        &self.root
+    }
+
+    /// Give back the root element, dropping the cache.
+    pub fn into_root(self) -> Element {
+        self.root
+    }
+
+    /// Select elements from the root of the document, using the cached result
+    /// if the same selector was already used.
+    pub fn query(&self, selector: &Selector) -> Vec<&Element> {
+        if let Some(paths) = self.cache.borrow().get(selector) {
+            return paths
+                .iter()
+                .map(|path| {
+                    path.iter().fold(&self.root, |element, position| {
+                        element
+                            .children()
+                            .nth(*position)
+                            .expect("the document is never modified")
+                    })
+                })
+                .collect();
+        }
+        let result = selector.select(&self.root);
+        let slots: HashMap<*const Element, usize> = result
+            .iter()
+            .enumerate()
+            .map(|(slot, element)| (*element as *const Element, slot))
+            .collect();
+        let mut paths = vec![Vec::new(); result.len()];
+        record_paths(&self.root, &mut Vec::new(), &slots, &mut paths);
+        self.cache.borrow_mut().insert(selector.clone(), paths);
+        result
+    }
+
+    /// Number of queries currently cached.
+    pub fn cached_queries(&self) -> usize {
+        self.cache.borrow().len()
+    }
+
+    /// Empty the cache.
+    pub fn clear_cache(&self) {
+        self.cache.borrow_mut().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn cached_results_are_identical() {
+        let xml: &'static str =
+            r#"<root><a><b id="1" /></a><a><b id="2" /><b id="3" /></a></root>"#;
+        let document = FrozenDocument::new(xml.parse().unwrap());
+        let selector = Selector::parse("a/b[@id]").unwrap();
+        let first = document.query(&selector);
+        let second = document.query(&selector);
+        assert_eq!(3, second.len());
+        assert!(first
+            .iter()
+            .zip(second.iter())
+            .all(|(a, b)| std::ptr::eq(*a, *b)));
+        document.clear_cache();
+        assert_eq!(0, document.cached_queries());
+    }
+}
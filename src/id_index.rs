@@ -0,0 +1,331 @@
+use crate::{suggest::attribute_not_found, Error, IdRef};
+use minidom::Element;
+use std::collections::{hash_map::Entry, HashMap};
+
+const REF: &str = "ref";
+
+/// How identifiers are made unique in an [`IdIndex`].
+///
+/// [`IdIndex`]: struct.IdIndex.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IdScope {
+    /// Identifiers are unique in the whole document
+    Document,
+    /// Identifiers are unique among the elements with the same (local) name,
+    /// so a `Line` and a `Route` can share the same identifier
+    ElementName,
+}
+
+/// An identifier used by several elements of the same scope.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DuplicateId<'a> {
+    /// The duplicated identifier
+    pub id: &'a str,
+    /// The element kept in the index (the first one in document order)
+    pub first: &'a Element,
+    /// Another element with the same identifier
+    pub duplicate: &'a Element,
+}
+
+/// An index of the elements of a document by identifier, to resolve
+/// references in constant time instead of scanning the tree.
+///
+/// The index is built in one pass over the tree. When an identifier is used
+/// several times in the same scope, the first element in document order is
+/// kept and the others are reported by [`duplicates`].
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{AttributeElementExt, IdIndex, IdRef};
+///
+/// enum Line {}
+///
+/// let xml: &'static str = r#"<root>
+///         <lines><Line id="line:1" name="Metro 1" /></lines>
+///         <journeys><Journey id="journey:1"><LineRef ref="line:1" /></Journey></journeys>
+///     </root>"#;
+/// let root: Element = xml.parse().unwrap();
+/// let index = IdIndex::new(&root);
+/// let journey = index.get("journey:1").unwrap();
+/// let line_ref = journey.children().next().unwrap();
+/// let line: IdRef<Line> = line_ref.try_attribute("ref").unwrap();
+/// assert_eq!(Some("Metro 1"), index.resolve(&line).unwrap().attr("name"));
+/// assert!(index.duplicates().is_empty());
+/// ```
+///
+/// [`duplicates`]: #method.duplicates
+#[derive(Debug, Clone)]
+pub struct IdIndex<'a> {
+    scope: IdScope,
+    /// Elements by scope (the element name, or an empty string for the
+    /// whole document) and by identifier
+    elements: HashMap<(&'a str, &'a str), &'a Element>,
+    /// Scopes in which each identifier is used, in document order
+    scopes: HashMap<&'a str, Vec<&'a str>>,
+    duplicates: Vec<DuplicateId<'a>>,
+}
+
+impl<'a> IdIndex<'a> {
+    /// Index the elements of `root` (including `root`) by their `id`
+    /// attribute, identifiers being unique in the whole document.
+    pub fn new(root: &'a Element) -> Self {
+        IdIndex::with_attribute(root, "id", IdScope::Document)
+    }
+
+    /// Index the elements of `root` (including `root`) by their `attr_name`
+    /// attribute, identifiers being unique in the given `scope`.
+    ///
+    /// Surrounding whitespaces are removed from the identifiers, as with
+    /// [`IdRef`], and elements with an empty identifier are not indexed.
+    ///
+    /// [`IdRef`]: struct.IdRef.html
+    pub fn with_attribute(root: &'a Element, attr_name: &str, scope: IdScope) -> Self {
+        let mut index = IdIndex {
+            scope,
+            elements: HashMap::new(),
+            scopes: HashMap::new(),
+            duplicates: Vec::new(),
+        };
+        index.insert(root, attr_name);
+        index
+    }
+
+    fn scope_of<'n>(&self, element_name: &'n str) -> &'n str {
+        match self.scope {
+            IdScope::Document => "",
+            IdScope::ElementName => element_name,
+        }
+    }
+
+    fn insert(&mut self, element: &'a Element, attr_name: &str) {
+        if let Some(id) = element.attr(attr_name).map(str::trim) {
+            if !id.is_empty() {
+                let scope = self.scope_of(element.name());
+                match self.elements.entry((scope, id)) {
+                    Entry::Occupied(first) => self.duplicates.push(DuplicateId {
+                        id,
+                        first: first.get(),
+                        duplicate: element,
+                    }),
+                    Entry::Vacant(entry) => {
+                        entry.insert(element);
+                        self.scopes.entry(id).or_default().push(scope);
+                    }
+                }
+            }
+        }
+        for child in element.children() {
+            self.insert(child, attr_name);
+        }
+    }
+
+    /// Names of the elements using the identifier `id` with the
+    /// [`ElementName`] scope (an empty string with the [`Document`] scope).
+    ///
+    /// [`Document`]: enum.IdScope.html#variant.Document
+    /// [`ElementName`]: enum.IdScope.html#variant.ElementName
+    pub(crate) fn scopes(&self, id: &str) -> &[&'a str] {
+        self.scopes.get(id.trim()).map_or(&[], Vec::as_slice)
+    }
+
+    /// Get the element with the identifier `id`.
+    ///
+    /// With the [`ElementName`] scope, `None` is returned when elements with
+    /// different names share the identifier, see [`get_scoped`].
+    ///
+    /// [`ElementName`]: enum.IdScope.html#variant.ElementName
+    /// [`get_scoped`]: #method.get_scoped
+    pub fn get(&self, id: &str) -> Option<&'a Element> {
+        let id = id.trim();
+        match self.scopes(id) {
+            [scope] => self.elements.get(&(*scope, id)).copied(),
+            _ => None,
+        }
+    }
+
+    /// Get the element named `element_name` with the identifier `id`.
+    pub fn get_scoped(&self, element_name: &str, id: &str) -> Option<&'a Element> {
+        self.elements
+            .get(&(self.scope_of(element_name), id.trim()))
+            .copied()
+            .filter(|element| element.name() == element_name)
+    }
+
+    /// Try to get the element with the identifier `id` and return a
+    /// [`Result`], an [`UnknownId`] error if no element can be found.
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`UnknownId`]: enum.Error.html#variant.UnknownId
+    pub fn try_get(&self, id: &str) -> Result<&'a Element, Error> {
+        self.get(id)
+            .ok_or_else(|| Error::UnknownId { id: id.to_owned() })
+    }
+
+    /// Get the element referenced by an [`IdRef`], like [`get`].
+    ///
+    /// With the [`ElementName`] scope, the name of the referenced element is
+    /// needed when elements with different names share the identifier, see
+    /// [`resolve_scoped`].
+    ///
+    /// [`ElementName`]: enum.IdScope.html#variant.ElementName
+    /// [`get`]: #method.get
+    /// [`IdRef`]: struct.IdRef.html
+    /// [`resolve_scoped`]: #method.resolve_scoped
+    pub fn resolve<T>(&self, id: &IdRef<T>) -> Option<&'a Element> {
+        self.get(id.as_str())
+    }
+
+    /// Get the element named `element_name` referenced by an [`IdRef`], like
+    /// [`get_scoped`].
+    ///
+    /// [`get_scoped`]: #method.get_scoped
+    /// [`IdRef`]: struct.IdRef.html
+    pub fn resolve_scoped<T>(&self, element_name: &str, id: &IdRef<T>) -> Option<&'a Element> {
+        self.get_scoped(element_name, id.as_str())
+    }
+
+    /// Try to get the element referenced by the `ref` attribute of `element`
+    /// and return a [`Result`].
+    ///
+    /// Returns an [`AttributeNotFound`] error if `element` has no `ref`
+    /// attribute, a [`DanglingReference`] error if no element has the
+    /// referenced identifier and, with the [`ElementName`] scope, an
+    /// [`AmbiguousReference`] error if elements with different names have it.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::IdIndex;
+    ///
+    /// let xml: &'static str = r#"<root>
+    ///         <Line id="line:1" />
+    ///         <LineRef ref="line:1" />
+    ///         <LineRef ref="line:2" />
+    ///     </root>"#;
+    /// let root: Element = xml.parse().unwrap();
+    /// let index = IdIndex::new(&root);
+    /// let mut line_refs = root.children().skip(1);
+    /// let line = index.try_resolve_ref(line_refs.next().unwrap()).unwrap();
+    /// assert_eq!("Line", line.name());
+    /// let error = index.try_resolve_ref(line_refs.next().unwrap()).unwrap_err();
+    /// assert_eq!(
+    ///     "Reference 'line:2' of element 'LineRef' doesn't match any identifier",
+    ///     error.to_string()
+    /// );
+    /// ```
+    ///
+    /// [`AmbiguousReference`]: enum.Error.html#variant.AmbiguousReference
+    /// [`AttributeNotFound`]: enum.Error.html#variant.AttributeNotFound
+    /// [`DanglingReference`]: enum.Error.html#variant.DanglingReference
+    /// [`ElementName`]: enum.IdScope.html#variant.ElementName
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    pub fn try_resolve_ref(&self, element: &Element) -> Result<&'a Element, Error> {
+        let ref_value = element
+            .attr(REF)
+            .ok_or_else(|| attribute_not_found(element, REF))?;
+        match self.scopes(ref_value) {
+            [] => Err(Error::DanglingReference {
+                ref_value: ref_value.to_owned(),
+                from_element: element.name().to_owned(),
+            }),
+            [scope] => Ok(self.elements[&(*scope, ref_value.trim())]),
+            scopes => Err(Error::AmbiguousReference {
+                ref_value: ref_value.to_owned(),
+                from_element: element.name().to_owned(),
+                element_names: scopes.iter().map(|scope| (*scope).to_owned()).collect(),
+            }),
+        }
+    }
+
+    /// Get the element referenced by the `ref` attribute of `element` if
+    /// present and resolved and return an [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    pub fn resolve_ref(&self, element: &Element) -> Option<&'a Element> {
+        self.try_resolve_ref(element).ok()
+    }
+
+    /// The identifiers used by several elements of the same scope, in
+    /// document order.
+    pub fn duplicates(&self) -> &[DuplicateId<'a>] {
+        &self.duplicates
+    }
+
+    /// Number of indexed identifiers.
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// The index has no identifier.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+    use pretty_assertions::assert_eq;
+
+    const XML: &str = r#"<root>
+            <Line id="1" name="Metro 1" />
+            <Route id="1" />
+            <Line id="1" name="Metro 1 bis" />
+            <Stop key=" s " />
+        </root>"#;
+
+    #[test]
+    fn document_scope() {
+        let root: Element = XML.parse().unwrap();
+        let index = IdIndex::new(&root);
+        assert_eq!(1, index.len());
+        assert_eq!(Some("Metro 1"), index.get("1").unwrap().attr("name"));
+        assert_eq!(None, index.get_scoped("Route", "1"));
+        let duplicates: Vec<&str> = index
+            .duplicates()
+            .iter()
+            .map(|duplicate| duplicate.duplicate.name())
+            .collect();
+        assert_eq!(vec!["Route", "Line"], duplicates);
+        let error = index.try_get("2").unwrap_err();
+        assert_eq!("No element with identifier '2'", format!("{}", error));
+    }
+
+    #[test]
+    fn missing_ref_attribute() {
+        let root: Element = XML.parse().unwrap();
+        let index = IdIndex::new(&root);
+        let line = root.children().next().unwrap();
+        let error = index.try_resolve_ref(line).unwrap_err();
+        assert_eq!(
+            "Failed to find attribute 'ref' in element 'Line' (available: id, name)",
+            format!("{}", error)
+        );
+        assert_eq!(None, index.resolve_ref(line));
+    }
+
+    #[test]
+    fn element_name_scope() {
+        let root: Element = XML.parse().unwrap();
+        let index = IdIndex::with_attribute(&root, "id", IdScope::ElementName);
+        assert_eq!(2, index.len());
+        assert_eq!(None, index.get("1"));
+        assert_eq!("Route", index.get_scoped("Route", "1").unwrap().name());
+        let route: IdRef<()> = IdRef::new(" 1 ");
+        assert_eq!(None, index.resolve(&route));
+        assert_eq!(
+            "Route",
+            index.resolve_scoped("Route", &route).unwrap().name()
+        );
+        assert_eq!(1, index.duplicates().len());
+        let line_ref: Element = r#"<LineRef ref="1" />"#.parse().unwrap();
+        let error = index.try_resolve_ref(&line_ref).unwrap_err();
+        assert_eq!(ErrorKind::AmbiguousReference, error.kind());
+        assert_eq!(
+            "Reference '1' of element 'LineRef' matches several elements (Line, Route)",
+            error.to_string()
+        );
+        let keys = IdIndex::with_attribute(&root, "key", IdScope::Document);
+        assert_eq!("Stop", keys.get("s").unwrap().name());
+    }
+}
@@ -0,0 +1,151 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    str::FromStr,
+};
+
+/// Error returned when an identifier is not valid.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum IdRefError {
+    /// The identifier is empty or only made of whitespaces.
+    #[error("Empty identifier")]
+    Empty,
+}
+
+/// An identifier of an element, tagged with a marker type.
+///
+/// References between elements (like the `ref` attribute of a `LineRef`)
+/// can be extracted as an `IdRef<Line>` instead of a `String`, so a line
+/// identifier cannot be mixed up with a stop identifier. The marker type is
+/// usually an empty type without any value. Surrounding whitespaces are
+/// removed and an empty identifier is an error.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{AttributeElementExt, IdRef};
+///
+/// enum Line {}
+///
+/// fn line_name(line: &IdRef<Line>) -> String {
+///     format!("Line {}", line)
+/// }
+///
+/// let xml: &'static str = r#"<LineRef ref=" line:1 " />"#;
+/// let line_ref: Element = xml.parse().unwrap();
+/// let line: IdRef<Line> = line_ref.try_attribute("ref").unwrap();
+/// assert_eq!("line:1", line.as_str());
+/// assert_eq!("Line line:1", line_name(&line));
+/// ```
+pub struct IdRef<T> {
+    id: String,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> IdRef<T> {
+    /// Tag an identifier, without any check.
+    pub fn new<S: Into<String>>(id: S) -> Self {
+        IdRef {
+            id: id.into(),
+            marker: PhantomData,
+        }
+    }
+
+    /// The identifier.
+    pub fn as_str(&self) -> &str {
+        &self.id
+    }
+
+    /// Convert into the identifier.
+    pub fn into_string(self) -> String {
+        self.id
+    }
+}
+
+impl<T> FromStr for IdRef<T> {
+    type Err = IdRefError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let id = s.trim();
+        if id.is_empty() {
+            Err(IdRefError::Empty)
+        } else {
+            Ok(IdRef::new(id))
+        }
+    }
+}
+
+impl<T> Clone for IdRef<T> {
+    fn clone(&self) -> Self {
+        IdRef::new(self.id.clone())
+    }
+}
+
+impl<T> PartialEq for IdRef<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for IdRef<T> {}
+
+impl<T> PartialOrd for IdRef<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for IdRef<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.id.cmp(&other.id)
+    }
+}
+
+impl<T> Hash for IdRef<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state)
+    }
+}
+
+impl<T> fmt::Debug for IdRef<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IdRef").field(&self.id).finish()
+    }
+}
+
+impl<T> fmt::Display for IdRef<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AttributeElementExt;
+    use minidom::Element;
+    use pretty_assertions::assert_eq;
+
+    enum Stop {}
+
+    #[test]
+    fn empty_identifier() {
+        let element: Element = r#"<StopRef ref="  " />"#.parse().unwrap();
+        match element.try_attribute::<IdRef<Stop>>("ref").unwrap_err() {
+            crate::Error::ParseError { source, .. } => {
+                assert_eq!("Empty identifier", source.to_string())
+            }
+            e => panic!("unexpected error {:?}", e),
+        }
+    }
+
+    #[test]
+    fn comparisons() {
+        let first: IdRef<Stop> = IdRef::new("stop:1");
+        assert_eq!(first, first.clone());
+        assert!(first < IdRef::new("stop:2"));
+        assert_eq!("IdRef(\"stop:1\")", format!("{:?}", first));
+        assert_eq!("stop:1", first.into_string());
+    }
+}
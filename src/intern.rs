@@ -0,0 +1,197 @@
+use minidom::Element;
+use std::{collections::HashSet, sync::Arc};
+
+/// Which attribute values an [`Interner`] shares.
+///
+/// [`Interner`]: struct.Interner.html
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+enum InternedValues {
+    #[default]
+    None,
+    All,
+    Of(HashSet<String>),
+}
+
+/// A pool of shared strings, where each distinct value is only stored once.
+///
+/// Names are always interned by [`InternedElement`], but attribute values
+/// only on opt-in, as interning the unique ones (like identifiers) costs
+/// more than it saves. This is useful for enumeration-like attributes
+/// repeating the same handful of values many times (like
+/// `TransportMode="bus"`), see [`with_values_of`].
+///
+/// [`InternedElement`]: struct.InternedElement.html
+/// [`with_values_of`]: #method.with_values_of
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    values: HashSet<Arc<str>>,
+    interned_values: InternedValues,
+}
+
+impl Interner {
+    /// Create an empty pool, only interning names.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Intern the values of all the attributes too.
+    pub fn with_all_values(mut self) -> Self {
+        self.interned_values = InternedValues::All;
+        self
+    }
+
+    /// Intern the values of the attributes named `attr_names` too.
+    pub fn with_values_of(mut self, attr_names: &[&str]) -> Self {
+        let mut names = match self.interned_values {
+            InternedValues::Of(names) => names,
+            _ => HashSet::new(),
+        };
+        names.extend(attr_names.iter().map(|name| (*name).to_owned()));
+        self.interned_values = InternedValues::Of(names);
+        self
+    }
+
+    /// Get the shared copy of `value`, adding it to the pool if needed.
+    pub fn intern(&mut self, value: &str) -> Arc<str> {
+        if let Some(interned) = self.values.get(value) {
+            return Arc::clone(interned);
+        }
+        let interned: Arc<str> = Arc::from(value);
+        self.values.insert(Arc::clone(&interned));
+        interned
+    }
+
+    /// Get the shared copy of the value of the attribute `attr_name` if its
+    /// values are interned, or an unshared copy otherwise.
+    pub fn intern_value(&mut self, attr_name: &str, value: &str) -> Arc<str> {
+        let interned = match &self.interned_values {
+            InternedValues::None => false,
+            InternedValues::All => true,
+            InternedValues::Of(names) => names.contains(attr_name),
+        };
+        if interned {
+            self.intern(value)
+        } else {
+            Arc::from(value)
+        }
+    }
+
+    /// Number of distinct values in the pool.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Check if the pool is empty.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// A read-only copy of an element tree where names, and the attribute values
+/// chosen by the [`Interner`], are interned, for analytics workloads keeping
+/// many elements in memory.
+///
+/// Text content is not kept. Unlike [`Element`], the view can be shared
+/// between threads.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{InternedElement, Interner};
+/// use std::sync::Arc;
+///
+/// let xml: &'static str = r#"<lines>
+///         <Line id="1" TransportMode="bus" />
+///         <Line id="2" TransportMode="bus" />
+///     </lines>"#;
+/// let root: Element = xml.parse().unwrap();
+/// let mut interner = Interner::new().with_values_of(&["TransportMode"]);
+/// let lines = InternedElement::new(&root, &mut interner);
+/// let modes: Vec<&Arc<str>> = lines
+///     .children()
+///     .iter()
+///     .filter_map(|line| line.attr("TransportMode"))
+///     .collect();
+/// assert!(Arc::ptr_eq(modes[0], modes[1]));
+/// ```
+///
+/// [`Element`]: ../minidom/element/struct.Element.html
+/// [`Interner`]: struct.Interner.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InternedElement {
+    name: Arc<str>,
+    attributes: Vec<(Arc<str>, Arc<str>)>,
+    children: Vec<InternedElement>,
+}
+
+impl InternedElement {
+    /// Copy the tree of `element`, interning names and the attribute values
+    /// chosen by `interner` in it.
+    pub fn new(element: &Element, interner: &mut Interner) -> Self {
+        InternedElement {
+            name: interner.intern(element.name()),
+            attributes: element
+                .attrs()
+                .map(|(name, value)| (interner.intern(name), interner.intern_value(name, value)))
+                .collect(),
+            children: element
+                .children()
+                .map(|child| InternedElement::new(child, interner))
+                .collect(),
+        }
+    }
+
+    /// The local name of the element.
+    pub fn name(&self) -> &Arc<str> {
+        &self.name
+    }
+
+    /// The value of an attribute, if present.
+    pub fn attr(&self, name: &str) -> Option<&Arc<str>> {
+        self.attributes
+            .iter()
+            .find(|(attr_name, _)| attr_name.as_ref() == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Iterate over the attributes, in the order of the original element.
+    pub fn attrs(&self) -> impl Iterator<Item = (&Arc<str>, &Arc<str>)> {
+        self.attributes.iter().map(|(name, value)| (name, value))
+    }
+
+    /// The child elements.
+    pub fn children(&self) -> &[InternedElement] {
+        &self.children
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn distinct_values() {
+        let xml: &'static str = r#"<root><a v="x" /><a v="y" /><b v="x" w="a" /></root>"#;
+        let root: Element = xml.parse().unwrap();
+        let mut interner = Interner::new();
+        let view = InternedElement::new(&root, &mut interner);
+        // root, a, b, v, w
+        assert_eq!(5, interner.len());
+        assert_eq!(Some("a"), view.children()[2].attr("w").map(AsRef::as_ref));
+        assert!(!Arc::ptr_eq(
+            view.children()[0].name(),
+            view.children()[2].attr("w").unwrap()
+        ));
+        let mut interner = Interner::new().with_values_of(&["v"]);
+        InternedElement::new(&root, &mut interner);
+        // and x, y
+        assert_eq!(7, interner.len());
+        let mut interner = Interner::new().with_all_values();
+        let view = InternedElement::new(&root, &mut interner);
+        assert_eq!(7, interner.len());
+        assert!(Arc::ptr_eq(
+            view.children()[0].name(),
+            view.children()[2].attr("w").unwrap()
+        ));
+    }
+}
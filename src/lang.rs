@@ -0,0 +1,188 @@
+use crate::ElementCursor;
+use minidom::Element;
+
+/// Check if a language tag matches a preferred language, either exactly or
+/// as a more specific tag (`fr-CA` matches `fr`), ignoring case.
+fn lang_matches(lang: &str, preferred: &str) -> bool {
+    lang.eq_ignore_ascii_case(preferred)
+        || (lang.len() > preferred.len()
+            && lang.as_bytes()[preferred.len()] == b'-'
+            && lang[..preferred.len()].eq_ignore_ascii_case(preferred))
+}
+
+/// A language tag followed by its less specific versions (`de-CH-1996`,
+/// `de-CH`, `de`), as in the lookup scheme of RFC 4647.
+fn truncations(lang: &str) -> impl Iterator<Item = &str> {
+    let mut end = Some(lang.len());
+    std::iter::from_fn(move || {
+        let current = &lang[..end?];
+        end = current.rfind('-');
+        Some(current)
+    })
+}
+
+/// The language of an element declared by its own `xml:lang`, `Some("")`
+/// meaning that the language is explicitly unknown.
+fn own_lang(element: &Element) -> Option<&str> {
+    element.attr("xml:lang")
+}
+
+/// Select the best element of `candidates` for the list of preferred
+/// languages (in order of preference).
+///
+/// Candidates without `xml:lang` are in the `inherited` language. If none
+/// matches, the first candidate without language is returned, or else the
+/// first candidate.
+fn select_by_lang<'a, I>(
+    candidates: I,
+    inherited: Option<&str>,
+    preferred: &[&str],
+) -> Option<&'a Element>
+where
+    I: Iterator<Item = &'a Element>,
+{
+    let candidates: Vec<(&Element, Option<&str>)> = candidates
+        .map(|candidate| {
+            let lang = own_lang(candidate)
+                .or(inherited)
+                .filter(|lang| !lang.is_empty());
+            (candidate, lang)
+        })
+        .collect();
+    preferred
+        .iter()
+        .flat_map(|preferred| truncations(preferred))
+        .find_map(|preferred| {
+            candidates.iter().find(|(_, lang)| {
+                lang.map(|lang| lang_matches(lang, preferred))
+                    .unwrap_or(false)
+            })
+        })
+        .or_else(|| candidates.iter().find(|(_, lang)| lang.is_none()))
+        .or_else(|| candidates.first())
+        .map(|(candidate, _)| *candidate)
+}
+
+/// Handle multilingual content marked with `xml:lang`.
+pub trait LangElementExt {
+    /// The language declared on the element itself with `xml:lang`.
+    ///
+    /// `xml:lang` is inherited from ancestors, see
+    /// [`ElementCursor::effective_lang`] to take them into account.
+    ///
+    /// [`ElementCursor::effective_lang`]: struct.ElementCursor.html#method.effective_lang
+    fn lang(&self) -> Option<&str>;
+
+    /// Select the child with the given name best matching the preferred
+    /// languages (in order of preference, like `["fr-FR", "fr", "en"]`).
+    ///
+    /// A language matches if it is the same or a more specific one (`fr-CA`
+    /// matches `fr`), and less specific versions of each preferred language
+    /// are tried before the next one (`fr-CA` then `fr`). Children without
+    /// `xml:lang` inherit the language of the element. If no child matches,
+    /// the first child without language is returned, or else the first child.
+    fn child_by_lang(&self, child_name: &str, preferred: &[&str]) -> Option<&Self>;
+}
+
+impl LangElementExt for Element {
+    /// Implementation of [`LangElementExt`] for [`Element`] picks among
+    /// repeated multilingual children.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::LangElementExt;
+    ///
+    /// let xml: &'static str = r#"<StopPlace xml:lang="en">
+    ///         <Name>North Station</Name>
+    ///         <Name xml:lang="fr">Gare du Nord</Name>
+    ///     </StopPlace>"#;
+    /// let stop: Element = xml.parse().unwrap();
+    /// let name = stop.child_by_lang("Name", &["de", "en"]).unwrap();
+    /// assert_eq!("North Station", name.text());
+    /// let name = stop.child_by_lang("Name", &["fr-FR", "en"]).unwrap();
+    /// assert_eq!("Gare du Nord", name.text());
+    /// ```
+    ///
+    /// [`LangElementExt`]: trait.LangElementExt.html
+    /// [`Element`]: ../minidom/element/struct.Element.html
+    fn lang(&self) -> Option<&str> {
+        own_lang(self)
+    }
+
+    fn child_by_lang(&self, child_name: &str, preferred: &[&str]) -> Option<&Self> {
+        select_by_lang(
+            self.children().filter(|child| child.name() == child_name),
+            own_lang(self),
+            preferred,
+        )
+    }
+}
+
+impl<'a> ElementCursor<'a> {
+    /// The language of the current element, declared with `xml:lang` on the
+    /// element itself or inherited from the closest ancestor declaring it.
+    ///
+    /// An empty `xml:lang=""` explicitly means that the language is unknown,
+    /// in which case `None` is returned.
+    pub fn effective_lang(&self) -> Option<&'a str> {
+        self.ancestors_or_self()
+            .find_map(own_lang)
+            .filter(|lang| !lang.is_empty())
+    }
+
+    /// Same as [`LangElementExt::child_by_lang`], taking into account the
+    /// language inherited from all the ancestors.
+    ///
+    /// [`LangElementExt::child_by_lang`]: trait.LangElementExt.html#tymethod.child_by_lang
+    pub fn child_by_lang(&self, child_name: &str, preferred: &[&str]) -> Option<ElementCursor<'a>> {
+        select_by_lang(
+            self.element()
+                .children()
+                .filter(|child| child.name() == child_name),
+            self.effective_lang(),
+            preferred,
+        )
+        .map(|child| self.child(child))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn matching_languages() {
+        assert!(lang_matches("fr", "fr"));
+        assert!(lang_matches("fr-CA", "FR"));
+        assert!(!lang_matches("fr", "fr-CA"));
+        assert!(!lang_matches("fra", "fr"));
+        assert_eq!(
+            vec!["de-CH-1996", "de-CH", "de"],
+            truncations("de-CH-1996").collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn inherited_lang() {
+        let xml: &'static str = r#"<root xml:lang="de">
+                <frame>
+                    <Name xml:lang="en">Main station</Name>
+                    <Name>Hauptbahnhof</Name>
+                </frame>
+                <frame xml:lang=""><Name /></frame>
+            </root>"#;
+        let root: Element = xml.parse().unwrap();
+        let cursor = ElementCursor::new(&root);
+        let mut frames = cursor.children();
+        let frame = frames.next().unwrap();
+        assert_eq!(Some("de"), frame.effective_lang());
+        let name = frame.child_by_lang("Name", &["de-AT"]).unwrap();
+        assert_eq!("Hauptbahnhof", name.element().text());
+        assert_eq!(Some("de"), name.effective_lang());
+        let name = frame.child_by_lang("Name", &["it"]).unwrap();
+        assert_eq!("Main station", name.element().text());
+        let frame = frames.next().unwrap();
+        assert_eq!(None, frame.effective_lang());
+    }
+}
@@ -4,59 +4,478 @@
 //!
 //! ## Description
 //! - [`OnlyChildElementExt`]: provides helper to extract one and only one child
-//!   of an [`Element`]
+//!   of an [`Element`] (or the first or last one when duplicates must be
+//!   tolerated), optionally tagged as an [`ElementRef`], and [`IdRef`] tags
+//!   the identifiers referencing elements, resolved by an [`IdIndex`];
+//!   [`validate_references`] reports all the dangling references at once
 //! - [`AttributeElementExt`]: provides helper to extract and parse into desired
-//!   type an attribute of an [`Element`]
+//!   type an attribute of an [`Element`], optionally namespaced (see
+//!   [`XSI_NS`] and [`XML_NS`] for well-known namespaces), and [`AttrValue`]
+//!   supports XML-specific representations like [`List`];
+//!   [`MutateElementExt`] writes typed values back, and replaces, removes,
+//!   creates or sorts children; [`MergeElementExt`] overlays a tree on top of
+//!   another one
+//! - [`TextElementExt`]: provides helper to parse into desired type the text
+//!   of an [`Element`]; attributes and texts can be cleaned before being
+//!   parsed by a [`Normalizer`], see [`ParseOptions`], and a [`TextIndex`]
+//!   finds the elements containing a term without rescanning the tree
+//! - [`ContactElementExt`]: provides lexical validation of emails, phone
+//!   numbers and URIs
+//! - [`FromElement`]: builds a typed value from an [`Element`], and
+//!   [`Dispatcher`] selects the right one depending on `xsi:type`; extension
+//!   points are preserved, ignored or rejected depending on an
+//!   [`ExtensionPolicy`] (see [`ExtensionElementExt`]), and the content
+//!   an extraction doesn't model can be kept as [`Extras`];
+//!   [`ChildrenMapElementExt`] parses children into a map keyed by one of
+//!   their attributes, and [`RequiredChildrenElementExt`] reports all the
+//!   missing, forbidden and unknown children of an element before its
+//!   extraction; [`xml_view!`] generates views with lazy, memoized typed
+//!   accessors, and [`xml!`] builds trees declaratively, while
+//!   [`ElementBuilderExt`] appends typed values (see [`IntoElement`]) to
+//!   `minidom`'s builder
+//! - [`Document`]: a parsed document keeping its XML declaration, the
+//!   comments and processing instructions around its root and the name of
+//!   its source (reported by the errors of the extractions run through it),
+//!   and dereferencing to its root [`Element`]
+//! - [`parse_fragments`]: parses concatenated fragments which are not
+//!   enclosed in a single root element
+//! - [`parse_tolerant`]: skips the byte order mark, blank lines or log
+//!   prefixes found before a document
+//! - [`ElementCursor`]: navigates a tree while remembering the ancestors (to
+//!   reach the parent, the siblings or the path of an element, to inherit
+//!   attributes or to locate errors in big documents), and [`LangElementExt`]
+//!   selects multilingual content using `xml:lang`; values extracted through
+//!   a cursor can be [`Traced`] back to their origin, and errors can be
+//!   located in the source of a [`PositionedDocument`]; [`ElemResultExt`]
+//!   attaches the parent elements to the errors of nested extraction code,
+//!   and [`Validator`] collects all the errors of a document instead of
+//!   failing at the first one, along with [`Finding`]s which may carry an
+//!   automatic fix, like the violations of declarative [`ValidationRules`]
+//!   or of cross-element business rules ([`Assertions`]), which
+//!   [`aggregate_findings`] groups by rule, element and attribute; errors have
+//!   stable codes and can be turned into machine-readable [`Diagnostic`]s
+//!   and flat [`ErrorReport`]s (serializable with the `serde` feature), or
+//!   rendered as reports with the `miette` feature
+//! - `UrlElementExt` (feature `url`): parses URL attributes and texts,
+//!   optionally resolved against a base, and
+//!   `ElementCursor::try_resolve_attribute_uri` resolves relative URIs
+//!   against the inherited `xml:base`; `Catalog` maps these URIs to local
+//!   files for offline pipelines
+//! - [`DaysOfWeek`], [`DateRange`] and [`XsDuration`]: calendar types
+//!   commonly found in attributes, to be used with [`AttributeElementExt`]
+//! - [`NameElementExt`]: provides helper to check the name and namespace of
+//!   an [`Element`]
+//! - [`NamespaceElementExt`]: provides helpers to resolve prefixes and list
+//!   namespace declarations of an [`Element`], and [`namespace_report`]
+//!   finds the unused declarations of a document; namespace URIs can be
+//!   kept as [`Ns`] handles, cheap to clone, compare and hash
+//! - [`pseudonymize_ids`]: replaces identifiers with deterministic pseudonyms
+//!   to derive test datasets from production files
+//! - [`shrink`]: reduces a document to a minimal one still reproducing a bug
+//! - [`CanonicalElementExt`]: serializes a subtree into a deterministic
+//!   canonical form, for hashing, caching and diff-friendly storage, and
+//!   hashes it cheaply to deduplicate subtrees or detect their changes, and
+//!   [`PrettyElementExt`] serializes it for humans, with the layout of
+//!   [`PrettyOptions`], while [`minify`] strips its insignificant
+//!   whitespaces
+//! - [`changed_elements`]: lists the records added, removed or modified
+//!   between two versions of a document, and [`compare_entities`] details
+//!   the changes of a single entity; [`diff`] lists all the structural
+//!   differences between two trees, and [`semantic_eq`] compares them
+//!   ignoring prefixes, attribute order, comments and indentation
+//! - [`Selector`]: selects elements with a small subset of XPath, and
+//!   [`FrozenDocument`] memoizes the results of selectors; `Selector::explain`
+//!   traces the matches of each step to debug empty results; [`path!`]
+//!   builds selectors checked at compile time; [`Materialize`] turns the
+//!   selected elements into owned copies detached from their document, and
+//!   [`ResultSet`] looks them up with the errors of [`OnlyChildElementExt`]
+//! - [`filter_stream`]: copies a document too big to be held in memory,
+//!   dropping the subtrees matching a selector, and [`StreamTransform`]
+//!   rewrites attributes while copying such a document
+//! - [`Transform`]: rewrites attributes of the elements matching selectors,
+//!   with a dry-run mode reporting the changes without applying them, and
+//!   [`repair`] applies safe corrections declared by [`RepairRules`]
+//! - [`InternedElement`]: a compact read-only copy of a tree, sharing the
+//!   repeated names (and attribute values on opt-in) through an
+//!   [`Interner`], and
+//!   [`DocumentStore`] shares the identical subtrees of many documents
+//! - [`preview`]: parses only the beginning of a huge document, tolerating
+//!   syntax errors, to show a quick peek of its content
+//! - [`Profile`]: lists the element names of a schema to detect selectors
+//!   which can never match, and describes them to render errors
+//!   understandable by end users
+//! - [`util`]: the building blocks of the extension traits (uniqueness
+//!   checks, parsing wrapped in the errors of the crate) to write helpers
+//!   of your own
+//! - `Base64ElementExt` (feature `base64`): decodes `xs:base64Binary`
+//!   attributes and texts
+//! - `DateTimeElementExt` (feature `chrono`): parses `xs:dateTime` and
+//!   `xs:date` attributes and texts into `chrono` types, and `XsFormat`
+//!   writes them back
+//! - `DecimalElementExt` (feature `rust_decimal`): parses `xs:decimal`
+//!   attributes and texts into exact decimals, tolerating a leading `+`
+//! - `digest` (feature `sha2`): hashes the canonical serialization of a
+//!   subtree to compute integrity checksums
+//! - `MoneyElementExt` (feature `money`, enabling `rust_decimal`): provides
+//!   helper to extract an exact amount and its ISO 4217 currency
+//! - `assert_xml_eq!` (feature `testing`): asserts that two trees are the
+//!   same for [`semantic_eq`], showing their first differences
+//! - `UuidElementExt` (feature `uuid`): parses UUID attributes and texts,
+//!   with or without braces, in any case
+//! - `XsdSchema` (feature `xsd`, which only gates code and adds no
+//!   dependency): compiles an XML Schema into validation rules, to validate
+//!   documents before processing them
 //!
 //! ## Examples
 //! Follow the links to see some example:
 //! - [`OnlyChildElementExt::try_find_only_child`]
 //! - [`OnlyChildElementExt::try_only_child`]
 //! - [`AttributeElementExt::try_attribute`]
+//! - [`AttributeElementExt::try_attribute_ns`]
+//! - [`TextElementExt::try_text`]
 //!
+//! [`aggregate_findings`]: fn.aggregate_findings.html
+//! [`Assertions`]: struct.Assertions.html
+//! [`AttrValue`]: trait.AttrValue.html
 //! [`AttributeElementExt`]: trait.AttributeElementExt.html
+//! [`CanonicalElementExt`]: trait.CanonicalElementExt.html
+//! [`changed_elements`]: fn.changed_elements.html
+//! [`compare_entities`]: fn.compare_entities.html
+//! [`ChildrenMapElementExt`]: trait.ChildrenMapElementExt.html
+//! [`ContactElementExt`]: trait.ContactElementExt.html
+//! [`DateRange`]: struct.DateRange.html
+//! [`DaysOfWeek`]: struct.DaysOfWeek.html
+//! [`Diagnostic`]: struct.Diagnostic.html
+//! [`diff`]: fn.diff.html
+//! [`Dispatcher`]: struct.Dispatcher.html
+//! [`Document`]: struct.Document.html
+//! [`DocumentStore`]: struct.DocumentStore.html
+//! [`ElemResultExt`]: trait.ElemResultExt.html
 //! [`Element`]: ../minidom/element/struct.Element.html
+//! [`ElementBuilderExt`]: trait.ElementBuilderExt.html
+//! [`ElementCursor`]: struct.ElementCursor.html
+//! [`ElementRef`]: struct.ElementRef.html
+//! [`ErrorReport`]: struct.ErrorReport.html
+//! [`ExtensionElementExt`]: trait.ExtensionElementExt.html
+//! [`ExtensionPolicy`]: enum.ExtensionPolicy.html
+//! [`Extras`]: struct.Extras.html
+//! [`filter_stream`]: fn.filter_stream.html
+//! [`Finding`]: struct.Finding.html
+//! [`FrozenDocument`]: struct.FrozenDocument.html
+//! [`FromElement`]: trait.FromElement.html
+//! [`IdIndex`]: struct.IdIndex.html
+//! [`IdRef`]: struct.IdRef.html
+//! [`IntoElement`]: trait.IntoElement.html
+//! [`InternedElement`]: struct.InternedElement.html
+//! [`Interner`]: struct.Interner.html
+//! [`LangElementExt`]: trait.LangElementExt.html
+//! [`List`]: struct.List.html
+//! [`Materialize`]: trait.Materialize.html
+//! [`MergeElementExt`]: trait.MergeElementExt.html
 //! [`minidom`]: ../minidom/index.html
+//! [`minify`]: fn.minify.html
+//! [`MutateElementExt`]: trait.MutateElementExt.html
+//! [`NameElementExt`]: trait.NameElementExt.html
+//! [`namespace_report`]: fn.namespace_report.html
+//! [`NamespaceElementExt`]: trait.NamespaceElementExt.html
+//! [`Normalizer`]: struct.Normalizer.html
+//! [`Ns`]: struct.Ns.html
 //! [`OnlyChildElementExt`]: trait.OnlyChildElementExt.html
 //! [`OnlyChildElementExt::try_find_only_child`]: trait.OnlyChildElementExt.html#impl-OnlyChildElementExt-for-Element
 //! [`OnlyChildElementExt::try_only_child`]: trait.OnlyChildElementExt.html#impl-OnlyChildElementExt-for-Element
 //! [`AttributeElementExt::try_attribute`]: trait.AttributeElementExt.html#impl-AttributeElementExt-for-Element
+//! [`AttributeElementExt::try_attribute_ns`]: trait.AttributeElementExt.html#impl-AttributeElementExt-for-Element
+//! [`parse_fragments`]: fn.parse_fragments.html
+//! [`parse_tolerant`]: fn.parse_tolerant.html
+//! [`ParseOptions`]: struct.ParseOptions.html
+//! [`path!`]: macro.path.html
+//! [`PositionedDocument`]: struct.PositionedDocument.html
+//! [`preview`]: fn.preview.html
+//! [`PrettyElementExt`]: trait.PrettyElementExt.html
+//! [`PrettyOptions`]: struct.PrettyOptions.html
+//! [`Profile`]: struct.Profile.html
+//! [`pseudonymize_ids`]: fn.pseudonymize_ids.html
+//! [`repair`]: fn.repair.html
+//! [`RepairRules`]: struct.RepairRules.html
+//! [`RequiredChildrenElementExt`]: trait.RequiredChildrenElementExt.html
+//! [`ResultSet`]: struct.ResultSet.html
+//! [`Selector`]: struct.Selector.html
+//! [`semantic_eq`]: fn.semantic_eq.html
+//! [`shrink`]: fn.shrink.html
+//! [`StreamTransform`]: struct.StreamTransform.html
+//! [`Traced`]: struct.Traced.html
+//! [`Transform`]: struct.Transform.html
+//! [`TextElementExt`]: trait.TextElementExt.html
+//! [`TextElementExt::try_text`]: trait.TextElementExt.html#impl-TextElementExt-for-Element
+//! [`TextIndex`]: struct.TextIndex.html
+//! [`validate_references`]: fn.validate_references.html
+//! [`ValidationRules`]: struct.ValidationRules.html
+//! [`util`]: util/index.html
+//! [`Validator`]: struct.Validator.html
+//! [`XsDuration`]: struct.XsDuration.html
+//! [`XML_NS`]: constant.XML_NS.html
+//! [`xml!`]: macro.xml.html
+//! [`xml_view!`]: macro.xml_view.html
+//! [`XSI_NS`]: constant.XSI_NS.html
 
+mod assertion;
+pub use assertion::{Assertion, Assertions, Test};
 mod attribute;
+#[cfg(feature = "url")]
+mod base;
 pub use attribute::AttributeElementExt;
+#[cfg(feature = "url")]
+pub use base::UrlElementExt;
+#[cfg(feature = "base64")]
+mod binary;
+#[cfg(feature = "base64")]
+pub use binary::Base64ElementExt;
+mod builder;
+pub use builder::{ElementBuilderExt, IntoElement};
+mod calendar;
+#[cfg(feature = "url")]
+mod catalog;
+#[cfg(feature = "url")]
+pub use catalog::{Catalog, CATALOG_NS};
+mod canonical;
+pub use calendar::{CalendarError, Date, DateRange, DaysOfWeek, Weekday};
+pub use canonical::CanonicalElementExt;
+mod changes;
+pub use changes::{
+    changed_elements, compare_entities, AttributeChange, ChildChanges, ElementChanges, EntityDiff,
+};
+mod children_map;
+pub use children_map::ChildrenMapElementExt;
+mod contact;
+pub use contact::{ContactElementExt, ContactError, Email, PhoneNumber, StrictUri};
+mod context;
+pub use context::ElemResultExt;
+mod cursor;
+pub use cursor::ElementCursor;
+#[cfg(feature = "chrono")]
+mod datetime;
+#[cfg(feature = "chrono")]
+pub use datetime::{DateTimeElementExt, DateTimeError};
+#[cfg(feature = "rust_decimal")]
+mod decimal;
+#[cfg(feature = "rust_decimal")]
+pub use decimal::DecimalElementExt;
+mod diff;
+pub use diff::{diff, diff_with, DiffOptions, Difference};
+#[cfg(feature = "sha2")]
+mod digest;
+#[cfg(feature = "sha2")]
+pub use digest::{digest, DigestAlgorithm};
+mod diagnostic;
+pub use diagnostic::{Diagnostic, ErrorReport};
+mod dispatch;
+pub use dispatch::Dispatcher;
+mod document;
+pub use document::{Document, MiscNode, XmlDeclaration};
+mod duration;
+pub use duration::{DurationError, XsDuration};
+mod element_ref;
+pub use element_ref::ElementRef;
+mod extension;
+pub use extension::{ExtensionElementExt, ExtensionPolicy, Extras};
+mod finding;
+pub use finding::{aggregate_findings, Edit, Finding, FindingGroup, Severity};
+mod fragment;
+pub use fragment::{parse_fragments, parse_fragments_with_root};
+mod from_element;
+pub use from_element::FromElement;
+mod frozen;
+pub use frozen::FrozenDocument;
+mod id_index;
+pub use id_index::{DuplicateId, IdIndex, IdScope};
+mod id_ref;
+pub use id_ref::{IdRef, IdRefError};
+mod intern;
+pub use intern::{InternedElement, Interner};
+mod lang;
+pub use lang::LangElementExt;
+mod materialize;
+pub use materialize::Materialize;
+mod merge;
+pub use merge::{MergeElementExt, MergeStrategy};
+mod minify;
+pub use minify::minify;
+#[cfg(feature = "money")]
+mod money;
+#[cfg(feature = "money")]
+pub use money::{Currency, MoneyElementExt, MoneyError};
+mod mutate;
+pub use mutate::MutateElementExt;
+mod name;
+pub use name::NameElementExt;
+mod namespace;
+pub use namespace::{NamespaceElementExt, QName, QNameError, XMLNS_NS, XML_NS, XSI_NS};
+mod namespace_usage;
+pub use namespace_usage::{
+    namespace_report, remove_unused_ns_decls, NamespaceDeclaration, NamespaceReport,
+};
+mod normalize;
+pub use normalize::{Normalizer, ParseOptions, WithOptions};
+mod ns;
+pub use ns::Ns;
 mod only_child;
-pub use only_child::OnlyChildElementExt;
+pub use only_child::{DuplicateChildWarning, DuplicatePolicy, OnlyChildElementExt};
+mod path;
+#[doc(hidden)]
+pub mod __path {
+    pub use crate::path::step;
+}
+mod position;
+pub use position::{Position, PositionedDocument};
+mod pretty;
+pub use pretty::{PrettyElementExt, PrettyOptions};
+mod preview;
+pub use preview::{preview, preview_file, Preview};
+mod profile;
+pub use profile::{LintWarning, Profile};
+mod provenance;
+pub use provenance::{Provenance, Traced};
+mod pseudonymize;
+pub use pseudonymize::pseudonymize_ids;
+mod references;
+pub use references::{validate_references, ReferenceConfig, UnresolvedReference};
+mod repair;
+pub use repair::{repair, Repair, RepairPolicy, RepairRules};
+mod required_children;
+pub use required_children::RequiredChildrenElementExt;
+mod result_set;
+pub use result_set::ResultSet;
+mod sanitize;
+pub use sanitize::ControlCharacters;
+mod scratch;
+mod selector;
+pub use selector::{Axis, Explanation, NameTest, Predicate, Selector, Step, StepExplanation};
+mod semantic;
+pub use semantic::semantic_eq;
+mod shrink;
+pub use shrink::shrink;
+mod store;
+pub use store::{DocumentStore, StoredElement, StoredNode};
+mod stream;
+pub use stream::{filter_stream, StreamTransform};
+mod suggest;
+#[cfg(feature = "testing")]
+mod testing;
+#[cfg(feature = "testing")]
+#[doc(hidden)]
+pub mod __testing {
+    pub use crate::testing::{assert_xml_eq, XmlOperand};
+}
+mod text;
+pub use text::TextElementExt;
+mod text_index;
+pub use text_index::TextIndex;
+mod tolerant;
+pub use tolerant::{parse_tolerant, LeadingJunk, SkippedJunk, TolerantDocument};
+mod transform;
+pub use transform::{Modification, Transform};
+mod tree;
+#[cfg(feature = "uuid")]
+mod uuid;
+#[cfg(feature = "uuid")]
+pub use crate::uuid::UuidElementExt;
+pub mod util;
+mod validate;
+pub use validate::{ElementRules, ValidationRules};
+mod validator;
+pub use validator::Validator;
+mod value;
+pub use value::{
+    AttrValue, HexBinary, HexError, List, ListError, XsBoolean, XsBooleanError, XsEnumeration,
+    XsFormat,
+};
+mod view;
+#[doc(hidden)]
+pub mod __view {
+    pub use minidom::Element;
+}
+mod xml;
+#[doc(hidden)]
+pub mod __xml {
+    pub use minidom::Element;
+}
+#[cfg(feature = "xsd")]
+mod xsd;
+#[cfg(feature = "xsd")]
+pub use xsd::{XsdSchema, XS_NS};
 
 use thiserror::Error;
 
 /// Error type for `minidom_ext`
+///
+/// New variants may be added in future versions, use [`kind`] to match on
+/// the kind of error without depending on the exact variants.
+///
+/// [`kind`]: #method.kind
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum Error {
     /// Returned when the attribute could not be found by name.
-    /// First parameter is the element's name, second parameter is
-    /// attribute's name.
-    #[error("Failed to find attribute '{1}' in element '{0}'")]
-    AttributeNotFound(String, String),
+    #[error("Failed to find attribute '{attribute_name}' in element '{element_name}'{}{}", suggest::did_you_mean(.suggestion), suggest::available(.available))]
+    AttributeNotFound {
+        /// Element's name
+        element_name: String,
+        /// Attribute's name
+        attribute_name: String,
+        /// The closest attribute of the element, if close enough to be a
+        /// typo or a casing mistake
+        suggestion: Option<String>,
+        /// Attributes of the element (at most 10), empty if unknown
+        available: Vec<String>,
+    },
     /// Returned when no children can be matched with the predicate.
-    /// First parameter is the element's name.
-    #[error("No children matching predicate found in Element '{0}'")]
-    NoChildrenFound(String),
+    #[error("No children matching predicate found in Element '{element_name}'")]
+    NoChildrenFound {
+        /// Element's name
+        element_name: String,
+    },
     /// Returned when no children can be matched with the expected child's name.
-    /// First parameter is the element's name, second parameter is the child's
-    /// name.
-    #[error("No children with name '{1}' in Element '{0}'")]
-    NoChildren(String, String),
+    #[error("No children with name '{child_name}' in Element '{element_name}'{}{}", suggest::did_you_mean(.suggestion), suggest::available(.available))]
+    NoChildren {
+        /// Element's name
+        element_name: String,
+        /// Expected child's name
+        child_name: String,
+        /// The closest child name of the element, if close enough to be a
+        /// typo or a casing mistake
+        suggestion: Option<String>,
+        /// Names of the children of the element (at most 10), empty if
+        /// unknown
+        available: Vec<String>,
+    },
     /// Returned when multiple children can be matched with the predicate.
-    /// First parameter is the element's name, second parameter is the number of
-    /// matching children found.
-    #[error("Multiple children matching predicate found in Element '{0}' (found {1} elements)")]
-    MultipleChildrenFound(String, usize),
+    #[error("Multiple children matching predicate found in Element '{element_name}' (found {count} elements)")]
+    MultipleChildrenFound {
+        /// Element's name
+        element_name: String,
+        /// Number of matching children found
+        count: usize,
+    },
     /// Returned when multiple children can be matched with the expected child's
     /// name.
-    /// First parameter is the element's name, second parameter is the child's
-    /// name, third parameter is the number of matching children found.
-    #[error("Multiple children with name '{1}' in Element '{0}' (found {2} elements)")]
-    MultipleChildren(String, String, usize),
+    #[error("Multiple children with name '{child_name}' in Element '{element_name}' (found {count} elements)")]
+    MultipleChildren {
+        /// Element's name
+        element_name: String,
+        /// Expected child's name
+        child_name: String,
+        /// Number of matching children found
+        count: usize,
+    },
+    /// Returned when an element doesn't have the expected name or namespace.
+    /// Names are formatted as `{namespace}name`.
+    #[error("Expected element '{expected}' but found '{found}'")]
+    UnexpectedElement {
+        /// Expected name of the element
+        expected: String,
+        /// Actual name of the element
+        found: String,
+    },
     /// Returned when the attribute cannot be parsed or convert into the
     /// expected type.
     #[error("Failed to parse and convert the value '{value}' of attribute '{attribute_name}' in element '{element_name}'")]
@@ -75,6 +494,364 @@ pub enum Error {
         /// [`i64`]: https://doc.rust-lang.org/std/primitive.i64.html
         /// [`ParseIntError`]: https://doc.rust-lang.org/std/num/struct.ParseIntError.html
         #[source]
-        source: anyhow::Error,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    /// Returned when no parser is registered for an element.
+    #[error("No parser registered for element '{element_name}' with type '{type_name}'")]
+    NoParserFound {
+        /// Element's name
+        element_name: String,
+        /// Element's `xsi:type` (or its name if it has no `xsi:type`)
+        type_name: String,
+    },
+    /// Returned when a value is expected from an element marked with
+    /// `xsi:nil="true"`.
+    #[error("Element '{element_name}' is nil")]
+    NilElement {
+        /// Element's name
+        element_name: String,
+    },
+    /// Returned when a selector cannot be compiled.
+    #[error("Invalid selector '{selector}': {reason}")]
+    InvalidSelector {
+        /// The selector, as written
+        selector: String,
+        /// Why the selector is not valid
+        reason: String,
+    },
+    /// Returned when no element has the given identifier.
+    #[error("No element with identifier '{id}'")]
+    UnknownId {
+        /// The identifier
+        id: String,
+    },
+    /// Returned when a reference doesn't match the identifier of any element.
+    #[error("Reference '{ref_value}' of element '{from_element}' doesn't match any identifier")]
+    DanglingReference {
+        /// The value of the reference
+        ref_value: String,
+        /// Name of the element holding the reference
+        from_element: String,
+    },
+    /// Returned when a reference matches elements with different names
+    /// sharing the identifier, with the [`IdScope::ElementName`] scope.
+    ///
+    /// [`IdScope::ElementName`]: enum.IdScope.html#variant.ElementName
+    #[error(
+        "Reference '{ref_value}' of element '{from_element}' matches several elements ({})",
+        .element_names.join(", ")
+    )]
+    AmbiguousReference {
+        /// The value of the reference
+        ref_value: String,
+        /// Name of the element holding the reference
+        from_element: String,
+        /// Names of the elements with the referenced identifier
+        element_names: Vec<String>,
+    },
+    /// Wraps an error with the path of the element it is about, see
+    /// [`ElementCursor::try_with`] and [`ElemResultExt`].
+    ///
+    /// [`ElementCursor::try_with`]: struct.ElementCursor.html#method.try_with
+    /// [`ElemResultExt`]: trait.ElemResultExt.html
+    #[error("In {path}: {source}")]
+    WithPath {
+        /// Path of the element, like `root > frame[2] > Line[1]`
+        path: String,
+        /// The original error
+        #[source]
+        source: Box<Error>,
+    },
+    /// Wraps an error with the position of the element it is about in the
+    /// source of the document, see [`PositionedDocument`].
+    ///
+    /// [`PositionedDocument`]: struct.PositionedDocument.html
+    #[error("At {position}: {source}")]
+    WithPosition {
+        /// Position of the element
+        position: Position,
+        /// The original error
+        #[source]
+        source: Box<Error>,
+    },
+    /// Wraps an error with the name of the source of the document (a file
+    /// path or a logical name), see [`Document`].
+    ///
+    /// [`Document`]: struct.Document.html
+    #[error("{source_name}: {source}")]
+    WithSourceName {
+        /// Name of the source of the document
+        source_name: String,
+        /// The original error
+        #[source]
+        source: Box<Error>,
+    },
+    /// Returned when a document cannot be read.
+    #[error("Failed to read the document: {source}")]
+    InvalidDocument {
+        /// The original error
+        #[source]
+        source: minidom::Error,
     },
+    /// Returned when a document starts with content which is not allowed to
+    /// be skipped, see [`LeadingJunk`].
+    ///
+    /// [`LeadingJunk`]: enum.LeadingJunk.html
+    #[error("Unexpected content '{content}' before the beginning of the document")]
+    UnexpectedLeadingContent {
+        /// The unexpected content
+        content: String,
+    },
+    /// Returned when a forbidden control character is found in a value, see
+    /// [`ControlCharacters`].
+    ///
+    /// [`ControlCharacters`]: enum.ControlCharacters.html
+    #[error("Forbidden control character U+{code_point:04X} at byte {position} of {location}")]
+    ControlCharacter {
+        /// Where the value comes from (attribute or text of an element)
+        location: String,
+        /// Code point of the control character
+        code_point: u32,
+        /// Position of the control character in the value, in bytes
+        position: usize,
+    },
+    /// Returned when an extension point is found while extensions are
+    /// rejected, see [`ExtensionPolicy`].
+    ///
+    /// [`ExtensionPolicy`]: enum.ExtensionPolicy.html
+    #[error("Unexpected extension '{extension_name}' in element '{element_name}'")]
+    UnexpectedExtension {
+        /// Element's name
+        element_name: String,
+        /// Name of the extension element
+        extension_name: String,
+    },
+    /// Returned when the text of an element cannot be parsed or convert into
+    /// the expected type.
+    #[error("Failed to parse and convert the text '{value}' of element '{element_name}'")]
+    TextParseError {
+        /// Element's name
+        element_name: String,
+        /// Text of the element
+        value: String,
+        /// Original parsing error. The specific type depends on what type the
+        /// text is parsed into.
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    /// Returned when two children have the same key, see
+    /// [`ChildrenMapElementExt`].
+    ///
+    /// [`ChildrenMapElementExt`]: trait.ChildrenMapElementExt.html
+    #[error("Duplicate key '{key}' of attribute '{attribute_name}' for children with name '{child_name}' in Element '{element_name}'")]
+    DuplicateKey {
+        /// Element's name
+        element_name: String,
+        /// Children's name
+        child_name: String,
+        /// Name of the attribute holding the key
+        attribute_name: String,
+        /// Value of the duplicate key
+        key: String,
+    },
+    /// Returned when an element has a child it must not have, see
+    /// [`RequiredChildrenElementExt::try_forbid_children`].
+    ///
+    /// [`RequiredChildrenElementExt::try_forbid_children`]: trait.RequiredChildrenElementExt.html#tymethod.try_forbid_children
+    #[error("Forbidden child '{child_name}' in Element '{element_name}' (found {count} elements)")]
+    ForbiddenChild {
+        /// Element's name
+        element_name: String,
+        /// Forbidden child's name
+        child_name: String,
+        /// Number of forbidden children found
+        count: usize,
+    },
+    /// Returned when an element has children whose name is not expected, see
+    /// [`RequiredChildrenElementExt::try_deny_unknown_children`].
+    ///
+    /// [`RequiredChildrenElementExt::try_deny_unknown_children`]: trait.RequiredChildrenElementExt.html#tymethod.try_deny_unknown_children
+    #[error("Unknown child '{child_name}' in Element '{element_name}' (found {count} elements)")]
+    UnknownChild {
+        /// Element's name
+        element_name: String,
+        /// Unknown child's name
+        child_name: String,
+        /// Number of children with this name
+        count: usize,
+    },
+    /// Returned when the value of an attribute is not one of the allowed
+    /// values, see [`AttributeElementExt::try_attribute_enum`].
+    ///
+    /// [`AttributeElementExt::try_attribute_enum`]: trait.AttributeElementExt.html#tymethod.try_attribute_enum
+    #[error("Value '{value}' of attribute '{attribute_name}' in element '{element_name}' is not one of: {}", .allowed.join(", "))]
+    UnexpectedValue {
+        /// Element's name
+        element_name: String,
+        /// Attribute's name
+        attribute_name: String,
+        /// Value of the attribute
+        value: String,
+        /// The allowed values
+        allowed: Vec<String>,
+    },
+    /// Returned when two elements merged with [`MergeStrategy::Error`] have
+    /// different values for the same attribute or different texts, see
+    /// [`MergeElementExt::merge_from`].
+    ///
+    /// [`MergeElementExt::merge_from`]: trait.MergeElementExt.html#tymethod.merge_from
+    /// [`MergeStrategy::Error`]: enum.MergeStrategy.html#variant.Error
+    #[error("Conflicting values '{value}' and '{other_value}' for {} of element '{element_name}'",
+        .attribute_name.as_ref().map_or_else(|| "the text".to_owned(), |name| format!("attribute '{}'", name)))]
+    MergeConflict {
+        /// Element's name
+        element_name: String,
+        /// Attribute's name, or `None` for the text of the element
+        attribute_name: Option<String>,
+        /// Value in the element merged into
+        value: String,
+        /// Value in the element merged from
+        other_value: String,
+    },
+    /// Appended to the errors of a [`Validator`] which kept only some of them,
+    /// see [`Validator::with_max_findings`].
+    ///
+    /// [`Validator`]: struct.Validator.html
+    /// [`Validator::with_max_findings`]: struct.Validator.html#method.with_max_findings
+    #[error("{count} more findings omitted")]
+    Omitted {
+        /// Number of errors and findings counted but not kept
+        count: usize,
+    },
+    /// Returned when a URI is not mapped to any local file, see `Catalog`.
+    #[error("No catalog entry for URI '{uri}'")]
+    UnmappedUri {
+        /// The URI
+        uri: String,
+    },
+    /// Returned when the local copy of a resource cannot be read, see
+    /// `Catalog`.
+    #[error("Failed to read resource '{uri}' from '{}': {source}", .path.display())]
+    UnreadableResource {
+        /// The URI of the resource
+        uri: String,
+        /// The local file the URI is mapped to
+        path: std::path::PathBuf,
+        /// The original error
+        #[source]
+        source: minidom::Error,
+    },
+}
+
+/// The kind of an [`Error`], see [`Error::kind`].
+///
+/// [`Error`]: enum.Error.html
+/// [`Error::kind`]: enum.Error.html#method.kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// See [`Error::AttributeNotFound`](enum.Error.html#variant.AttributeNotFound)
+    AttributeNotFound,
+    /// See [`Error::NoChildrenFound`](enum.Error.html#variant.NoChildrenFound)
+    NoChildrenFound,
+    /// See [`Error::NoChildren`](enum.Error.html#variant.NoChildren)
+    NoChildren,
+    /// See [`Error::MultipleChildrenFound`](enum.Error.html#variant.MultipleChildrenFound)
+    MultipleChildrenFound,
+    /// See [`Error::MultipleChildren`](enum.Error.html#variant.MultipleChildren)
+    MultipleChildren,
+    /// See [`Error::UnexpectedElement`](enum.Error.html#variant.UnexpectedElement)
+    UnexpectedElement,
+    /// See [`Error::ParseError`](enum.Error.html#variant.ParseError)
+    ParseError,
+    /// See [`Error::NoParserFound`](enum.Error.html#variant.NoParserFound)
+    NoParserFound,
+    /// See [`Error::NilElement`](enum.Error.html#variant.NilElement)
+    NilElement,
+    /// See [`Error::InvalidSelector`](enum.Error.html#variant.InvalidSelector)
+    InvalidSelector,
+    /// See [`Error::UnknownId`](enum.Error.html#variant.UnknownId)
+    UnknownId,
+    /// See [`Error::DanglingReference`](enum.Error.html#variant.DanglingReference)
+    DanglingReference,
+    /// See [`Error::InvalidDocument`](enum.Error.html#variant.InvalidDocument)
+    InvalidDocument,
+    /// See [`Error::UnexpectedLeadingContent`](enum.Error.html#variant.UnexpectedLeadingContent)
+    UnexpectedLeadingContent,
+    /// See [`Error::ControlCharacter`](enum.Error.html#variant.ControlCharacter)
+    ControlCharacter,
+    /// See [`Error::UnexpectedExtension`](enum.Error.html#variant.UnexpectedExtension)
+    UnexpectedExtension,
+    /// See [`Error::TextParseError`](enum.Error.html#variant.TextParseError)
+    TextParseError,
+    /// See [`Error::DuplicateKey`](enum.Error.html#variant.DuplicateKey)
+    DuplicateKey,
+    /// See [`Error::UnmappedUri`](enum.Error.html#variant.UnmappedUri)
+    UnmappedUri,
+    /// See [`Error::UnreadableResource`](enum.Error.html#variant.UnreadableResource)
+    UnreadableResource,
+    /// See [`Error::ForbiddenChild`](enum.Error.html#variant.ForbiddenChild)
+    ForbiddenChild,
+    /// See [`Error::UnexpectedValue`](enum.Error.html#variant.UnexpectedValue)
+    UnexpectedValue,
+    /// See [`Error::UnknownChild`](enum.Error.html#variant.UnknownChild)
+    UnknownChild,
+    /// See [`Error::MergeConflict`](enum.Error.html#variant.MergeConflict)
+    MergeConflict,
+    /// See [`Error::AmbiguousReference`](enum.Error.html#variant.AmbiguousReference)
+    AmbiguousReference,
+    /// See [`Error::Omitted`](enum.Error.html#variant.Omitted)
+    Omitted,
+}
+
+impl Error {
+    /// The kind of the error.
+    ///
+    /// [`WithPath`] and [`WithPosition`] errors have the kind of the error
+    /// they wrap.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::{ElemResultExt, ErrorKind, OnlyChildElementExt};
+    ///
+    /// let root: Element = r#"<root />"#.parse().unwrap();
+    /// let error = root.try_only_child("Line").ctx(&root).unwrap_err();
+    /// assert_eq!(ErrorKind::NoChildren, error.kind());
+    /// ```
+    ///
+    /// [`WithPath`]: #variant.WithPath
+    /// [`WithPosition`]: #variant.WithPosition
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::AttributeNotFound { .. } => ErrorKind::AttributeNotFound,
+            Error::NoChildrenFound { .. } => ErrorKind::NoChildrenFound,
+            Error::NoChildren { .. } => ErrorKind::NoChildren,
+            Error::MultipleChildrenFound { .. } => ErrorKind::MultipleChildrenFound,
+            Error::MultipleChildren { .. } => ErrorKind::MultipleChildren,
+            Error::UnexpectedElement { .. } => ErrorKind::UnexpectedElement,
+            Error::ParseError { .. } => ErrorKind::ParseError,
+            Error::NoParserFound { .. } => ErrorKind::NoParserFound,
+            Error::NilElement { .. } => ErrorKind::NilElement,
+            Error::InvalidSelector { .. } => ErrorKind::InvalidSelector,
+            Error::UnknownId { .. } => ErrorKind::UnknownId,
+            Error::DanglingReference { .. } => ErrorKind::DanglingReference,
+            Error::AmbiguousReference { .. } => ErrorKind::AmbiguousReference,
+            Error::WithPath { source, .. }
+            | Error::WithPosition { source, .. }
+            | Error::WithSourceName { source, .. } => source.kind(),
+            Error::InvalidDocument { .. } => ErrorKind::InvalidDocument,
+            Error::UnexpectedLeadingContent { .. } => ErrorKind::UnexpectedLeadingContent,
+            Error::ControlCharacter { .. } => ErrorKind::ControlCharacter,
+            Error::UnexpectedExtension { .. } => ErrorKind::UnexpectedExtension,
+            Error::TextParseError { .. } => ErrorKind::TextParseError,
+            Error::DuplicateKey { .. } => ErrorKind::DuplicateKey,
+            Error::UnmappedUri { .. } => ErrorKind::UnmappedUri,
+            Error::UnreadableResource { .. } => ErrorKind::UnreadableResource,
+            Error::ForbiddenChild { .. } => ErrorKind::ForbiddenChild,
+            Error::UnexpectedValue { .. } => ErrorKind::UnexpectedValue,
+            Error::UnknownChild { .. } => ErrorKind::UnknownChild,
+            Error::MergeConflict { .. } => ErrorKind::MergeConflict,
+            Error::Omitted { .. } => ErrorKind::Omitted,
+        }
+    }
 }
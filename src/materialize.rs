@@ -0,0 +1,183 @@
+use crate::{
+    namespace::{lookup_prefix, split_qname},
+    namespace_usage::is_local_name,
+    tree::{OwnedElement, OwnedNode},
+};
+use minidom::Element;
+use std::collections::BTreeSet;
+
+/// Collect the prefixes used by the elements of the subtree, in their names,
+/// in the names of their attributes or in qualified name values and texts.
+fn used_prefixes<'a>(element: &'a OwnedElement, used: &mut BTreeSet<Option<&'a str>>) {
+    used.insert(split_qname(&element.qname).0);
+    for (name, value) in &element.attributes {
+        if let (Some(prefix), _) = split_qname(name) {
+            used.insert(Some(prefix));
+        }
+        if let (Some(prefix), local) = split_qname(value.trim()) {
+            if is_local_name(local) {
+                used.insert(Some(prefix));
+            }
+        }
+    }
+    for node in &element.nodes {
+        match node {
+            OwnedNode::Element(child) => used_prefixes(child, used),
+            OwnedNode::Text(text) => {
+                if let (Some(prefix), local) = split_qname(text.trim()) {
+                    if is_local_name(local) {
+                        used.insert(Some(prefix));
+                    }
+                }
+            }
+            OwnedNode::Comment(_) => {}
+        }
+    }
+}
+
+/// Deep-clone `element` into a standalone tree, declaring on its root the
+/// namespaces inherited from its ancestors which are used in the subtree.
+pub(crate) fn detach(element: &Element) -> Element {
+    detached_tree(element).to_element()
+}
+
+/// Copy `element` into an editable tree, declaring on its root the namespaces
+/// inherited from its ancestors which are used in the subtree, like
+/// [`detach`].
+///
+/// [`detach`]: fn.detach.html
+pub(crate) fn detached_tree(element: &Element) -> OwnedElement {
+    let mut tree = OwnedElement::from(element);
+    let mut used = BTreeSet::new();
+    used_prefixes(&tree, &mut used);
+    let mut inherited = Vec::new();
+    for prefix in used {
+        if prefix == Some("xml") || prefix == Some("xmlns") {
+            continue;
+        }
+        let key = prefix.map(ToOwned::to_owned);
+        if tree.namespaces.contains_key(&key) {
+            continue;
+        }
+        if let Some(namespace) = lookup_prefix(element, prefix) {
+            inherited.push((key, namespace));
+        }
+    }
+    tree.namespaces.extend(inherited);
+    tree
+}
+
+/// Turn the borrowed results of a query into owned elements, detached from
+/// the tree they were selected in.
+///
+/// `minidom` shares the namespace declarations of an element with its
+/// ancestors, so a plain `clone()` of a selected element is still bound to the
+/// original tree and is serialized without the namespaces declared by its
+/// ancestors. A materialized element is a deep copy declaring on its root the
+/// inherited namespaces it uses, so it can be cached, modified or serialized
+/// on its own.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{Materialize, Selector};
+///
+/// let xml: &'static str = r#"<root xmlns="urn:netex" xmlns:gml="urn:gml">
+///         <Line id="1"><gml:pos>1 2</gml:pos></Line>
+///         <Line id="2" />
+///     </root>"#;
+/// let lines: Vec<Element> = {
+///     let root: Element = xml.parse().unwrap();
+///     Selector::parse("Line").unwrap().select(&root).materialize()
+/// };
+/// assert_eq!(2, lines.len());
+/// let mut output = Vec::new();
+/// lines[0].write_to(&mut output).unwrap();
+/// assert_eq!(
+///     r#"<Line xmlns="urn:netex" xmlns:gml="urn:gml" id="1"><gml:pos>1 2</gml:pos></Line>"#,
+///     String::from_utf8(output).unwrap()
+/// );
+/// ```
+pub trait Materialize {
+    /// The owned version of the results.
+    type Output;
+
+    /// Deep-clone the results into owned elements.
+    fn materialize(self) -> Self::Output;
+}
+
+impl Materialize for &Element {
+    type Output = Element;
+
+    fn materialize(self) -> Element {
+        detach(self)
+    }
+}
+
+impl Materialize for Option<&Element> {
+    type Output = Option<Element>;
+
+    fn materialize(self) -> Option<Element> {
+        self.map(detach)
+    }
+}
+
+impl Materialize for Vec<&Element> {
+    type Output = Vec<Element>;
+
+    fn materialize(self) -> Vec<Element> {
+        self.into_iter().map(detach).collect()
+    }
+}
+
+impl Materialize for &[&Element] {
+    type Output = Vec<Element>;
+
+    fn materialize(self) -> Vec<Element> {
+        self.iter().map(|element| detach(element)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Selector;
+    use pretty_assertions::assert_eq;
+
+    fn serialize(element: &Element) -> String {
+        let mut output = Vec::new();
+        element.write_to(&mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn inherited_namespaces() {
+        let xml: &'static str = r#"<root xmlns="urn:default" xmlns:a="urn:a" xmlns:n="urn:n" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns:unused="urn:unused">
+                <a:frame xsi:type="n:Frame" href="http://example.com"><child xml:lang="fr" /></a:frame>
+            </root>"#;
+        let root: Element = xml.parse().unwrap();
+        let frame = Selector::parse("frame")
+            .unwrap()
+            .select_first(&root)
+            .materialize()
+            .unwrap();
+        assert_eq!(
+            r#"<a:frame xmlns="urn:default" xmlns:a="urn:a" xmlns:n="urn:n" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" href="http://example.com" xsi:type="n:Frame"><child xml:lang="fr"/></a:frame>"#,
+            serialize(&frame)
+        );
+        assert_eq!(Some("urn:a".to_owned()), frame.ns());
+        assert_eq!(
+            Some("urn:default".to_owned()),
+            frame.children().next().unwrap().ns()
+        );
+    }
+
+    #[test]
+    fn own_declarations_are_kept() {
+        let xml: &'static str =
+            r#"<root xmlns:a="urn:a1"><a:x xmlns:a="urn:a2"><a:y /></a:x></root>"#;
+        let root: Element = xml.parse().unwrap();
+        let elements = Selector::parse("x").unwrap().select(&root);
+        let x = elements.as_slice().materialize().remove(0);
+        assert_eq!(r#"<a:x xmlns:a="urn:a2"><a:y/></a:x>"#, serialize(&x));
+    }
+}
@@ -0,0 +1,254 @@
+use crate::{materialize::detach, Error};
+use minidom::Element;
+use std::collections::HashMap;
+
+/// How [`MergeElementExt::merge_from`] resolves an attribute or a text with
+/// different values in both elements.
+///
+/// [`MergeElementExt::merge_from`]: trait.MergeElementExt.html#tymethod.merge_from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Keep the value of the element merged into
+    Keep,
+    /// Take the value of the element merged from
+    Overwrite,
+    /// Fail with a [`MergeConflict`] error
+    ///
+    /// [`MergeConflict`]: enum.Error.html#variant.MergeConflict
+    Error,
+}
+
+/// Overlay a tree on top of another one, like an operator-specific patch
+/// document on top of a base feed.
+pub trait MergeElementExt {
+    /// Merge `other` into the element, whatever their names.
+    ///
+    /// The attributes of `other` missing from the element are added, and
+    /// its texts are added if the element has none. Each child of `other` is
+    /// merged recursively into the child of the element with the same name,
+    /// namespace and `id` attribute (the n-th child with these being paired
+    /// with the n-th one), or appended if there is none. An attribute or a
+    /// text (compared without leading and trailing whitespaces) with
+    /// different values in both elements is resolved with `strategy`.
+    ///
+    /// The text of an element is compared and merged as a whole: with mixed
+    /// content, when the text of `other` wins, it replaces the first text
+    /// node of the element and the other text nodes are emptied, the child
+    /// elements staying in place.
+    ///
+    /// On error, the element is left unchanged.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::{MergeElementExt, MergeStrategy};
+    ///
+    /// let xml: &'static str = r#"<lines>
+    ///         <Line id="1" version="1"><Name>A</Name></Line>
+    ///         <Line id="2" version="1"><Name>B</Name></Line>
+    ///     </lines>"#;
+    /// let mut base: Element = xml.parse().unwrap();
+    /// let xml: &'static str = r#"<lines>
+    ///         <Line id="2" version="2"><Name>B</Name><Colour>FFCE00</Colour></Line>
+    ///         <Line id="3" version="1" />
+    ///     </lines>"#;
+    /// let patch: Element = xml.parse().unwrap();
+    ///
+    /// let error = base.clone().merge_from(&patch, MergeStrategy::Error).unwrap_err();
+    /// assert_eq!(
+    ///     "Conflicting values '1' and '2' for attribute 'version' of element 'Line'",
+    ///     error.to_string()
+    /// );
+    /// base.merge_from(&patch, MergeStrategy::Overwrite).unwrap();
+    /// let versions: Vec<&str> = base.children().filter_map(|line| line.attr("version")).collect();
+    /// assert_eq!(vec!["1", "2", "1"], versions);
+    /// assert!(base.children().nth(1).unwrap().has_child("Colour", minidom::NSChoice::Any));
+    /// ```
+    fn merge_from(&mut self, other: &Element, strategy: MergeStrategy) -> Result<(), Error>;
+}
+
+/// Whether the value of `other` must replace the value of `element`.
+fn overwrite(
+    element: &Element,
+    attribute_name: Option<&str>,
+    value: &str,
+    other_value: &str,
+    strategy: MergeStrategy,
+) -> Result<bool, Error> {
+    match strategy {
+        MergeStrategy::Keep => Ok(false),
+        MergeStrategy::Overwrite => Ok(true),
+        MergeStrategy::Error => Err(Error::MergeConflict {
+            element_name: element.name().to_owned(),
+            attribute_name: attribute_name.map(ToOwned::to_owned),
+            value: value.to_owned(),
+            other_value: other_value.to_owned(),
+        }),
+    }
+}
+
+/// Name, namespace and `id` attribute of an element, identifying the
+/// children to merge together.
+type EntityKey<'a> = (&'a str, Option<String>, Option<&'a str>);
+
+fn entity_key(element: &Element) -> EntityKey<'_> {
+    (element.name(), element.ns(), element.attr("id"))
+}
+
+/// Find the child of `element` paired with each child of `other`, by position
+/// among the children of `element`: the n-th child of `other` with a given
+/// [`EntityKey`] is paired with the n-th child of `element` with this key.
+fn pair_children(element: &Element, other: &Element) -> Vec<Option<usize>> {
+    let mut positions: HashMap<EntityKey<'_>, Vec<usize>> = HashMap::new();
+    for (position, child) in element.children().enumerate() {
+        positions
+            .entry(entity_key(child))
+            .or_default()
+            .push(position);
+    }
+    let mut occurrences: HashMap<EntityKey<'_>, usize> = HashMap::new();
+    other
+        .children()
+        .map(|other_child| {
+            let key = entity_key(other_child);
+            let position = positions
+                .get(&key)
+                .and_then(|positions| positions.get(occurrences.get(&key).copied().unwrap_or(0)))
+                .copied();
+            *occurrences.entry(key).or_default() += 1;
+            position
+        })
+        .collect()
+}
+
+/// Find the first conflict between `element` and `other`, to fail before
+/// changing anything with [`MergeStrategy::Error`].
+fn check(element: &Element, other: &Element) -> Result<(), Error> {
+    for (name, other_value) in other.attrs() {
+        if let Some(value) = element.attr(name) {
+            if value != other_value {
+                overwrite(
+                    element,
+                    Some(name),
+                    value,
+                    other_value,
+                    MergeStrategy::Error,
+                )?;
+            }
+        }
+    }
+    let other_text = other.text();
+    let other_text = other_text.trim();
+    let text = element.text();
+    let text = text.trim();
+    if !text.is_empty() && !other_text.is_empty() && text != other_text {
+        overwrite(element, None, text, other_text, MergeStrategy::Error)?;
+    }
+    let children: Vec<&Element> = element.children().collect();
+    for (other_child, position) in other.children().zip(pair_children(element, other)) {
+        if let Some(position) = position {
+            check(children[position], other_child)?;
+        }
+    }
+    Ok(())
+}
+
+fn merge(element: &mut Element, other: &Element, strategy: MergeStrategy) -> Result<(), Error> {
+    for (name, other_value) in other.attrs() {
+        match element.attr(name) {
+            Some(value) if value == other_value => {}
+            Some(value) => {
+                if overwrite(element, Some(name), value, other_value, strategy)? {
+                    element.set_attr(name, other_value);
+                }
+            }
+            None => element.set_attr(name, other_value),
+        }
+    }
+    let other_text = other.text();
+    let other_text = other_text.trim();
+    let text = element.text();
+    let text = text.trim();
+    if text.is_empty() && !other_text.is_empty() {
+        element.append_text_node(other_text);
+    } else if !other_text.is_empty()
+        && text != other_text
+        && overwrite(element, None, text, other_text, strategy)?
+    {
+        let mut texts = element.texts_mut();
+        if let Some(first) = texts.next() {
+            *first = other_text.to_owned();
+        }
+        texts.for_each(String::clear);
+    }
+    let pairs = pair_children(element, other);
+    let mut appended = Vec::new();
+    let mut children: Vec<&mut Element> = element.children_mut().collect();
+    for (other_child, position) in other.children().zip(pairs) {
+        match position {
+            Some(position) => merge(children[position], other_child, strategy)?,
+            None => appended.push(detach(other_child)),
+        }
+    }
+    for child in appended {
+        element.append_child(child);
+    }
+    Ok(())
+}
+
+impl MergeElementExt for Element {
+    fn merge_from(&mut self, other: &Element, strategy: MergeStrategy) -> Result<(), Error> {
+        // Conflicts are found beforehand, so no error can occur once the
+        // element starts being changed
+        if strategy == MergeStrategy::Error {
+            check(self, other)?;
+        }
+        merge(self, other, strategy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn strategies() {
+        let base: Element = r#"<a x="1"><b>text</b><c/><c/></a>"#.parse().unwrap();
+        let other: Element = r#"<a x="2" y="3"><b> other </b><c/><c>new</c><c/></a>"#
+            .parse()
+            .unwrap();
+        let mut kept = base.clone();
+        kept.merge_from(&other, MergeStrategy::Keep).unwrap();
+        assert_eq!(
+            r#"<a x="1" y="3"><b>text</b><c/><c>new</c><c/></a>"#,
+            String::from(&kept)
+        );
+        let mut overwritten = base.clone();
+        overwritten
+            .merge_from(&other, MergeStrategy::Overwrite)
+            .unwrap();
+        assert_eq!(
+            r#"<a x="2" y="3"><b>other</b><c/><c>new</c><c/></a>"#,
+            String::from(&overwritten)
+        );
+        let mut failed = base.clone();
+        let other: Element = r#"<a x="1"><b>other</b></a>"#.parse().unwrap();
+        let error = failed.merge_from(&other, MergeStrategy::Error).unwrap_err();
+        assert_eq!(ErrorKind::MergeConflict, error.kind());
+        assert_eq!(
+            "Conflicting values 'text' and 'other' for the text of element 'b'",
+            error.to_string()
+        );
+        assert_eq!(base, failed);
+    }
+
+    #[test]
+    fn mixed_content() {
+        let mut base: Element = r#"<p>Before <b>bold</b> after</p>"#.parse().unwrap();
+        let other: Element = r#"<p>Replaced<i/></p>"#.parse().unwrap();
+        base.merge_from(&other, MergeStrategy::Overwrite).unwrap();
+        assert_eq!(r#"<p>Replaced<b>bold</b><i/></p>"#, String::from(&base));
+        assert_eq!("Replaced", base.text());
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{
+    materialize::detached_tree,
+    tree::{OwnedElement, OwnedNode},
+};
+use minidom::Element;
+
+fn is_blank(text: &str) -> bool {
+    text.chars().all(char::is_whitespace)
+}
+
+fn minify_tree(element: &mut OwnedElement, preserve: bool) {
+    let space = element
+        .attributes
+        .iter()
+        .find(|(name, _)| name == "xml:space")
+        .map(|(_, value)| value.as_str());
+    let preserve = match space {
+        Some("preserve") => true,
+        Some("default") => false,
+        _ => preserve,
+    };
+    if !preserve {
+        let mixed = element
+            .nodes
+            .iter()
+            .any(|node| matches!(node, OwnedNode::Text(text) if !is_blank(text)));
+        if mixed {
+            if let Some(OwnedNode::Text(text)) = element.nodes.first_mut() {
+                *text = text.trim_start().to_owned();
+            }
+            if let Some(OwnedNode::Text(text)) = element.nodes.last_mut() {
+                *text = text.trim_end().to_owned();
+            }
+        }
+        element
+            .nodes
+            .retain(|node| !matches!(node, OwnedNode::Text(text) if text.is_empty() || !mixed && is_blank(text)));
+    }
+    for node in &mut element.nodes {
+        if let OwnedNode::Element(child) = node {
+            minify_tree(child, preserve);
+        }
+    }
+}
+
+/// Remove the insignificant whitespaces of a tree, to reduce the size of the
+/// documents written back.
+///
+/// The texts made only of whitespaces are removed from the elements without
+/// other texts (like indentation). In mixed content, only the whitespaces
+/// at the start and at the end of the content are removed, the ones between
+/// the texts and the children being significant. The elements under
+/// `xml:space="preserve"` are left untouched (unless reset by
+/// `xml:space="default"`). Comments are kept, and the namespaces inherited
+/// from the ancestors of the element are declared on it.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::minify;
+///
+/// let xml: &'static str = r#"<Line>
+///     <Name> Metro <b>1</b> </Name>
+///     <Code xml:space="preserve"> M1 </Code>
+/// </Line>"#;
+/// let mut line: Element = xml.parse().unwrap();
+/// minify(&mut line);
+/// assert_eq!(
+///     r#"<Line><Name>Metro <b>1</b></Name><Code xml:space="preserve"> M1 </Code></Line>"#,
+///     String::from(&line)
+/// );
+/// ```
+pub fn minify(element: &mut Element) {
+    let mut tree = detached_tree(element);
+    minify_tree(&mut tree, false);
+    *element = tree.to_element();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::semantic_eq;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn whitespaces() {
+        let xml: &'static str = r#"<a xmlns:x="urn:x" xml:space="preserve">
+  <x:b xml:space="default">
+    <c> <!-- c --> </c>
+    <d> </d>
+  </x:b>
+  <e> </e>
+</a>"#;
+        let original: Element = xml.parse().unwrap();
+        let mut element = original.clone();
+        minify(&mut element);
+        assert_eq!(
+            "<a xmlns:x=\"urn:x\" xml:space=\"preserve\">\n  <x:b xml:space=\"default\"><c><!-- c --></c><d/></x:b>\n  <e> </e>\n</a>",
+            String::from(&element)
+        );
+        assert!(semantic_eq(&original, &element));
+    }
+}
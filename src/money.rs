@@ -0,0 +1,237 @@
+use crate::{AttributeElementExt, DecimalElementExt, Error};
+use minidom::Element;
+use rust_decimal::Decimal;
+use std::{fmt, str::FromStr};
+
+/// Active ISO 4217 currency codes with their number of minor units (digits
+/// after the decimal separator).
+#[rustfmt::skip]
+const CURRENCIES: &[(&str, u8)] = &[
+    ("AED", 2), ("AFN", 2), ("ALL", 2), ("AMD", 2), ("ANG", 2), ("AOA", 2),
+    ("ARS", 2), ("AUD", 2), ("AWG", 2), ("AZN", 2), ("BAM", 2), ("BBD", 2),
+    ("BDT", 2), ("BGN", 2), ("BHD", 3), ("BIF", 0), ("BMD", 2), ("BND", 2),
+    ("BOB", 2), ("BRL", 2), ("BSD", 2), ("BTN", 2), ("BWP", 2), ("BYN", 2),
+    ("BZD", 2), ("CAD", 2), ("CDF", 2), ("CHF", 2), ("CLP", 0), ("CNY", 2),
+    ("COP", 2), ("CRC", 2), ("CUP", 2), ("CVE", 2), ("CZK", 2), ("DJF", 0),
+    ("DKK", 2), ("DOP", 2), ("DZD", 2), ("EGP", 2), ("ERN", 2), ("ETB", 2),
+    ("EUR", 2), ("FJD", 2), ("FKP", 2), ("GBP", 2), ("GEL", 2), ("GHS", 2),
+    ("GIP", 2), ("GMD", 2), ("GNF", 0), ("GTQ", 2), ("GYD", 2), ("HKD", 2),
+    ("HNL", 2), ("HTG", 2), ("HUF", 2), ("IDR", 2), ("ILS", 2), ("INR", 2),
+    ("IQD", 3), ("IRR", 2), ("ISK", 0), ("JMD", 2), ("JOD", 3), ("JPY", 0),
+    ("KES", 2), ("KGS", 2), ("KHR", 2), ("KMF", 0), ("KPW", 2), ("KRW", 0),
+    ("KWD", 3), ("KYD", 2), ("KZT", 2), ("LAK", 2), ("LBP", 2), ("LKR", 2),
+    ("LRD", 2), ("LSL", 2), ("LYD", 3), ("MAD", 2), ("MDL", 2), ("MGA", 2),
+    ("MKD", 2), ("MMK", 2), ("MNT", 2), ("MOP", 2), ("MRU", 2), ("MUR", 2),
+    ("MVR", 2), ("MWK", 2), ("MXN", 2), ("MYR", 2), ("MZN", 2), ("NAD", 2),
+    ("NGN", 2), ("NIO", 2), ("NOK", 2), ("NPR", 2), ("NZD", 2), ("OMR", 3),
+    ("PAB", 2), ("PEN", 2), ("PGK", 2), ("PHP", 2), ("PKR", 2), ("PLN", 2),
+    ("PYG", 0), ("QAR", 2), ("RON", 2), ("RSD", 2), ("RUB", 2), ("RWF", 0),
+    ("SAR", 2), ("SBD", 2), ("SCR", 2), ("SDG", 2), ("SEK", 2), ("SGD", 2),
+    ("SHP", 2), ("SLE", 2), ("SOS", 2), ("SRD", 2), ("SSP", 2), ("STN", 2),
+    ("SVC", 2), ("SYP", 2), ("SZL", 2), ("THB", 2), ("TJS", 2), ("TMT", 2),
+    ("TND", 3), ("TOP", 2), ("TRY", 2), ("TTD", 2), ("TWD", 2), ("TZS", 2),
+    ("UAH", 2), ("UGX", 0), ("USD", 2), ("UYU", 2), ("UZS", 2), ("VED", 2),
+    ("VES", 2), ("VND", 0), ("VUV", 0), ("WST", 2), ("XAF", 0), ("XCD", 2),
+    ("XOF", 0), ("XPF", 0), ("YER", 2), ("ZAR", 2), ("ZMW", 2), ("ZWG", 2),
+];
+
+/// Error returned when a currency or an amount is not valid.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum MoneyError {
+    /// The currency code is not an active ISO 4217 code.
+    #[error("Unknown ISO 4217 currency code '{0}'")]
+    UnknownCurrency(String),
+    /// The amount has more decimals than the currency allows.
+    #[error(
+        "Amount '{amount}' has more than {minor_units} decimal(s) allowed by currency '{currency}'"
+    )]
+    TooManyDecimals {
+        /// The amount as written in the document
+        amount: String,
+        /// The currency of the amount
+        currency: Currency,
+        /// Number of decimals allowed by the currency
+        minor_units: u8,
+    },
+}
+
+/// An ISO 4217 currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Currency {
+    code: &'static str,
+    minor_units: u8,
+}
+
+impl Currency {
+    /// Alphabetic ISO 4217 code of the currency (`EUR`, `USD`, ...).
+    pub fn code(self) -> &'static str {
+        self.code
+    }
+
+    /// Number of digits after the decimal separator used by the currency.
+    pub fn minor_units(self) -> u8 {
+        self.minor_units
+    }
+}
+
+impl FromStr for Currency {
+    type Err = MoneyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s.trim();
+        CURRENCIES
+            .binary_search_by(|(c, _)| c.cmp(&code))
+            .map(|index| Currency {
+                code: CURRENCIES[index].0,
+                minor_units: CURRENCIES[index].1,
+            })
+            .map_err(|_| MoneyError::UnknownCurrency(s.to_owned()))
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code)
+    }
+}
+
+/// Extract monetary amounts made of an amount attribute and a currency
+/// attribute.
+pub trait MoneyElementExt {
+    /// Try to get an amount and its currency from two attributes and return a
+    /// [`Result`].
+    ///
+    /// The currency must be an active ISO 4217 code and the amount, an
+    /// `xs:decimal` (see [`DecimalElementExt`]), must not have more decimals
+    /// than the currency allows.
+    ///
+    /// [`DecimalElementExt`]: trait.DecimalElementExt.html
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_attribute_money(
+        &self,
+        amount_attr: &str,
+        currency_attr: &str,
+    ) -> Result<(Decimal, Currency), Error>;
+
+    /// Get an amount and its currency from two attributes if present and
+    /// valid and return an [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn attribute_money(
+        &self,
+        amount_attr: &str,
+        currency_attr: &str,
+    ) -> Option<(Decimal, Currency)> {
+        self.try_attribute_money(amount_attr, currency_attr).ok()
+    }
+}
+
+impl MoneyElementExt for Element {
+    /// Implementation of [`MoneyElementExt`] for [`Element`] validates the
+    /// currency and the precision of the amount.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::MoneyElementExt;
+    ///
+    /// let xml: &'static str = r#"<Price amount="12.50" currency="EUR" />"#;
+    /// let price: Element = xml.parse().unwrap();
+    /// let (amount, currency) = price.try_attribute_money("amount", "currency").unwrap();
+    /// assert_eq!("12.50", amount.to_string());
+    /// assert_eq!("EUR", currency.code());
+    /// ```
+    ///
+    /// [`MoneyElementExt`]: trait.MoneyElementExt.html
+    /// [`Element`]: ../minidom/element/struct.Element.html
+    fn try_attribute_money(
+        &self,
+        amount_attr: &str,
+        currency_attr: &str,
+    ) -> Result<(Decimal, Currency), Error> {
+        let currency: Currency = self.try_attribute(currency_attr)?;
+        let amount = self.try_attribute_decimal(amount_attr)?;
+        // Trailing zeros don't add any precision ("12.00" is a valid JPY amount)
+        if amount.normalize().scale() > u32::from(currency.minor_units) {
+            let raw_amount = self.attr(amount_attr).unwrap_or_default().to_owned();
+            return Err(Error::ParseError {
+                element_name: self.name().to_owned(),
+                attribute_name: amount_attr.to_owned(),
+                value: raw_amount.clone(),
+                source: MoneyError::TooManyDecimals {
+                    amount: raw_amount,
+                    currency,
+                    minor_units: currency.minor_units,
+                }
+                .into(),
+            });
+        }
+        Ok((amount, currency))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn currencies_are_sorted() {
+        assert!(CURRENCIES.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn unknown_currency() {
+        let xml: &'static str = r#"<Price amount="12" currency="EUU" />"#;
+        let price: Element = xml.parse().unwrap();
+        let error = price.try_attribute_money("amount", "currency").unwrap_err();
+        assert_eq!(
+            "Failed to parse and convert the value \'EUU\' of attribute \'currency\' in element \'Price\'",
+            format!("{}", error)
+        );
+    }
+
+    #[test]
+    fn too_many_decimals() {
+        let xml: &'static str = r#"<Price amount="12.5" currency="JPY" />"#;
+        let price: Element = xml.parse().unwrap();
+        let error = price.try_attribute_money("amount", "currency").unwrap_err();
+        match error {
+            Error::ParseError { source, .. } => assert_eq!(
+                "Amount '12.5' has more than 0 decimal(s) allowed by currency 'JPY'",
+                source.to_string()
+            ),
+            e => panic!("unexpected error {:?}", e),
+        }
+    }
+
+    #[test]
+    fn three_decimals_currency() {
+        let xml: &'static str = r#"<Price amount="1.125" currency="KWD" />"#;
+        let price: Element = xml.parse().unwrap();
+        let (amount, currency) = price.attribute_money("amount", "currency").unwrap();
+        assert_eq!("1.125", amount.to_string());
+        assert_eq!(3, currency.minor_units());
+        let xml: &'static str = r#"<Price amount="1E2" currency="KWD" />"#;
+        let price: Element = xml.parse().unwrap();
+        assert_eq!(None, price.attribute_money("amount", "currency"));
+        let xml: &'static str = r#"<Price amount="+.5" currency="VED" />"#;
+        let price: Element = xml.parse().unwrap();
+        let (amount, _) = price.attribute_money("amount", "currency").unwrap();
+        assert_eq!("0.5", amount.to_string());
+    }
+
+    #[test]
+    fn trailing_zeros() {
+        let xml: &'static str = r#"<Price amount="12.00" currency="JPY" />"#;
+        let price: Element = xml.parse().unwrap();
+        let (amount, _) = price.try_attribute_money("amount", "currency").unwrap();
+        assert_eq!("12.00", amount.to_string());
+        let xml: &'static str = r#"<Price amount="1.500" currency="EUR" />"#;
+        let price: Element = xml.parse().unwrap();
+        let (amount, _) = price.try_attribute_money("amount", "currency").unwrap();
+        assert_eq!("1.500", amount.to_string());
+        let xml: &'static str = r#"<Price amount="1.505" currency="EUR" />"#;
+        let price: Element = xml.parse().unwrap();
+        assert_eq!(None, price.attribute_money("amount", "currency"));
+    }
+}
@@ -0,0 +1,372 @@
+use crate::{
+    namespace::lookup_prefix,
+    tree::{rebuild, replace_start_tag, OwnedElement},
+    util::collect_unique,
+    Error,
+};
+use minidom::{Element, NSChoice, Node};
+use std::{cmp::Ordering, fmt::Display, mem};
+
+/// Write typed values back into an element, the counterpart of
+/// [`AttributeElementExt`].
+///
+/// [`AttributeElementExt`]: trait.AttributeElementExt.html
+pub trait MutateElementExt {
+    /// Set the attribute `attr_name` to the [`Display`] representation of
+    /// `value`, replacing its current value if any.
+    ///
+    /// Numbers and booleans are written with the lexical representation of
+    /// XML Schema (`true` and `false` for `bool` or [`XsBoolean`]), except
+    /// the float infinities and not-a-number value: wrap floats into
+    /// [`XsFormat`] to write them as `INF`, `-INF` and `NaN`, as well as
+    /// dates and date-times (feature `chrono`).
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::{AttributeElementExt, MutateElementExt};
+    ///
+    /// let mut line: Element = r#"<Line version="1" />"#.parse().unwrap();
+    /// let version: u32 = line.try_attribute("version").unwrap();
+    /// line.set_attr_value("version", version + 1);
+    /// line.set_attr_value("active", true);
+    /// assert_eq!(Some("2"), line.attr("version"));
+    /// assert_eq!(Some("true"), line.attr("active"));
+    /// ```
+    ///
+    /// [`Display`]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+    /// [`XsBoolean`]: struct.XsBoolean.html
+    /// [`XsFormat`]: struct.XsFormat.html
+    fn set_attr_value<T: Display>(&mut self, attr_name: &str, value: T);
+
+    /// Remove the attribute `attr_name` and return its value, if it was
+    /// present.
+    ///
+    /// `minidom` cannot remove an attribute, so the start tag of the element
+    /// is rebuilt, its nodes being moved into it: the namespaces inherited
+    /// from its ancestors stay in scope.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::MutateElementExt;
+    ///
+    /// let mut line: Element = r#"<Line id="1" changed="2024-01-01" />"#.parse().unwrap();
+    /// assert_eq!(Some("2024-01-01".to_owned()), line.remove_attr("changed"));
+    /// assert_eq!(None, line.remove_attr("changed"));
+    /// assert_eq!(r#"<Line id="1"/>"#, String::from(&line));
+    /// ```
+    fn remove_attr(&mut self, attr_name: &str) -> Option<String>;
+
+    /// Replace the one and only child named `child_name` with `child`, and
+    /// return the replaced child.
+    ///
+    /// Fails with the errors of [`OnlyChildElementExt::try_only_child`] when
+    /// there is no such child or more than one, the element being left
+    /// unchanged.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::MutateElementExt;
+    ///
+    /// let mut line: Element = r#"<Line><Name>A</Name><Code>1</Code></Line>"#.parse().unwrap();
+    /// let name: Element = r#"<Name>B</Name>"#.parse().unwrap();
+    /// let old = line.replace_only_child("Name", name).unwrap();
+    /// assert_eq!("A", old.text());
+    /// assert_eq!(r#"<Line><Name>B</Name><Code>1</Code></Line>"#, String::from(&line));
+    /// assert!(line.replace_only_child("Color", Element::bare("Color")).is_err());
+    /// ```
+    ///
+    /// [`OnlyChildElementExt::try_only_child`]: trait.OnlyChildElementExt.html#tymethod.try_only_child
+    fn replace_only_child(&mut self, child_name: &str, child: Element) -> Result<Element, Error>;
+
+    /// Remove all the children matching `predicate` and return how many
+    /// were removed.
+    ///
+    /// Like [`remove_attr`], the start tag of the element is rebuilt when
+    /// children are removed.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::MutateElementExt;
+    ///
+    /// let xml: &'static str = r#"<Line><Name>A</Name><Note private="true" /><Note /></Line>"#;
+    /// let mut line: Element = xml.parse().unwrap();
+    /// let removed = line.remove_children_where(|child| child.attr("private") == Some("true"));
+    /// assert_eq!(1, removed);
+    /// assert_eq!(r#"<Line><Name>A</Name><Note/></Line>"#, String::from(&line));
+    /// ```
+    ///
+    /// [`remove_attr`]: #tymethod.remove_attr
+    fn remove_children_where<P>(&mut self, predicate: P) -> usize
+    where
+        P: FnMut(&Element) -> bool;
+
+    /// Get the one and only child with the given name and namespace, creating
+    /// it at the end of the element if there is none.
+    ///
+    /// The namespace can be anything convertible into a [`NSChoice`], as in
+    /// [`Element::is`]. A created child is in the namespace given by
+    /// `NSChoice::OneOf` (or the first one of `NSChoice::AnyOf`), declared
+    /// on the child unless it is the default namespace of the element, and
+    /// without any namespace of its own otherwise. Fails with a
+    /// [`MultipleChildren`] error when the child is duplicated.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::MutateElementExt;
+    ///
+    /// const NS: &str = "urn:config";
+    /// let mut config: Element = r#"<Config xmlns="urn:config"><Timeout>10</Timeout></Config>"#
+    ///     .parse()
+    ///     .unwrap();
+    /// config.upsert_child("Timeout", NS).unwrap().set_attr("unit", "s");
+    /// config.upsert_child("Retries", NS).unwrap().append_text_node("3");
+    /// assert_eq!(
+    ///     r#"<Config xmlns="urn:config"><Timeout unit="s">10</Timeout><Retries>3</Retries></Config>"#,
+    ///     String::from(&config)
+    /// );
+    /// ```
+    ///
+    /// [`Element::is`]: ../minidom/element/struct.Element.html#method.is
+    /// [`MultipleChildren`]: enum.Error.html#variant.MultipleChildren
+    /// [`NSChoice`]: ../minidom/enum.NSChoice.html
+    fn upsert_child<'a, NS>(&mut self, child_name: &str, ns: NS) -> Result<&mut Element, Error>
+    where
+        NS: Into<NSChoice<'a>>;
+
+    /// Reorder the children with `compare`, keeping the order of the equal
+    /// ones.
+    ///
+    /// The texts and comments before a child (like its indentation) move
+    /// with it, and the ones after the last child stay at the end. The nodes
+    /// are moved in place, so the element keeps its namespaces.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::MutateElementExt;
+    ///
+    /// let xml: &'static str = r#"<lines>
+    ///     <Line id="2" />
+    ///     <!-- first line -->
+    ///     <Line id="1" />
+    /// </lines>"#;
+    /// let mut lines: Element = xml.parse().unwrap();
+    /// lines.sort_children_by(|a, b| a.attr("id").cmp(&b.attr("id")));
+    /// assert_eq!(
+    ///     r#"<lines>
+    ///     <!-- first line -->
+    ///     <Line id="1"/>
+    ///     <Line id="2"/>
+    /// </lines>"#,
+    ///     String::from(&lines)
+    /// );
+    /// ```
+    fn sort_children_by<F>(&mut self, compare: F)
+    where
+        F: FnMut(&Element, &Element) -> Ordering;
+
+    /// Reorder the children by name, see [`sort_children_by`].
+    ///
+    /// [`sort_children_by`]: #tymethod.sort_children_by
+    fn sort_children_by_name(&mut self) {
+        self.sort_children_by(|a, b| a.name().cmp(b.name()));
+    }
+
+    /// Reorder the children by the value of the attribute `attr_name`, the
+    /// children without it coming first, see [`sort_children_by`].
+    ///
+    /// [`sort_children_by`]: #tymethod.sort_children_by
+    fn sort_children_by_attribute(&mut self, attr_name: &str) {
+        self.sort_children_by(|a, b| a.attr(attr_name).cmp(&b.attr(attr_name)));
+    }
+}
+
+impl MutateElementExt for Element {
+    fn set_attr_value<T: Display>(&mut self, attr_name: &str, value: T) {
+        self.set_attr(attr_name, value.to_string());
+    }
+
+    fn remove_attr(&mut self, attr_name: &str) -> Option<String> {
+        let value = self.attr(attr_name)?.to_owned();
+        let mut start = OwnedElement::start_tag(self);
+        start.attributes.retain(|(name, _)| name != attr_name);
+        replace_start_tag(self, &start);
+        Some(value)
+    }
+
+    fn replace_only_child(&mut self, child_name: &str, child: Element) -> Result<Element, Error> {
+        collect_unique(
+            self,
+            child_name,
+            self.children().filter(|child| child.name() == child_name),
+        )?;
+        let replaced = self
+            .children_mut()
+            .find(|child| child.name() == child_name)
+            .expect("the child has just been found");
+        Ok(mem::replace(replaced, child))
+    }
+
+    fn remove_children_where<P>(&mut self, mut predicate: P) -> usize
+    where
+        P: FnMut(&Element) -> bool,
+    {
+        let removed: Vec<usize> = self
+            .nodes()
+            .enumerate()
+            .filter(|(_, node)| matches!(node, Node::Element(child) if predicate(child)))
+            .map(|(index, _)| index)
+            .collect();
+        if removed.is_empty() {
+            return 0;
+        }
+        let start = OwnedElement::start_tag(self);
+        rebuild(self, &start, |index| removed.binary_search(&index).is_err());
+        removed.len()
+    }
+
+    fn upsert_child<'a, NS>(&mut self, child_name: &str, ns: NS) -> Result<&mut Element, Error>
+    where
+        NS: Into<NSChoice<'a>>,
+    {
+        let ns = ns.into();
+        let count = self
+            .children()
+            .filter(|child| child.is(child_name, ns))
+            .count();
+        if count > 1 {
+            return Err(Error::MultipleChildren {
+                element_name: self.name().to_owned(),
+                child_name: child_name.to_owned(),
+                count,
+            });
+        }
+        if count == 0 {
+            let namespace = match ns {
+                NSChoice::OneOf(namespace) => Some(namespace),
+                NSChoice::AnyOf(namespaces) => namespaces.first().copied(),
+                NSChoice::None | NSChoice::Any => None,
+            };
+            let child = match namespace {
+                Some(namespace) if lookup_prefix(self, None).as_deref() != Some(namespace) => {
+                    Element::builder(child_name).ns(namespace).build()
+                }
+                _ => Element::bare(child_name),
+            };
+            return Ok(self.append_child(child));
+        }
+        Ok(self
+            .children_mut()
+            .find(|child| child.is(child_name, ns))
+            .expect("the child has just been found"))
+    }
+
+    fn sort_children_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&Element, &Element) -> Ordering,
+    {
+        let children: Vec<&Element> = self.children().collect();
+        let mut order: Vec<usize> = (0..children.len()).collect();
+        order.sort_by(|a, b| compare(children[*a], children[*b]));
+        if order
+            .iter()
+            .enumerate()
+            .all(|(position, index)| position == *index)
+        {
+            return;
+        }
+        // Nodes are moved out and written back in their new order, the
+        // element itself being left untouched
+        let mut groups = Vec::new();
+        let mut group = Vec::new();
+        for node in self.nodes_mut() {
+            let node = mem::replace(node, Node::Text(String::new()));
+            let is_element = matches!(node, Node::Element(_));
+            group.push(node);
+            if is_element {
+                groups.push(mem::take(&mut group));
+            }
+        }
+        let mut groups: Vec<Option<Vec<Node>>> = groups.into_iter().map(Some).collect();
+        let sorted = order
+            .into_iter()
+            .flat_map(|index| groups[index].take().unwrap_or_default())
+            .chain(group);
+        for (slot, node) in self.nodes_mut().zip(sorted) {
+            *slot = node;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::XsFormat;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn nested_element() {
+        let xml: &'static str =
+            r#"<root xmlns:n="urn:n"><n:a n:id="1" v="2">text<b/></n:a></root>"#;
+        let root: Element = xml.parse().unwrap();
+        let mut a = root.children().next().unwrap().clone();
+        assert_eq!(Some("1".to_owned()), a.remove_attr("n:id"));
+        a.set_attr_value("v", 2.5);
+        a.set_attr_value("min", XsFormat(f32::NEG_INFINITY));
+        a.set_attr_value("max", XsFormat(f64::NAN));
+        assert_eq!(
+            r#"<n:a max="NaN" min="-INF" v="2.5">text<b/></n:a>"#,
+            String::from(&a)
+        );
+        assert_eq!(Some("urn:n".to_owned()), a.ns());
+        assert!(matches!(a.nodes().next(), Some(Node::Text(_))));
+    }
+
+    #[test]
+    fn sorted_children() {
+        let mut line: Element = r#"<Line>a<C/>b<B k="2"/><A k="1"/>c</Line>"#.parse().unwrap();
+        line.sort_children_by_name();
+        assert_eq!(
+            r#"<Line><A k="1"/>b<B k="2"/>a<C/>c</Line>"#,
+            String::from(&line)
+        );
+        line.sort_children_by_attribute("k");
+        assert_eq!(
+            r#"<Line>a<C/><A k="1"/>b<B k="2"/>c</Line>"#,
+            String::from(&line)
+        );
+    }
+
+    #[test]
+    fn sorted_namespaced_children() {
+        let xml: &'static str = r#"<root xmlns:n="urn:n"><n:lines><n:b/><n:a/></n:lines></root>"#;
+        let root: Element = xml.parse().unwrap();
+        let mut lines = root.children().next().unwrap().clone();
+        lines.sort_children_by_name();
+        assert_eq!(r#"<n:lines><n:a/><n:b/></n:lines>"#, String::from(&lines));
+        assert!(lines.children().all(|child| child.has_ns("urn:n")));
+    }
+
+    #[test]
+    fn children() {
+        let mut line: Element = r#"<Line><Name /><Name /><Code /></Line>"#.parse().unwrap();
+        let error = line
+            .replace_only_child("Name", Element::bare("Name"))
+            .unwrap_err();
+        assert_eq!(crate::ErrorKind::MultipleChildren, error.kind());
+        assert_eq!(0, line.remove_children_where(|_| false));
+        assert_eq!(
+            2,
+            line.remove_children_where(|child| child.name() == "Name")
+        );
+        assert_eq!(r#"<Line><Code/></Line>"#, String::from(&line));
+        line.upsert_child("Code", NSChoice::Any).unwrap();
+        line.upsert_child("Name", NSChoice::None).unwrap();
+        assert_eq!(r#"<Line><Code/><Name/></Line>"#, String::from(&line));
+        line.append_child(Element::bare("Name"));
+        let error = line.upsert_child("Name", NSChoice::Any).unwrap_err();
+        assert_eq!(
+            "Multiple children with name 'Name' in Element 'Line' (found 2 elements)",
+            error.to_string()
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use crate::Error;
+use minidom::{Element, NSChoice};
+
+/// Format a name in Clark notation (`{namespace}name`), or just `name` when
+/// there is no namespace.
+pub(crate) fn clark_name(ns: Option<&str>, name: &str) -> String {
+    match ns {
+        Some(ns) => format!("{{{}}}{}", ns, name),
+        None => name.to_owned(),
+    }
+}
+
+fn expected_name(ns: NSChoice<'_>, name: &str) -> String {
+    match ns {
+        NSChoice::None | NSChoice::Any => name.to_owned(),
+        NSChoice::OneOf(ns) => clark_name(Some(ns), name),
+        NSChoice::AnyOf(namespaces) => format!("{{{}}}{}", namespaces.join("|"), name),
+    }
+}
+
+/// Check the identity of an element.
+pub trait NameElementExt {
+    /// Check that the element has the expected name and namespace and return
+    /// it, or an [`UnexpectedElement`] error.
+    ///
+    /// The namespace can be anything convertible into a [`NSChoice`], as in
+    /// [`Element::is`].
+    ///
+    /// [`Element::is`]: ../minidom/element/struct.Element.html#method.is
+    /// [`NSChoice`]: ../minidom/enum.NSChoice.html
+    /// [`UnexpectedElement`]: enum.Error.html#variant.UnexpectedElement
+    fn expect_named<'a, NS>(&self, ns: NS, name: &str) -> Result<&Self, Error>
+    where
+        NS: Into<NSChoice<'a>>;
+}
+
+impl NameElementExt for Element {
+    /// Implementation of [`NameElementExt`] for [`Element`] compares both the
+    /// local name and the namespace of the element.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::NameElementExt;
+    ///
+    /// let xml: &'static str = r#"<Siri xmlns="http://www.siri.org.uk/siri" />"#;
+    /// let root: Element = xml.parse().unwrap();
+    /// assert!(root.expect_named("http://www.siri.org.uk/siri", "Siri").is_ok());
+    /// let error = root.expect_named("http://www.netex.org.uk/netex", "PublicationDelivery").unwrap_err();
+    /// assert_eq!(
+    ///     "Expected element '{http://www.netex.org.uk/netex}PublicationDelivery' but found '{http://www.siri.org.uk/siri}Siri'",
+    ///     error.to_string()
+    /// );
+    /// ```
+    ///
+    /// [`NameElementExt`]: trait.NameElementExt.html
+    /// [`Element`]: ../minidom/element/struct.Element.html
+    fn expect_named<'a, NS>(&self, ns: NS, name: &str) -> Result<&Self, Error>
+    where
+        NS: Into<NSChoice<'a>>,
+    {
+        let ns = ns.into();
+        if self.is(name, ns) {
+            Ok(self)
+        } else {
+            Err(Error::UnexpectedElement {
+                expected: expected_name(ns, name),
+                found: clark_name(self.ns().as_deref(), self.name()),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn expected_element() {
+        let xml: &'static str = r#"<root />"#;
+        let root: Element = xml.parse().unwrap();
+        assert!(root.expect_named(NSChoice::None, "root").is_ok());
+        assert!(root.expect_named(NSChoice::Any, "root").is_ok());
+    }
+
+    #[test]
+    fn unexpected_namespace() {
+        let xml: &'static str = r#"<root xmlns="urn:a" />"#;
+        let root: Element = xml.parse().unwrap();
+        let error = root
+            .expect_named(NSChoice::AnyOf(&["urn:b", "urn:c"]), "root")
+            .unwrap_err();
+        assert_eq!(
+            "Expected element \'{urn:b|urn:c}root\' but found \'{urn:a}root\'",
+            format!("{}", error)
+        );
+    }
+}
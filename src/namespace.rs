@@ -0,0 +1,472 @@
+use crate::{name::clark_name, suggest::attribute_not_found, Error};
+use minidom::Element;
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+};
+
+/// Namespace bound to the reserved `xml` prefix (`xml:lang`, `xml:base`,
+/// `xml:space`, ...).
+pub const XML_NS: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// Namespace bound to the reserved `xmlns` prefix.
+pub const XMLNS_NS: &str = "http://www.w3.org/2000/xmlns/";
+
+/// Namespace of XML Schema instance attributes (`xsi:type`, `xsi:nil`,
+/// `xsi:schemaLocation`, ...).
+pub const XSI_NS: &str = "http://www.w3.org/2001/XMLSchema-instance";
+
+/// Split a qualified name into its prefix (if any) and its local part.
+pub(crate) fn split_qname(qname: &str) -> (Option<&str>, &str) {
+    match qname.find(':') {
+        Some(index) => (Some(&qname[..index]), &qname[index + 1..]),
+        None => (None, qname),
+    }
+}
+
+/// Resolve a prefix into a namespace URI, taking into account the
+/// declarations of the element and of all its ancestors (see
+/// [`NamespaceScope`]).
+pub(crate) fn lookup_prefix(element: &Element, prefix: Option<&str>) -> Option<String> {
+    PrefixResolver::new(element).resolve(prefix)
+}
+
+/// Resolve several prefixes in the scope of the same element, reading its
+/// [`NamespaceScope`] at most once for all the prefixes.
+pub(crate) struct PrefixResolver<'a> {
+    element: &'a Element,
+    scope: Option<NamespaceScope>,
+}
+
+impl<'a> PrefixResolver<'a> {
+    pub(crate) fn new(element: &'a Element) -> Self {
+        PrefixResolver {
+            element,
+            scope: None,
+        }
+    }
+
+    pub(crate) fn resolve(&mut self, prefix: Option<&str>) -> Option<String> {
+        match prefix {
+            Some("xml") => return Some(XML_NS.to_owned()),
+            Some("xmlns") => return Some(XMLNS_NS.to_owned()),
+            _ => {}
+        }
+        let element = self.element;
+        if element.prefix() == prefix {
+            return element.ns();
+        }
+        self.scope
+            .get_or_insert_with(|| NamespaceScope::of(element))
+            .resolve(prefix)
+            .map(ToOwned::to_owned)
+    }
+}
+
+/// Namespace declarations in scope for an element: its own declarations
+/// first, then the ones of its parent, and so on up to the root.
+///
+/// `minidom` keeps this chain private and only exposes it through the
+/// `Debug` implementation of `Element`, in which the `namespaces` field comes
+/// right after the name. The chain is read from there, by a formatter which
+/// stops as soon as the field is complete, so neither the attributes nor the
+/// children of the element are formatted.
+pub(crate) struct NamespaceScope {
+    levels: Vec<BTreeMap<Option<String>, String>>,
+}
+
+impl NamespaceScope {
+    pub(crate) fn of(element: &Element) -> Self {
+        let mut writer = ScopeWriter::default();
+        // The error is expected as the writer aborts right after the field
+        let _ = write!(writer, "{:?}", element);
+        NamespaceScope {
+            levels: parse_scope(&writer.buffer).unwrap_or_default(),
+        }
+    }
+
+    /// The namespace bound to the prefix by the closest declaration.
+    pub(crate) fn resolve(&self, prefix: Option<&str>) -> Option<&str> {
+        let key = prefix.map(ToOwned::to_owned);
+        self.levels
+            .iter()
+            .find_map(|declarations| declarations.get(&key))
+            .map(String::as_str)
+    }
+
+    /// The declarations of the element itself.
+    pub(crate) fn into_own(self) -> BTreeMap<Option<String>, String> {
+        self.levels.into_iter().next().unwrap_or_default()
+    }
+}
+
+const NAMESPACES_FIELD: &str = ", namespaces: ";
+
+/// Formatter sink keeping only the `namespaces` field of a formatted
+/// element, and aborting the formatting once the field is complete.
+#[derive(Default)]
+struct ScopeWriter {
+    buffer: String,
+    in_field: bool,
+    depth: usize,
+    quoted: bool,
+    escaped: bool,
+}
+
+impl fmt::Write for ScopeWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if !self.in_field {
+            self.buffer.push_str(s);
+            return match self.buffer.find(NAMESPACES_FIELD) {
+                Some(index) => {
+                    let field = self.buffer.split_off(index + NAMESPACES_FIELD.len());
+                    self.buffer.clear();
+                    self.in_field = true;
+                    self.write_str(&field)
+                }
+                None => Ok(()),
+            };
+        }
+        for c in s.chars() {
+            self.buffer.push(c);
+            if self.quoted {
+                match c {
+                    _ if self.escaped => self.escaped = false,
+                    '\\' => self.escaped = true,
+                    '"' => self.quoted = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match c {
+                '"' => self.quoted = true,
+                '(' => self.depth += 1,
+                ')' => {
+                    self.depth -= 1;
+                    if self.depth == 0 {
+                        return Err(fmt::Error);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse the declarations out of a formatted namespace set, formatted as
+/// `NamespaceSet(xmlns="...", xmlns:prefix="...", parent: Some(...))`.
+fn parse_scope(mut chain: &str) -> Option<Vec<BTreeMap<Option<String>, String>>> {
+    let mut levels = Vec::new();
+    loop {
+        chain = chain.strip_prefix("NamespaceSet(")?;
+        let mut declarations = BTreeMap::new();
+        while let Some(rest) = chain.strip_prefix("xmlns") {
+            let (prefix, rest) = match rest.strip_prefix(':') {
+                Some(rest) => {
+                    let end = rest.find('=')?;
+                    (Some(rest[..end].to_owned()), &rest[end..])
+                }
+                None => (None, rest),
+            };
+            let (namespace, rest) = parse_quoted(rest.strip_prefix('=')?)?;
+            declarations.insert(prefix, namespace);
+            chain = rest.strip_prefix(", ")?;
+        }
+        levels.push(declarations);
+        chain = chain.strip_prefix("parent: ")?;
+        match chain.strip_prefix("Some(") {
+            Some(parent) => chain = parent,
+            None => return Some(levels),
+        }
+    }
+}
+
+/// Parse a string formatted with `Debug`, returning it unescaped along with
+/// what follows it.
+fn parse_quoted(formatted: &str) -> Option<(String, &str)> {
+    let mut chars = formatted.strip_prefix('"')?.char_indices();
+    let mut value = String::new();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => return Some((value, &formatted[index + 2..])),
+            '\\' => {
+                let escaped = match chars.next()?.1 {
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    '0' => '\0',
+                    'u' => {
+                        let code: String = chars
+                            .by_ref()
+                            .map(|(_, c)| c)
+                            .skip(1)
+                            .take_while(|c| *c != '}')
+                            .collect();
+                        char::from_u32(u32::from_str_radix(&code, 16).ok()?)?
+                    }
+                    c => c,
+                };
+                value.push(escaped);
+            }
+            c => value.push(c),
+        }
+    }
+    None
+}
+
+/// Get the namespace declarations of the element itself (the `xmlns` and
+/// `xmlns:*` attributes), which `minidom` keeps private.
+pub(crate) fn own_declarations(element: &Element) -> BTreeMap<Option<String>, String> {
+    NamespaceScope::of(element).into_own()
+}
+
+/// Error returned when a qualified name found in a value cannot be resolved.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum QNameError {
+    /// The value is not a valid qualified name (`prefix:local` or `local`).
+    #[error("Invalid qualified name '{0}'")]
+    InvalidQName(String),
+    /// The prefix is not bound to any namespace in scope.
+    #[error("Prefix '{0}' is not declared")]
+    UndeclaredPrefix(String),
+}
+
+/// A qualified name resolved into a namespace and a local name.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct QName {
+    /// Namespace URI, if any
+    pub namespace: Option<String>,
+    /// Local name
+    pub local: String,
+}
+
+impl QName {
+    /// Check if the qualified name has the given namespace (a URI or an
+    /// [`Ns`] handle) and local name.
+    ///
+    /// [`Ns`]: struct.Ns.html
+    pub fn is(&self, namespace: impl AsRef<str>, local: &str) -> bool {
+        self.namespace.as_deref() == Some(namespace.as_ref()) && self.local == local
+    }
+}
+
+impl fmt::Display for QName {
+    /// Display the qualified name in Clark notation (`{namespace}local`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", clark_name(self.namespace.as_deref(), &self.local))
+    }
+}
+
+fn resolve_qname(element: &Element, value: &str) -> Result<QName, QNameError> {
+    let value = value.trim();
+    let (prefix, local) = split_qname(value);
+    if local.is_empty() || local.contains(':') || prefix == Some("") {
+        return Err(QNameError::InvalidQName(value.to_owned()));
+    }
+    let namespace = lookup_prefix(element, prefix);
+    if let (Some(prefix), None) = (prefix, &namespace) {
+        return Err(QNameError::UndeclaredPrefix(prefix.to_owned()));
+    }
+    Ok(QName {
+        namespace,
+        local: local.to_owned(),
+    })
+}
+
+/// Inspect the namespace declarations of an element.
+pub trait NamespaceElementExt {
+    /// Resolve a prefix into the namespace URI it is bound to, taking into
+    /// account the declarations of the element and of all its ancestors.
+    ///
+    /// The reserved `xml` and `xmlns` prefixes are always resolved. Returns
+    /// [`None`] if the prefix is not declared.
+    ///
+    /// [`None`]:  https://doc.rust-lang.org/std/option/enum.Option.html#variant.None
+    fn resolve_prefix(&self, prefix: &str) -> Option<String>;
+
+    /// Resolve the default namespace (declared with `xmlns="..."`) in scope
+    /// for the element.
+    fn default_namespace(&self) -> Option<String>;
+
+    /// Get the namespaces declared on the element itself, by prefix (`None`
+    /// being the default namespace). Declarations inherited from ancestors are
+    /// not included.
+    fn declared_namespaces(&self) -> BTreeMap<Option<String>, String>;
+
+    /// Try to get an attribute whose value is a qualified name (like
+    /// `xsi:type="netex:StopPlace_VersionStructure"`) and resolve its prefix
+    /// against the namespaces in scope. An unprefixed value is in the default
+    /// namespace.
+    ///
+    /// Returns a [`ParseError`] with a [`QNameError`] source if the value is
+    /// not a qualified name or if its prefix is not declared.
+    ///
+    /// [`ParseError`]: enum.Error.html#variant.ParseError
+    /// [`QNameError`]: enum.QNameError.html
+    fn try_attribute_qname(&self, attr_name: &str) -> Result<QName, Error>;
+
+    /// Get an attribute whose value is a qualified name if present and valid
+    /// and return an [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn attribute_qname(&self, attr_name: &str) -> Option<QName> {
+        self.try_attribute_qname(attr_name).ok()
+    }
+}
+
+impl NamespaceElementExt for Element {
+    /// Implementation of [`NamespaceElementExt`] for [`Element`] gives you
+    /// access to the namespace declarations that `minidom` otherwise only uses
+    /// internally, for example to resolve prefixed values found in attributes
+    /// or texts.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::NamespaceElementExt;
+    ///
+    /// let xml: &'static str = r#"<Siri xmlns:siri="http://www.siri.org.uk/siri">
+    ///         <Request type="siri:StopMonitoring" />
+    ///     </Siri>"#;
+    /// let root: Element = xml.parse().unwrap();
+    /// let request = root.children().next().unwrap();
+    /// assert_eq!(
+    ///     Some("http://www.siri.org.uk/siri".to_owned()),
+    ///     request.resolve_prefix("siri")
+    /// );
+    /// ```
+    ///
+    /// [`NamespaceElementExt`]: trait.NamespaceElementExt.html
+    /// [`Element`]: ../minidom/element/struct.Element.html
+    fn resolve_prefix(&self, prefix: &str) -> Option<String> {
+        lookup_prefix(self, Some(prefix))
+    }
+
+    fn default_namespace(&self) -> Option<String> {
+        lookup_prefix(self, None)
+    }
+
+    fn declared_namespaces(&self) -> BTreeMap<Option<String>, String> {
+        own_declarations(self)
+    }
+
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::{NamespaceElementExt, XSI_NS};
+    ///
+    /// let xml: &'static str = r#"<root xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns:n="urn:netex">
+    ///         <Place xsi:type="n:StopPlace" />
+    ///     </root>"#;
+    /// let root: Element = xml.parse().unwrap();
+    /// let place = root.children().next().unwrap();
+    /// let qname = place.try_attribute_qname("xsi:type").unwrap();
+    /// assert!(qname.is("urn:netex", "StopPlace"));
+    /// ```
+    fn try_attribute_qname(&self, attr_name: &str) -> Result<QName, Error> {
+        let value = self
+            .attr(attr_name)
+            .ok_or_else(|| attribute_not_found(self, attr_name))?;
+        resolve_qname(self, value).map_err(|e| Error::ParseError {
+            element_name: self.name().to_owned(),
+            attribute_name: attr_name.to_owned(),
+            value: value.to_owned(),
+            source: e.into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn own_namespace_declarations() {
+        let xml: &'static str = r#"<root xmlns="default" xmlns:a="urn:a" a:attr="a &gt; b">
+                <child xmlns:b="urn:b?x=1&amp;y=2" />
+            </root>"#;
+        let root: Element = xml.parse().unwrap();
+        let mut expected = BTreeMap::new();
+        expected.insert(None, "default".to_owned());
+        expected.insert(Some("a".to_owned()), "urn:a".to_owned());
+        assert_eq!(expected, root.declared_namespaces());
+        let child = root.children().next().unwrap();
+        let mut expected = BTreeMap::new();
+        expected.insert(Some("b".to_owned()), "urn:b?x=1&y=2".to_owned());
+        assert_eq!(expected, child.declared_namespaces());
+        assert_eq!(Some("default".to_owned()), child.default_namespace());
+    }
+
+    #[test]
+    fn qname_attributes() {
+        let xml: &'static str = r#"<root xmlns="urn:default" xmlns:a="urn:a"
+                first="a:Type" second="Type" third="b:Type" fourth="a:" />"#;
+        let root: Element = xml.parse().unwrap();
+        assert_eq!(
+            "{urn:a}Type",
+            root.try_attribute_qname("first").unwrap().to_string()
+        );
+        assert_eq!(
+            "{urn:default}Type",
+            root.try_attribute_qname("second").unwrap().to_string()
+        );
+        let error = root.try_attribute_qname("third").unwrap_err();
+        match error {
+            Error::ParseError { source, .. } => {
+                assert_eq!("Prefix 'b' is not declared", source.to_string())
+            }
+            e => panic!("unexpected error {:?}", e),
+        }
+        assert!(root.attribute_qname("fourth").is_none());
+    }
+
+    #[test]
+    fn split_prefixed_name() {
+        assert_eq!((Some("xsi"), "type"), split_qname("xsi:type"));
+        assert_eq!((None, "type"), split_qname("type"));
+    }
+
+    #[test]
+    fn lookup_inherited_prefix() {
+        let xml: &'static str = r#"<root xmlns="default" xmlns:a="urn:a">
+                <child xmlns:b="urn:b" />
+            </root>"#;
+        let root: Element = xml.parse().unwrap();
+        let child = root.children().next().unwrap();
+        assert_eq!(Some("urn:a".to_owned()), lookup_prefix(child, Some("a")));
+        assert_eq!(Some("urn:b".to_owned()), lookup_prefix(child, Some("b")));
+        assert_eq!(Some("default".to_owned()), lookup_prefix(child, None));
+        assert_eq!(Some(XML_NS.to_owned()), lookup_prefix(child, Some("xml")));
+        assert_eq!(None, lookup_prefix(child, Some("c")));
+        assert_eq!(None, lookup_prefix(&root, Some("b")));
+    }
+
+    #[test]
+    fn lookup_prefix_redeclared_in_subtree() {
+        let xml: &'static str = r#"<root xmlns:a="urn:a">
+                <child><x:b xmlns:x="urn:x" xmlns:a="urn:other"><c/></x:b><d/></child>
+            </root>"#;
+        let root: Element = xml.parse().unwrap();
+        let child = root.children().next().unwrap();
+        let mut resolver = PrefixResolver::new(child);
+        assert_eq!(Some("urn:a".to_owned()), resolver.resolve(Some("a")));
+        assert_eq!(None, resolver.resolve(Some("x")));
+        assert_eq!(None, resolver.resolve(None));
+    }
+
+    #[test]
+    fn lookup_escaped_and_redeclared_prefixes() {
+        let xml: &'static str = r#"<root xmlns:q="urn:&quot;q&quot;\é&#9;" xmlns:a="urn:a">
+                <mid xmlns:a="urn:mid"><leaf a:x="1" /></mid>
+            </root>"#;
+        let root: Element = xml.parse().unwrap();
+        let leaf = root.children().next().unwrap().children().next().unwrap();
+        assert_eq!(
+            Some("urn:\"q\"\\é\t".to_owned()),
+            lookup_prefix(leaf, Some("q"))
+        );
+        assert_eq!(Some("urn:mid".to_owned()), lookup_prefix(leaf, Some("a")));
+        assert_eq!(Some("urn:a".to_owned()), lookup_prefix(&root, Some("a")));
+        assert!(leaf.declared_namespaces().is_empty());
+    }
+}
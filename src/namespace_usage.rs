@@ -0,0 +1,298 @@
+use crate::{
+    namespace::{own_declarations, split_qname},
+    tree::{replace_start_tag, OwnedElement},
+};
+use minidom::Element;
+use std::collections::{BTreeSet, HashMap};
+
+/// A namespace declaration found in a document, see [`namespace_report`].
+///
+/// [`namespace_report`]: fn.namespace_report.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamespaceDeclaration {
+    /// Path of the declaring element, like `/root/frame[1]`
+    pub path: String,
+    /// The declared prefix, `None` for the default namespace
+    pub prefix: Option<String>,
+    /// The namespace URI
+    pub namespace: String,
+    /// Paths of the elements using this declaration in their name, in the
+    /// name of one of their attributes or in a qualified name value or text
+    /// (like `xsi:type="netex:StopPlace"` or `<Type>netex:StopPlace</Type>`),
+    /// in document order
+    pub used_in: Vec<String>,
+}
+
+impl NamespaceDeclaration {
+    /// Check if the declaration is used by any element.
+    pub fn is_used(&self) -> bool {
+        !self.used_in.is_empty()
+    }
+}
+
+/// All the namespace declarations of a document, see [`namespace_report`].
+///
+/// [`namespace_report`]: fn.namespace_report.html
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NamespaceReport {
+    /// The declarations, in document order
+    pub declarations: Vec<NamespaceDeclaration>,
+}
+
+impl NamespaceReport {
+    /// Iterate over the declarations never used in their scope.
+    pub fn unused(&self) -> impl Iterator<Item = &NamespaceDeclaration> {
+        self.declarations
+            .iter()
+            .filter(|declaration| !declaration.is_used())
+    }
+}
+
+pub(crate) fn is_local_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(|c: char| c == ':' || c == '/' || c.is_whitespace())
+}
+
+struct Scope {
+    prefix: Option<String>,
+    declaration: usize,
+}
+
+/// Walk the tree, recording every declaration (with the index of the
+/// declaring element in document order) and marking them as used.
+fn walk(
+    element: &Element,
+    path: &str,
+    index: &mut usize,
+    scopes: &mut Vec<Scope>,
+    declarations: &mut Vec<(usize, NamespaceDeclaration)>,
+) {
+    let scope_len = scopes.len();
+    for (prefix, namespace) in own_declarations(element) {
+        scopes.push(Scope {
+            prefix: prefix.clone(),
+            declaration: declarations.len(),
+        });
+        declarations.push((
+            *index,
+            NamespaceDeclaration {
+                path: path.to_owned(),
+                prefix,
+                namespace,
+                used_in: Vec::new(),
+            },
+        ));
+    }
+    *index += 1;
+
+    // Values and texts are only considered as qualified names when their
+    // prefix is declared, to avoid taking URLs or times for qualified names
+    let qname_prefix = |value: &'_ str| match split_qname(value.trim()) {
+        (Some(prefix), local)
+            if is_local_name(local)
+                && scopes.iter().any(|s| s.prefix.as_deref() == Some(prefix)) =>
+        {
+            Some(prefix.to_owned())
+        }
+        _ => None,
+    };
+    let mut used: BTreeSet<Option<String>> = BTreeSet::new();
+    used.insert(element.prefix().map(ToOwned::to_owned));
+    for (name, value) in element.attrs() {
+        if let (Some(prefix), _) = split_qname(name) {
+            used.insert(Some(prefix.to_owned()));
+        }
+        if let Some(prefix) = qname_prefix(value) {
+            used.insert(Some(prefix));
+        }
+    }
+    for text in element.texts() {
+        if let Some(prefix) = qname_prefix(text) {
+            used.insert(Some(prefix));
+        }
+    }
+    for prefix in used {
+        if let Some(scope) = scopes.iter().rev().find(|s| s.prefix == prefix) {
+            declarations[scope.declaration]
+                .1
+                .used_in
+                .push(path.to_owned());
+        }
+    }
+
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for child in element.children() {
+        let name = child.name();
+        let position = match counts.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, count)) => {
+                *count += 1;
+                *count
+            }
+            None => {
+                counts.push((name, 1));
+                1
+            }
+        };
+        let child_path = format!("{}/{}[{}]", path, name, position);
+        walk(child, &child_path, index, scopes, declarations);
+    }
+    scopes.truncate(scope_len);
+}
+
+fn collect_declarations(element: &Element) -> Vec<(usize, NamespaceDeclaration)> {
+    let mut declarations = Vec::new();
+    let root_path = format!("/{}", element.name());
+    walk(
+        element,
+        &root_path,
+        &mut 0,
+        &mut Vec::new(),
+        &mut declarations,
+    );
+    declarations
+}
+
+/// Remove the `unused` declarations, by index of the declaring element in
+/// document order.
+fn remove_declarations(
+    element: &mut Element,
+    index: &mut usize,
+    unused: &HashMap<usize, Vec<Option<String>>>,
+) {
+    if let Some(prefixes) = unused.get(index) {
+        let mut start = OwnedElement::start_tag(element);
+        for prefix in prefixes {
+            start.namespaces.remove(prefix);
+        }
+        replace_start_tag(element, &start);
+    }
+    *index += 1;
+    for child in element.children_mut() {
+        remove_declarations(child, index, unused);
+    }
+}
+
+/// List the namespace declarations of a document, where each one is used and
+/// which ones are never used.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::namespace_report;
+///
+/// let xml: &'static str = r#"<root xmlns="urn:netex" xmlns:gml="urn:gml" xmlns:siri="urn:siri">
+///         <gml:pos>1 2</gml:pos>
+///     </root>"#;
+/// let root: Element = xml.parse().unwrap();
+/// let report = namespace_report(&root);
+/// assert_eq!(3, report.declarations.len());
+/// let unused: Vec<_> = report.unused().map(|d| d.namespace.as_str()).collect();
+/// assert_eq!(vec!["urn:siri"], unused);
+/// ```
+pub fn namespace_report(element: &Element) -> NamespaceReport {
+    let declarations = collect_declarations(element)
+        .into_iter()
+        .map(|(_, declaration)| declaration)
+        .collect();
+    NamespaceReport { declarations }
+}
+
+/// Remove the namespace declarations which are never used in their scope, see
+/// [`namespace_report`]. Returns the number of removed declarations.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::remove_unused_ns_decls;
+///
+/// let xml: &'static str = r#"<root xmlns="urn:netex" xmlns:siri="urn:siri"><Line /></root>"#;
+/// let mut root: Element = xml.parse().unwrap();
+/// assert_eq!(1, remove_unused_ns_decls(&mut root));
+/// let mut output = Vec::new();
+/// root.write_to(&mut output).unwrap();
+/// assert_eq!(
+///     r#"<root xmlns="urn:netex"><Line/></root>"#,
+///     String::from_utf8(output).unwrap()
+/// );
+/// ```
+///
+/// [`namespace_report`]: fn.namespace_report.html
+pub fn remove_unused_ns_decls(element: &mut Element) -> usize {
+    let mut unused: HashMap<usize, Vec<Option<String>>> = HashMap::new();
+    let mut count = 0;
+    for (index, declaration) in collect_declarations(element) {
+        if !declaration.is_used() {
+            unused.entry(index).or_default().push(declaration.prefix);
+            count += 1;
+        }
+    }
+    if count > 0 {
+        remove_declarations(element, &mut 0, &unused);
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn shadowed_declarations() {
+        let xml: &'static str = r#"<a:root xmlns:a="urn:a1" xmlns:b="urn:b">
+                <a:x xmlns:a="urn:a2"><a:y /></a:x>
+                <z xmlns:a="urn:a3" />
+            </a:root>"#;
+        let root: Element = xml.parse().unwrap();
+        let report = namespace_report(&root);
+        let declarations: Vec<(&str, &str, Vec<&str>)> = report
+            .declarations
+            .iter()
+            .map(|d| {
+                (
+                    d.path.as_str(),
+                    d.namespace.as_str(),
+                    d.used_in.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                ("/root", "urn:a1", vec!["/root"]),
+                ("/root", "urn:b", vec![]),
+                (
+                    "/root/x[1]",
+                    "urn:a2",
+                    vec!["/root/x[1]", "/root/x[1]/y[1]"]
+                ),
+                ("/root/z[1]", "urn:a3", vec![]),
+            ],
+            declarations
+        );
+    }
+
+    #[test]
+    fn qualified_name_values() {
+        let xml: &'static str = r#"<root xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns:n="urn:n" xmlns:h="urn:h">
+                <Place xsi:type="n:StopPlace" href="h://example" />
+            </root>"#;
+        let mut root: Element = xml.parse().unwrap();
+        assert_eq!(1, remove_unused_ns_decls(&mut root));
+        let unused: Vec<_> = namespace_report(&root).unused().cloned().collect();
+        assert!(unused.is_empty());
+        assert_eq!(0, remove_unused_ns_decls(&mut root));
+    }
+
+    #[test]
+    fn qualified_name_texts() {
+        let xml: &'static str = r#"<root xmlns:n="urn:n" xmlns:t="urn:t" xmlns:u="urn:u"><frame xmlns:v="urn:v"><t:Type>n:StopPlace</t:Type><Time>10:30</Time></frame></root>"#;
+        let mut root: Element = xml.parse().unwrap();
+        assert_eq!(2, remove_unused_ns_decls(&mut root));
+        assert_eq!(
+            r#"<root xmlns:n="urn:n" xmlns:t="urn:t"><frame><t:Type>n:StopPlace</t:Type><Time>10:30</Time></frame></root>"#,
+            String::from(&root)
+        );
+        let frame = root.children().next().unwrap();
+        assert_eq!(
+            Some("urn:t".to_owned()),
+            frame.children().next().unwrap().ns()
+        );
+    }
+}
@@ -0,0 +1,390 @@
+use crate::{
+    attribute::{find_attribute_ns, parse_enum},
+    name::clark_name,
+    sanitize::sanitize,
+    scratch::with_text,
+    suggest::attribute_not_found,
+    util::{parse_text, parse_value},
+    value::parse_list,
+    AttrValue, AttributeElementExt, ControlCharacters, Error, TextElementExt,
+};
+use minidom::Element;
+use std::{borrow::Cow, fmt, sync::Arc};
+
+#[derive(Clone)]
+enum Step {
+    Trim,
+    CollapseWhitespace,
+    Custom(Arc<dyn Fn(&str) -> String + Send + Sync>),
+}
+
+impl fmt::Debug for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::Trim => write!(f, "Trim"),
+            Step::CollapseWhitespace => write!(f, "CollapseWhitespace"),
+            Step::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+/// Check if a value has no leading, trailing or repeated whitespaces, nor
+/// whitespaces other than spaces.
+fn is_collapsed(value: &str) -> bool {
+    value.trim() == value
+        && !value.contains("  ")
+        && !value.contains(|c: char| c.is_whitespace() && c != ' ')
+}
+
+impl Step {
+    fn apply<'a>(&self, value: Cow<'a, str>) -> Cow<'a, str> {
+        match (self, value) {
+            (Step::Trim, Cow::Borrowed(value)) => Cow::Borrowed(value.trim()),
+            (Step::Trim, Cow::Owned(value)) => Cow::Owned(value.trim().to_owned()),
+            (Step::CollapseWhitespace, value) if is_collapsed(&value) => value,
+            (Step::CollapseWhitespace, value) => {
+                Cow::Owned(value.split_whitespace().collect::<Vec<_>>().join(" "))
+            }
+            (Step::Custom(f), value) => Cow::Owned(f(&value)),
+        }
+    }
+}
+
+/// A chain of normalizations applied to attributes and texts before they are
+/// parsed, to clean dirty feeds consistently, see [`ParseOptions`].
+///
+/// Steps are applied in the order they are added. Values which are already
+/// normalized are not copied (except by custom steps).
+///
+/// ```
+/// use minidom_ext::Normalizer;
+///
+/// let normalizer = Normalizer::new()
+///     .collapse_whitespace()
+///     .with_fn(|value| value.replace('’', "'"));
+/// assert_eq!("Gare de l'Est", normalizer.normalize("  Gare  de\nl’Est "));
+/// ```
+///
+/// [`ParseOptions`]: struct.ParseOptions.html
+#[derive(Debug, Clone, Default)]
+pub struct Normalizer {
+    steps: Vec<Step>,
+}
+
+impl Normalizer {
+    /// Create a normalizer leaving values untouched.
+    pub fn new() -> Self {
+        Normalizer::default()
+    }
+
+    /// Remove leading and trailing whitespaces.
+    pub fn trim(mut self) -> Self {
+        self.steps.push(Step::Trim);
+        self
+    }
+
+    /// Remove leading and trailing whitespaces and replace the inner
+    /// sequences of whitespaces by a single space.
+    pub fn collapse_whitespace(mut self) -> Self {
+        self.steps.push(Step::CollapseWhitespace);
+        self
+    }
+
+    /// Apply a custom normalization, like the Unicode NFC form computed by
+    /// the `unicode-normalization` crate.
+    pub fn with_fn<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.steps.push(Step::Custom(Arc::new(f)));
+        self
+    }
+
+    /// Normalize a value.
+    pub fn normalize<'a>(&self, value: &'a str) -> Cow<'a, str> {
+        self.apply(Cow::Borrowed(value))
+    }
+
+    fn apply<'a>(&self, value: Cow<'a, str>) -> Cow<'a, str> {
+        self.steps
+            .iter()
+            .fold(value, |value, step| step.apply(value))
+    }
+}
+
+/// How attributes and texts are prepared before being parsed by
+/// [`AttributeElementExt::try_attribute_with`] and
+/// [`TextElementExt::try_text_with`].
+///
+/// Control characters are handled first (see [`ControlCharacters`]), then the
+/// value is normalized (see [`Normalizer`]).
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{AttributeElementExt, ControlCharacters, Normalizer, ParseOptions};
+///
+/// let options = ParseOptions::new()
+///     .with_control_characters(ControlCharacters::Scrub)
+///     .with_normalizer(Normalizer::new().collapse_whitespace());
+/// let xml: &'static str = r#"<Line name=" Line&#7;  1 " />"#;
+/// let line: Element = xml.parse().unwrap();
+/// let name: String = line.try_attribute_with("name", &options).unwrap();
+/// assert_eq!("Line 1", name);
+/// ```
+///
+/// To apply the options in all the typed getters (booleans, durations,
+/// lists, namespaced attributes, ...), get a view of the element with
+/// [`ParseOptions::on`].
+///
+/// [`AttributeElementExt::try_attribute_with`]: trait.AttributeElementExt.html#tymethod.try_attribute_with
+/// [`ControlCharacters`]: enum.ControlCharacters.html
+/// [`Normalizer`]: struct.Normalizer.html
+/// [`ParseOptions::on`]: struct.ParseOptions.html#method.on
+/// [`TextElementExt::try_text_with`]: trait.TextElementExt.html#tymethod.try_text_with
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    control_characters: Option<ControlCharacters>,
+    normalizer: Normalizer,
+}
+
+impl ParseOptions {
+    /// Create options leaving values untouched.
+    pub fn new() -> Self {
+        ParseOptions::default()
+    }
+
+    /// Reject or remove the control characters of the values.
+    pub fn with_control_characters(mut self, policy: ControlCharacters) -> Self {
+        self.control_characters = Some(policy);
+        self
+    }
+
+    /// Normalize the values.
+    pub fn with_normalizer(mut self, normalizer: Normalizer) -> Self {
+        self.normalizer = normalizer;
+        self
+    }
+
+    /// View `element` through the options: all the getters of
+    /// [`AttributeElementExt`] and [`TextElementExt`] prepare the values with
+    /// the options before parsing them.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::{AttributeElementExt, ControlCharacters, ParseOptions, TextElementExt};
+    ///
+    /// let options = ParseOptions::new().with_control_characters(ControlCharacters::Scrub);
+    /// let xml: &'static str = r#"<Quay accessible="true&#7;" codes="1 2&#7;">PT5M&#7;</Quay>"#;
+    /// let quay: Element = xml.parse().unwrap();
+    /// assert!(quay.try_attribute_bool("accessible").is_err());
+    /// let quay = options.on(&quay);
+    /// assert!(quay.try_attribute_bool("accessible").unwrap());
+    /// assert_eq!(vec![1, 2], quay.try_attribute_list::<u32>("codes").unwrap());
+    /// assert_eq!(300, quay.try_text_duration().unwrap().as_secs());
+    /// ```
+    ///
+    /// [`AttributeElementExt`]: trait.AttributeElementExt.html
+    /// [`TextElementExt`]: trait.TextElementExt.html
+    pub fn on<'a>(&'a self, element: &'a Element) -> WithOptions<'a> {
+        WithOptions {
+            element,
+            options: self,
+        }
+    }
+
+    /// Prepare a value to be parsed, `location` describing where the value
+    /// comes from in case of error.
+    pub(crate) fn prepare<'a, L>(&self, value: &'a str, location: L) -> Result<Cow<'a, str>, Error>
+    where
+        L: FnOnce() -> String,
+    {
+        let value = match self.control_characters {
+            Some(policy) => sanitize(value, policy, location)?,
+            None => Cow::Borrowed(value),
+        };
+        Ok(self.normalizer.apply(value))
+    }
+}
+
+impl From<Normalizer> for ParseOptions {
+    fn from(normalizer: Normalizer) -> Self {
+        ParseOptions::new().with_normalizer(normalizer)
+    }
+}
+
+/// An element whose values are prepared by [`ParseOptions`] before being
+/// parsed, see [`ParseOptions::on`].
+///
+/// [`ParseOptions`]: struct.ParseOptions.html
+/// [`ParseOptions::on`]: struct.ParseOptions.html#method.on
+#[derive(Debug, Clone, Copy)]
+pub struct WithOptions<'a> {
+    element: &'a Element,
+    options: &'a ParseOptions,
+}
+
+impl<'a> WithOptions<'a> {
+    /// The element viewed through the options.
+    pub fn element(&self) -> &'a Element {
+        self.element
+    }
+
+    fn attribute_location(&self, attr_name: &str) -> String {
+        format!(
+            "attribute '{}' of element '{}'",
+            attr_name,
+            self.element.name()
+        )
+    }
+
+    fn text_location(&self) -> String {
+        format!("text of element '{}'", self.element.name())
+    }
+
+    fn check_nil(&self) -> Result<(), Error> {
+        if self.element.is_nil() {
+            return Err(Error::NilElement {
+                element_name: self.element.name().to_owned(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl AttributeElementExt for WithOptions<'_> {
+    fn try_attribute<F>(&self, attr_name: &str) -> Result<F, Error>
+    where
+        F: AttrValue,
+    {
+        self.element.try_attribute_with(attr_name, self.options)
+    }
+
+    /// The values are prepared by the options of the view, then by
+    /// `options`.
+    fn try_attribute_with<F>(&self, attr_name: &str, options: &ParseOptions) -> Result<F, Error>
+    where
+        F: AttrValue,
+    {
+        let value = self
+            .element
+            .attr(attr_name)
+            .ok_or_else(|| attribute_not_found(self.element, attr_name))?;
+        let value = self
+            .options
+            .prepare(value, || self.attribute_location(attr_name))?;
+        let value = options.prepare(&value, || self.attribute_location(attr_name))?;
+        parse_value(self.element, attr_name, &value)
+    }
+
+    fn try_attribute_ns<F>(&self, ns: impl AsRef<str>, attr_name: &str) -> Result<F, Error>
+    where
+        F: AttrValue,
+    {
+        let ns = ns.as_ref();
+        let (qualified_name, value) = find_attribute_ns(self.element, ns, attr_name)
+            .ok_or_else(|| attribute_not_found(self.element, &clark_name(Some(ns), attr_name)))?;
+        let value = self
+            .options
+            .prepare(value, || self.attribute_location(qualified_name))?;
+        parse_value(self.element, qualified_name, &value)
+    }
+
+    fn try_attribute_enum<T>(&self, attr_name: &str, allowed: &[&str]) -> Result<T, Error>
+    where
+        T: AttrValue,
+    {
+        let value = self
+            .element
+            .attr(attr_name)
+            .ok_or_else(|| attribute_not_found(self.element, attr_name))?;
+        let value = self
+            .options
+            .prepare(value, || self.attribute_location(attr_name))?;
+        parse_enum(self.element, attr_name, &value, allowed)
+    }
+}
+
+impl TextElementExt for WithOptions<'_> {
+    fn try_text<F>(&self) -> Result<F, Error>
+    where
+        F: AttrValue,
+    {
+        self.element.try_text_with(self.options)
+    }
+
+    /// The text is prepared by the options of the view, then by `options`.
+    fn try_text_with<F>(&self, options: &ParseOptions) -> Result<F, Error>
+    where
+        F: AttrValue,
+    {
+        self.check_nil()?;
+        with_text(self.element, |text| {
+            let text = self.options.prepare(text, || self.text_location())?;
+            let text = options.prepare(&text, || self.text_location())?;
+            parse_text(self.element, &text)
+        })
+    }
+
+    fn is_nil(&self) -> bool {
+        self.element.is_nil()
+    }
+
+    fn try_text_list<F>(&self) -> Result<Vec<F>, Error>
+    where
+        F: AttrValue,
+    {
+        self.check_nil()?;
+        with_text(self.element, |text| {
+            let text = self.options.prepare(text, || self.text_location())?;
+            let value = text.trim();
+            parse_list(value, F::parse_attr).map_err(|e| Error::TextParseError {
+                element_name: self.element.name().to_owned(),
+                value: value.to_owned(),
+                source: e.into(),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn normalizations() {
+        let collapse = Normalizer::new().collapse_whitespace();
+        assert!(matches!(collapse.normalize("a b"), Cow::Borrowed("a b")));
+        assert_eq!("a b", collapse.normalize(" a \t b\n"));
+        let trim = Normalizer::new().trim();
+        assert!(matches!(trim.normalize(" a  b "), Cow::Borrowed("a  b")));
+        let chained = Normalizer::new().with_fn(str::to_uppercase).trim();
+        assert_eq!("A", chained.normalize(" a "));
+        assert_eq!(" a ", Normalizer::new().normalize(" a "));
+    }
+
+    #[test]
+    fn typed_getters_with_options() {
+        let options = ParseOptions::new().with_control_characters(ControlCharacters::Scrub);
+        let xml: &'static str =
+            r#"<a xmlns:x="urn:x" x:id="4&#7;2" mode="bus&#7;" hex="CA&#7;FE">1 &#7;2</a>"#;
+        let element: Element = xml.parse().unwrap();
+        let view = options.on(&element);
+        assert_eq!(42, view.try_attribute_ns::<u32>("urn:x", "id").unwrap());
+        assert_eq!(
+            "bus",
+            view.try_attribute_enum::<String>("mode", &["bus"]).unwrap()
+        );
+        assert_eq!(vec![0xca, 0xfe], view.try_attribute_hex("hex").unwrap());
+        assert_eq!(vec![1, 2], view.try_text_list::<u32>().unwrap());
+        let error = ParseOptions::new()
+            .with_control_characters(ControlCharacters::Reject)
+            .on(&element)
+            .try_text_list::<u32>()
+            .unwrap_err();
+        assert_eq!(
+            "Forbidden control character U+0007 at byte 2 of text of element 'a'",
+            error.to_string()
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use minidom::NSChoice;
+use std::{
+    collections::{hash_map::DefaultHasher, HashSet},
+    fmt,
+    hash::{Hash, Hasher},
+    sync::{Arc, Mutex, OnceLock},
+};
+
+/// Get the shared storage of the URI, adding it to the global table (which
+/// is never emptied) on first use.
+fn interned(uri: &str) -> Arc<str> {
+    static INTERNER: OnceLock<Mutex<HashSet<Arc<str>>>> = OnceLock::new();
+    let mut interner = INTERNER
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match interner.get(uri) {
+        Some(uri) => Arc::clone(uri),
+        None => {
+            let uri: Arc<str> = Arc::from(uri);
+            interner.insert(Arc::clone(&uri));
+            uri
+        }
+    }
+}
+
+/// A namespace URI which can be stored and passed around cheaply.
+///
+/// Handles created from the same URI share the same storage, so cloning a
+/// handle, comparing two handles or hashing one (it only writes a
+/// pre-computed hash) is cheap, which makes them convenient keys for maps of
+/// namespaces. Every namespace-aware method accepts a `&Ns` as well as a
+/// `&str`, but the lookups themselves still compare the URI as a string.
+///
+/// The URIs are interned in a global table which is never emptied: every
+/// distinct URI converted into an `Ns` stays allocated until the program
+/// exits. Only create handles for a bounded set of namespaces, like the ones
+/// known by the application, and not for URIs read from the documents.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{AttributeElementExt, Ns, XSI_NS};
+///
+/// let xsi = Ns::from(XSI_NS);
+/// assert_eq!(xsi, Ns::from(XSI_NS.to_owned()));
+/// let xml: &'static str = r#"<root xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+///         <Place xsi:type="StopPlace" />
+///     </root>"#;
+/// let root: Element = xml.parse().unwrap();
+/// let place = root.children().next().unwrap();
+/// let xsi_type: String = place.try_attribute_ns(&xsi, "type").unwrap();
+/// assert_eq!("StopPlace", xsi_type);
+/// ```
+#[derive(Clone)]
+pub struct Ns {
+    uri: Arc<str>,
+    hash: u64,
+}
+
+impl Ns {
+    /// The namespace URI.
+    pub fn as_str(&self) -> &str {
+        &self.uri
+    }
+
+    /// Check if the handle is for the given URI.
+    pub fn matches(&self, uri: &str) -> bool {
+        self.uri.len() == uri.len() && *self.uri == *uri
+    }
+}
+
+impl From<&str> for Ns {
+    fn from(uri: &str) -> Self {
+        let mut hasher = DefaultHasher::new();
+        uri.hash(&mut hasher);
+        Ns {
+            uri: interned(uri),
+            hash: hasher.finish(),
+        }
+    }
+}
+
+impl From<String> for Ns {
+    fn from(uri: String) -> Self {
+        Ns::from(uri.as_str())
+    }
+}
+
+impl<'a> From<&'a Ns> for NSChoice<'a> {
+    fn from(ns: &'a Ns) -> Self {
+        NSChoice::OneOf(ns.as_str())
+    }
+}
+
+impl AsRef<str> for Ns {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq for Ns {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.uri, &other.uri) || (self.hash == other.hash && self.uri == other.uri)
+    }
+}
+
+impl Eq for Ns {}
+
+impl PartialEq<str> for Ns {
+    fn eq(&self, other: &str) -> bool {
+        self.matches(other)
+    }
+}
+
+impl PartialEq<&str> for Ns {
+    fn eq(&self, other: &&str) -> bool {
+        self.matches(other)
+    }
+}
+
+impl Hash for Ns {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
+}
+
+impl PartialOrd for Ns {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Ns {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.uri.cmp(&other.uri)
+    }
+}
+
+impl fmt::Debug for Ns {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Ns({:?})", self.uri)
+    }
+}
+
+impl fmt::Display for Ns {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.uri)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NameElementExt;
+    use minidom::Element;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn interned_handles() {
+        let a = Ns::from("urn:a");
+        let b = Ns::from(String::from("urn:a"));
+        assert!(Arc::ptr_eq(&a.uri, &b.uri));
+        assert_eq!(a, b);
+        assert_eq!(a, "urn:a");
+        let c = Ns::from("urn:b");
+        assert!(a != c);
+        assert_eq!("urn:a", a.to_string());
+
+        let root: Element = r#"<a:root xmlns:a="urn:a" />"#.parse().unwrap();
+        assert!(root.has_ns(&a));
+        assert!(root.expect_named(&a, "root").is_ok());
+    }
+}
@@ -1,5 +1,49 @@
-use crate::Error;
+use crate::{
+    suggest::no_children,
+    util::{collect_unique, unique},
+    ElementRef, Error,
+};
 use minidom::Element;
+use std::fmt;
+
+/// Which child to keep when a child expected to be unique is duplicated, see
+/// [`try_only_child_lenient`].
+///
+/// [`try_only_child_lenient`]: trait.OnlyChildElementExt.html#tymethod.try_only_child_lenient
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DuplicatePolicy {
+    /// Keep the first child in document order
+    TakeFirst,
+    /// Keep the last child in document order
+    TakeLast,
+}
+
+/// Warning returned when a child expected to be unique is duplicated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateChildWarning {
+    /// Name of the parent element
+    pub element_name: String,
+    /// Name of the duplicated child
+    pub child_name: String,
+    /// Number of children found
+    pub count: usize,
+    /// The policy used to choose the child
+    pub policy: DuplicatePolicy,
+}
+
+impl fmt::Display for DuplicateChildWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kept = match self.policy {
+            DuplicatePolicy::TakeFirst => "first",
+            DuplicatePolicy::TakeLast => "last",
+        };
+        write!(
+            f,
+            "Multiple children with name '{}' in Element '{}' (found {} elements), the {} one is used",
+            self.child_name, self.element_name, self.count, kept
+        )
+    }
+}
 
 /// Get the one and only child of an element.
 ///
@@ -58,6 +102,52 @@ pub trait OnlyChildElementExt {
     fn only_child<'a>(&'a self, child_name: &str) -> Option<&'a Self> {
         self.try_only_child(child_name).ok()
     }
+
+    /// Try to get an unique child from its name, tagged with the marker type
+    /// `M`, and return a [`Result`].
+    ///
+    /// Same errors as [`try_only_child`].
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`try_only_child`]: #tymethod.try_only_child
+    fn try_only_child_as<'a, M>(&'a self, child_name: &str) -> Result<ElementRef<'a, M>, Error>;
+
+    /// Get an unique child from its name, tagged with the marker type `M`,
+    /// and return an [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn only_child_as<'a, M>(&'a self, child_name: &str) -> Option<ElementRef<'a, M>> {
+        self.try_only_child_as(child_name).ok()
+    }
+
+    /// Try to get a child expected to be unique from its name and return a
+    /// [`Result`], tolerating duplicates.
+    ///
+    /// When the child is duplicated, the one chosen by `policy` is returned
+    /// with a [`DuplicateChildWarning`] instead of a [`MultipleChildren`]
+    /// error. Returns a [`NoChildren`] error if the child can't be found.
+    ///
+    /// [`DuplicateChildWarning`]: struct.DuplicateChildWarning.html
+    /// [`MultipleChildren`]: enum.Error.html#variant.MultipleChildren
+    /// [`NoChildren`]: enum.Error.html#variant.NoChildren
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_only_child_lenient<'a>(
+        &'a self,
+        child_name: &str,
+        policy: DuplicatePolicy,
+    ) -> Result<(&'a Self, Option<DuplicateChildWarning>), Error>;
+
+    /// Get a child expected to be unique from its name, tolerating
+    /// duplicates, and return an [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn only_child_lenient<'a>(
+        &'a self,
+        child_name: &str,
+        policy: DuplicatePolicy,
+    ) -> Option<(&'a Self, Option<DuplicateChildWarning>)> {
+        self.try_only_child_lenient(child_name, policy).ok()
+    }
 }
 
 impl OnlyChildElementExt for Element {
@@ -89,19 +179,22 @@ impl OnlyChildElementExt for Element {
     where
         P: Fn(&'a Self) -> bool,
     {
-        let mut child_iterator = self.children().filter(|child| predicate(child));
-        if let Some(child) = child_iterator.next() {
-            if child_iterator.next().is_none() {
-                Ok(child)
-            } else {
-                Err(Error::MultipleChildrenFound(
-                    self.name().to_owned(),
-                    2 + child_iterator.count(),
-                ))
-            }
-        } else {
-            Err(Error::NoChildrenFound(self.name().to_owned()))
-        }
+        unique(self.children().filter(|child| predicate(child))).map_err(|count| match count {
+            0 => Error::NoChildrenFound {
+                element_name: self.name().to_owned(),
+            },
+            count => Error::MultipleChildrenFound {
+                element_name: self.name().to_owned(),
+                count,
+            },
+        })
+    }
+
+    fn find_only_child<'a, P>(&'a self, predicate: P) -> Option<&'a Self>
+    where
+        P: Fn(&'a Self) -> bool,
+    {
+        unique(self.children().filter(|child| predicate(child))).ok()
     }
 
     /// Implementation of [`OnlyChildElementExt`] for [`Element`] gives you the ability to
@@ -125,16 +218,80 @@ impl OnlyChildElementExt for Element {
     /// [`OnlyChildElementExt`]: trait.OnlyChildElementExt.html
     /// [`Element`]: ../minidom/element/struct.Element.html
     fn try_only_child<'a>(&'a self, child_name: &str) -> Result<&'a Self, Error> {
-        self.try_find_only_child(|element| element.name() == child_name)
-            .map_err(|e| match e {
-                Error::MultipleChildrenFound(element_name, count) => {
-                    Error::MultipleChildren(element_name, child_name.to_owned(), count)
-                }
-                Error::NoChildrenFound(element_name) => {
-                    Error::NoChildren(element_name, child_name.to_owned())
-                }
-                e => e,
-            })
+        collect_unique(
+            self,
+            child_name,
+            self.children().filter(|child| child.name() == child_name),
+        )
+    }
+
+    fn only_child<'a>(&'a self, child_name: &str) -> Option<&'a Self> {
+        unique(self.children().filter(|child| child.name() == child_name)).ok()
+    }
+
+    fn try_only_child_as<'a, M>(&'a self, child_name: &str) -> Result<ElementRef<'a, M>, Error> {
+        self.try_only_child(child_name)
+            .and_then(|child| ElementRef::try_new(child, child_name))
+    }
+
+    fn only_child_as<'a, M>(&'a self, child_name: &str) -> Option<ElementRef<'a, M>> {
+        self.only_child(child_name)
+            .and_then(|child| ElementRef::try_new(child, child_name).ok())
+    }
+
+    /// Implementation of [`OnlyChildElementExt`] for [`Element`] keeps
+    /// ingesting documents whose producer duplicates a child which is
+    /// semantically unique.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::{DuplicatePolicy, OnlyChildElementExt};
+    ///
+    /// let xml: &'static str = r#"<Line>
+    ///         <Name>Metro 1</Name>
+    ///         <Name>Métro 1</Name>
+    ///     </Line>"#;
+    /// let line: Element = xml.parse().unwrap();
+    /// let (name, warning) = line
+    ///     .try_only_child_lenient("Name", DuplicatePolicy::TakeLast)
+    ///     .unwrap();
+    /// assert_eq!("Métro 1", name.text());
+    /// assert_eq!(
+    ///     "Multiple children with name 'Name' in Element 'Line' (found 2 elements), the last one is used",
+    ///     warning.unwrap().to_string()
+    /// );
+    /// ```
+    ///
+    /// [`OnlyChildElementExt`]: trait.OnlyChildElementExt.html
+    /// [`Element`]: ../minidom/element/struct.Element.html
+    fn try_only_child_lenient<'a>(
+        &'a self,
+        child_name: &str,
+        policy: DuplicatePolicy,
+    ) -> Result<(&'a Self, Option<DuplicateChildWarning>), Error> {
+        let mut children = self.children().filter(|child| child.name() == child_name);
+        let first = children
+            .next()
+            .ok_or_else(|| no_children(self, child_name))?;
+        let mut count = 1;
+        let mut last = first;
+        for child in children {
+            count += 1;
+            last = child;
+        }
+        if count == 1 {
+            return Ok((first, None));
+        }
+        let warning = DuplicateChildWarning {
+            element_name: self.name().to_owned(),
+            child_name: child_name.to_owned(),
+            count,
+            policy,
+        };
+        match policy {
+            DuplicatePolicy::TakeFirst => Ok((first, Some(warning))),
+            DuplicatePolicy::TakeLast => Ok((last, Some(warning))),
+        }
     }
 }
 
@@ -171,6 +328,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn lenient_only_child() {
+        let xml: &'static str = r#"<root>
+                <child id="1" />
+                <other />
+                <child id="2" />
+                <child id="3" />
+            </root>"#;
+        let root: Element = xml.parse().unwrap();
+        let (child, warning) = root
+            .try_only_child_lenient("child", DuplicatePolicy::TakeFirst)
+            .unwrap();
+        assert_eq!(Some("1"), child.attr("id"));
+        assert_eq!(3, warning.unwrap().count);
+        let (other, warning) = root
+            .try_only_child_lenient("other", DuplicatePolicy::TakeLast)
+            .unwrap();
+        assert_eq!("other", other.name());
+        assert_eq!(None, warning);
+        let error = root
+            .try_only_child_lenient("missing", DuplicatePolicy::TakeLast)
+            .unwrap_err();
+        assert_eq!(
+            "No children with name 'missing' in Element 'root' (available: child, other)",
+            format!("{}", error)
+        );
+    }
+
     #[test]
     fn multiple_child() {
         let xml: &'static str = r#"<root>
@@ -0,0 +1,73 @@
+//! Support of the [`path!`] macro.
+//!
+//! [`path!`]: ../macro.path.html
+
+use crate::{Axis, NameTest, Step};
+use std::borrow::Cow;
+
+/// Build the step of a name, failing the compilation if the name is not
+/// valid in a selector.
+#[doc(hidden)]
+pub const fn step(name: &'static str) -> Step {
+    let bytes = name.as_bytes();
+    if bytes.is_empty() {
+        panic!("empty name in path");
+    }
+    let name_test = if bytes.len() == 1 && bytes[0] == b'*' {
+        NameTest::Any
+    } else {
+        let mut index = 0;
+        while index < bytes.len() {
+            match bytes[index] {
+                b'/' | b'[' | b']' | b'@' | b'=' | b'\'' | b'"' | b'*' | b' ' | b'\t' | b'\r'
+                | b'\n' => panic!("invalid character in a path name"),
+                _ => index += 1,
+            }
+        }
+        NameTest::Name(Cow::Borrowed(name))
+    };
+    Step {
+        axis: Axis::Child,
+        name_test,
+        predicates: Cow::Borrowed(&[]),
+    }
+}
+
+/// Build a [`Selector`] made of child steps at compile time.
+///
+/// Each name is checked during the compilation, so a typo like a missing
+/// separator (`"lines/Line"`) fails to compile instead of failing at runtime.
+/// The result can be used in `const` items.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{path, Selector};
+///
+/// const LINES: Selector = path!("ServiceFrame" / "lines" / "Line");
+///
+/// let xml: &'static str = r#"<root>
+///         <ServiceFrame><lines><Line id="1" /><Line id="2" /></lines></ServiceFrame>
+///     </root>"#;
+/// let root: Element = xml.parse().unwrap();
+/// assert_eq!(2, LINES.select(&root).len());
+/// assert_eq!("ServiceFrame/lines/Line", LINES.as_str());
+/// assert_eq!(Selector::parse("ServiceFrame/lines/Line").unwrap(), LINES);
+/// ```
+///
+/// ```compile_fail
+/// use minidom_ext::{path, Selector};
+///
+/// const LINES: Selector = path!("ServiceFrame" / "lines/Line");
+/// ```
+///
+/// [`Selector`]: struct.Selector.html
+#[macro_export]
+macro_rules! path {
+    ($first:literal $(/ $rest:literal)*) => {{
+        const STEPS: &[$crate::Step] =
+            &[$crate::__path::step($first) $(, $crate::__path::step($rest))*];
+        const SELECTOR: $crate::Selector =
+            $crate::Selector::from_static(concat!($first $(, "/", $rest)*), STEPS);
+        SELECTOR
+    }};
+}
@@ -0,0 +1,212 @@
+use crate::Error;
+use minidom::{
+    quick_xml::{events::Event, Reader},
+    Element,
+};
+use std::fmt;
+
+/// Position of an element in the source of a document, see
+/// [`PositionedDocument`].
+///
+/// [`PositionedDocument`]: struct.PositionedDocument.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    /// Offset of the start tag, in bytes
+    pub offset: usize,
+    /// Line of the start tag, starting at 1
+    pub line: usize,
+    /// Column of the start tag, in characters, starting at 1
+    pub column: usize,
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// A parsed document remembering where each element starts in the source,
+/// to tell the people fixing the data where the rejected elements are.
+///
+/// Positions are recorded in document order, so locating an element walks
+/// the tree: this is meant to be done on errors only.
+///
+/// ```
+/// use minidom_ext::{AttributeElementExt, PositionedDocument};
+///
+/// let xml: &'static str = "<root>\n  <Line id=\"1\" />\n  <Line id=\"x\" />\n</root>";
+/// let document = PositionedDocument::parse(xml).unwrap();
+/// let line = document.root().children().nth(1).unwrap();
+/// let error = document
+///     .try_with(line, |line| line.try_attribute::<u32>("id"))
+///     .unwrap_err();
+/// assert_eq!(
+///     "At line 3, column 3: Failed to parse and convert the value 'x' of attribute 'id' in element 'Line'",
+///     error.to_string()
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct PositionedDocument {
+    root: Element,
+    /// Positions of the elements, in document order
+    positions: Vec<Position>,
+}
+
+/// Record the position of every start tag of `input`, in document order.
+fn record_positions(input: &str) -> Result<Vec<Position>, Error> {
+    let mut reader = Reader::from_str(input);
+    let mut buffer = Vec::new();
+    let mut positions = Vec::new();
+    // Characters before `scanned` on the current line, so that each part of
+    // the input is only counted once
+    let (mut line, mut column, mut scanned) = (1, 0, 0);
+    loop {
+        let offset = reader.buffer_position();
+        match reader.read_event(&mut buffer) {
+            Ok(Event::Start(_)) | Ok(Event::Empty(_)) => {
+                for c in input[scanned..offset].chars() {
+                    if c == '\n' {
+                        line += 1;
+                        column = 0;
+                    } else {
+                        column += 1;
+                    }
+                }
+                scanned = offset;
+                positions.push(Position {
+                    offset,
+                    line,
+                    column: column + 1,
+                });
+            }
+            Ok(Event::Eof) => return Ok(positions),
+            Ok(_) => {}
+            Err(error) => {
+                return Err(Error::InvalidDocument {
+                    source: minidom::Error::XmlError(error),
+                })
+            }
+        }
+        buffer.clear();
+    }
+}
+
+/// Find the index of `target` in the document order of the tree.
+fn document_index(element: &Element, target: &Element, index: &mut usize) -> bool {
+    if std::ptr::eq(element, target) {
+        return true;
+    }
+    for child in element.children() {
+        *index += 1;
+        if document_index(child, target, index) {
+            return true;
+        }
+    }
+    false
+}
+
+impl PositionedDocument {
+    /// Parse a document, recording the position of its elements.
+    pub fn parse(input: &str) -> Result<Self, Error> {
+        let root = input
+            .parse()
+            .map_err(|source| Error::InvalidDocument { source })?;
+        let positions = record_positions(input)?;
+        Ok(PositionedDocument { root, positions })
+    }
+
+    /// The root element of the document.
+    pub fn root(&self) -> &Element {
+        &self.root
+    }
+
+    /// Give back the root element, dropping the positions.
+    pub fn into_root(self) -> Element {
+        self.root
+    }
+
+    /// Position of an element of the document, `None` if the element is not
+    /// part of this document.
+    pub fn position(&self, element: &Element) -> Option<Position> {
+        let mut index = 0;
+        if document_index(&self.root, element, &mut index) {
+            self.positions.get(index).copied()
+        } else {
+            None
+        }
+    }
+
+    /// Wrap an error about `element` in a [`WithPosition`] error, the error
+    /// being returned as is if the element is not part of this document.
+    ///
+    /// [`WithPosition`]: enum.Error.html#variant.WithPosition
+    pub fn locate(&self, element: &Element, error: Error) -> Error {
+        match self.position(element) {
+            Some(position) => Error::WithPosition {
+                position,
+                source: Box::new(error),
+            },
+            None => error,
+        }
+    }
+
+    /// Call a helper on an element of the document, an error being wrapped
+    /// in a [`WithPosition`] error reporting where the element starts.
+    ///
+    /// [`WithPosition`]: enum.Error.html#variant.WithPosition
+    pub fn try_with<'a, T, F>(&self, element: &'a Element, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&'a Element) -> Result<T, Error>,
+    {
+        f(element).map_err(|error| self.locate(element, error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ErrorKind, OnlyChildElementExt};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn positions() {
+        let xml: &'static str = "<?xml version=\"1.0\"?>\n<!-- é -->\n<root><a>\n\t<b/><!-- <c/> --><é/></a>\n<d><![CDATA[<e/>]]></d></root>";
+        let document = PositionedDocument::parse(xml).unwrap();
+        let root = document.root();
+        let a = root.try_only_child("a").unwrap();
+        let b = a.try_only_child("b").unwrap();
+        let accented = a.try_only_child("é").unwrap();
+        let d = root.try_only_child("d").unwrap();
+        let located: Vec<(usize, usize)> = [root, a, b, accented, d]
+            .iter()
+            .map(|element| {
+                let position = document.position(element).unwrap();
+                (position.line, position.column)
+            })
+            .collect();
+        assert_eq!(vec![(3, 1), (3, 7), (4, 2), (4, 19), (5, 1)], located);
+        assert_eq!(
+            Some(b'<'),
+            xml.as_bytes()
+                .get(document.position(d).unwrap().offset)
+                .copied()
+        );
+        let other: Element = "<root/>".parse().unwrap();
+        assert_eq!(None, document.position(&other));
+        let error = document
+            .try_with(b, |b| b.try_only_child("missing"))
+            .unwrap_err();
+        assert_eq!(ErrorKind::NoChildren, error.kind());
+    }
+
+    #[test]
+    fn positions_on_a_single_line() {
+        let document = PositionedDocument::parse("<root><é/><a/><é/><a/></root>").unwrap();
+        let columns: Vec<usize> = document
+            .root()
+            .children()
+            .map(|child| document.position(child).unwrap().column)
+            .collect();
+        assert_eq!(vec![7, 11, 15, 19], columns);
+    }
+}
@@ -0,0 +1,246 @@
+use crate::{
+    materialize::detached_tree,
+    tree::{push_escaped, OwnedElement, OwnedNode},
+};
+use minidom::Element;
+use std::io;
+
+/// How [`PrettyElementExt`] lays out a tree.
+///
+/// [`PrettyElementExt`]: trait.PrettyElementExt.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrettyOptions {
+    indent: usize,
+    max_inline_attributes: Option<usize>,
+    self_closing: bool,
+}
+
+impl Default for PrettyOptions {
+    fn default() -> Self {
+        PrettyOptions {
+            indent: 2,
+            max_inline_attributes: None,
+            self_closing: true,
+        }
+    }
+}
+
+impl PrettyOptions {
+    /// Create options indenting by 2 spaces, keeping all the attributes on
+    /// the line of their element and self-closing the empty elements.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Indent each level by `indent` spaces.
+    pub fn with_indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Write each attribute (and namespace declaration) on its own line when
+    /// an element has more than `max` of them.
+    pub fn with_max_inline_attributes(mut self, max: usize) -> Self {
+        self.max_inline_attributes = Some(max);
+        self
+    }
+
+    /// Write the empty elements as `<a/>` (the default) or as `<a></a>`.
+    pub fn with_self_closing(mut self, self_closing: bool) -> Self {
+        self.self_closing = self_closing;
+        self
+    }
+
+    fn push_indentation(&self, depth: usize, output: &mut String) {
+        output.push('\n');
+        output.extend(std::iter::repeat_n(' ', self.indent * depth));
+    }
+}
+
+/// Serialize a tree for humans, one element per line.
+pub trait PrettyElementExt {
+    /// Write the element and its descendants with the layout of `options`.
+    ///
+    /// The texts made only of whitespaces between elements are replaced by
+    /// the indentation. Mixed content (elements with texts which are not only
+    /// whitespaces) and the elements under `xml:space="preserve"` are written
+    /// as is, on a single line, so their texts are unchanged. The namespaces
+    /// inherited from the ancestors of the element are declared on it.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::{PrettyElementExt, PrettyOptions};
+    ///
+    /// let xml: &'static str = r#"<Line xmlns="urn:netex" id="1" version="2"><Name>Metro <b>1</b></Name><Notes/></Line>"#;
+    /// let line: Element = xml.parse().unwrap();
+    /// let options = PrettyOptions::new()
+    ///     .with_indent(4)
+    ///     .with_max_inline_attributes(2)
+    ///     .with_self_closing(false);
+    /// let mut output = Vec::new();
+    /// line.write_pretty(&mut output, &options).unwrap();
+    /// assert_eq!(
+    ///     r#"<Line
+    ///     xmlns="urn:netex"
+    ///     id="1"
+    ///     version="2">
+    ///     <Name>Metro <b>1</b></Name>
+    ///     <Notes></Notes>
+    /// </Line>"#,
+    ///     String::from_utf8(output).unwrap()
+    /// );
+    /// ```
+    fn write_pretty<W: io::Write>(&self, writer: W, options: &PrettyOptions) -> io::Result<()>;
+
+    /// Serialize the element like [`write_pretty`] into a string.
+    ///
+    /// [`write_pretty`]: #tymethod.write_pretty
+    fn to_pretty_string(&self, options: &PrettyOptions) -> String;
+}
+
+impl PrettyElementExt for Element {
+    fn write_pretty<W: io::Write>(&self, mut writer: W, options: &PrettyOptions) -> io::Result<()> {
+        writer.write_all(self.to_pretty_string(options).as_bytes())
+    }
+
+    fn to_pretty_string(&self, options: &PrettyOptions) -> String {
+        let tree = detached_tree(self);
+        let mut output = String::new();
+        write(&tree, options, Some(0), &mut output);
+        output
+    }
+}
+
+fn is_blank(text: &str) -> bool {
+    text.chars().all(char::is_whitespace)
+}
+
+/// Write an element at `depth`, or on a single line without any added
+/// whitespace when `depth` is `None`.
+fn write(
+    element: &OwnedElement,
+    options: &PrettyOptions,
+    depth: Option<usize>,
+    output: &mut String,
+) {
+    let mut attributes: Vec<(String, &str)> = element
+        .namespaces
+        .iter()
+        .map(|(prefix, namespace)| {
+            let name = match prefix {
+                Some(prefix) => format!("xmlns:{}", prefix),
+                None => "xmlns".to_owned(),
+            };
+            (name, namespace.as_str())
+        })
+        .collect();
+    attributes.extend(
+        element
+            .attributes
+            .iter()
+            .map(|(name, value)| (name.clone(), value.as_str())),
+    );
+    let attribute_depth = depth.filter(|_| {
+        options
+            .max_inline_attributes
+            .is_some_and(|max| attributes.len() > max)
+    });
+    output.push('<');
+    output.push_str(&element.qname);
+    for (name, value) in &attributes {
+        match attribute_depth {
+            Some(depth) => options.push_indentation(depth + 1, output),
+            None => output.push(' '),
+        }
+        output.push_str(name);
+        output.push_str("=\"");
+        push_escaped(output, value);
+        output.push('"');
+    }
+    let preserve = element
+        .attributes
+        .iter()
+        .any(|(name, value)| name == "xml:space" && value == "preserve");
+    let mixed = element
+        .nodes
+        .iter()
+        .any(|node| matches!(node, OwnedNode::Text(text) if !is_blank(text)));
+    let depth = depth.filter(|_| !preserve && !mixed);
+    let nodes: Vec<&OwnedNode> = element
+        .nodes
+        .iter()
+        .filter(|node| match node {
+            OwnedNode::Text(text) => depth.is_none() || !is_blank(text),
+            _ => true,
+        })
+        .collect();
+    if nodes.is_empty() {
+        if options.self_closing {
+            output.push_str("/>");
+        } else {
+            output.push_str("></");
+            output.push_str(&element.qname);
+            output.push('>');
+        }
+        return;
+    }
+    output.push('>');
+    for node in nodes {
+        if let Some(depth) = depth {
+            options.push_indentation(depth + 1, output);
+        }
+        match node {
+            OwnedNode::Element(child) => {
+                write(child, options, depth.map(|depth| depth + 1), output)
+            }
+            OwnedNode::Text(text) => push_escaped(output, text),
+            OwnedNode::Comment(comment) => {
+                output.push_str("<!--");
+                output.push_str(comment);
+                output.push_str("-->");
+            }
+        }
+    }
+    if let Some(depth) = depth {
+        options.push_indentation(depth, output);
+    }
+    output.push_str("</");
+    output.push_str(&element.qname);
+    output.push('>');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn layout() {
+        let xml: &'static str = r#"<root xmlns:n="urn:n">
+  <n:a id="&quot;1&quot;"> <b/> <!-- c --> </n:a>
+  <p xml:space="preserve"> <b/> </p><c/>
+</root>"#;
+        let root: Element = xml.parse().unwrap();
+        assert_eq!(
+            r#"<root xmlns:n="urn:n">
+  <n:a id="&quot;1&quot;">
+    <b/>
+    <!-- c -->
+  </n:a>
+  <p xml:space="preserve"> <b/> </p>
+  <c/>
+</root>"#,
+            root.to_pretty_string(&PrettyOptions::new())
+        );
+        let a = root.children().next().unwrap();
+        assert_eq!(
+            "<n:a\n xmlns:n=\"urn:n\"\n id=\"&quot;1&quot;\">\n <b></b>\n <!-- c -->\n</n:a>",
+            a.to_pretty_string(
+                &PrettyOptions::new()
+                    .with_indent(1)
+                    .with_max_inline_attributes(1)
+                    .with_self_closing(false)
+            )
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use crate::Error;
+use minidom::{
+    quick_xml::{
+        events::{BytesEnd, Event},
+        Reader, Writer,
+    },
+    Element,
+};
+use std::{fs::File, io::BufRead, io::BufReader, path::Path};
+
+/// The beginning of a document, as returned by [`preview`].
+///
+/// [`preview`]: fn.preview.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preview {
+    /// The root element with the elements read so far, the elements left
+    /// open being closed
+    pub element: Element,
+    /// The document has more elements than the preview, or an error was
+    /// found after the beginning of the root element
+    pub truncated: bool,
+}
+
+/// Parse only the first `max_elements` elements of a document (the root
+/// element is always read, even if `max_elements` is `0`).
+///
+/// Reading stops as soon as the limit is reached, so the rest of a huge
+/// document is never read. A syntax error after the beginning of the root
+/// element also stops the reading instead of failing: the elements read so
+/// far are returned and the preview is marked as truncated. An error is only
+/// returned when there is no root element.
+///
+/// ```
+/// use minidom_ext::preview;
+///
+/// let xml: &'static str = r#"<Lines>
+///         <Line id="1"><Name>Metro 1</Name></Line>
+///         <Line id="2"><Name>Metro 2</Name></Line>
+///         <Line id="3"><Name>Metro 3</Name></Line>
+///     </Lines>"#;
+/// let preview = preview(xml.as_bytes(), 4).unwrap();
+/// assert!(preview.truncated);
+/// let ids: Vec<&str> = preview.element.children().filter_map(|line| line.attr("id")).collect();
+/// assert_eq!(vec!["1", "2"], ids);
+/// ```
+pub fn preview<R: BufRead>(reader: R, max_elements: usize) -> Result<Preview, Error> {
+    let mut reader = Reader::from_reader(reader);
+    let mut writer = Writer::new(Vec::new());
+    let mut open: Vec<Vec<u8>> = Vec::new();
+    let mut count = 0;
+    let mut truncated = false;
+    let mut buf = Vec::new();
+    loop {
+        let event = match reader.read_event(&mut buf) {
+            Ok(event) => event,
+            Err(_) if count > 0 => {
+                truncated = true;
+                break;
+            }
+            Err(error) => {
+                return Err(Error::InvalidDocument {
+                    source: minidom::Error::XmlError(error),
+                })
+            }
+        };
+        let write = match event {
+            Event::Start(_) | Event::Empty(_) if count > 0 && count >= max_elements => {
+                truncated = true;
+                break;
+            }
+            Event::Start(ref start) => {
+                count += 1;
+                open.push(start.name().to_vec());
+                true
+            }
+            Event::Empty(_) => {
+                count += 1;
+                true
+            }
+            Event::End(_) => {
+                open.pop();
+                true
+            }
+            Event::Eof => {
+                truncated = !open.is_empty();
+                break;
+            }
+            Event::Decl(_) | Event::PI(_) | Event::DocType(_) => false,
+            Event::Text(_) | Event::CData(_) | Event::Comment(_) => !open.is_empty(),
+        };
+        if write {
+            writer
+                .write_event(&event)
+                .map_err(|error| Error::InvalidDocument {
+                    source: minidom::Error::XmlError(error),
+                })?;
+        }
+        if count > 0 && open.is_empty() {
+            break;
+        }
+        buf.clear();
+    }
+    for name in open.iter().rev() {
+        writer
+            .write_event(Event::End(BytesEnd::borrowed(name)))
+            .map_err(|error| Error::InvalidDocument {
+                source: minidom::Error::XmlError(error),
+            })?;
+    }
+    let content = writer.into_inner();
+    let element = Element::from_reader(&mut Reader::from_reader(content.as_slice()))
+        .map_err(|source| Error::InvalidDocument { source })?;
+    Ok(Preview { element, truncated })
+}
+
+/// Parse only the first `max_elements` elements of a file, see [`preview`].
+///
+/// [`preview`]: fn.preview.html
+pub fn preview_file<P: AsRef<Path>>(path: P, max_elements: usize) -> Result<Preview, Error> {
+    let file = File::open(path).map_err(|error| Error::InvalidDocument {
+        source: minidom::Error::IoError(error),
+    })?;
+    preview(BufReader::new(file), max_elements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn complete_document() {
+        let xml: &'static str = r#"<?xml version="1.0"?>
+            <!-- prolog -->
+            <root xmlns="urn:root"><a>text</a><b/></root>"#;
+        let preview = preview(xml.as_bytes(), 3).unwrap();
+        assert!(!preview.truncated);
+        assert_eq!(xml.parse::<Element>().unwrap(), preview.element);
+    }
+
+    #[test]
+    fn stops_at_limit() {
+        let xml: &'static str = r#"<root><a><b>1</b><c>2</c></a><d/></root>"#;
+        let preview = preview(xml.as_bytes(), 3).unwrap();
+        assert!(preview.truncated);
+        let expected: Element = r#"<root><a><b>1</b></a></root>"#.parse().unwrap();
+        assert_eq!(expected, preview.element);
+        let root = super::preview(xml.as_bytes(), 0).unwrap();
+        assert_eq!(0, root.element.children().count());
+    }
+
+    #[test]
+    fn tolerates_errors() {
+        let xml: &'static str = r#"<root><a>1</a><b>2</c></root>"#;
+        let preview = preview(xml.as_bytes(), 10).unwrap();
+        assert!(preview.truncated);
+        let expected: Element = r#"<root><a>1</a><b>2</b></root>"#.parse().unwrap();
+        assert_eq!(expected, preview.element);
+        let truncated = super::preview(&xml.as_bytes()[..12], 10).unwrap();
+        assert!(truncated.truncated);
+        assert_eq!(1, truncated.element.children().count());
+    }
+
+    #[test]
+    fn no_root_element() {
+        let error = preview("   ".as_bytes(), 10).unwrap_err();
+        assert_eq!(
+            "Failed to read the document: the end of the document has been reached prematurely",
+            format!("{}", error)
+        );
+    }
+}
@@ -0,0 +1,282 @@
+use crate::{suggest::closest, Error, NameTest, Selector};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+/// The element names allowed by a schema or a profile of a schema.
+///
+/// A profile is used to check selectors before processing any document: a
+/// selector referencing an element name which is not in the profile can never
+/// match and is most likely a typo.
+///
+/// ```
+/// use minidom_ext::{Profile, Selector};
+///
+/// let profile = Profile::new()
+///     .with_element("ServiceFrame")
+///     .with_element("lines")
+///     .with_element("Line");
+/// let selector: Selector = "ServiceFrame/lnes/Line".parse().unwrap();
+/// let warnings = profile.lint(&selector);
+/// assert_eq!(1, warnings.len());
+/// assert_eq!(
+///     "Element 'lnes' of step 2 is not in the profile, did you mean 'lines'?",
+///     warnings[0].to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profile {
+    elements: BTreeSet<String>,
+    /// Human descriptions by element name and attribute name (`None` for
+    /// the element itself)
+    descriptions: BTreeMap<(String, Option<String>), String>,
+}
+
+/// A selector step referencing an element name unknown to a [`Profile`].
+///
+/// [`Profile`]: struct.Profile.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintWarning {
+    /// Index of the step in the selector, starting at 0
+    pub step: usize,
+    /// The unknown element name
+    pub name: String,
+    /// The closest known element name, if close enough to be a typo
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Element '{}' of step {} is not in the profile",
+            self.name,
+            self.step + 1
+        )?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, ", did you mean '{}'?", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+impl Profile {
+    /// Create a profile without any element.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare an element name (local name, without prefix).
+    pub fn with_element(mut self, name: &str) -> Self {
+        self.elements.insert(name.to_owned());
+        self
+    }
+
+    /// Declare an element name with a human description, displayed by
+    /// [`render_error`].
+    ///
+    /// [`render_error`]: #method.render_error
+    pub fn with_element_description(mut self, name: &str, description: &str) -> Self {
+        self.elements.insert(name.to_owned());
+        self.descriptions
+            .insert((name.to_owned(), None), description.to_owned());
+        self
+    }
+
+    /// Describe an attribute of an element, the description being displayed
+    /// by [`render_error`].
+    ///
+    /// [`render_error`]: #method.render_error
+    pub fn with_attribute_description(
+        mut self,
+        element_name: &str,
+        attr_name: &str,
+        description: &str,
+    ) -> Self {
+        self.elements.insert(element_name.to_owned());
+        self.descriptions.insert(
+            (element_name.to_owned(), Some(attr_name.to_owned())),
+            description.to_owned(),
+        );
+        self
+    }
+
+    /// The description of an element, or of one of its attributes.
+    pub fn description(&self, element_name: &str, attr_name: Option<&str>) -> Option<&str> {
+        self.descriptions
+            .get(&(element_name.to_owned(), attr_name.map(ToOwned::to_owned)))
+            .map(String::as_str)
+    }
+
+    /// Render an error for end users, followed by the description of the
+    /// element or attribute it is about, if any.
+    ///
+    /// Attribute errors fall back to the description of the element when
+    /// the attribute has none.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::{AttributeElementExt, Profile};
+    ///
+    /// let profile = Profile::new().with_attribute_description(
+    ///     "Call",
+    ///     "DepartureTime",
+    ///     "'DepartureTime' is the scheduled departure in local time",
+    /// );
+    /// let call: Element = r#"<Call DepartureTime="8h30" />"#.parse().unwrap();
+    /// let error = call.try_attribute::<u32>("DepartureTime").unwrap_err();
+    /// assert_eq!(
+    ///     "Failed to parse and convert the value '8h30' of attribute 'DepartureTime' in element 'Call'\n\
+    ///      'DepartureTime' is the scheduled departure in local time",
+    ///     profile.render_error(&error)
+    /// );
+    /// ```
+    pub fn render_error(&self, error: &Error) -> String {
+        match self.error_description(error) {
+            Some(description) => format!("{}\n{}", error, description),
+            None => error.to_string(),
+        }
+    }
+
+    /// The description of the element or attribute an error is about.
+    fn error_description(&self, error: &Error) -> Option<&str> {
+        let (element_name, attr_name): (&str, Option<&str>) = match error {
+            Error::AttributeNotFound {
+                element_name,
+                attribute_name,
+                ..
+            } => (element_name, Some(attribute_name.as_str())),
+            Error::ParseError {
+                element_name,
+                attribute_name,
+                ..
+            } => (element_name, Some(attribute_name.as_str())),
+            Error::NoChildren { child_name, .. } | Error::MultipleChildren { child_name, .. } => {
+                (child_name, None)
+            }
+            Error::UnexpectedElement { expected, .. } => (expected, None),
+            Error::NilElement { element_name }
+            | Error::TextParseError { element_name, .. }
+            | Error::DanglingReference {
+                from_element: element_name,
+                ..
+            }
+            | Error::AmbiguousReference {
+                from_element: element_name,
+                ..
+            } => (element_name, None),
+            Error::WithPath { source, .. }
+            | Error::WithPosition { source, .. }
+            | Error::WithSourceName { source, .. } => return self.error_description(source),
+            _ => return None,
+        };
+        attr_name
+            .and_then(|attr_name| self.description(element_name, Some(attr_name)))
+            .or_else(|| self.description(element_name, None))
+    }
+
+    /// Check if an element name is declared in the profile.
+    pub fn contains(&self, name: &str) -> bool {
+        self.elements.contains(name)
+    }
+
+    /// Iterate over the declared element names, in alphabetical order.
+    pub fn elements(&self) -> impl Iterator<Item = &str> {
+        self.elements.iter().map(String::as_str)
+    }
+
+    /// List the steps of `selector` referencing element names not declared
+    /// in the profile, such a selector never matching a valid document.
+    pub fn lint(&self, selector: &Selector) -> Vec<LintWarning> {
+        selector
+            .steps()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, step)| match &step.name_test {
+                NameTest::Name(name) if !self.contains(name) => Some(LintWarning {
+                    step: index,
+                    name: name.to_string(),
+                    suggestion: self.closest(name).map(ToOwned::to_owned),
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The declared name closest to `name`, if at most 2 edits away.
+    fn closest(&self, name: &str) -> Option<&str> {
+        closest(name, self.elements())
+    }
+}
+
+impl<'a> std::iter::FromIterator<&'a str> for Profile {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        Profile {
+            elements: iter.into_iter().map(ToOwned::to_owned).collect(),
+            descriptions: BTreeMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn render_errors() {
+        let profile = Profile::new()
+            .with_element_description("Name", "The name displayed to travellers")
+            .with_attribute_description("Line", "mode", "The transport mode");
+        let missing = Error::NoChildren {
+            element_name: "Line".to_owned(),
+            child_name: "Name".to_owned(),
+            suggestion: None,
+            available: Vec::new(),
+        };
+        assert_eq!(
+            "No children with name 'Name' in Element 'Line'\nThe name displayed to travellers",
+            profile.render_error(&missing)
+        );
+        let other = Error::AttributeNotFound {
+            element_name: "Line".to_owned(),
+            attribute_name: "id".to_owned(),
+            suggestion: None,
+            available: Vec::new(),
+        };
+        assert_eq!(
+            "Failed to find attribute 'id' in element 'Line'",
+            profile.render_error(&other)
+        );
+        let located = Error::WithPath {
+            path: "root > Line".to_owned(),
+            source: Box::new(missing),
+        };
+        assert_eq!(
+            "In root > Line: No children with name 'Name' in Element 'Line'\nThe name displayed to travellers",
+            profile.render_error(&located)
+        );
+        assert!(profile.contains("Line"));
+        assert_eq!(
+            Some("The transport mode"),
+            profile.description("Line", Some("mode"))
+        );
+    }
+
+    #[test]
+    fn lint() {
+        let profile: Profile = vec!["Line", "Route", "StopPlace"].into_iter().collect();
+        let selector = Selector::parse("//*/Line[@id]/Quay").unwrap();
+        assert_eq!(
+            vec![LintWarning {
+                step: 2,
+                name: "Quay".to_owned(),
+                suggestion: None,
+            }],
+            profile.lint(&selector)
+        );
+        let selector = Selector::parse("StopPlace//Line").unwrap();
+        assert!(profile.lint(&selector).is_empty());
+    }
+}
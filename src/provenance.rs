@@ -0,0 +1,158 @@
+use crate::{AttrValue, AttributeElementExt, ElementCursor, Error, TextElementExt};
+use minidom::Element;
+use std::fmt;
+
+/// Where an extracted value comes from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Provenance {
+    /// The source of the document, usually a file name
+    pub source: Option<String>,
+    /// Path of the element, like `/root/frame[1]/Line[2]`
+    pub path: String,
+    /// Name of the attribute, `None` when the value is the text of the
+    /// element
+    pub attribute: Option<String>,
+}
+
+impl fmt::Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(source) = &self.source {
+            write!(f, "{}:", source)?;
+        }
+        match &self.attribute {
+            Some(attribute) => write!(f, "{}/@{}", self.path, attribute),
+            None => write!(f, "{}/text()", self.path),
+        }
+    }
+}
+
+/// A value extracted from a document with its [`Provenance`].
+///
+/// [`Provenance`]: struct.Provenance.html
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Traced<T> {
+    /// The extracted value
+    pub value: T,
+    /// Where the value comes from
+    pub provenance: Provenance,
+}
+
+impl<T> Traced<T> {
+    /// Set the source of the document the value comes from.
+    pub fn with_source<S: Into<String>>(mut self, source: S) -> Self {
+        self.provenance.source = Some(source.into());
+        self
+    }
+
+    /// Transform the value, keeping its provenance.
+    pub fn map<U, F>(self, f: F) -> Traced<U>
+    where
+        F: FnOnce(T) -> U,
+    {
+        Traced {
+            value: f(self.value),
+            provenance: self.provenance,
+        }
+    }
+
+    /// Append the provenance as a comment to an element built from the
+    /// value, to keep track of it in an exported document.
+    pub fn annotate(&self, element: &mut Element) {
+        element.append_comment_node(format!(" from {} ", self.provenance));
+    }
+}
+
+impl<'a> ElementCursor<'a> {
+    /// Try to get an attribute of the current element, as
+    /// [`AttributeElementExt::try_attribute`], with its [`Provenance`].
+    ///
+    /// Values merged from several documents can be traced back to their
+    /// origin, and [`Traced::annotate`] embeds the provenance in an exported
+    /// document. A sidecar map can be built by collecting the provenances.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::{ElementCursor, Traced};
+    ///
+    /// let xml: &'static str = r#"<root><Line id="1" /><Line id="2" /></root>"#;
+    /// let root: Element = xml.parse().unwrap();
+    /// let line = ElementCursor::new(&root).children().nth(1).unwrap();
+    /// let id: Traced<u32> = line.try_attribute_traced("id").unwrap().with_source("lines.xml");
+    /// assert_eq!(2, id.value);
+    /// assert_eq!("lines.xml:/root/Line[2]/@id", id.provenance.to_string());
+    ///
+    /// let mut exported = Element::bare("ExportedLine");
+    /// id.annotate(&mut exported);
+    /// assert_eq!(
+    ///     "<ExportedLine><!-- from lines.xml:/root/Line[2]/@id --></ExportedLine>",
+    ///     String::from(&exported)
+    /// );
+    /// ```
+    ///
+    /// [`AttributeElementExt::try_attribute`]: trait.AttributeElementExt.html#tymethod.try_attribute
+    /// [`Provenance`]: struct.Provenance.html
+    /// [`Traced::annotate`]: struct.Traced.html#method.annotate
+    pub fn try_attribute_traced<F>(&self, attr_name: &str) -> Result<Traced<F>, Error>
+    where
+        F: AttrValue,
+    {
+        let value = self.element().try_attribute(attr_name)?;
+        Ok(Traced {
+            value,
+            provenance: Provenance {
+                source: None,
+                path: self.path(),
+                attribute: Some(attr_name.to_owned()),
+            },
+        })
+    }
+
+    /// Try to parse the text of the current element, as
+    /// [`TextElementExt::try_text`], with its [`Provenance`].
+    ///
+    /// [`TextElementExt::try_text`]: trait.TextElementExt.html#tymethod.try_text
+    /// [`Provenance`]: struct.Provenance.html
+    pub fn try_text_traced<F>(&self) -> Result<Traced<F>, Error>
+    where
+        F: AttrValue,
+    {
+        let value = self.element().try_text()?;
+        Ok(Traced {
+            value,
+            provenance: Provenance {
+                source: None,
+                path: self.path(),
+                attribute: None,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn traced_text() {
+        let xml: &'static str = r#"<root><frame><Name>a</Name><Name>b</Name></frame></root>"#;
+        let root: Element = xml.parse().unwrap();
+        let frame = ElementCursor::new(&root).children().next().unwrap();
+        let names: Vec<String> = frame
+            .children()
+            .map(|name| {
+                let name: Traced<String> = name.try_text_traced().unwrap();
+                let name = name.map(|value| value.to_uppercase());
+                format!("{} from {}", name.value, name.provenance)
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                "A from /root/frame[1]/Name[1]/text()",
+                "B from /root/frame[1]/Name[2]/text()"
+            ],
+            names
+        );
+        assert!(frame.try_attribute_traced::<String>("missing").is_err());
+    }
+}
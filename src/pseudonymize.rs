@@ -0,0 +1,81 @@
+use minidom::Element;
+
+/// 64 bits FNV-1a hash, used because it is stable across platforms and Rust
+/// versions (unlike `std::collections::hash_map::DefaultHasher`).
+pub(crate) fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+pub(crate) const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+fn pseudonym(value: &str, salt: &str) -> String {
+    let hash = fnv1a(fnv1a(FNV_OFFSET_BASIS, salt.as_bytes()), value.as_bytes());
+    format!("{:016x}", hash)
+}
+
+/// Replace the values of identifier attributes in the whole tree with
+/// deterministic pseudonyms.
+///
+/// Every attribute whose name is in `ids` (for example `id` and `ref`) is
+/// replaced. The same value always gives the same pseudonym for a given
+/// `salt`, whatever the attribute it is found in, so references between
+/// elements are kept consistent. Returns the number of replaced values.
+///
+/// The pseudonyms are not cryptographically secure: anyone knowing the salt
+/// can check a guessed identifier. Keep the salt secret if that matters.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::pseudonymize_ids;
+///
+/// let xml: &'static str = r#"<root>
+///         <StopPlace id="stop:1" />
+///         <StopPlaceRef ref="stop:1" />
+///     </root>"#;
+/// let mut root: Element = xml.parse().unwrap();
+/// assert_eq!(2, pseudonymize_ids(&mut root, &["id", "ref"], "secret"));
+/// let mut children = root.children();
+/// let id = children.next().unwrap().attr("id").unwrap().to_owned();
+/// assert_ne!("stop:1", id);
+/// assert_eq!(Some(id.as_str()), children.next().unwrap().attr("ref"));
+/// ```
+pub fn pseudonymize_ids(element: &mut Element, ids: &[&str], salt: &str) -> usize {
+    let mut count = 0;
+    for (name, value) in element.attrs_mut() {
+        if ids.contains(&name) {
+            *value = pseudonym(value, salt);
+            count += 1;
+        }
+    }
+    for child in element.children_mut() {
+        count += pseudonymize_ids(child, ids, salt);
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn salt_changes_pseudonyms() {
+        assert_eq!(pseudonym("stop:1", "a"), pseudonym("stop:1", "a"));
+        assert_ne!(pseudonym("stop:1", "a"), pseudonym("stop:1", "b"));
+        assert_ne!(pseudonym("stop:1", "a"), pseudonym("stop:2", "a"));
+    }
+
+    #[test]
+    fn other_attributes_are_kept() {
+        let xml: &'static str = r#"<root id="1" name="Gare du Nord"><child id="2" /></root>"#;
+        let mut root: Element = xml.parse().unwrap();
+        assert_eq!(2, pseudonymize_ids(&mut root, &["id"], ""));
+        assert_eq!(Some("Gare du Nord"), root.attr("name"));
+        assert_eq!(
+            Some(pseudonym("2", "").as_str()),
+            root.children().next().unwrap().attr("id")
+        );
+    }
+}
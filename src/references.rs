@@ -0,0 +1,202 @@
+use crate::{ElementCursor, IdIndex, IdScope};
+use minidom::Element;
+use std::fmt;
+
+/// The attributes holding identifiers and references, checked by
+/// [`validate_references`].
+///
+/// By default, identifiers are read from the `id` attribute and are unique
+/// in the whole document, and references are read from the `ref`
+/// attribute.
+///
+/// [`validate_references`]: fn.validate_references.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceConfig {
+    id_attribute: String,
+    scope: IdScope,
+    ref_attributes: Vec<String>,
+}
+
+impl Default for ReferenceConfig {
+    fn default() -> Self {
+        ReferenceConfig {
+            id_attribute: "id".to_owned(),
+            scope: IdScope::Document,
+            ref_attributes: vec!["ref".to_owned()],
+        }
+    }
+}
+
+impl ReferenceConfig {
+    /// Create the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the identifiers from another attribute.
+    pub fn with_id_attribute(mut self, attr_name: &str) -> Self {
+        self.id_attribute = attr_name.to_owned();
+        self
+    }
+
+    /// Change the scope in which identifiers are unique.
+    pub fn with_scope(mut self, scope: IdScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Check an additional reference attribute.
+    pub fn with_ref_attribute(mut self, attr_name: &str) -> Self {
+        self.ref_attributes.push(attr_name.to_owned());
+        self
+    }
+}
+
+/// A reference which doesn't match the identifier of any element, or which
+/// is ambiguous.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnresolvedReference {
+    /// Path of the element holding the reference, like `/root/LineRef[2]`
+    pub path: String,
+    /// Name of the reference attribute
+    pub attribute: String,
+    /// Value of the reference
+    pub value: String,
+    /// Names of the elements sharing the referenced identifier with the
+    /// [`IdScope::ElementName`] scope, empty if no element has it
+    ///
+    /// [`IdScope::ElementName`]: enum.IdScope.html#variant.ElementName
+    pub candidates: Vec<String>,
+}
+
+impl fmt::Display for UnresolvedReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/@{}: '{}' ", self.path, self.attribute, self.value)?;
+        if self.candidates.is_empty() {
+            write!(f, "doesn't match any identifier")
+        } else {
+            write!(
+                f,
+                "matches several elements ({})",
+                self.candidates.join(", ")
+            )
+        }
+    }
+}
+
+fn check(
+    cursor: &ElementCursor<'_>,
+    index: &IdIndex<'_>,
+    config: &ReferenceConfig,
+    unresolved: &mut Vec<UnresolvedReference>,
+) {
+    let element = cursor.element();
+    for attribute in &config.ref_attributes {
+        if let Some(value) = element.attr(attribute) {
+            let scopes = index.scopes(value);
+            if scopes.len() != 1 {
+                unresolved.push(UnresolvedReference {
+                    path: cursor.path(),
+                    attribute: attribute.clone(),
+                    value: value.to_owned(),
+                    candidates: scopes.iter().map(|scope| (*scope).to_owned()).collect(),
+                });
+            }
+        }
+    }
+    for child in cursor.children() {
+        check(&child, index, config, unresolved);
+    }
+}
+
+/// Check that every reference of a document matches the identifier of an
+/// element, and return all the references which don't, in document order.
+///
+/// With the [`IdScope::ElementName`] scope, the references to an identifier
+/// shared by elements with different names are reported as ambiguous.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{validate_references, ReferenceConfig};
+///
+/// let xml: &'static str = r#"<root>
+///         <Line id="line:1" />
+///         <Journey id="journey:1"><LineRef ref="line:1" /></Journey>
+///         <Journey id="journey:2"><LineRef ref="line:2" /></Journey>
+///         <Interchange from="journey:1" to="journey:3" />
+///     </root>"#;
+/// let root: Element = xml.parse().unwrap();
+/// let config = ReferenceConfig::new()
+///     .with_ref_attribute("from")
+///     .with_ref_attribute("to");
+/// let unresolved: Vec<String> = validate_references(&root, &config)
+///     .iter()
+///     .map(ToString::to_string)
+///     .collect();
+/// assert_eq!(
+///     vec![
+///         "/root/Journey[2]/LineRef[1]/@ref: 'line:2' doesn't match any identifier",
+///         "/root/Interchange[1]/@to: 'journey:3' doesn't match any identifier",
+///     ],
+///     unresolved
+/// );
+/// ```
+///
+/// [`IdScope::ElementName`]: enum.IdScope.html#variant.ElementName
+pub fn validate_references(root: &Element, config: &ReferenceConfig) -> Vec<UnresolvedReference> {
+    let index = IdIndex::with_attribute(root, &config.id_attribute, config.scope);
+    let mut unresolved = Vec::new();
+    check(&ElementCursor::new(root), &index, config, &mut unresolved);
+    unresolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn custom_id_attribute() {
+        let xml: &'static str = r#"<root>
+                <Stop key="s1" />
+                <StopRef ref=" s1 " />
+                <StopRef ref="s2" />
+            </root>"#;
+        let root: Element = xml.parse().unwrap();
+        let unresolved = validate_references(&root, &ReferenceConfig::default());
+        assert_eq!(2, unresolved.len());
+        let config = ReferenceConfig::new().with_id_attribute("key");
+        let unresolved = validate_references(&root, &config);
+        assert_eq!(
+            vec![UnresolvedReference {
+                path: "/root/StopRef[2]".to_owned(),
+                attribute: "ref".to_owned(),
+                value: "s2".to_owned(),
+                candidates: Vec::new(),
+            }],
+            unresolved
+        );
+    }
+
+    #[test]
+    fn ambiguous_references() {
+        let xml: &'static str = r#"<root>
+                <Line id="1" />
+                <Route id="1" />
+                <Stop id="2" />
+                <LineRef ref="1" />
+                <StopRef ref="2" />
+            </root>"#;
+        let root: Element = xml.parse().unwrap();
+        assert!(validate_references(&root, &ReferenceConfig::default()).is_empty());
+        let config = ReferenceConfig::new().with_scope(IdScope::ElementName);
+        let unresolved: Vec<String> = validate_references(&root, &config)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            vec!["/root/LineRef[1]/@ref: '1' matches several elements (Line, Route)"],
+            unresolved
+        );
+    }
+}
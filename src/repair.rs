@@ -0,0 +1,358 @@
+use crate::{
+    cursor::path_step,
+    materialize::detached_tree,
+    namespace::split_qname,
+    tree::{OwnedElement, OwnedNode},
+};
+use minidom::Element;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+/// The corrections [`repair`] is allowed to make, elements being matched by
+/// their local name.
+///
+/// [`repair`]: fn.repair.html
+#[derive(Debug, Clone, Default)]
+pub struct RepairRules {
+    /// Default values of attributes, by element name
+    defaults: BTreeMap<String, Vec<(String, String)>>,
+    /// Declared order of the children, by element name
+    sequences: BTreeMap<String, Vec<String>>,
+    /// Elements which can be dropped when empty
+    optional: BTreeSet<String>,
+}
+
+impl RepairRules {
+    /// Create rules without any correction.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare the default value of an attribute, set when the attribute is
+    /// missing.
+    pub fn with_default(mut self, element: &str, attribute: &str, value: &str) -> Self {
+        self.defaults
+            .entry(element.to_owned())
+            .or_default()
+            .push((attribute.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Declare the order of the children of an element, like an `xs:sequence`.
+    /// Children not listed are moved after the listed ones.
+    pub fn with_sequence(mut self, element: &str, children: &[&str]) -> Self {
+        self.sequences.insert(
+            element.to_owned(),
+            children.iter().map(|child| (*child).to_owned()).collect(),
+        );
+        self
+    }
+
+    /// Declare an optional element, dropped when it has no attribute, no
+    /// child and no text other than whitespaces.
+    pub fn with_optional(mut self, element: &str) -> Self {
+        self.optional.insert(element.to_owned());
+        self
+    }
+}
+
+/// Which kinds of corrections [`repair`] applies.
+///
+/// All the corrections are applied by default.
+///
+/// [`repair`]: fn.repair.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RepairPolicy {
+    /// Set the missing attributes having a default value
+    pub fill_defaults: bool,
+    /// Reorder the children to match the declared sequences
+    pub reorder_children: bool,
+    /// Drop the empty optional elements
+    pub drop_empty_optional: bool,
+    /// Only report the corrections, without changing the tree
+    pub dry_run: bool,
+}
+
+impl Default for RepairPolicy {
+    fn default() -> Self {
+        RepairPolicy {
+            fill_defaults: true,
+            reorder_children: true,
+            drop_empty_optional: true,
+            dry_run: false,
+        }
+    }
+}
+
+/// A correction made (or that would be made in dry-run mode) by [`repair`].
+///
+/// Paths are like `/root/frame[1]/Line[2]`, where the positions count the
+/// siblings with the same name, starting at 1.
+///
+/// [`repair`]: fn.repair.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Repair {
+    /// A missing attribute was set to its default value
+    DefaultFilled {
+        /// Path of the element
+        path: String,
+        /// Name of the attribute
+        attribute: String,
+        /// The default value
+        value: String,
+    },
+    /// The children of an element were reordered
+    ChildrenReordered {
+        /// Path of the element
+        path: String,
+    },
+    /// An empty optional element was dropped
+    EmptyElementDropped {
+        /// Path of the element, before it was dropped
+        path: String,
+    },
+}
+
+impl fmt::Display for Repair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Repair::DefaultFilled {
+                path,
+                attribute,
+                value,
+            } => write!(f, "{}/@{}: set to default '{}'", path, attribute, value),
+            Repair::ChildrenReordered { path } => write!(f, "{}: children reordered", path),
+            Repair::EmptyElementDropped { path } => write!(f, "{}: empty element dropped", path),
+        }
+    }
+}
+
+fn local_name(element: &OwnedElement) -> &str {
+    split_qname(&element.qname).1
+}
+
+fn is_empty(element: &OwnedElement) -> bool {
+    element.attributes.is_empty()
+        && element.nodes.iter().all(|node| match node {
+            OwnedNode::Element(_) => false,
+            OwnedNode::Text(text) => text.trim().is_empty(),
+            OwnedNode::Comment(_) => true,
+        })
+}
+
+/// Paths of the children of an element, by index of node.
+fn child_paths(element: &OwnedElement, path: &str) -> BTreeMap<usize, String> {
+    let mut positions: BTreeMap<&str, usize> = BTreeMap::new();
+    element
+        .nodes
+        .iter()
+        .enumerate()
+        .filter_map(|(index, node)| match node {
+            OwnedNode::Element(child) => {
+                let name = local_name(child);
+                let position = positions.entry(name).or_insert(0);
+                *position += 1;
+                Some((index, format!("{}/{}", path, path_step(name, *position))))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Sort the children of an element depending on their rank in `sequence`,
+/// the other nodes moving with the element following them. Return `false`
+/// if the children were already in order.
+fn reorder(element: &mut OwnedElement, sequence: &[String]) -> bool {
+    let rank = |node: &OwnedNode| match node {
+        OwnedNode::Element(child) => sequence
+            .iter()
+            .position(|name| name == local_name(child))
+            .unwrap_or(sequence.len()),
+        _ => 0,
+    };
+    let ranks: Vec<usize> = element
+        .nodes
+        .iter()
+        .filter(|node| matches!(node, OwnedNode::Element(_)))
+        .map(rank)
+        .collect();
+    if ranks.windows(2).all(|pair| pair[0] <= pair[1]) {
+        return false;
+    }
+    let mut groups: Vec<(usize, Vec<OwnedNode>)> = Vec::new();
+    let mut pending = Vec::new();
+    for node in element.nodes.drain(..) {
+        let is_element = matches!(node, OwnedNode::Element(_));
+        let node_rank = rank(&node);
+        pending.push(node);
+        if is_element {
+            groups.push((node_rank, std::mem::take(&mut pending)));
+        }
+    }
+    groups.sort_by_key(|(rank, _)| *rank);
+    element.nodes = groups.into_iter().flat_map(|(_, nodes)| nodes).collect();
+    element.nodes.extend(pending);
+    true
+}
+
+fn repair_structure(
+    element: &mut OwnedElement,
+    path: &str,
+    rules: &RepairRules,
+    policy: RepairPolicy,
+    repairs: &mut Vec<Repair>,
+) {
+    let paths = child_paths(element, path);
+    for (index, node) in element.nodes.iter_mut().enumerate() {
+        if let OwnedNode::Element(child) = node {
+            repair_structure(child, &paths[&index], rules, policy, repairs);
+        }
+    }
+    if policy.drop_empty_optional {
+        let mut index = 0;
+        element.nodes.retain(|node| {
+            let dropped = match node {
+                OwnedNode::Element(child) => {
+                    rules.optional.contains(local_name(child)) && is_empty(child)
+                }
+                _ => false,
+            };
+            if dropped {
+                repairs.push(Repair::EmptyElementDropped {
+                    path: paths[&index].clone(),
+                });
+            }
+            index += 1;
+            !dropped
+        });
+    }
+    if policy.reorder_children {
+        if let Some(sequence) = rules.sequences.get(local_name(element)) {
+            if reorder(element, sequence) {
+                repairs.push(Repair::ChildrenReordered {
+                    path: path.to_owned(),
+                });
+            }
+        }
+    }
+}
+
+fn fill_defaults(
+    element: &mut OwnedElement,
+    path: &str,
+    rules: &RepairRules,
+    repairs: &mut Vec<Repair>,
+) {
+    if let Some(defaults) = rules.defaults.get(local_name(element)) {
+        for (attribute, value) in defaults {
+            if element.attributes.iter().all(|(name, _)| name != attribute) {
+                element.attributes.push((attribute.clone(), value.clone()));
+                repairs.push(Repair::DefaultFilled {
+                    path: path.to_owned(),
+                    attribute: attribute.clone(),
+                    value: value.clone(),
+                });
+            }
+        }
+    }
+    let paths = child_paths(element, path);
+    for (index, node) in element.nodes.iter_mut().enumerate() {
+        if let OwnedNode::Element(child) = node {
+            fill_defaults(child, &paths[&index], rules, repairs);
+        }
+    }
+}
+
+/// Apply safe automatic corrections on a tree, and return them.
+///
+/// Empty optional elements are dropped and children are reordered first,
+/// from the leaves to the root, then the missing attributes are set to their
+/// default value. In dry-run mode, the corrections are only reported and the
+/// tree is left untouched. Otherwise, the namespaces inherited from the
+/// ancestors of the element are declared on it.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{repair, RepairPolicy, RepairRules};
+///
+/// let xml: &'static str = r#"<Line><Name>A</Name><Notes> </Notes><Id>1</Id></Line>"#;
+/// let mut line: Element = xml.parse().unwrap();
+/// let rules = RepairRules::new()
+///     .with_default("Line", "version", "any")
+///     .with_sequence("Line", &["Id", "Name"])
+///     .with_optional("Notes");
+/// let repairs: Vec<String> = repair(&mut line, &rules, RepairPolicy::default())
+///     .iter()
+///     .map(ToString::to_string)
+///     .collect();
+/// assert_eq!(
+///     vec![
+///         "/Line/Notes[1]: empty element dropped",
+///         "/Line: children reordered",
+///         "/Line/@version: set to default 'any'",
+///     ],
+///     repairs
+/// );
+/// let expected: Element = r#"<Line version="any"><Id>1</Id><Name>A</Name></Line>"#
+///     .parse()
+///     .unwrap();
+/// assert_eq!(expected, line);
+/// ```
+pub fn repair(element: &mut Element, rules: &RepairRules, policy: RepairPolicy) -> Vec<Repair> {
+    let mut tree = detached_tree(element);
+    let path = format!("/{}", local_name(&tree));
+    let mut repairs = Vec::new();
+    repair_structure(&mut tree, &path, rules, policy, &mut repairs);
+    if policy.fill_defaults {
+        fill_defaults(&mut tree, &path, rules, &mut repairs);
+    }
+    if !policy.dry_run && !repairs.is_empty() {
+        *element = tree.to_element();
+    }
+    repairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn reorder_with_comments() {
+        let xml: &'static str = r#"<a><!-- c --><c/>t<b/><x/><b/></a>"#;
+        let mut root: Element = xml.parse().unwrap();
+        let original = root.clone();
+        let rules = RepairRules::new().with_sequence("a", &["b", "c"]);
+        let dry_run = RepairPolicy {
+            dry_run: true,
+            ..RepairPolicy::default()
+        };
+        assert_eq!(1, repair(&mut root, &rules, dry_run).len());
+        assert_eq!(original, root);
+        repair(&mut root, &rules, RepairPolicy::default());
+        let expected: Element = r#"<a>t<b/><b/><!-- c --><c/><x/></a>"#.parse().unwrap();
+        assert_eq!(expected, root);
+        assert!(repair(&mut root, &rules, RepairPolicy::default()).is_empty());
+    }
+
+    #[test]
+    fn inherited_namespaces() {
+        let xml: &'static str =
+            r#"<root xmlns:a="urn:a"><a:Line><a:Name>A</a:Name><a:Id>1</a:Id></a:Line></root>"#;
+        let mut root: Element = xml.parse().unwrap();
+        let line = root.children_mut().next().unwrap();
+        let rules = RepairRules::new().with_sequence("Line", &["Id", "Name"]);
+        assert_eq!(1, repair(line, &rules, RepairPolicy::default()).len());
+        assert_eq!(Some("urn:a".to_owned()), line.ns());
+        assert_eq!(
+            vec![Some("urn:a".to_owned()); 2],
+            line.children().map(Element::ns).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            r#"<a:Line xmlns:a="urn:a"><a:Id>1</a:Id><a:Name>A</a:Name></a:Line>"#,
+            String::from(&*line)
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use crate::{suggest::no_children, Error};
+use minidom::Element;
+
+/// Check which children an element has before extracting it, reporting all
+/// the violations at once.
+pub trait RequiredChildrenElementExt {
+    /// Check that the element has at least one child with each of the given
+    /// names.
+    ///
+    /// Returns a [`NoChildren`] error for each missing child, in the order of
+    /// `names`.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::RequiredChildrenElementExt;
+    ///
+    /// let line: Element = r#"<Line><Nmae>A</Nmae></Line>"#.parse().unwrap();
+    /// let errors: Vec<String> = line
+    ///     .try_require_children(&["Name", "TransportMode"])
+    ///     .unwrap_err()
+    ///     .iter()
+    ///     .map(ToString::to_string)
+    ///     .collect();
+    /// assert_eq!(
+    ///     vec![
+    ///         "No children with name 'Name' in Element 'Line' (did you mean 'Nmae'?) (available: Nmae)",
+    ///         "No children with name 'TransportMode' in Element 'Line' (available: Nmae)",
+    ///     ],
+    ///     errors
+    /// );
+    /// ```
+    ///
+    /// [`NoChildren`]: enum.Error.html#variant.NoChildren
+    fn try_require_children(&self, names: &[&str]) -> Result<(), Vec<Error>>;
+
+    /// Check that the element has no child with any of the given names.
+    ///
+    /// Returns a [`ForbiddenChild`] error for each name found, in the order
+    /// of `names`.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::RequiredChildrenElementExt;
+    ///
+    /// let line: Element = r#"<Line><Deprecated /><Deprecated /></Line>"#.parse().unwrap();
+    /// let errors = line.try_forbid_children(&["Deprecated", "Legacy"]).unwrap_err();
+    /// assert_eq!(1, errors.len());
+    /// assert_eq!(
+    ///     "Forbidden child 'Deprecated' in Element 'Line' (found 2 elements)",
+    ///     errors[0].to_string()
+    /// );
+    /// ```
+    ///
+    /// [`ForbiddenChild`]: enum.Error.html#variant.ForbiddenChild
+    fn try_forbid_children(&self, names: &[&str]) -> Result<(), Vec<Error>>;
+
+    /// Check that the element has no child whose name is not in `names`, to
+    /// fail loudly on the children an extraction would silently drop.
+    ///
+    /// Returns an [`UnknownChild`] error for each unknown name, with the
+    /// number of children with this name, in document order.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::RequiredChildrenElementExt;
+    ///
+    /// let xml: &'static str = r#"<Line><Name /><Color /><Note /><Note /></Line>"#;
+    /// let line: Element = xml.parse().unwrap();
+    /// let errors: Vec<String> = line
+    ///     .try_deny_unknown_children(&["Name", "TransportMode"])
+    ///     .unwrap_err()
+    ///     .iter()
+    ///     .map(ToString::to_string)
+    ///     .collect();
+    /// assert_eq!(
+    ///     vec![
+    ///         "Unknown child 'Color' in Element 'Line' (found 1 elements)",
+    ///         "Unknown child 'Note' in Element 'Line' (found 2 elements)",
+    ///     ],
+    ///     errors
+    /// );
+    /// ```
+    ///
+    /// [`UnknownChild`]: enum.Error.html#variant.UnknownChild
+    fn try_deny_unknown_children(&self, names: &[&str]) -> Result<(), Vec<Error>>;
+}
+
+fn violations(errors: Vec<Error>) -> Result<(), Vec<Error>> {
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+impl RequiredChildrenElementExt for Element {
+    fn try_require_children(&self, names: &[&str]) -> Result<(), Vec<Error>> {
+        violations(
+            names
+                .iter()
+                .filter(|name| self.children().all(|child| child.name() != **name))
+                .map(|name| no_children(self, name))
+                .collect(),
+        )
+    }
+
+    fn try_forbid_children(&self, names: &[&str]) -> Result<(), Vec<Error>> {
+        violations(
+            names
+                .iter()
+                .filter_map(|name| {
+                    let count = self
+                        .children()
+                        .filter(|child| child.name() == *name)
+                        .count();
+                    if count == 0 {
+                        return None;
+                    }
+                    Some(Error::ForbiddenChild {
+                        element_name: self.name().to_owned(),
+                        child_name: (*name).to_owned(),
+                        count,
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    fn try_deny_unknown_children(&self, names: &[&str]) -> Result<(), Vec<Error>> {
+        let mut unknown: Vec<(&str, usize)> = Vec::new();
+        for name in self.children().map(Element::name) {
+            if names.contains(&name) {
+                continue;
+            }
+            match unknown.iter_mut().find(|(unknown, _)| *unknown == name) {
+                Some((_, count)) => *count += 1,
+                None => unknown.push((name, 1)),
+            }
+        }
+        violations(
+            unknown
+                .into_iter()
+                .map(|(name, count)| Error::UnknownChild {
+                    element_name: self.name().to_owned(),
+                    child_name: name.to_owned(),
+                    count,
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn all_violations() {
+        let line: Element = r#"<Line><Name /><Deprecated /><Legacy /></Line>"#.parse().unwrap();
+        assert!(line.try_require_children(&["Name"]).is_ok());
+        assert!(line.try_forbid_children(&["Notes"]).is_ok());
+        let kinds: Vec<ErrorKind> = line
+            .try_require_children(&["Name", "TransportMode", "Notes"])
+            .unwrap_err()
+            .iter()
+            .map(Error::kind)
+            .collect();
+        assert_eq!(vec![ErrorKind::NoChildren, ErrorKind::NoChildren], kinds);
+        let errors = line
+            .try_forbid_children(&["Legacy", "Deprecated"])
+            .unwrap_err();
+        assert_eq!(2, errors.len());
+        assert_eq!(ErrorKind::ForbiddenChild, errors[0].kind());
+        assert_eq!("MDX0021", errors[1].code());
+        assert!(line
+            .try_deny_unknown_children(&["Name", "Deprecated", "Legacy"])
+            .is_ok());
+        let errors = line.try_deny_unknown_children(&["Name"]).unwrap_err();
+        assert_eq!(2, errors.len());
+        assert_eq!(ErrorKind::UnknownChild, errors[0].kind());
+    }
+}
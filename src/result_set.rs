@@ -0,0 +1,183 @@
+use crate::{Error, FromElement, Selector};
+use minidom::Element;
+use std::{ops::Deref, vec::IntoIter};
+
+/// The elements selected by a [`Selector`], see [`Selector::select_set`].
+///
+/// A result set remembers the element the selector was applied on and the
+/// selector itself, so the lookups on the results report the same errors as
+/// the lookups of children (see [`OnlyChildElementExt`]), the selector being
+/// reported as the name of the child.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::Selector;
+///
+/// let xml: &'static str = r#"<root>
+///         <frame><Line id="1" /></frame>
+///         <frame><Line id="2" /><Line id="3" /></frame>
+///     </root>"#;
+/// let root: Element = xml.parse().unwrap();
+/// let lines = Selector::parse("frame/Line").unwrap().select_set(&root);
+/// assert_eq!(3, lines.len());
+/// assert_eq!(Some("1"), lines.first_or_err().unwrap().attr("id"));
+/// assert_eq!(
+///     "Multiple children with name 'frame/Line' in Element 'root' (found 3 elements)",
+///     lines.only().unwrap_err().to_string()
+/// );
+/// ```
+///
+/// [`OnlyChildElementExt`]: trait.OnlyChildElementExt.html
+/// [`Selector`]: struct.Selector.html
+/// [`Selector::select_set`]: struct.Selector.html#method.select_set
+#[derive(Debug, Clone)]
+pub struct ResultSet<'a> {
+    context: &'a Element,
+    selector: String,
+    elements: Vec<&'a Element>,
+}
+
+impl<'a> ResultSet<'a> {
+    pub(crate) fn new(context: &'a Element, selector: &Selector) -> Self {
+        ResultSet {
+            context,
+            selector: selector.as_str().to_owned(),
+            elements: selector.select(context),
+        }
+    }
+
+    /// The element the selector was applied on.
+    pub fn context(&self) -> &'a Element {
+        self.context
+    }
+
+    /// The selector, as written.
+    pub fn selector(&self) -> &str {
+        &self.selector
+    }
+
+    /// Get the one and only selected element.
+    ///
+    /// Returns a [`NoChildren`] error if no element is selected, or a
+    /// [`MultipleChildren`] error if several elements are selected.
+    ///
+    /// [`NoChildren`]: enum.Error.html#variant.NoChildren
+    /// [`MultipleChildren`]: enum.Error.html#variant.MultipleChildren
+    pub fn only(&self) -> Result<&'a Element, Error> {
+        match self.elements.as_slice() {
+            [element] => Ok(element),
+            [] => Err(self.no_children()),
+            elements => Err(Error::MultipleChildren {
+                element_name: self.context.name().to_owned(),
+                child_name: self.selector.clone(),
+                count: elements.len(),
+            }),
+        }
+    }
+
+    /// Get the first selected element in document order.
+    ///
+    /// Returns a [`NoChildren`] error if no element is selected.
+    ///
+    /// [`NoChildren`]: enum.Error.html#variant.NoChildren
+    pub fn first_or_err(&self) -> Result<&'a Element, Error> {
+        self.elements
+            .first()
+            .copied()
+            .ok_or_else(|| self.no_children())
+    }
+
+    /// Build a value from each selected element, stopping at the first
+    /// error.
+    pub fn parse_all<T>(&self) -> Result<Vec<T>, Error>
+    where
+        T: FromElement,
+    {
+        self.elements
+            .iter()
+            .map(|element| T::from_element(element))
+            .collect()
+    }
+
+    /// Give back the selected elements.
+    pub fn into_vec(self) -> Vec<&'a Element> {
+        self.elements
+    }
+
+    fn no_children(&self) -> Error {
+        Error::NoChildren {
+            element_name: self.context.name().to_owned(),
+            child_name: self.selector.clone(),
+            suggestion: None,
+            available: Vec::new(),
+        }
+    }
+}
+
+impl<'a> Deref for ResultSet<'a> {
+    type Target = [&'a Element];
+
+    fn deref(&self) -> &Self::Target {
+        &self.elements
+    }
+}
+
+impl<'a> IntoIterator for ResultSet<'a> {
+    type Item = &'a Element;
+    type IntoIter = IntoIter<&'a Element>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.elements.into_iter()
+    }
+}
+
+impl<'a> From<ResultSet<'a>> for Vec<&'a Element> {
+    fn from(result_set: ResultSet<'a>) -> Self {
+        result_set.elements
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AttributeElementExt;
+    use pretty_assertions::assert_eq;
+
+    struct Line {
+        id: u32,
+    }
+
+    impl FromElement for Line {
+        fn from_element(element: &Element) -> Result<Self, Error> {
+            Ok(Line {
+                id: element.try_attribute("id")?,
+            })
+        }
+    }
+
+    #[test]
+    fn lookups() {
+        let xml: &'static str = r#"<root><Line id="1" /><Line id="2" /><Stop id="x" /></root>"#;
+        let root: Element = xml.parse().unwrap();
+        let select = |selector: &str| Selector::parse(selector).unwrap().select_set(&root);
+        assert_eq!(Some("x"), select("Stop").only().unwrap().attr("id"));
+        let ids: Vec<u32> = select("Line")
+            .parse_all::<Line>()
+            .unwrap()
+            .iter()
+            .map(|line| line.id)
+            .collect();
+        assert_eq!(vec![1, 2], ids);
+        assert!(select("Stop").parse_all::<Line>().is_err());
+        assert_eq!(
+            "No children with name 'Route' in Element 'root'",
+            select("Route").first_or_err().unwrap_err().to_string()
+        );
+        assert!(matches!(
+            select("Route").only(),
+            Err(Error::NoChildren { ref element_name, ref child_name, .. })
+                if element_name == "root" && child_name == "Route"
+        ));
+        assert_eq!(2, select("Line").into_iter().count());
+    }
+}
@@ -0,0 +1,72 @@
+use crate::Error;
+use std::borrow::Cow;
+
+/// What to do with C0 control characters (`U+0000` to `U+001F`, except tab,
+/// line feed and carriage return) found in extracted values.
+///
+/// Such characters are allowed by `minidom` when written as character
+/// references (`&#7;`) but break many downstream encoders. Values are always
+/// valid UTF-8 as `minidom` rejects documents which are not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ControlCharacters {
+    /// Return a [`ControlCharacter`] error
+    ///
+    /// [`ControlCharacter`]: enum.Error.html#variant.ControlCharacter
+    Reject,
+    /// Remove the control characters
+    Scrub,
+}
+
+fn is_forbidden(c: char) -> bool {
+    c.is_ascii_control() && c != '\t' && c != '\n' && c != '\r' && c != '\u{7f}'
+}
+
+/// Apply the policy on `value`, `location` describing where the value comes
+/// from in case of error.
+pub(crate) fn sanitize<'a, L>(
+    value: &'a str,
+    policy: ControlCharacters,
+    location: L,
+) -> Result<Cow<'a, str>, Error>
+where
+    L: FnOnce() -> String,
+{
+    match value.char_indices().find(|(_, c)| is_forbidden(*c)) {
+        None => Ok(Cow::Borrowed(value)),
+        Some((position, c)) => match policy {
+            ControlCharacters::Reject => Err(Error::ControlCharacter {
+                location: location(),
+                code_point: u32::from(c),
+                position,
+            }),
+            ControlCharacters::Scrub => Ok(Cow::Owned(
+                value.chars().filter(|c| !is_forbidden(*c)).collect(),
+            )),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn policies() {
+        let location = || "text of element 'Name'".to_owned();
+        let value = "Gare\u{7}\tdu\u{0}Nord";
+        assert_eq!(
+            "Gare\tduNord",
+            sanitize(value, ControlCharacters::Scrub, location).unwrap()
+        );
+        let error = sanitize(value, ControlCharacters::Reject, location).unwrap_err();
+        assert_eq!(
+            "Forbidden control character U+0007 at byte 4 of text of element 'Name'",
+            format!("{}", error)
+        );
+        assert!(matches!(
+            sanitize("a\nb", ControlCharacters::Reject, location).unwrap(),
+            Cow::Borrowed(_)
+        ));
+    }
+}
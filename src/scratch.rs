@@ -0,0 +1,69 @@
+//! Scratch buffers reused by the extraction helpers, to avoid allocating a
+//! new `String` for the text of every extracted element.
+
+use minidom::Element;
+use std::cell::RefCell;
+
+/// Buffers larger than this are released after use, so a single huge text
+/// doesn't keep the memory of a thread forever.
+const MAX_RETAINED_CAPACITY: usize = 64 * 1024;
+
+thread_local! {
+    static TEXT: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+/// Call `f` with the text of `element` (the concatenation of its text nodes).
+///
+/// An element with a single text node (the vast majority) is borrowed as is,
+/// other ones are concatenated in a thread-local buffer. Reentrant calls
+/// (from `f`) fall back to a newly allocated `String`.
+pub(crate) fn with_text<R, F>(element: &Element, f: F) -> R
+where
+    F: FnOnce(&str) -> R,
+{
+    let mut texts = element.texts();
+    let first = match texts.next() {
+        None => return f(""),
+        Some(first) => first,
+    };
+    let second = match texts.next() {
+        None => return f(first),
+        Some(second) => second,
+    };
+    TEXT.with(|buffer| match buffer.try_borrow_mut() {
+        Ok(mut buffer) => {
+            buffer.clear();
+            buffer.push_str(first);
+            buffer.push_str(second);
+            texts.for_each(|text| buffer.push_str(text));
+            let result = f(&buffer);
+            if buffer.capacity() > MAX_RETAINED_CAPACITY {
+                *buffer = String::new();
+            }
+            result
+        }
+        Err(_) => f(&element.text()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn concatenated_texts() {
+        let element: Element = "<a>x<b>y</b>z<!-- c -->t</a>".parse().unwrap();
+        let single: Element = "<a>x</a>".parse().unwrap();
+        let empty: Element = "<a><b /></a>".parse().unwrap();
+        assert_eq!("xzt", with_text(&element, str::to_owned));
+        assert_eq!(
+            "xzt x",
+            with_text(&element, |outer| {
+                with_text(&element, |inner| format!("{} {}", outer, &inner[..1]))
+            })
+        );
+        assert_eq!("x", with_text(&single, str::to_owned));
+        assert_eq!("", with_text(&empty, str::to_owned));
+    }
+}
@@ -0,0 +1,514 @@
+use crate::{Error, ResultSet};
+use minidom::Element;
+use std::{borrow::Cow, collections::HashSet, fmt, str::FromStr};
+
+/// How a step of a [`Selector`] navigates from the current elements.
+///
+/// [`Selector`]: struct.Selector.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    /// Direct children (`a/b`)
+    Child,
+    /// Descendants at any depth (`a//b`)
+    Descendant,
+}
+
+/// Test on the name of an element.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum NameTest {
+    /// Any element (`*`)
+    Any,
+    /// Elements with the given local name
+    Name(Cow<'static, str>),
+}
+
+/// Additional condition on an element selected by a step.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Predicate {
+    /// The element has the attribute (`[@id]`)
+    HasAttribute(Cow<'static, str>),
+    /// The attribute has the given value (`[@id='42']`)
+    AttributeEquals(Cow<'static, str>, Cow<'static, str>),
+    /// Position of the element among its siblings matching the name test,
+    /// starting at 1 (`[2]`)
+    Position(usize),
+}
+
+/// A step of a [`Selector`].
+///
+/// [`Selector`]: struct.Selector.html
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Step {
+    /// How to navigate from the current elements
+    pub axis: Axis,
+    /// Test on the name of the elements
+    pub name_test: NameTest,
+    /// Additional conditions, all of them must match
+    pub predicates: Cow<'static, [Predicate]>,
+}
+
+/// A compiled query selecting elements in a tree, with a small subset of the
+/// XPath syntax.
+///
+/// - `a/b` selects the `b` children of the `a` children of the context element
+/// - `a//b` selects the `b` descendants (at any depth) of the `a` children
+/// - `//b` selects all the `b` descendants of the context element
+/// - `*` matches any element name
+/// - `b[@id]` only keeps the `b` elements having an `id` attribute
+/// - `b[@id='42']` only keeps the `b` elements whose `id` is `42`
+/// - `b[2]` only keeps the second `b` element (of each parent)
+///
+/// Names are matched against the local name of the elements, whatever their
+/// namespace. Results are in document order, without duplicates.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::Selector;
+///
+/// let xml: &'static str = r#"<PublicationDelivery>
+///         <ServiceFrame>
+///             <lines>
+///                 <Line id="1" />
+///                 <Line id="2" />
+///             </lines>
+///         </ServiceFrame>
+///     </PublicationDelivery>"#;
+/// let root: Element = xml.parse().unwrap();
+/// let selector: Selector = "ServiceFrame/lines/Line[@id='2']".parse().unwrap();
+/// let lines = selector.select(&root);
+/// assert_eq!(1, lines.len());
+/// assert_eq!(2, "//Line".parse::<Selector>().unwrap().select(&root).len());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Selector {
+    source: Cow<'static, str>,
+    steps: Cow<'static, [Step]>,
+}
+
+impl Selector {
+    /// Compile a selector, returning an [`InvalidSelector`] error if the
+    /// syntax is not valid.
+    ///
+    /// [`InvalidSelector`]: enum.Error.html#variant.InvalidSelector
+    pub fn parse(selector: &str) -> Result<Selector, Error> {
+        let steps = Parser::new(selector)
+            .parse()
+            .map_err(|reason| Error::InvalidSelector {
+                selector: selector.to_owned(),
+                reason,
+            })?;
+        Ok(Selector {
+            source: Cow::Owned(selector.to_owned()),
+            steps: Cow::Owned(steps),
+        })
+    }
+
+    /// Build a selector from already compiled steps, which is possible in
+    /// `const` contexts.
+    pub const fn from_static(source: &'static str, steps: &'static [Step]) -> Selector {
+        Selector {
+            source: Cow::Borrowed(source),
+            steps: Cow::Borrowed(steps),
+        }
+    }
+
+    /// The steps of the selector.
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// The selector as written.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Select the matching elements, relatively to `element`.
+    pub fn select<'a>(&self, element: &'a Element) -> Vec<&'a Element> {
+        self.steps
+            .iter()
+            .fold(vec![element], |context, step| step.apply(&context))
+    }
+
+    /// Select the matching elements, relatively to `element`, as a
+    /// [`ResultSet`] offering the same lookups as the children of an element.
+    ///
+    /// [`ResultSet`]: struct.ResultSet.html
+    pub fn select_set<'a>(&self, element: &'a Element) -> ResultSet<'a> {
+        ResultSet::new(element, self)
+    }
+
+    /// Trace the evaluation of the selector on `element`, step by step, to
+    /// understand why a query does not return the expected elements.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::Selector;
+    ///
+    /// let xml: &'static str = r#"<root><lines><Line id="1" /></lines></root>"#;
+    /// let root: Element = xml.parse().unwrap();
+    /// let selector: Selector = "lines/Line[@id='2']".parse().unwrap();
+    /// let explanation = selector.explain(&root);
+    /// assert_eq!(Some(1), explanation.failing_step());
+    /// assert_eq!(
+    ///     "lines: 1 match(es)\n/Line[@id='2']: 0 match(es) <- no more matches",
+    ///     explanation.to_string()
+    /// );
+    /// ```
+    pub fn explain(&self, element: &Element) -> Explanation {
+        let mut context = vec![element];
+        let steps = self
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(index, step)| {
+                context = step.apply(&context);
+                StepExplanation {
+                    step: step.to_string_at(index),
+                    matches: context.len(),
+                }
+            })
+            .collect();
+        Explanation { steps }
+    }
+
+    /// Select the first matching element, relatively to `element`.
+    pub fn select_first<'a>(&self, element: &'a Element) -> Option<&'a Element> {
+        self.select(element).into_iter().next()
+    }
+}
+
+impl Step {
+    pub(crate) fn matches(&self, element: &Element) -> bool {
+        let name_matches = match &self.name_test {
+            NameTest::Any => true,
+            NameTest::Name(name) => element.name() == name,
+        };
+        name_matches
+            && self.predicates.iter().all(|predicate| match predicate {
+                Predicate::HasAttribute(name) => element.attr(name).is_some(),
+                Predicate::AttributeEquals(name, value) => {
+                    element.attr(name) == Some(value.as_ref())
+                }
+                Predicate::Position(_) => true,
+            })
+    }
+
+    pub(crate) fn position(&self) -> Option<usize> {
+        self.predicates
+            .iter()
+            .find_map(|predicate| match predicate {
+                Predicate::Position(position) => Some(*position),
+                _ => None,
+            })
+    }
+
+    /// Write the step as in a selector, the separator of the first step of a
+    /// selector being omitted for the child axis.
+    fn to_string_at(&self, index: usize) -> String {
+        let mut result = match (self.axis, index) {
+            (Axis::Child, 0) => String::new(),
+            (Axis::Child, _) => String::from("/"),
+            (Axis::Descendant, _) => String::from("//"),
+        };
+        match &self.name_test {
+            NameTest::Any => result.push('*'),
+            NameTest::Name(name) => result.push_str(name),
+        }
+        for predicate in self.predicates.iter() {
+            match predicate {
+                Predicate::HasAttribute(name) => result.push_str(&format!("[@{}]", name)),
+                Predicate::AttributeEquals(name, value) => {
+                    result.push_str(&format!("[@{}='{}']", name, value))
+                }
+                Predicate::Position(position) => result.push_str(&format!("[{}]", position)),
+            }
+        }
+        result
+    }
+
+    /// Apply the step on every element of the context, keeping the document
+    /// order and removing duplicates.
+    pub(crate) fn apply<'a>(&self, context: &[&'a Element]) -> Vec<&'a Element> {
+        let mut result = Vec::new();
+        let mut seen = HashSet::new();
+        for element in context {
+            let mut candidates = Vec::new();
+            match self.axis {
+                Axis::Child => collect_children(element, self, &mut candidates),
+                Axis::Descendant => collect_descendants(element, self, &mut candidates),
+            }
+            for candidate in candidates {
+                if self.axis == Axis::Child || seen.insert(candidate as *const Element) {
+                    result.push(candidate);
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Collect the children matching the step, the position being counted among
+/// the children of `element`.
+fn collect_children<'a>(element: &'a Element, step: &Step, result: &mut Vec<&'a Element>) {
+    let mut children = element.children().filter(|child| step.matches(child));
+    match step.position() {
+        // Positions start at 1, a step built with `Position(0)` matches nothing
+        Some(position) => {
+            result.extend(position.checked_sub(1).and_then(|skip| children.nth(skip)))
+        }
+        None => result.extend(children),
+    }
+}
+
+/// Collect the descendants matching the step in document order, the position
+/// being counted among the children of each parent, like `//b[2]` in XPath.
+fn collect_descendants<'a>(element: &'a Element, step: &Step, result: &mut Vec<&'a Element>) {
+    let mut matched = 0;
+    for child in element.children() {
+        if step.matches(child) {
+            matched += 1;
+            if step.position().is_none_or(|position| position == matched) {
+                result.push(child);
+            }
+        }
+        collect_descendants(child, step, result);
+    }
+}
+
+/// Number of elements matched after a step of a [`Selector`], see
+/// [`Selector::explain`].
+///
+/// [`Selector`]: struct.Selector.html
+/// [`Selector::explain`]: struct.Selector.html#method.explain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepExplanation {
+    /// The step, as it would be written in a selector
+    pub step: String,
+    /// Number of elements matched after applying the step
+    pub matches: usize,
+}
+
+/// Trace of the evaluation of a [`Selector`], see [`Selector::explain`].
+///
+/// [`Selector`]: struct.Selector.html
+/// [`Selector::explain`]: struct.Selector.html#method.explain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    /// The evaluated steps, in order
+    pub steps: Vec<StepExplanation>,
+}
+
+impl Explanation {
+    /// Index of the first step after which no element was matched anymore.
+    pub fn failing_step(&self) -> Option<usize> {
+        self.steps.iter().position(|step| step.matches == 0)
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let failing_step = self.failing_step();
+        for (index, step) in self.steps.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}: {} match(es)", step.step, step.matches)?;
+            if failing_step == Some(index) {
+                write!(f, " <- no more matches")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Selector {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Selector::parse(s)
+    }
+}
+
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Parser { input, position: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        if self.rest().starts_with(token) {
+            self.position += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn name(&mut self) -> Result<&'a str, String> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| "/[]@='\"".contains(c) || c.is_whitespace())
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(format!("expected a name at position {}", self.position));
+        }
+        self.position += len;
+        Ok(&rest[..len])
+    }
+
+    fn quoted(&mut self) -> Result<&'a str, String> {
+        let quote = match self.rest().chars().next() {
+            Some(quote) if quote == '\'' || quote == '"' => quote,
+            _ => {
+                return Err(format!(
+                    "expected a quoted value at position {}",
+                    self.position
+                ))
+            }
+        };
+        self.position += 1;
+        let rest = self.rest();
+        let len = rest
+            .find(quote)
+            .ok_or_else(|| format!("unterminated value at position {}", self.position))?;
+        self.position += len + 1;
+        Ok(&rest[..len])
+    }
+
+    fn predicate(&mut self) -> Result<Predicate, String> {
+        let predicate = if self.eat("@") {
+            let name = self.name()?.to_owned();
+            if self.eat("=") {
+                let value = self.quoted()?.to_owned();
+                Predicate::AttributeEquals(name.into(), value.into())
+            } else {
+                Predicate::HasAttribute(name.into())
+            }
+        } else {
+            let position = self.name()?;
+            match position.parse() {
+                Ok(position) if position > 0 => Predicate::Position(position),
+                _ => return Err(format!("invalid position '{}'", position)),
+            }
+        };
+        if self.eat("]") {
+            Ok(predicate)
+        } else {
+            Err(format!("expected ']' at position {}", self.position))
+        }
+    }
+
+    fn parse(mut self) -> Result<Vec<Step>, String> {
+        let mut steps = Vec::new();
+        let mut axis = if self.eat("//") {
+            Axis::Descendant
+        } else {
+            Axis::Child
+        };
+        loop {
+            let name_test = match self.name()? {
+                "*" => NameTest::Any,
+                name => NameTest::Name(name.to_owned().into()),
+            };
+            let mut predicates = Vec::new();
+            while self.eat("[") {
+                predicates.push(self.predicate()?);
+            }
+            steps.push(Step {
+                axis,
+                name_test,
+                predicates: predicates.into(),
+            });
+            if self.rest().is_empty() {
+                return Ok(steps);
+            }
+            axis = if self.eat("//") {
+                Axis::Descendant
+            } else if self.eat("/") {
+                Axis::Child
+            } else {
+                return Err(format!(
+                    "unexpected character at position {}",
+                    self.position
+                ));
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn ids(elements: Vec<&Element>) -> Vec<&str> {
+        elements.iter().filter_map(|e| e.attr("id")).collect()
+    }
+
+    #[test]
+    fn select() {
+        let xml: &'static str = r#"<root>
+                <a id="a1"><b id="b1" /><b id="b2" type="x" /></a>
+                <a id="a2"><b id="b3" /><c id="c1"><b id="b4" /></c></a>
+            </root>"#;
+        let root: Element = xml.parse().unwrap();
+        let select = |selector: &str| ids(Selector::parse(selector).unwrap().select(&root));
+        assert_eq!(vec!["b1", "b2", "b3"], select("a/b"));
+        assert_eq!(vec!["b1", "b2", "b3", "b4"], select("//b"));
+        assert_eq!(vec!["b3", "b4"], select("a[@id='a2']//b"));
+        assert_eq!(vec!["b2"], select("a/b[@type]"));
+        assert_eq!(vec!["b2"], select("*/b[@type=\"x\"]"));
+        assert_eq!(vec!["b1", "b3"], select("a/b[1]"));
+        assert_eq!(vec!["b1", "b3", "b4"], select("//b[1]"));
+        assert_eq!(vec!["b2"], select("//b[2]"));
+        assert_eq!(vec!["c1"], select("//c"));
+        assert_eq!(vec!["b4"], select("//a//c/b"));
+        assert_eq!(Vec::<&str>::new(), select("b"));
+        const ZERO: &[Step] = &[Step {
+            axis: Axis::Child,
+            name_test: NameTest::Any,
+            predicates: Cow::Borrowed(&[Predicate::Position(0)]),
+        }];
+        let zero = Selector::from_static("*[0]", ZERO);
+        assert!(zero.select(&root).is_empty());
+    }
+
+    #[test]
+    fn explain() {
+        let xml: &'static str = r#"<root><a><b /><b /><c><b /><b /></c></a><a /></root>"#;
+        let root: Element = xml.parse().unwrap();
+        let explanation = Selector::parse("a//b[2]/c[@id]/d").unwrap().explain(&root);
+        let matches: Vec<_> = explanation.steps.iter().map(|s| s.matches).collect();
+        assert_eq!(vec![2, 2, 0, 0], matches);
+        assert_eq!(Some(2), explanation.failing_step());
+        assert_eq!("//b[2]", explanation.steps[1].step);
+        let explanation = Selector::parse("a/b").unwrap().explain(&root);
+        assert_eq!(None, explanation.failing_step());
+    }
+
+    #[test]
+    fn invalid_selectors() {
+        for selector in &["", "a/", "a[@id", "a[@id=1]", "a[0]", "a]", "a//"] {
+            assert!(Selector::parse(selector).is_err(), "{}", selector);
+        }
+        let error = Selector::parse("a[@id='1'").unwrap_err();
+        assert_eq!(
+            "Invalid selector \'a[@id=\'1\'\': expected \']\' at position 9",
+            format!("{}", error)
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use crate::namespace::{split_qname, PrefixResolver};
+use minidom::{Element, Node};
+use std::collections::BTreeMap;
+
+/// The content of an element as compared by [`semantic_eq`]: the child
+/// elements and the texts, trimmed and merged across comments, without the
+/// texts made only of whitespaces.
+///
+/// [`semantic_eq`]: fn.semantic_eq.html
+enum Content<'a> {
+    Element(&'a Element),
+    Text(String),
+}
+
+fn content(element: &Element) -> Vec<Content<'_>> {
+    let mut content = Vec::new();
+    let mut text = String::new();
+    let flush = |text: &mut String, content: &mut Vec<Content<'_>>| {
+        let trimmed = text.trim();
+        if !trimmed.is_empty() {
+            content.push(Content::Text(trimmed.to_owned()));
+        }
+        text.clear();
+    };
+    for node in element.nodes() {
+        match node {
+            Node::Element(child) => {
+                flush(&mut text, &mut content);
+                content.push(Content::Element(child));
+            }
+            Node::Text(value) => text.push_str(value),
+            Node::Comment(_) => {}
+        }
+    }
+    flush(&mut text, &mut content);
+    content
+}
+
+/// The attributes of an element by namespace and local name.
+fn attributes(element: &Element) -> BTreeMap<(Option<String>, &str), &str> {
+    let mut resolver = PrefixResolver::new(element);
+    element
+        .attrs()
+        .map(|(name, value)| {
+            let (prefix, local_name) = split_qname(name);
+            let namespace = prefix.and_then(|prefix| resolver.resolve(Some(prefix)));
+            ((namespace, local_name), value)
+        })
+        .collect()
+}
+
+/// Check if two trees are the same for any namespace-aware consumer, which
+/// `minidom`'s `PartialEq` is too strict for when comparing re-serialized
+/// documents.
+///
+/// Elements and attributes are compared by namespace and local name, so the
+/// spelling of the prefixes doesn't matter, nor does the order of the
+/// attributes. Comments are ignored, texts are compared without their
+/// leading and trailing whitespaces, and the texts made only of whitespaces
+/// (like indentation) are ignored.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::semantic_eq;
+///
+/// let expected: Element = r#"<n:Line xmlns:n="urn:netex" id="1" version="2">
+///         <n:Name> Metro 1 </n:Name>
+///     </n:Line>"#
+///     .parse()
+///     .unwrap();
+/// let actual: Element = r#"<Line xmlns="urn:netex" version="2" id="1"><!-- generated --><Name>Metro 1</Name></Line>"#
+///     .parse()
+///     .unwrap();
+/// assert!(expected != actual);
+/// assert!(semantic_eq(&expected, &actual));
+/// let other: Element = r#"<Line xmlns="urn:other" id="1" version="2"><Name>Metro 1</Name></Line>"#
+///     .parse()
+///     .unwrap();
+/// assert!(!semantic_eq(&expected, &other));
+/// ```
+pub fn semantic_eq(a: &Element, b: &Element) -> bool {
+    if a.name() != b.name() || a.ns() != b.ns() || attributes(a) != attributes(b) {
+        return false;
+    }
+    let (a, b) = (content(a), content(b));
+    a.len() == b.len()
+        && a.iter().zip(&b).all(|pair| match pair {
+            (Content::Element(a), Content::Element(b)) => semantic_eq(a, b),
+            (Content::Text(a), Content::Text(b)) => a == b,
+            _ => false,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eq(a: &str, b: &str) -> bool {
+        semantic_eq(&a.parse().unwrap(), &b.parse().unwrap())
+    }
+
+    #[test]
+    fn significant_differences() {
+        assert!(eq(
+            r#"<a xmlns:x="urn:x" x:id="1">t<!-- c -->ext</a>"#,
+            r#"<a xmlns:y="urn:x" y:id="1">text</a>"#
+        ));
+        assert!(!eq(
+            r#"<a xmlns:x="urn:x" x:id="1" />"#,
+            r#"<a xmlns:x="urn:y" x:id="1" />"#
+        ));
+        assert!(!eq(r#"<a><b /><c /></a>"#, r#"<a><c /><b /></a>"#));
+        assert!(!eq(r#"<a>x<b /></a>"#, r#"<a><b />x</a>"#));
+        assert!(!eq(r#"<a>x y</a>"#, r#"<a>x  y</a>"#));
+        assert!(!eq(r#"<a id="1" />"#, r#"<a />"#));
+    }
+}
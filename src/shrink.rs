@@ -0,0 +1,168 @@
+use crate::{materialize::detached_tree, tree::OwnedElement};
+use minidom::Element;
+
+/// Try to remove chunks of nodes, then chunks of attributes, of the element at
+/// `path`, keeping each removal for which the predicate still holds.
+fn shrink_element<P>(tree: &mut OwnedElement, path: &[usize], predicate: &mut P) -> bool
+where
+    P: FnMut(&Element) -> bool,
+{
+    let mut changed = false;
+    loop {
+        let len = match tree.get_mut(path) {
+            Some(element) => element.nodes.len(),
+            None => return changed,
+        };
+        if !remove_chunks(tree, path, len, predicate, |element, range| {
+            element.nodes.drain(range);
+        }) {
+            break;
+        }
+        changed = true;
+    }
+    loop {
+        let len = match tree.get_mut(path) {
+            Some(element) => element.attributes.len(),
+            None => return changed,
+        };
+        if !remove_chunks(tree, path, len, predicate, |element, range| {
+            element.attributes.drain(range);
+        }) {
+            break;
+        }
+        changed = true;
+    }
+    changed
+}
+
+/// Try to remove `len / 2`, then `len / 4`, ... and finally single items,
+/// returning as soon as one removal is kept.
+fn remove_chunks<P, R>(
+    tree: &mut OwnedElement,
+    path: &[usize],
+    len: usize,
+    predicate: &mut P,
+    remove: R,
+) -> bool
+where
+    P: FnMut(&Element) -> bool,
+    R: Fn(&mut OwnedElement, std::ops::Range<usize>),
+{
+    let mut chunk_size = len;
+    while chunk_size > 0 {
+        for start in (0..len).step_by(chunk_size) {
+            let mut candidate = tree.clone();
+            if let Some(element) = candidate.get_mut(path) {
+                remove(element, start..usize::min(start + chunk_size, len));
+            }
+            if predicate(&candidate.to_element()) {
+                *tree = candidate;
+                return true;
+            }
+        }
+        chunk_size /= 2;
+    }
+    false
+}
+
+/// Shrink a document to a minimal version for which the predicate still
+/// holds, by repeatedly removing child nodes and attributes (delta debugging).
+///
+/// This is useful to turn a huge document making a downstream parser fail into
+/// a small one which can be attached to a bug report. The predicate is
+/// typically "my parser still fails on this document". The root element itself
+/// is always kept, declaring the namespaces inherited from its ancestors. If
+/// the predicate doesn't hold for `element`, it is returned unchanged.
+///
+/// The predicate is called many times, each time on a new candidate document,
+/// so it should be reasonably fast.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::shrink;
+///
+/// let xml: &'static str = r#"<root version="1">
+///         <Line id="1" />
+///         <Line id="2" name="" />
+///         <Line id="3" />
+///     </root>"#;
+/// let root: Element = xml.parse().unwrap();
+/// // The bug happens for lines with an empty name
+/// let minimal = shrink(&root, |root| {
+///     root.children().any(|line| line.attr("name") == Some(""))
+/// });
+/// assert_eq!(r#"<root><Line name=""/></root>"#, String::from(&minimal));
+/// ```
+pub fn shrink<P>(element: &Element, mut predicate: P) -> Element
+where
+    P: FnMut(&Element) -> bool,
+{
+    if !predicate(element) {
+        return element.clone();
+    }
+    let mut tree = detached_tree(element);
+    loop {
+        let mut paths = Vec::new();
+        tree.collect_paths(&mut Vec::new(), &mut paths);
+        let mut changed = false;
+        // Paths might be shifted by previous removals, which is only
+        // suboptimal: every candidate is checked against the predicate, and
+        // the loop runs until nothing can be removed anymore.
+        for path in paths {
+            changed |= shrink_element(&mut tree, &path, &mut predicate);
+        }
+        if !changed {
+            break;
+        }
+    }
+    tree.to_element()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn predicate_not_holding() {
+        let xml: &'static str = r#"<root><child /></root>"#;
+        let root: Element = xml.parse().unwrap();
+        assert_eq!(root, shrink(&root, |_| false));
+    }
+
+    #[test]
+    fn nested_elements() {
+        let xml: &'static str = r#"<root xmlns="urn:a" a="1" b="2">
+                <frame id="1"><Line id="1" /><Line id="2" /></frame>
+                <frame id="2"><Line id="3" /><Stop id="4" /><Line id="5" /></frame>
+            </root>"#;
+        let root: Element = xml.parse().unwrap();
+        let mut calls = 0;
+        let minimal = shrink(&root, |root| {
+            calls += 1;
+            root.children()
+                .flat_map(|frame| frame.children())
+                .any(|child| child.name() == "Stop")
+        });
+        assert_eq!(
+            r#"<root xmlns="urn:a"><frame><Stop/></frame></root>"#,
+            String::from(&minimal)
+        );
+        assert!(calls < 100);
+    }
+
+    #[test]
+    fn inherited_namespaces() {
+        let xml: &'static str =
+            r#"<root xmlns:a="urn:a"><a:frame><a:Line/><a:Stop/></a:frame></root>"#;
+        let root: Element = xml.parse().unwrap();
+        let frame = root.children().next().unwrap();
+        let minimal = shrink(frame, |frame| {
+            frame.has_child("Stop", "urn:a") && frame.ns().as_deref() == Some("urn:a")
+        });
+        assert_eq!(
+            r#"<a:frame xmlns:a="urn:a"><a:Stop/></a:frame>"#,
+            String::from(&minimal)
+        );
+    }
+}
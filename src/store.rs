@@ -0,0 +1,388 @@
+use crate::Interner;
+use minidom::{Element, Node};
+use std::{
+    collections::{hash_map::DefaultHasher, HashSet},
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+/// A node of a [`StoredElement`].
+///
+/// [`StoredElement`]: struct.StoredElement.html
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum StoredNode {
+    /// A child element, shared with the identical subtrees
+    Element(Arc<StoredElement>),
+    /// A text node
+    Text(Arc<str>),
+}
+
+/// A read-only element stored in a [`DocumentStore`], identical subtrees
+/// being shared between all the documents of the store.
+///
+/// The namespace of the element and its text and element nodes are kept, in
+/// document order, so mixed content is preserved. Comments and namespace
+/// prefixes are dropped.
+///
+/// [`DocumentStore`]: struct.DocumentStore.html
+#[derive(Debug)]
+pub struct StoredElement {
+    /// Structural hash, computed from the hashes of the children
+    hash: u64,
+    name: Arc<str>,
+    ns: Option<Arc<str>>,
+    attributes: Vec<(Arc<str>, Arc<str>)>,
+    nodes: Vec<StoredNode>,
+}
+
+impl StoredElement {
+    /// The local name of the element.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The namespace of the element, if any.
+    pub fn ns(&self) -> Option<&str> {
+        self.ns.as_deref()
+    }
+
+    /// The value of an attribute, if present.
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attr_name, _)| attr_name.as_ref() == name)
+            .map(|(_, value)| value.as_ref())
+    }
+
+    /// Iterate over the attributes, sorted by name.
+    pub fn attrs(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.attributes
+            .iter()
+            .map(|(name, value)| (name.as_ref(), value.as_ref()))
+    }
+
+    /// The text and element nodes, in document order.
+    pub fn nodes(&self) -> &[StoredNode] {
+        &self.nodes
+    }
+
+    /// The concatenation of the texts directly under the element.
+    pub fn text(&self) -> String {
+        self.nodes
+            .iter()
+            .filter_map(|node| match node {
+                StoredNode::Text(text) => Some(text.as_ref()),
+                StoredNode::Element(_) => None,
+            })
+            .collect()
+    }
+
+    /// Iterate over the child elements.
+    pub fn children(&self) -> impl Iterator<Item = &Arc<StoredElement>> {
+        self.nodes.iter().filter_map(|node| match node {
+            StoredNode::Element(child) => Some(child),
+            StoredNode::Text(_) => None,
+        })
+    }
+
+    /// Copy the subtree into an [`Element`], each namespace being declared
+    /// as the default namespace of the elements where it changes.
+    ///
+    /// [`Element`]: ../minidom/element/struct.Element.html
+    pub fn to_element(&self) -> Element {
+        self.to_element_in(None)
+    }
+
+    fn to_element_in(&self, parent_ns: Option<&str>) -> Element {
+        let mut element = match self.ns() {
+            // Inherited once appended to the parent
+            ns if ns == parent_ns => Element::bare(self.name.as_ref()),
+            ns => Element::builder(self.name.as_ref())
+                .ns(ns.unwrap_or_default())
+                .build(),
+        };
+        for (name, value) in &self.attributes {
+            element.set_attr(name.as_ref(), value.as_ref());
+        }
+        for node in &self.nodes {
+            match node {
+                StoredNode::Element(child) => {
+                    element.append_child(child.to_element_in(self.ns()));
+                }
+                StoredNode::Text(text) => element.append_text_node(text.as_ref()),
+            }
+        }
+        element
+    }
+}
+
+impl Hash for StoredElement {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
+}
+
+impl PartialEq for StoredElement {
+    /// Children being shared, they are compared by address.
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash
+            && self.name == other.name
+            && self.ns == other.ns
+            && self.attributes == other.attributes
+            && self.nodes.len() == other.nodes.len()
+            && self
+                .nodes
+                .iter()
+                .zip(&other.nodes)
+                .all(|nodes| match nodes {
+                    (StoredNode::Element(a), StoredNode::Element(b)) => Arc::ptr_eq(a, b),
+                    (StoredNode::Text(a), StoredNode::Text(b)) => a == b,
+                    _ => false,
+                })
+    }
+}
+
+impl Eq for StoredElement {}
+
+/// Build the node of an element whose children are already shared, given in
+/// document order.
+fn node<F>(element: &Element, children: Vec<Arc<StoredElement>>, mut intern: F) -> StoredElement
+where
+    F: FnMut(&str) -> Arc<str>,
+{
+    let name = intern(element.name());
+    let ns = element.ns().map(|ns| intern(&ns));
+    let attributes: Vec<(Arc<str>, Arc<str>)> = element
+        .attrs()
+        .map(|(name, value)| (intern(name), intern(value)))
+        .collect();
+    let mut children = children.into_iter();
+    let nodes: Vec<StoredNode> = element
+        .nodes()
+        .filter_map(|node| match node {
+            Node::Element(_) => children.next().map(StoredNode::Element),
+            Node::Text(text) => Some(StoredNode::Text(intern(text))),
+            Node::Comment(_) => None,
+        })
+        .collect();
+    let mut hasher = DefaultHasher::new();
+    name.hash(&mut hasher);
+    ns.hash(&mut hasher);
+    attributes.hash(&mut hasher);
+    for node in &nodes {
+        match node {
+            StoredNode::Element(child) => child.hash.hash(&mut hasher),
+            StoredNode::Text(text) => text.hash(&mut hasher),
+        }
+    }
+    StoredElement {
+        hash: hasher.finish(),
+        name,
+        ns,
+        attributes,
+        nodes,
+    }
+}
+
+/// A store of many parsed documents, where identical subtrees are only kept
+/// once, to hold a whole corpus (like a month of daily deliveries) in memory
+/// for comparative analysis.
+///
+/// Subtrees are deduplicated bottom-up by structural hash, so a subtree
+/// appearing unchanged in every delivery costs its size only once. Names and
+/// attribute values are interned as well.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::DocumentStore;
+///
+/// let monday: Element = r#"<delivery><Line id="1"><Name>Metro 1</Name></Line></delivery>"#
+///     .parse()
+///     .unwrap();
+/// let tuesday: Element = r#"<delivery day="2"><Line id="1"><Name>Metro 1</Name></Line><Line id="2" /></delivery>"#
+///     .parse()
+///     .unwrap();
+/// let mut store = DocumentStore::new();
+/// store.insert("monday.xml", &monday);
+/// store.insert("tuesday.xml", &tuesday);
+/// // Line 1 and its name are shared, the roots differ
+/// assert_eq!(5, store.distinct_subtrees());
+/// let lines: Vec<(&str, Option<&str>)> = store
+///     .find("Line")
+///     .into_iter()
+///     .map(|(document, line)| (document, line.attr("id")))
+///     .collect();
+/// assert_eq!(
+///     vec![("monday.xml", Some("1")), ("tuesday.xml", Some("1")), ("tuesday.xml", Some("2"))],
+///     lines
+/// );
+/// let line: Element = r#"<Line id="2" />"#.parse().unwrap();
+/// assert_eq!(vec!["tuesday.xml"], store.documents_containing(&line));
+/// ```
+#[derive(Debug, Default)]
+pub struct DocumentStore {
+    interner: Interner,
+    subtrees: HashSet<Arc<StoredElement>>,
+    documents: Vec<(String, Arc<StoredElement>)>,
+}
+
+impl DocumentStore {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build the node of an element whose children are already interned.
+    fn node(&mut self, element: &Element, children: Vec<Arc<StoredElement>>) -> StoredElement {
+        let interner = &mut self.interner;
+        node(element, children, |value| interner.intern(value))
+    }
+
+    fn intern(&mut self, element: &Element) -> Arc<StoredElement> {
+        let children = element.children().map(|child| self.intern(child)).collect();
+        let node = self.node(element, children);
+        if let Some(shared) = self.subtrees.get(&node) {
+            return Arc::clone(shared);
+        }
+        let node = Arc::new(node);
+        self.subtrees.insert(Arc::clone(&node));
+        node
+    }
+
+    /// Find the shared copy of a subtree, without adding it.
+    fn lookup(&self, element: &Element) -> Option<Arc<StoredElement>> {
+        let children = element
+            .children()
+            .map(|child| self.lookup(child))
+            .collect::<Option<Vec<_>>>()?;
+        let node = node(element, children, |value| Arc::from(value));
+        self.subtrees.get(&node).cloned()
+    }
+
+    /// Add a document under the name `name`, and return its root.
+    pub fn insert(&mut self, name: &str, root: &Element) -> Arc<StoredElement> {
+        let root = self.intern(root);
+        self.documents.push((name.to_owned(), Arc::clone(&root)));
+        root
+    }
+
+    /// The root of the document named `name`.
+    pub fn get(&self, name: &str) -> Option<&Arc<StoredElement>> {
+        self.documents
+            .iter()
+            .find(|(document, _)| document == name)
+            .map(|(_, root)| root)
+    }
+
+    /// Iterate over the documents and their roots, in insertion order.
+    pub fn documents(&self) -> impl Iterator<Item = (&str, &Arc<StoredElement>)> {
+        self.documents
+            .iter()
+            .map(|(name, root)| (name.as_str(), root))
+    }
+
+    /// Number of documents.
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    /// Check if the store has no document.
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// Number of distinct subtrees kept in memory.
+    pub fn distinct_subtrees(&self) -> usize {
+        self.subtrees.len()
+    }
+
+    /// All the elements named `element_name` in all documents, with the name
+    /// of their document, in insertion and document order.
+    pub fn find(&self, element_name: &str) -> Vec<(&str, &Arc<StoredElement>)> {
+        fn collect<'a>(
+            document: &'a str,
+            element: &'a Arc<StoredElement>,
+            name: &str,
+            result: &mut Vec<(&'a str, &'a Arc<StoredElement>)>,
+        ) {
+            if element.name() == name {
+                result.push((document, element));
+            }
+            for child in element.children() {
+                collect(document, child, name, result);
+            }
+        }
+        let mut result = Vec::new();
+        for (document, root) in self.documents() {
+            collect(document, root, element_name, &mut result);
+        }
+        result
+    }
+
+    /// The names of the documents containing a subtree identical to
+    /// `subtree` (comments and namespace prefixes aside), in insertion order.
+    pub fn documents_containing(&self, subtree: &Element) -> Vec<&str> {
+        fn contains(element: &Arc<StoredElement>, subtree: &Arc<StoredElement>) -> bool {
+            Arc::ptr_eq(element, subtree)
+                || element.children().any(|child| contains(child, subtree))
+        }
+        match self.lookup(subtree) {
+            Some(subtree) => self
+                .documents()
+                .filter(|(_, root)| contains(root, &subtree))
+                .map(|(name, _)| name)
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn shared_subtrees() {
+        let first: Element = r#"<a><b x="1">t</b><b x="1">t</b><b x="2" /></a>"#.parse().unwrap();
+        let second: Element = r#"<a><b x="1">t</b><b x="1">t</b><b x="2" /></a>"#.parse().unwrap();
+        let mut store = DocumentStore::new();
+        let first = store.insert("first", &first);
+        let second = store.insert("second", &second);
+        assert!(Arc::ptr_eq(&first, &second));
+        let children: Vec<_> = first.children().collect();
+        assert!(Arc::ptr_eq(children[0], children[1]));
+        assert_eq!(3, store.distinct_subtrees());
+        assert_eq!(2, store.len());
+        let other: Element = r#"<b x="1">u</b>"#.parse().unwrap();
+        assert!(store.documents_containing(&other).is_empty());
+        assert_eq!(3, store.distinct_subtrees());
+    }
+
+    #[test]
+    fn to_element() {
+        let xml: &'static str = r#"<a y="2" x="1">text<b /></a>"#;
+        let element: Element = xml.parse().unwrap();
+        let mut store = DocumentStore::new();
+        let root = store.insert("doc", &element);
+        assert_eq!(element, root.to_element());
+        assert_eq!("text", store.get("doc").unwrap().text());
+    }
+
+    #[test]
+    fn mixed_content_and_namespaces() {
+        let xml: &'static str = r#"<p xmlns="urn:a">one <b>two</b> three<c xmlns="urn:c"/></p>"#;
+        let element: Element = xml.parse().unwrap();
+        let other: Element = r#"<p xmlns="urn:b">one <b>two</b> three<c xmlns="urn:c"/></p>"#
+            .parse()
+            .unwrap();
+        let mut store = DocumentStore::new();
+        let root = store.insert("a", &element);
+        store.insert("b", &other);
+        assert_eq!(5, store.distinct_subtrees());
+        assert_eq!(Some("urn:a"), root.ns());
+        assert_eq!("one  three", root.text());
+        assert_eq!(String::from(&element), String::from(&root.to_element()));
+        assert_eq!(vec!["a"], store.documents_containing(&element));
+    }
+}
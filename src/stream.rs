@@ -0,0 +1,431 @@
+//! Copy of documents event by event, without building a tree, to process
+//! files too big to be held in memory.
+
+use crate::{Axis, Error, Selector};
+use minidom::{
+    quick_xml::{
+        events::{attributes::Attribute, BytesStart, Event},
+        Reader, Writer,
+    },
+    Element,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    io::{BufRead, Write},
+    str,
+};
+
+#[derive(Debug, Default)]
+struct Frame {
+    /// Index of the steps to apply on the children of this element
+    steps: Vec<usize>,
+    /// Number of children already matched by each step, to evaluate the
+    /// position predicates
+    counters: HashMap<usize, usize>,
+}
+
+/// Evaluate a [`Selector`] on the elements of a document as they are read,
+/// the root element being the context of the selector.
+///
+/// [`Selector`]: struct.Selector.html
+pub(crate) struct StreamMatcher<'s> {
+    selector: &'s Selector,
+    frames: Vec<Frame>,
+}
+
+impl<'s> StreamMatcher<'s> {
+    pub(crate) fn new(selector: &'s Selector) -> Self {
+        StreamMatcher {
+            selector,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Open an element (only its name and attributes are needed) and tell
+    /// if it is selected.
+    pub(crate) fn start(&mut self, element: &Element) -> bool {
+        let steps = self.selector.steps();
+        let parent = match self.frames.last_mut() {
+            Some(parent) => parent,
+            None => {
+                self.frames.push(Frame {
+                    steps: vec![0],
+                    counters: HashMap::new(),
+                });
+                return false;
+            }
+        };
+        let mut next_steps = Vec::new();
+        let mut selected = false;
+        for index in parent.steps.clone() {
+            let step = &steps[index];
+            if step.axis == Axis::Descendant && !next_steps.contains(&index) {
+                next_steps.push(index);
+            }
+            if !step.matches(element) {
+                continue;
+            }
+            // Positions are counted among the children of the parent, like
+            // in `Step::apply`
+            let counter = parent.counters.entry(index).or_insert(0);
+            *counter += 1;
+            if step.position().is_some_and(|position| position != *counter) {
+                continue;
+            }
+            if index + 1 == steps.len() {
+                selected = true;
+            } else if !next_steps.contains(&(index + 1)) {
+                next_steps.push(index + 1);
+            }
+        }
+        self.frames.push(Frame {
+            steps: next_steps,
+            counters: HashMap::new(),
+        });
+        selected
+    }
+
+    /// Close the last open element.
+    pub(crate) fn end(&mut self) {
+        self.frames.pop();
+    }
+}
+
+/// A bare element with the local name and the attributes of a start tag.
+pub(crate) fn bare_element(start: &BytesStart<'_>) -> Result<Element, Error> {
+    let name = str::from_utf8(start.local_name()).map_err(|e| Error::InvalidDocument {
+        source: minidom::Error::Utf8Error(e),
+    })?;
+    let mut element = Element::bare(name);
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(xml_error)?;
+        let value = attribute.unescaped_value().map_err(xml_error)?;
+        let key = str::from_utf8(attribute.key).map_err(|e| Error::InvalidDocument {
+            source: minidom::Error::Utf8Error(e),
+        })?;
+        let value = str::from_utf8(&value).map_err(|e| Error::InvalidDocument {
+            source: minidom::Error::Utf8Error(e),
+        })?;
+        element.set_attr(key, value);
+    }
+    Ok(element)
+}
+
+pub(crate) fn xml_error(error: minidom::quick_xml::Error) -> Error {
+    Error::InvalidDocument {
+        source: minidom::Error::XmlError(error),
+    }
+}
+
+/// Copy a document from `reader` to `writer`, dropping the subtrees selected
+/// by `drop`, and return the number of dropped subtrees.
+///
+/// The document is processed event by event, without building any tree, so
+/// the memory used doesn't depend on the size of the document. The selector
+/// is relative to the root element, as with [`Selector::select`]. Everything
+/// else is copied as is, including the whitespaces around the dropped
+/// subtrees.
+///
+/// ```
+/// use minidom_ext::{filter_stream, Selector};
+///
+/// let xml: &'static str = r#"<root><Stop id="1"><Geometry><pos>1 2</pos></Geometry></Stop><Geometry/></root>"#;
+/// let mut output = Vec::new();
+/// let selector = Selector::parse("Stop/Geometry").unwrap();
+/// let dropped = filter_stream(xml.as_bytes(), &mut output, &selector).unwrap();
+/// assert_eq!(1, dropped);
+/// assert_eq!(
+///     r#"<root><Stop id="1"></Stop><Geometry/></root>"#,
+///     String::from_utf8(output).unwrap()
+/// );
+/// ```
+///
+/// [`Selector::select`]: struct.Selector.html#method.select
+pub fn filter_stream<R, W>(reader: R, writer: W, drop: &Selector) -> Result<usize, Error>
+where
+    R: BufRead,
+    W: Write,
+{
+    let mut reader = Reader::from_reader(reader);
+    let mut writer = Writer::new(writer);
+    let mut matcher = StreamMatcher::new(drop);
+    // Depth of the open elements inside the subtree being dropped
+    let mut dropping: Option<usize> = None;
+    let mut dropped = 0;
+    let mut buf = Vec::new();
+    loop {
+        let event = reader.read_event(&mut buf).map_err(xml_error)?;
+        if let Some(depth) = dropping.as_mut() {
+            match event {
+                Event::Start(_) => *depth += 1,
+                Event::End(_) if *depth == 0 => dropping = None,
+                Event::End(_) => *depth -= 1,
+                Event::Eof => break,
+                _ => {}
+            }
+            if let (None, Event::End(_)) = (dropping, &event) {
+                matcher.end();
+            }
+            buf.clear();
+            continue;
+        }
+        let selected = match &event {
+            Event::Start(start) => {
+                let selected = matcher.start(&bare_element(start)?);
+                if selected {
+                    dropping = Some(0);
+                }
+                selected
+            }
+            Event::Empty(start) => {
+                let selected = matcher.start(&bare_element(start)?);
+                matcher.end();
+                selected
+            }
+            Event::End(_) => {
+                matcher.end();
+                false
+            }
+            Event::Eof => break,
+            _ => false,
+        };
+        if selected {
+            dropped += 1;
+        } else {
+            writer.write_event(&event).map_err(xml_error)?;
+        }
+        buf.clear();
+    }
+    Ok(dropped)
+}
+
+type StreamRewrite = Box<dyn Fn(&str) -> String>;
+
+struct StreamRule {
+    selector: Selector,
+    attribute: String,
+    rewrite: StreamRewrite,
+}
+
+/// A list of attribute rewrites applied while copying a document, without
+/// building any tree.
+///
+/// This is the streaming counterpart of [`Transform`], for files too big to
+/// be held in memory: rules are applied in the order they are declared, each
+/// rule seeing the changes of the previous ones, and selectors are relative
+/// to the root element. Any substitution can be expressed with
+/// [`map_attribute`], including regex-based ones.
+///
+/// ```
+/// use minidom_ext::{Selector, StreamTransform};
+///
+/// let xml: &'static str = r#"<root><Line mode="Bus" /><Stop mode="Bus" /></root>"#;
+/// let transform = StreamTransform::new()
+///     .replace_value(Selector::parse("Line").unwrap(), "mode", "Bus", "bus")
+///     .map_attribute(Selector::parse("//*").unwrap(), "mode", |mode| mode.to_uppercase());
+/// let mut output = Vec::new();
+/// let changes = transform.apply(xml.as_bytes(), &mut output).unwrap();
+/// assert_eq!(3, changes);
+/// assert_eq!(
+///     r#"<root><Line mode="BUS"/><Stop mode="BUS"/></root>"#,
+///     String::from_utf8(output).unwrap()
+/// );
+/// ```
+///
+/// [`Transform`]: struct.Transform.html
+/// [`map_attribute`]: #method.map_attribute
+#[derive(Default)]
+pub struct StreamTransform {
+    rules: Vec<StreamRule>,
+}
+
+impl StreamTransform {
+    /// Create a transform without any rule.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rewrite the attribute of the elements matching the selector, when
+    /// present.
+    pub fn map_attribute<F>(mut self, selector: Selector, attribute: &str, f: F) -> Self
+    where
+        F: Fn(&str) -> String + 'static,
+    {
+        self.rules.push(StreamRule {
+            selector,
+            attribute: attribute.to_owned(),
+            rewrite: Box::new(f),
+        });
+        self
+    }
+
+    /// Replace the value `from` of the attribute by `to`, for the elements
+    /// matching the selector.
+    pub fn replace_value(self, selector: Selector, attribute: &str, from: &str, to: &str) -> Self {
+        let (from, to) = (from.to_owned(), to.to_owned());
+        self.map_attribute(selector, attribute, move |value| {
+            if value == from {
+                to.clone()
+            } else {
+                value.to_owned()
+            }
+        })
+    }
+
+    /// Copy a document from `reader` to `writer` while applying the rules,
+    /// and return the number of changes made.
+    pub fn apply<R, W>(&self, reader: R, writer: W) -> Result<usize, Error>
+    where
+        R: BufRead,
+        W: Write,
+    {
+        let mut reader = Reader::from_reader(reader);
+        let mut writer = Writer::new(writer);
+        let mut matchers: Vec<StreamMatcher<'_>> = self
+            .rules
+            .iter()
+            .map(|rule| StreamMatcher::new(&rule.selector))
+            .collect();
+        let mut changes = 0;
+        let mut buf = Vec::new();
+        loop {
+            let event = reader.read_event(&mut buf).map_err(xml_error)?;
+            let rewritten = match &event {
+                Event::Start(start) | Event::Empty(start) => {
+                    let mut element = bare_element(start)?;
+                    let mut changed = HashSet::new();
+                    for (rule, matcher) in self.rules.iter().zip(matchers.iter_mut()) {
+                        if !matcher.start(&element) {
+                            continue;
+                        }
+                        if let Some(value) = element.attr(&rule.attribute) {
+                            let new_value = (rule.rewrite)(value);
+                            if new_value != value {
+                                element.set_attr(rule.attribute.as_str(), new_value);
+                                changed.insert(rule.attribute.as_str());
+                                changes += 1;
+                            }
+                        }
+                    }
+                    if let Event::Empty(_) = event {
+                        matchers.iter_mut().for_each(StreamMatcher::end);
+                    }
+                    if changed.is_empty() {
+                        None
+                    } else {
+                        let mut rewritten = BytesStart::borrowed_name(start.name());
+                        for attribute in start.attributes() {
+                            let attribute = attribute.map_err(xml_error)?;
+                            let key = str::from_utf8(attribute.key).map_err(|e| {
+                                Error::InvalidDocument {
+                                    source: minidom::Error::Utf8Error(e),
+                                }
+                            })?;
+                            match element.attr(key) {
+                                Some(value) if changed.contains(key) => {
+                                    rewritten.push_attribute(Attribute::from((key, value)))
+                                }
+                                _ => rewritten.push_attribute(attribute),
+                            }
+                        }
+                        Some(match event {
+                            Event::Start(_) => Event::Start(rewritten),
+                            _ => Event::Empty(rewritten),
+                        })
+                    }
+                }
+                Event::End(_) => {
+                    matchers.iter_mut().for_each(StreamMatcher::end);
+                    None
+                }
+                Event::Eof => break,
+                _ => None,
+            };
+            writer
+                .write_event(rewritten.as_ref().unwrap_or(&event))
+                .map_err(xml_error)?;
+            buf.clear();
+        }
+        Ok(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn filter(xml: &str, selector: &str) -> (String, usize) {
+        let mut output = Vec::new();
+        let selector = Selector::parse(selector).unwrap();
+        let dropped = filter_stream(xml.as_bytes(), &mut output, &selector).unwrap();
+        (String::from_utf8(output).unwrap(), dropped)
+    }
+
+    #[test]
+    fn dropped_subtrees() {
+        let xml: &'static str = r#"<root>
+                <a id="1"><b id="2"><a id="3"/></b></a>
+                <a id="4" x="y"><a id="5"/></a>
+                <c><a id="6"/><a id="7"/></c>
+            </root>"#;
+        let root: Element = xml.parse().unwrap();
+        let remaining: Selector = "//*[@id]".parse().unwrap();
+        for (selector, expected) in &[
+            ("a", vec!["6", "7"]),
+            ("//a", vec![]),
+            ("a[@x]", vec!["1", "2", "3", "6", "7"]),
+            ("//a[2]", vec!["1", "2", "3", "6"]),
+            ("c/a[2]", vec!["1", "2", "3", "4", "5", "6"]),
+            ("*//a", vec!["1", "2", "4"]),
+            ("a/*/a", vec!["1", "2", "4", "5", "6", "7"]),
+        ] {
+            let (output, _) = filter(xml, selector);
+            let filtered: Element = output.parse().unwrap();
+            let kept: Vec<&str> = remaining
+                .select(&filtered)
+                .into_iter()
+                .filter_map(|element| element.attr("id"))
+                .collect();
+            assert_eq!(expected, &kept, "{}", selector);
+            let selected: Vec<&str> = Selector::parse(selector)
+                .unwrap()
+                .select(&root)
+                .into_iter()
+                .filter_map(|element| element.attr("id"))
+                .collect();
+            assert!(kept.iter().all(|id| !selected.contains(id)), "{}", selector);
+        }
+    }
+
+    #[test]
+    fn keeps_everything_else() {
+        let xml: &'static str = "<?xml version=\"1.0\"?>\n<!-- c --><root xmlns=\"urn:a\"><![CDATA[<x>]]><b>&amp;</b></root>";
+        let (output, dropped) = filter(xml, "c");
+        assert_eq!(0, dropped);
+        assert_eq!(xml, output);
+    }
+
+    #[test]
+    fn rewrite_escaped_values() {
+        let xml: &'static str = r#"<root><a x="&lt;1&gt;" y="&amp;"><b x="2" /></a></root>"#;
+        let transform = StreamTransform::new()
+            .map_attribute(Selector::parse("a").unwrap(), "x", |x| format!("{}&", x))
+            .replace_value(Selector::parse("a/b[@x='2']").unwrap(), "x", "2", "3")
+            .replace_value(Selector::parse("a/b").unwrap(), "x", "2", "4");
+        let mut output = Vec::new();
+        assert_eq!(2, transform.apply(xml.as_bytes(), &mut output).unwrap());
+        assert_eq!(
+            r#"<root><a x="&lt;1&gt;&amp;" y="&amp;"><b x="3"/></a></root>"#,
+            String::from_utf8(output).unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_document() {
+        let mut output = Vec::new();
+        let selector = Selector::parse("a").unwrap();
+        let error = filter_stream("<root><a></b></root>".as_bytes(), &mut output, &selector);
+        assert!(error.is_err());
+    }
+}
@@ -0,0 +1,148 @@
+//! Suggestions of close names, to help fixing typos and inconsistent casing,
+//! and lists of the names actually present.
+
+use crate::Error;
+use minidom::Element;
+
+/// Maximum number of names listed in an error, to keep messages readable on
+/// elements with many children.
+const MAX_AVAILABLE: usize = 10;
+
+/// Levenshtein distance between two strings, ignoring case.
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().flat_map(char::to_lowercase).collect();
+    let b: Vec<char> = b.chars().flat_map(char::to_lowercase).collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// The candidate closest to `name`, if at most 2 edits away; the first one
+/// wins in case of a tie.
+pub(crate) fn closest<'a, I>(name: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Format the suggestion of an error message, if any.
+pub(crate) fn did_you_mean(suggestion: &Option<String>) -> String {
+    match suggestion {
+        Some(suggestion) => format!(" (did you mean '{}'?)", suggestion),
+        None => String::new(),
+    }
+}
+
+/// The distinct names, in order of appearance, up to `MAX_AVAILABLE`.
+fn distinct_names<'a, I>(names: I) -> Vec<String>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut distinct: Vec<String> = Vec::new();
+    for name in names {
+        if distinct.len() == MAX_AVAILABLE {
+            break;
+        }
+        if !distinct.iter().any(|known| known == name) {
+            distinct.push(name.to_owned());
+        }
+    }
+    distinct
+}
+
+/// Format the names present on an element, if known.
+pub(crate) fn available(names: &[String]) -> String {
+    if names.is_empty() {
+        String::new()
+    } else {
+        format!(" (available: {})", names.join(", "))
+    }
+}
+
+/// Build an [`AttributeNotFound`] error, suggesting the closest attribute of
+/// the element.
+///
+/// [`AttributeNotFound`]: ../enum.Error.html#variant.AttributeNotFound
+pub(crate) fn attribute_not_found(element: &Element, attr_name: &str) -> Error {
+    Error::AttributeNotFound {
+        element_name: element.name().to_owned(),
+        attribute_name: attr_name.to_owned(),
+        suggestion: closest(attr_name, element.attrs().map(|(name, _)| name))
+            .map(ToOwned::to_owned),
+        available: distinct_names(element.attrs().map(|(name, _)| name)),
+    }
+}
+
+/// Build a [`NoChildren`] error, suggesting the closest child name of the
+/// element.
+///
+/// [`NoChildren`]: ../enum.Error.html#variant.NoChildren
+pub(crate) fn no_children(element: &Element, child_name: &str) -> Error {
+    Error::NoChildren {
+        element_name: element.name().to_owned(),
+        child_name: child_name.to_owned(),
+        suggestion: closest(child_name, element.children().map(Element::name))
+            .map(ToOwned::to_owned),
+        available: distinct_names(element.children().map(Element::name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn distance() {
+        assert_eq!(0, edit_distance("Line", "line"));
+        assert_eq!(1, edit_distance("Lines", "Line"));
+        assert_eq!(2, edit_distance("Lnie", "Line"));
+        assert_eq!(4, edit_distance("", "Line"));
+    }
+
+    #[test]
+    fn suggestions() {
+        let line: Element = r#"<Line id="1" name="A"><Stop /><Route /></Line>"#.parse().unwrap();
+        assert_eq!(
+            "Failed to find attribute 'Id' in element 'Line' (did you mean 'id'?) (available: id, name)",
+            attribute_not_found(&line, "Id").to_string()
+        );
+        assert_eq!(
+            "Failed to find attribute 'color' in element 'Line' (available: id, name)",
+            attribute_not_found(&line, "color").to_string()
+        );
+        assert_eq!(
+            "No children with name 'Routes' in Element 'Line' (did you mean 'Route'?) (available: Stop, Route)",
+            no_children(&line, "Routes").to_string()
+        );
+    }
+
+    #[test]
+    fn available_names() {
+        let names = ["a", "b", "a", "c", "d", "e", "f", "g", "h", "i", "j", "k"];
+        let distinct = distinct_names(names.iter().copied());
+        assert_eq!(
+            vec!["a", "b", "c", "d", "e", "f", "g", "h", "i", "j"],
+            distinct
+        );
+        let empty: Element = "<root />".parse().unwrap();
+        assert_eq!(
+            "No children with name 'Line' in Element 'root'",
+            no_children(&empty, "Line").to_string()
+        );
+    }
+}
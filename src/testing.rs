@@ -0,0 +1,192 @@
+//! Support of the [`assert_xml_eq!`] macro.
+//!
+//! [`assert_xml_eq!`]: ../macro.assert_xml_eq.html
+
+use crate::{diff, semantic_eq, Document};
+use minidom::Element;
+use std::{borrow::Cow, fmt};
+
+/// Number of differences shown by a failing [`assert_xml_eq!`].
+///
+/// [`assert_xml_eq!`]: ../macro.assert_xml_eq.html
+const SHOWN_DIFFERENCES: usize = 5;
+
+/// A tree compared by [`assert_xml_eq!`]: an [`Element`], a [`Document`] or
+/// its XML source.
+///
+/// [`assert_xml_eq!`]: ../macro.assert_xml_eq.html
+/// [`Document`]: ../struct.Document.html
+/// [`Element`]: ../../minidom/element/struct.Element.html
+#[doc(hidden)]
+pub trait XmlOperand {
+    /// The tree, panicking if the source is not a valid document.
+    fn tree(&self) -> Cow<'_, Element>;
+}
+
+impl XmlOperand for Element {
+    fn tree(&self) -> Cow<'_, Element> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl XmlOperand for Document {
+    fn tree(&self) -> Cow<'_, Element> {
+        Cow::Borrowed(self.root())
+    }
+}
+
+impl XmlOperand for str {
+    fn tree(&self) -> Cow<'_, Element> {
+        match self.parse() {
+            Ok(tree) => Cow::Owned(tree),
+            Err(e) => panic!("invalid XML in assert_xml_eq!: {}\n{}", e, self),
+        }
+    }
+}
+
+impl XmlOperand for String {
+    fn tree(&self) -> Cow<'_, Element> {
+        self.as_str().tree()
+    }
+}
+
+impl<T: XmlOperand + ?Sized> XmlOperand for &T {
+    fn tree(&self) -> Cow<'_, Element> {
+        (**self).tree()
+    }
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_xml_eq<E, A>(expected: &E, actual: &A, message: Option<fmt::Arguments<'_>>)
+where
+    E: XmlOperand + ?Sized,
+    A: XmlOperand + ?Sized,
+{
+    let (expected, actual) = (expected.tree(), actual.tree());
+    if semantic_eq(&expected, &actual) {
+        return;
+    }
+    let mut report = String::from("assertion failed: `(expected xml == actual xml)`");
+    if let Some(message) = message {
+        report.push_str(&format!(": {}", message));
+    }
+    let differences = diff(&expected, &actual);
+    if differences.is_empty() {
+        // `diff` doesn't see every difference (like the namespaces), show
+        // both trees instead
+        report.push_str(&format!(
+            "\nexpected: {}\n  actual: {}",
+            String::from(&*expected),
+            String::from(&*actual)
+        ));
+    } else {
+        report.push_str(&format!(
+            "\n{} difference(s) from expected to actual",
+            differences.len()
+        ));
+        for difference in differences.iter().take(SHOWN_DIFFERENCES) {
+            report.push_str(&format!("\n  {}", difference));
+        }
+        if differences.len() > SHOWN_DIFFERENCES {
+            report.push_str(&format!(
+                "\n  and {} more",
+                differences.len() - SHOWN_DIFFERENCES
+            ));
+        }
+    }
+    panic!("{}", report);
+}
+
+/// Assert that two trees are the same (see [`semantic_eq`]), showing the
+/// first differences (see [`diff`]) instead of both trees when they are not.
+/// Both trees are shown when the differences are not reported by [`diff`],
+/// like different namespaces.
+///
+/// The trees can be [`Element`]s, [`Document`]s or XML sources (`&str` or
+/// `String`). Like `assert_eq!`, an additional message can be given with
+/// format arguments.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::assert_xml_eq;
+///
+/// let actual: Element = r#"<Line xmlns="urn:netex" id="1"><Name>A</Name></Line>"#
+///     .parse()
+///     .unwrap();
+/// assert_xml_eq!(
+///     r#"<n:Line xmlns:n="urn:netex" id="1">
+///         <n:Name>A</n:Name>
+///     </n:Line>"#,
+///     actual
+/// );
+/// ```
+///
+/// ```should_panic
+/// use minidom_ext::assert_xml_eq;
+///
+/// // Panics with:
+/// // assertion failed: `(expected xml == actual xml)`: line 1
+/// // 2 difference(s) from expected to actual
+/// //   /Line: attribute 'name' changed from 'A' to 'B'
+/// //   /Line/Stop[1]: removed
+/// assert_xml_eq!(
+///     r#"<Line name="A"><Stop /></Line>"#,
+///     r#"<Line name="B" />"#,
+///     "line {}",
+///     1
+/// );
+/// ```
+///
+/// [`diff`]: fn.diff.html
+/// [`Document`]: struct.Document.html
+/// [`Element`]: ../minidom/element/struct.Element.html
+/// [`semantic_eq`]: fn.semantic_eq.html
+#[macro_export]
+macro_rules! assert_xml_eq {
+    ($expected:expr, $actual:expr $(,)?) => {
+        $crate::__testing::assert_xml_eq(&$expected, &$actual, ::std::option::Option::None)
+    };
+    ($expected:expr, $actual:expr, $($arg:tt)+) => {
+        $crate::__testing::assert_xml_eq(
+            &$expected,
+            &$actual,
+            ::std::option::Option::Some(::std::format_args!($($arg)+)),
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn report(expected: &str, actual: &str) -> String {
+        let result = std::panic::catch_unwind(|| assert_xml_eq(expected, actual, None));
+        let payload = result.unwrap_err();
+        payload.downcast_ref::<String>().unwrap().clone()
+    }
+
+    #[test]
+    fn first_differences() {
+        let expected = r#"<root><a/><a/><a/><a/><a/><a/><a/></root>"#;
+        assert_eq!(
+            "assertion failed: `(expected xml == actual xml)`\n\
+             7 difference(s) from expected to actual\n  \
+             /root/a[1]: removed\n  \
+             /root/a[2]: removed\n  \
+             /root/a[3]: removed\n  \
+             /root/a[4]: removed\n  \
+             /root/a[5]: removed\n  \
+             and 2 more",
+            report(expected, "<root/>")
+        );
+        assert_eq!(
+            "assertion failed: `(expected xml == actual xml)`\n\
+             expected: <a xmlns=\"urn:a\"/>\n  \
+             actual: <a xmlns=\"urn:b\"/>",
+            report(r#"<a xmlns="urn:a" />"#, r#"<a xmlns="urn:b" />"#)
+        );
+        crate::assert_xml_eq!(expected.to_owned(), expected, "same {}", "tree");
+    }
+}
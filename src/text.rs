@@ -0,0 +1,355 @@
+use crate::{
+    duration::ExactDuration, scratch::with_text, util::parse_text, value::parse_list, AttrValue,
+    AttributeElementExt, ControlCharacters, Error, HexBinary, ParseOptions, XsBoolean, XSI_NS,
+};
+use minidom::Element;
+use std::time::Duration;
+
+/// Get the text of an element.
+pub trait TextElementExt {
+    /// Try to parse the text of the element and return a [`Result`].
+    ///
+    /// The text is the concatenation of all the text nodes of the element,
+    /// with leading and trailing whitespaces removed. The type of the return
+    /// value is chosen by the caller, as long as this type implements
+    /// [`AttrValue`], like for attributes.
+    ///
+    /// Returns a [`NilElement`] error if the element is marked with
+    /// `xsi:nil="true"`, see [`try_text_nillable`] to accept nil elements.
+    ///
+    /// [`AttrValue`]: trait.AttrValue.html
+    /// [`NilElement`]: enum.Error.html#variant.NilElement
+    /// [`try_text_nillable`]: #method.try_text_nillable
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_text<F>(&self) -> Result<F, Error>
+    where
+        F: AttrValue;
+
+    /// Try to parse the text of the element, rejecting or removing its control
+    /// characters depending on `policy`, and return a [`Result`].
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_text_sanitized<F>(&self, policy: ControlCharacters) -> Result<F, Error>
+    where
+        F: AttrValue,
+    {
+        self.try_text_with(&ParseOptions::new().with_control_characters(policy))
+    }
+
+    /// Try to parse the text of the element, prepared by `options` (before
+    /// leading and trailing whitespaces are removed), and return a
+    /// [`Result`].
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_text_with<F>(&self, options: &ParseOptions) -> Result<F, Error>
+    where
+        F: AttrValue;
+
+    /// Parse the text of the element, prepared by `options`, and return an
+    /// [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn text_with<F>(&self, options: &ParseOptions) -> Option<F>
+    where
+        F: AttrValue,
+    {
+        self.try_text_with(options).ok()
+    }
+
+    /// Check if the element is marked as nil with `xsi:nil="true"`.
+    fn is_nil(&self) -> bool;
+
+    /// Try to parse the text of the element, unless the element is nil, and
+    /// return a [`Result`].
+    ///
+    /// Returns `Ok(None)` if the element is marked with `xsi:nil="true"`, see
+    /// [`try_text`] otherwise.
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`try_text`]: #tymethod.try_text
+    fn try_text_nillable<F>(&self) -> Result<Option<F>, Error>
+    where
+        F: AttrValue,
+    {
+        if self.is_nil() {
+            Ok(None)
+        } else {
+            self.try_text().map(Some)
+        }
+    }
+
+    /// Parse the text of the element and return an [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn text_value<F>(&self) -> Option<F>
+    where
+        F: AttrValue,
+    {
+        self.try_text().ok()
+    }
+
+    /// Try to parse the text of the element as an `xs:boolean` (`true`,
+    /// `false`, `1` or `0`) and return a [`Result`].
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_text_bool(&self) -> Result<bool, Error> {
+        self.try_text::<XsBoolean>().map(bool::from)
+    }
+
+    /// Parse the text of the element as an `xs:boolean` and return an
+    /// [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn text_bool(&self) -> Option<bool> {
+        self.text_value::<XsBoolean>().map(bool::from)
+    }
+
+    /// Try to parse the text of the element as an `xs:duration` (like
+    /// `PT1H30M`) into an exact [`Duration`] and return a [`Result`].
+    ///
+    /// Negative durations and durations with years or months are an error,
+    /// see [`XsDuration`] to handle them.
+    ///
+    /// [`Duration`]: https://doc.rust-lang.org/std/time/struct.Duration.html
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`XsDuration`]: struct.XsDuration.html
+    fn try_text_duration(&self) -> Result<Duration, Error> {
+        self.try_text::<ExactDuration>().map(|duration| duration.0)
+    }
+
+    /// Parse the text of the element as an `xs:duration` into an exact
+    /// [`Duration`] and return an [`Option`].
+    ///
+    /// [`Duration`]: https://doc.rust-lang.org/std/time/struct.Duration.html
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn text_duration(&self) -> Option<Duration> {
+        self.text_value::<ExactDuration>()
+            .map(|duration| duration.0)
+    }
+
+    /// Try to decode the text of the element as `xs:hexBinary` and return a
+    /// [`Result`].
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::TextElementExt;
+    ///
+    /// let xml: &'static str = r#"<Digest> CAFE01 </Digest>"#;
+    /// let digest: Element = xml.parse().unwrap();
+    /// assert_eq!(vec![0xca, 0xfe, 0x01], digest.try_text_hex().unwrap());
+    /// ```
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_text_hex(&self) -> Result<Vec<u8>, Error> {
+        self.try_text::<HexBinary>().map(|binary| binary.0)
+    }
+
+    /// Decode the text of the element as `xs:hexBinary` and return an
+    /// [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn text_hex(&self) -> Option<Vec<u8>> {
+        self.text_value::<HexBinary>().map(|binary| binary.0)
+    }
+
+    /// Try to parse the text of the element as whitespace-separated values
+    /// (like `xs:list` or GML `posList`) and return a [`Result`].
+    ///
+    /// The source of the [`TextParseError`] is a [`ListError`] giving the
+    /// index of the failing token.
+    ///
+    /// [`ListError`]: struct.ListError.html
+    /// [`TextParseError`]: enum.Error.html#variant.TextParseError
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_text_list<F>(&self) -> Result<Vec<F>, Error>
+    where
+        F: AttrValue;
+
+    /// Parse the text of the element as whitespace-separated values and
+    /// return an [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn text_list<F>(&self) -> Option<Vec<F>>
+    where
+        F: AttrValue,
+    {
+        self.try_text_list().ok()
+    }
+}
+
+impl TextElementExt for Element {
+    /// Implementation of [`TextElementExt`] for [`Element`] gives you access
+    /// to the typed text content of an XML element. For example, the value
+    /// `42` of this XML element `<tag>42</tag>`.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::TextElementExt;
+    ///
+    /// let xml: &'static str = r#"<root> 42 </root>"#;
+    /// let root: Element = xml.parse().unwrap();
+    /// let value: u64 = root.try_text().unwrap();
+    /// assert_eq!(42, value);
+    /// ```
+    ///
+    /// [`TextElementExt`]: trait.TextElementExt.html
+    /// [`Element`]: ../minidom/element/struct.Element.html
+    fn try_text<F>(&self) -> Result<F, Error>
+    where
+        F: AttrValue,
+    {
+        if self.is_nil() {
+            return Err(Error::NilElement {
+                element_name: self.name().to_owned(),
+            });
+        }
+        with_text(self, |text| parse_text(self, text))
+    }
+
+    fn text_value<F>(&self) -> Option<F>
+    where
+        F: AttrValue,
+    {
+        if self.is_nil() {
+            return None;
+        }
+        with_text(self, |text| F::parse_attr(text.trim()).ok())
+    }
+
+    fn try_text_with<F>(&self, options: &ParseOptions) -> Result<F, Error>
+    where
+        F: AttrValue,
+    {
+        if self.is_nil() {
+            return Err(Error::NilElement {
+                element_name: self.name().to_owned(),
+            });
+        }
+        with_text(self, |text| {
+            let text = options.prepare(text, || format!("text of element '{}'", self.name()))?;
+            parse_text(self, &text)
+        })
+    }
+
+    fn try_text_list<F>(&self) -> Result<Vec<F>, Error>
+    where
+        F: AttrValue,
+    {
+        if self.is_nil() {
+            return Err(Error::NilElement {
+                element_name: self.name().to_owned(),
+            });
+        }
+        with_text(self, |text| {
+            let value = text.trim();
+            parse_list(value, F::parse_attr).map_err(|e| Error::TextParseError {
+                element_name: self.name().to_owned(),
+                value: value.to_owned(),
+                source: e.into(),
+            })
+        })
+    }
+
+    fn is_nil(&self) -> bool {
+        // Avoid resolving namespaces for the vast majority of elements without
+        // any prefixed attribute
+        self.attrs().any(|(name, _)| name.ends_with(":nil"))
+            && self
+                .attribute_ns::<XsBoolean>(XSI_NS, "nil")
+                .map(bool::from)
+                .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::List;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn nil_element() {
+        let xml: &'static str = r#"<root xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+                <value xsi:nil="true" />
+                <value xsi:nil="false">42</value>
+            </root>"#;
+        let root: Element = xml.parse().unwrap();
+        let mut values = root.children();
+        let nil = values.next().unwrap();
+        assert!(nil.is_nil());
+        assert_eq!(None, nil.try_text_nillable::<u64>().unwrap());
+        assert_eq!(
+            "Element 'value' is nil",
+            format!("{}", nil.try_text::<u64>().unwrap_err())
+        );
+        let not_nil = values.next().unwrap();
+        assert!(!not_nil.is_nil());
+        assert_eq!(Some(42), not_nil.try_text_nillable::<u64>().unwrap());
+    }
+
+    #[test]
+    fn boolean_text() {
+        let xml: &'static str = r#"<root><a> 0 </a><b>yes</b></root>"#;
+        let root: Element = xml.parse().unwrap();
+        let mut values = root.children();
+        assert_eq!(Some(false), values.next().unwrap().text_bool());
+        let error = values.next().unwrap().try_text_bool().unwrap_err();
+        assert_eq!(
+            "Failed to parse and convert the text 'yes' of element 'b'",
+            format!("{}", error)
+        );
+    }
+
+    #[test]
+    fn list_text() {
+        let xml: &'static str = r#"<posList>
+                48.85 2.35
+                48.86 north
+            </posList>"#;
+        let pos_list: Element = xml.parse().unwrap();
+        let error = pos_list.try_text_list::<f64>().unwrap_err();
+        match error {
+            Error::TextParseError { source, .. } => assert_eq!(
+                "Failed to parse token 'north' at index 3 of the list",
+                source.to_string()
+            ),
+            e => panic!("unexpected error {:?}", e),
+        }
+        let xml: &'static str = r#"<keys>a b</keys>"#;
+        let keys: Element = xml.parse().unwrap();
+        assert_eq!(Some(vec!["a".to_owned(), "b".to_owned()]), keys.text_list());
+        // Any `AttrValue`, not only `FromStr` types
+        let keys: List<String> = keys.try_text().unwrap();
+        assert_eq!(vec!["a", "b"], keys.0);
+    }
+
+    #[test]
+    fn sanitized_text() {
+        let xml: &'static str = r#"<root>&#1;42&#x1F;</root>"#;
+        let root: Element = xml.parse().unwrap();
+        assert!(root.try_text::<u64>().is_err());
+        assert_eq!(
+            42,
+            root.try_text_sanitized::<u64>(ControlCharacters::Scrub)
+                .unwrap()
+        );
+        let error = root
+            .try_text_sanitized::<u64>(ControlCharacters::Reject)
+            .unwrap_err();
+        assert_eq!(
+            "Forbidden control character U+0001 at byte 0 of text of element 'root'",
+            format!("{}", error)
+        );
+    }
+
+    #[test]
+    fn invalid_text() {
+        let xml: &'static str = r#"<root>forty-two</root>"#;
+        let root: Element = xml.parse().unwrap();
+        let error = root.try_text::<u64>().unwrap_err();
+        assert_eq!(
+            "Failed to parse and convert the text \'forty-two\' of element \'root\'",
+            format!("{}", error)
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use minidom::Element;
+use std::collections::BTreeMap;
+
+/// Split a text into lowercase terms, on anything which is not alphanumeric.
+fn terms(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// An inverted index of the terms of the texts and attribute values of a
+/// tree, to answer repeated "which elements contain this term" queries (like
+/// the search box of an interactive tool) without rescanning the tree.
+///
+/// Terms are the alphanumeric words of the texts and attribute values,
+/// matched case-insensitively. An element contains a term if its own text
+/// (not the text of its descendants) or one of its attribute values does.
+/// Queries return the elements in document order.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::TextIndex;
+///
+/// let xml: &'static str = r#"<root>
+///         <StopPlace id="stop:1"><Name>Gare de Lyon</Name></StopPlace>
+///         <StopPlace id="stop:2"><Name>Lyon Perrache</Name></StopPlace>
+///         <StopPlace id="stop:3"><Name>Lille Flandres</Name></StopPlace>
+///     </root>"#;
+/// let root: Element = xml.parse().unwrap();
+/// let index = TextIndex::build(&root);
+/// let names = |elements: Vec<&Element>| -> Vec<String> {
+///     elements.into_iter().map(Element::text).collect()
+/// };
+/// assert_eq!(vec!["Gare de Lyon", "Lyon Perrache"], names(index.find("LYON")));
+/// assert_eq!(vec!["Lille Flandres"], names(index.find_prefix("li")));
+/// assert_eq!(vec!["Lyon Perrache"], names(index.find_all("lyon per")));
+/// assert_eq!(Some("stop:2"), index.find("2")[0].attr("id"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TextIndex<'a> {
+    /// The indexed elements, in document order
+    elements: Vec<&'a Element>,
+    /// Indexes of the elements containing each term, sorted
+    terms: BTreeMap<String, Vec<usize>>,
+}
+
+impl<'a> TextIndex<'a> {
+    /// Index the texts and attribute values of `root` and its descendants.
+    pub fn build(root: &'a Element) -> Self {
+        let mut index = TextIndex {
+            elements: Vec::new(),
+            terms: BTreeMap::new(),
+        };
+        index.insert(root);
+        index
+    }
+
+    fn insert(&mut self, element: &'a Element) {
+        let position = self.elements.len();
+        self.elements.push(element);
+        let values = element.attrs().map(|(_, value)| value);
+        for term in element.texts().chain(values).flat_map(terms) {
+            let positions = self.terms.entry(term).or_default();
+            if positions.last() != Some(&position) {
+                positions.push(position);
+            }
+        }
+        for child in element.children() {
+            self.insert(child);
+        }
+    }
+
+    fn elements(&self, positions: &[usize]) -> Vec<&'a Element> {
+        positions
+            .iter()
+            .map(|position| self.elements[*position])
+            .collect()
+    }
+
+    fn positions_with_prefix(&self, prefix: &str) -> Vec<usize> {
+        let mut positions: Vec<usize> = self
+            .terms
+            .range(prefix.to_owned()..)
+            .take_while(|(term, _)| term.starts_with(prefix))
+            .flat_map(|(_, positions)| positions.iter().copied())
+            .collect();
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+    }
+
+    /// The elements containing the term (case-insensitive).
+    pub fn find(&self, term: &str) -> Vec<&'a Element> {
+        self.terms
+            .get(&term.to_lowercase())
+            .map(|positions| self.elements(positions))
+            .unwrap_or_default()
+    }
+
+    /// The elements containing a term starting with `prefix`
+    /// (case-insensitive), to search while typing.
+    pub fn find_prefix(&self, prefix: &str) -> Vec<&'a Element> {
+        self.elements(&self.positions_with_prefix(&prefix.to_lowercase()))
+    }
+
+    /// The elements containing all the terms of the query, the last term
+    /// being matched as a prefix since it may still be being typed.
+    pub fn find_all(&self, query: &str) -> Vec<&'a Element> {
+        let mut query: Vec<String> = terms(query).collect();
+        let last = match query.pop() {
+            Some(last) => last,
+            None => return Vec::new(),
+        };
+        let mut positions = self.positions_with_prefix(&last);
+        for term in &query {
+            let found = self.terms.get(term).map(Vec::as_slice).unwrap_or_default();
+            positions.retain(|position| found.binary_search(position).is_ok());
+        }
+        self.elements(&positions)
+    }
+
+    /// Number of distinct terms in the index.
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    /// Whether the index has no terms.
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn own_texts_and_attributes() {
+        let xml: &'static str =
+            r#"<root note="Night bus"><Line mode="bus">N1 <Name>Bus-de-nuit</Name></Line></root>"#;
+        let root: Element = xml.parse().unwrap();
+        let index = TextIndex::build(&root);
+        let names: Vec<&str> = index.find("Bus").into_iter().map(Element::name).collect();
+        assert_eq!(vec!["root", "Line", "Name"], names);
+        assert_eq!(1, index.find("n1").len());
+        assert_eq!("root", index.find_all("night bus")[0].name());
+        assert!(index.find_all(" ").is_empty());
+        assert!(index.find("nothing").is_empty());
+        assert_eq!(5, index.len());
+    }
+}
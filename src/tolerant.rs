@@ -0,0 +1,139 @@
+use crate::Error;
+use minidom::Element;
+
+/// A kind of content found before the beginning of a document, to be
+/// skipped by [`parse_tolerant`].
+///
+/// [`parse_tolerant`]: fn.parse_tolerant.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LeadingJunk {
+    /// The byte order mark `U+FEFF`
+    ByteOrderMark,
+    /// Spaces, tabulations and blank lines
+    Whitespace,
+    /// Any text before the first `<`, like a log prefix
+    Text,
+}
+
+/// Content skipped at the beginning of a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedJunk {
+    /// The kind of the skipped content
+    pub kind: LeadingJunk,
+    /// The skipped content
+    pub content: String,
+}
+
+/// A document parsed by [`parse_tolerant`].
+///
+/// [`parse_tolerant`]: fn.parse_tolerant.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct TolerantDocument {
+    /// The root element of the document
+    pub element: Element,
+    /// The content skipped before the document, in order
+    pub skipped: Vec<SkippedJunk>,
+}
+
+/// Length of the junk of the first accepted kind at the beginning of `input`.
+fn leading_junk(input: &str, junk: &[LeadingJunk]) -> Option<(LeadingJunk, usize)> {
+    if junk.contains(&LeadingJunk::ByteOrderMark) && input.starts_with('\u{feff}') {
+        return Some((LeadingJunk::ByteOrderMark, '\u{feff}'.len_utf8()));
+    }
+    let whitespaces = input.len() - input.trim_start().len();
+    if junk.contains(&LeadingJunk::Whitespace) && whitespaces > 0 {
+        return Some((LeadingJunk::Whitespace, whitespaces));
+    }
+    if junk.contains(&LeadingJunk::Text) && !input.is_empty() && !input.starts_with('<') {
+        return Some((LeadingJunk::Text, input.find('<').unwrap_or(input.len())));
+    }
+    None
+}
+
+/// Parse a document after skipping the `junk` found before it, recording
+/// what was skipped.
+///
+/// Files produced by real-world tools sometimes start with a byte order
+/// mark, blank lines or a log prefix. Only the kinds of junk listed in `junk`
+/// are skipped, in any order and any number of times; any other content
+/// before the first `<` is an [`UnexpectedLeadingContent`] error, instead of
+/// being silently ignored by the parser.
+///
+/// ```
+/// use minidom_ext::{parse_tolerant, LeadingJunk};
+///
+/// let input = "\u{feff}\n2024-03-01 INFO export: <?xml version=\"1.0\"?><Lines/>";
+/// let document = parse_tolerant(
+///     input,
+///     &[LeadingJunk::ByteOrderMark, LeadingJunk::Whitespace, LeadingJunk::Text],
+/// )
+/// .unwrap();
+/// assert_eq!("Lines", document.element.name());
+/// let kinds: Vec<LeadingJunk> = document.skipped.iter().map(|junk| junk.kind).collect();
+/// assert_eq!(
+///     vec![LeadingJunk::ByteOrderMark, LeadingJunk::Whitespace, LeadingJunk::Text],
+///     kinds
+/// );
+/// assert_eq!("2024-03-01 INFO export: ", document.skipped[2].content);
+/// ```
+///
+/// [`UnexpectedLeadingContent`]: enum.Error.html#variant.UnexpectedLeadingContent
+pub fn parse_tolerant(input: &str, junk: &[LeadingJunk]) -> Result<TolerantDocument, Error> {
+    let mut rest = input;
+    let mut skipped: Vec<SkippedJunk> = Vec::new();
+    while let Some((kind, len)) = leading_junk(rest, junk) {
+        match skipped.last_mut() {
+            Some(last) if last.kind == kind => last.content.push_str(&rest[..len]),
+            _ => skipped.push(SkippedJunk {
+                kind,
+                content: rest[..len].to_owned(),
+            }),
+        }
+        rest = &rest[len..];
+    }
+    if !rest.is_empty() && !rest.starts_with('<') {
+        let end = rest.find('<').unwrap_or(rest.len());
+        return Err(Error::UnexpectedLeadingContent {
+            content: rest[..end].to_owned(),
+        });
+    }
+    let element = rest
+        .parse()
+        .map_err(|source| Error::InvalidDocument { source })?;
+    Ok(TolerantDocument { element, skipped })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn only_configured_junk() {
+        let input = "\u{feff}<root/>";
+        let document = parse_tolerant(input, &[LeadingJunk::ByteOrderMark]).unwrap();
+        assert_eq!(
+            vec![SkippedJunk {
+                kind: LeadingJunk::ByteOrderMark,
+                content: "\u{feff}".to_owned()
+            }],
+            document.skipped
+        );
+        let error =
+            parse_tolerant(" \u{feff}log: <root/>", &[LeadingJunk::Whitespace]).unwrap_err();
+        assert_eq!(
+            "Unexpected content '\u{feff}log: ' before the beginning of the document",
+            format!("{}", error)
+        );
+    }
+
+    #[test]
+    fn text_includes_whitespaces_when_not_configured() {
+        let input = "  \nlog:\n<root/>";
+        let document = parse_tolerant(input, &[LeadingJunk::Text]).unwrap();
+        assert_eq!(1, document.skipped.len());
+        assert_eq!("  \nlog:\n", document.skipped[0].content);
+        let document = parse_tolerant("<root/>", &[LeadingJunk::Text]).unwrap();
+        assert!(document.skipped.is_empty());
+    }
+}
@@ -0,0 +1,282 @@
+use crate::{cursor::path_step, Selector};
+use minidom::Element;
+use std::{collections::HashMap, fmt};
+
+type Rewrite = Box<dyn Fn(Option<&str>) -> Option<String>>;
+
+struct Rule {
+    selector: Selector,
+    attribute: String,
+    rewrite: Rewrite,
+}
+
+/// A change of attribute value made (or that would be made in dry-run mode)
+/// by a [`Transform`].
+///
+/// [`Transform`]: struct.Transform.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Modification {
+    /// Path of the modified element, like `/root/frame[1]/Line[2]`, where the
+    /// positions count the siblings with the same name, starting at 1
+    pub path: String,
+    /// Name of the modified attribute
+    pub attribute: String,
+    /// Value before the change, `None` if the attribute was missing
+    pub before: Option<String>,
+    /// Value after the change
+    pub after: String,
+}
+
+/// An element of the tree, in preorder.
+struct Node<'a> {
+    parent: Option<usize>,
+    name: &'a str,
+    /// Position among the siblings with the same name, starting at 1
+    position: usize,
+}
+
+/// Index the elements of the tree in preorder, by address.
+fn index_nodes<'a>(
+    element: &'a Element,
+    parent: Option<usize>,
+    position: usize,
+    nodes: &mut Vec<Node<'a>>,
+    indices: &mut HashMap<*const Element, usize>,
+) {
+    let index = nodes.len();
+    nodes.push(Node {
+        parent,
+        name: element.name(),
+        position,
+    });
+    indices.insert(element as *const Element, index);
+    let mut positions: HashMap<&str, usize> = HashMap::new();
+    for child in element.children() {
+        let position = positions.entry(child.name()).or_insert(0);
+        *position += 1;
+        index_nodes(child, Some(index), *position, nodes, indices);
+    }
+}
+
+fn element_path(nodes: &[Node<'_>], index: usize) -> String {
+    let mut steps = Vec::new();
+    let mut current = Some(index);
+    while let Some(index) = current {
+        let node = &nodes[index];
+        match node.parent {
+            Some(_) => steps.push(format!("/{}", path_step(node.name, node.position))),
+            None => steps.push(format!("/{}", node.name)),
+        }
+        current = node.parent;
+    }
+    steps.reverse();
+    steps.concat()
+}
+
+/// Set the planned attributes, visiting the elements in preorder.
+fn apply_changes(
+    element: &mut Element,
+    index: &mut usize,
+    changes: &HashMap<usize, Vec<(&str, &str)>>,
+) {
+    if let Some(attributes) = changes.get(index) {
+        for (name, value) in attributes {
+            element.set_attr(*name, *value);
+        }
+    }
+    *index += 1;
+    for child in element.children_mut() {
+        apply_changes(child, index, changes);
+    }
+}
+
+impl fmt::Display for Modification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.before {
+            Some(before) => write!(
+                f,
+                "{}/@{}: '{}' -> '{}'",
+                self.path, self.attribute, before, self.after
+            ),
+            None => write!(
+                f,
+                "{}/@{}: added '{}'",
+                self.path, self.attribute, self.after
+            ),
+        }
+    }
+}
+
+/// A list of attribute rewrites applied on the elements matching selectors.
+///
+/// Rules are applied in the order they are declared, each rule seeing the
+/// values written by the previous ones. The selectors match the tree as it
+/// was before the transform, as the changes are planned first and then
+/// written in a single pass. Every actual change is reported as a
+/// [`Modification`]. In dry-run mode, the changes are only reported and the
+/// tree is left untouched, so they can be reviewed before being applied.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{Selector, Transform};
+///
+/// let xml: &'static str = r#"<root><Line id="1" mode="Bus" /><Line id="2" mode="bus" /></root>"#;
+/// let mut root: Element = xml.parse().unwrap();
+/// let transform = Transform::new()
+///     .map_attribute(Selector::parse("Line").unwrap(), "mode", |mode| mode.to_lowercase())
+///     .dry_run(true);
+/// let modifications = transform.apply(&mut root);
+/// assert_eq!(1, modifications.len());
+/// assert_eq!("/root/Line[1]/@mode: 'Bus' -> 'bus'", modifications[0].to_string());
+/// assert_eq!(Some("Bus"), root.children().next().unwrap().attr("mode"));
+/// ```
+///
+/// [`Modification`]: struct.Modification.html
+#[derive(Default)]
+pub struct Transform {
+    rules: Vec<Rule>,
+    dry_run: bool,
+}
+
+impl Transform {
+    /// Create a transform without any rule.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the attribute of the elements matching the selector, adding it if
+    /// missing.
+    pub fn set_attribute(self, selector: Selector, attribute: &str, value: &str) -> Self {
+        let value = value.to_owned();
+        self.rewrite_attribute(selector, attribute, move |_| Some(value.clone()))
+    }
+
+    /// Rewrite the attribute of the elements matching the selector, when
+    /// present.
+    pub fn map_attribute<F>(self, selector: Selector, attribute: &str, f: F) -> Self
+    where
+        F: Fn(&str) -> String + 'static,
+    {
+        self.rewrite_attribute(selector, attribute, move |value| value.map(&f))
+    }
+
+    /// Rewrite the attribute of the elements matching the selector with a
+    /// function receiving the current value (`None` if missing) and returning
+    /// the new one (`None` to leave the element untouched).
+    pub fn rewrite_attribute<F>(mut self, selector: Selector, attribute: &str, f: F) -> Self
+    where
+        F: Fn(Option<&str>) -> Option<String> + 'static,
+    {
+        self.rules.push(Rule {
+            selector,
+            attribute: attribute.to_owned(),
+            rewrite: Box::new(f),
+        });
+        self
+    }
+
+    /// Only report the modifications, without changing the tree.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Apply the rules on the tree (unless in dry-run mode) and return the
+    /// modifications.
+    pub fn apply(&self, element: &mut Element) -> Vec<Modification> {
+        let modifications = self.plan(element);
+        if !self.dry_run {
+            let mut changes: HashMap<usize, Vec<(&str, &str)>> = HashMap::new();
+            for (index, modification) in &modifications {
+                changes
+                    .entry(*index)
+                    .or_default()
+                    .push((&modification.attribute, &modification.after));
+            }
+            apply_changes(element, &mut 0, &changes);
+        }
+        modifications
+            .into_iter()
+            .map(|(_, modification)| modification)
+            .collect()
+    }
+
+    /// The modifications of the rules, with the preorder index of the
+    /// modified elements.
+    fn plan(&self, root: &Element) -> Vec<(usize, Modification)> {
+        let mut nodes = Vec::new();
+        let mut indices = HashMap::new();
+        index_nodes(root, None, 1, &mut nodes, &mut indices);
+        // Modifications of the previous rules, by element and attribute
+        let mut written: HashMap<(usize, &str), usize> = HashMap::new();
+        let mut modifications: Vec<(usize, Modification)> = Vec::new();
+        for rule in &self.rules {
+            let mut planned = Vec::new();
+            for element in rule.selector.select(root) {
+                let index = indices[&(element as *const Element)];
+                let before = match written.get(&(index, rule.attribute.as_str())) {
+                    Some(written) => Some(modifications[*written].1.after.as_str()),
+                    None => element.attr(&rule.attribute),
+                };
+                match (rule.rewrite)(before) {
+                    Some(after) if before != Some(after.as_str()) => planned.push((
+                        index,
+                        Modification {
+                            path: element_path(&nodes, index),
+                            attribute: rule.attribute.clone(),
+                            before: before.map(ToOwned::to_owned),
+                            after,
+                        },
+                    )),
+                    _ => {}
+                }
+            }
+            // Registered after the rule, so each rule reads the values of the
+            // previous ones
+            for (index, modification) in planned {
+                written.insert((index, rule.attribute.as_str()), modifications.len());
+                modifications.push((index, modification));
+            }
+        }
+        modifications
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn apply_in_order() {
+        let xml: &'static str = r#"<root><a><b /><c /><b v="1" /></a></root>"#;
+        let mut root: Element = xml.parse().unwrap();
+        let transform = Transform::new()
+            .set_attribute(Selector::parse("a/b").unwrap(), "v", "1")
+            .map_attribute(Selector::parse("//b").unwrap(), "v", |v| v.repeat(2));
+        let modifications = transform.apply(&mut root);
+        let modifications: Vec<String> = modifications.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            vec![
+                "/root/a[1]/b[1]/@v: added '1'",
+                "/root/a[1]/b[1]/@v: '1' -> '11'",
+                "/root/a[1]/b[2]/@v: '1' -> '11'",
+            ],
+            modifications
+        );
+        let b = root.children().next().unwrap().children().nth(2).unwrap();
+        assert_eq!(Some("11"), b.attr("v"));
+    }
+
+    #[test]
+    fn dry_run_keeps_tree() {
+        let xml: &'static str = r#"<root><a v="1" /></root>"#;
+        let mut root: Element = xml.parse().unwrap();
+        let original = root.clone();
+        let transform = Transform::new()
+            .set_attribute(Selector::parse("a").unwrap(), "v", "2")
+            .dry_run(true);
+        assert_eq!(1, transform.apply(&mut root).len());
+        assert_eq!(original, root);
+    }
+}
@@ -0,0 +1,212 @@
+//! A detached, owned copy of an [`Element`] tree which can be freely edited
+//! (removing attributes or nodes is not possible with `minidom` 0.12) and
+//! turned back into an [`Element`].
+//!
+//! [`Element`]: ../minidom/element/struct.Element.html
+
+use crate::namespace::{own_declarations, split_qname};
+use minidom::{element::escape, Element, Node};
+use std::{collections::BTreeMap, mem};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum OwnedNode {
+    Element(OwnedElement),
+    Text(String),
+    Comment(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OwnedElement {
+    /// Name of the element, with its prefix if any
+    pub(crate) qname: String,
+    /// Namespaces declared on the element itself
+    pub(crate) namespaces: BTreeMap<Option<String>, String>,
+    pub(crate) attributes: Vec<(String, String)>,
+    pub(crate) nodes: Vec<OwnedNode>,
+}
+
+impl From<&Element> for OwnedElement {
+    fn from(element: &Element) -> Self {
+        let mut owned = OwnedElement::start_tag(element);
+        owned.nodes = element
+            .nodes()
+            .map(|node| match node {
+                Node::Element(child) => OwnedNode::Element(OwnedElement::from(child)),
+                Node::Text(text) => OwnedNode::Text(text.clone()),
+                Node::Comment(comment) => OwnedNode::Comment(comment.clone()),
+            })
+            .collect();
+        owned
+    }
+}
+
+impl OwnedElement {
+    /// Copy the name, the namespace declarations and the attributes of
+    /// `element`, without its nodes.
+    pub(crate) fn start_tag(element: &Element) -> Self {
+        let qname = match element.prefix() {
+            Some(prefix) => format!("{}:{}", prefix, element.name()),
+            None => element.name().to_owned(),
+        };
+        OwnedElement {
+            qname,
+            namespaces: own_declarations(element),
+            attributes: element
+                .attrs()
+                .map(|(name, value)| (name.to_owned(), value.to_owned()))
+                .collect(),
+            nodes: Vec::new(),
+        }
+    }
+
+    /// Get a descendant element from the indexes of the nodes leading to it.
+    pub(crate) fn get_mut(&mut self, path: &[usize]) -> Option<&mut OwnedElement> {
+        match path.split_first() {
+            None => Some(self),
+            Some((index, rest)) => match self.nodes.get_mut(*index) {
+                Some(OwnedNode::Element(child)) => child.get_mut(rest),
+                _ => None,
+            },
+        }
+    }
+
+    /// Collect the paths of this element and all its descendant elements, in
+    /// document order.
+    pub(crate) fn collect_paths(&self, path: &mut Vec<usize>, paths: &mut Vec<Vec<usize>>) {
+        paths.push(path.clone());
+        for (index, node) in self.nodes.iter().enumerate() {
+            if let OwnedNode::Element(child) = node {
+                path.push(index);
+                child.collect_paths(path, paths);
+                path.pop();
+            }
+        }
+    }
+
+    fn write_namespaces(&self, output: &mut String) {
+        for (prefix, namespace) in &self.namespaces {
+            let name = match prefix {
+                Some(prefix) => format!("xmlns:{}", prefix),
+                None => "xmlns".to_owned(),
+            };
+            push_attribute(output, &name, namespace);
+        }
+    }
+
+    /// Build the [`Element`] tree, without serializing and parsing it again
+    /// as a whole.
+    ///
+    /// Only the start tag of an element declaring other namespaces than the
+    /// one of its own prefix is parsed, `minidom` having no other way to
+    /// declare them. It cannot fail: namespaces are escaped, and the names
+    /// come from `minidom` elements, which are parsed or built with names
+    /// whose prefix is split off the same way (`minidom` doesn't check
+    /// whether the prefixes are bound).
+    pub(crate) fn to_element(&self) -> Element {
+        let prefix = split_qname(&self.qname).0;
+        let mut element = if self.namespaces.keys().all(|key| key.as_deref() == prefix) {
+            let builder = Element::builder(&self.qname);
+            match self.namespaces.values().next() {
+                Some(namespace) => builder.ns(namespace.clone()).build(),
+                None => builder.build(),
+            }
+        } else {
+            let mut start_tag = String::new();
+            start_tag.push('<');
+            start_tag.push_str(&self.qname);
+            self.write_namespaces(&mut start_tag);
+            start_tag.push_str("/>");
+            start_tag
+                .parse()
+                .expect("the start tag of an element read by minidom is well-formed")
+        };
+        for (name, value) in &self.attributes {
+            element.set_attr(name.clone(), value.clone());
+        }
+        for node in &self.nodes {
+            match node {
+                OwnedNode::Element(child) => {
+                    element.append_child(child.to_element());
+                }
+                OwnedNode::Text(text) => element.append_text_node(text.clone()),
+                OwnedNode::Comment(comment) => element.append_comment_node(comment.clone()),
+            }
+        }
+        element
+    }
+}
+
+/// Replace the start tag of `element` (its name, namespace declarations and
+/// attributes) by `start`, an element without nodes, moving the nodes of
+/// `element` into it instead of copying them.
+///
+/// `minidom` links the namespace declarations of an element to the ones of
+/// its parent, and only sets this link when appending a child: the new
+/// element is appended to `element` before replacing it, so it is linked to
+/// the declarations of `element`, themselves linked to its ancestors, and the
+/// inherited namespaces stay in scope.
+pub(crate) fn replace_start_tag(element: &mut Element, start: &OwnedElement) {
+    rebuild(element, start, |_| true);
+}
+
+/// Like [`replace_start_tag`], only keeping the nodes for which `keep`,
+/// given the index of the node, returns `true`.
+///
+/// [`replace_start_tag`]: fn.replace_start_tag.html
+pub(crate) fn rebuild<F>(element: &mut Element, start: &OwnedElement, mut keep: F)
+where
+    F: FnMut(usize) -> bool,
+{
+    let mut replacement = start.to_element();
+    for (index, node) in element.nodes_mut().enumerate() {
+        if !keep(index) {
+            continue;
+        }
+        match mem::replace(node, Node::Text(String::new())) {
+            Node::Element(child) => {
+                replacement.append_child(child);
+            }
+            node => replacement.append_node(node),
+        }
+    }
+    element.append_child(replacement);
+    let last = element
+        .nodes_mut()
+        .last()
+        .map(|node| mem::replace(node, Node::Text(String::new())));
+    if let Some(Node::Element(replacement)) = last {
+        *element = replacement;
+    }
+}
+
+pub(crate) fn push_escaped(output: &mut String, value: &str) {
+    output.push_str(&String::from_utf8_lossy(&escape(value.as_bytes())));
+}
+
+fn push_attribute(output: &mut String, name: &str, value: &str) {
+    output.push(' ');
+    output.push_str(name);
+    output.push_str("=\"");
+    push_escaped(output, value);
+    output.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn round_trip() {
+        let xml: &'static str = r#"<a:root xmlns="urn:default" xmlns:a="urn:a" id="&quot;1&quot;">text &amp; <child/><!-- comment --></a:root>"#;
+        let root: Element = xml.parse().unwrap();
+        let owned = OwnedElement::from(&root);
+        let element = owned.to_element();
+        assert_eq!(xml, String::from(&element));
+        assert_eq!(root, element);
+        assert_eq!(
+            Some("urn:default".to_owned()),
+            element.children().next().unwrap().ns()
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! Building blocks of the extension traits, to write helpers reporting the
+//! same errors as the crate.
+//!
+//! ```
+//! use minidom::Element;
+//! use minidom_ext::{util, Error};
+//!
+//! /// The unique child of `element` whose name ends with `Ref`.
+//! fn try_only_ref(element: &Element) -> Result<&Element, Error> {
+//!     let refs = element.children().filter(|child| child.name().ends_with("Ref"));
+//!     util::collect_unique(element, "*Ref", refs)
+//! }
+//!
+//! let xml: &'static str = r#"<Journey><LineRef ref="1" /><RouteRef ref="2" /></Journey>"#;
+//! let journey: Element = xml.parse().unwrap();
+//! assert_eq!(
+//!     "Multiple children with name '*Ref' in Element 'Journey' (found 2 elements)",
+//!     try_only_ref(&journey).unwrap_err().to_string()
+//! );
+//! ```
+
+use crate::{suggest::no_children, AttrValue, Error};
+use minidom::Element;
+
+/// The one and only item of `items`, or the number of items (`0` or at least
+/// `2`), without building any error.
+///
+/// ```
+/// use minidom_ext::util::unique;
+///
+/// assert_eq!(Ok(1), unique(vec![1]));
+/// assert_eq!(Err(0), unique(Vec::<u32>::new()));
+/// assert_eq!(Err(3), unique(vec![1, 2, 3]));
+/// ```
+pub fn unique<T, I>(items: I) -> Result<T, usize>
+where
+    I: IntoIterator<Item = T>,
+{
+    let mut items = items.into_iter();
+    match (items.next(), items.next()) {
+        (Some(item), None) => Ok(item),
+        (None, _) => Err(0),
+        (Some(_), Some(_)) => Err(2 + items.count()),
+    }
+}
+
+/// The one and only child of `children`, selected among the children of
+/// `element`, with the errors of [`OnlyChildElementExt::try_only_child`]: a
+/// [`NoChildren`] error (suggesting the closest child name) or a
+/// [`MultipleChildren`] error, both about `child_name`.
+///
+/// [`MultipleChildren`]: ../enum.Error.html#variant.MultipleChildren
+/// [`NoChildren`]: ../enum.Error.html#variant.NoChildren
+/// [`OnlyChildElementExt::try_only_child`]: ../trait.OnlyChildElementExt.html#tymethod.try_only_child
+pub fn collect_unique<'a, I>(
+    element: &Element,
+    child_name: &str,
+    children: I,
+) -> Result<&'a Element, Error>
+where
+    I: IntoIterator<Item = &'a Element>,
+{
+    unique(children).map_err(|count| match count {
+        0 => no_children(element, child_name),
+        count => Error::MultipleChildren {
+            element_name: element.name().to_owned(),
+            child_name: child_name.to_owned(),
+            count,
+        },
+    })
+}
+
+/// Parse the value of the attribute `attr_name` of `element`, with the
+/// [`ParseError`] of [`AttributeElementExt::try_attribute`].
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::util::parse_value;
+///
+/// let stop: Element = r#"<Stop coordinates="2.35,48.85" />"#.parse().unwrap();
+/// let coordinates = stop.attr("coordinates").unwrap();
+/// let longitude = coordinates.split(',').next().unwrap();
+/// assert_eq!(2.35, parse_value::<f64>(&stop, "coordinates", longitude).unwrap());
+/// assert!(parse_value::<u8>(&stop, "coordinates", longitude).is_err());
+/// ```
+///
+/// [`AttributeElementExt::try_attribute`]: ../trait.AttributeElementExt.html#tymethod.try_attribute
+/// [`ParseError`]: ../enum.Error.html#variant.ParseError
+pub fn parse_value<F>(element: &Element, attr_name: &str, value: &str) -> Result<F, Error>
+where
+    F: AttrValue,
+{
+    F::parse_attr(value).map_err(|e| Error::ParseError {
+        element_name: element.name().to_owned(),
+        attribute_name: attr_name.to_owned(),
+        value: value.to_owned(),
+        source: e.into(),
+    })
+}
+
+/// Parse a text of `element` (without leading and trailing whitespaces),
+/// with the [`TextParseError`] of [`TextElementExt::try_text`].
+///
+/// [`TextElementExt::try_text`]: ../trait.TextElementExt.html#tymethod.try_text
+/// [`TextParseError`]: ../enum.Error.html#variant.TextParseError
+pub fn parse_text<F>(element: &Element, text: &str) -> Result<F, Error>
+where
+    F: AttrValue,
+{
+    let value = text.trim();
+    F::parse_attr(value).map_err(|e| Error::TextParseError {
+        element_name: element.name().to_owned(),
+        value: value.to_owned(),
+        source: e.into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn errors_of_the_traits() {
+        let line: Element = r#"<Line><Name /><Name /></Line>"#.parse().unwrap();
+        let error = collect_unique(&line, "Nmae", std::iter::empty()).unwrap_err();
+        assert_eq!(ErrorKind::NoChildren, error.kind());
+        assert!(error.to_string().contains("did you mean 'Name'?"));
+        let error = collect_unique(&line, "Name", line.children()).unwrap_err();
+        assert_eq!(ErrorKind::MultipleChildren, error.kind());
+        let error = parse_text::<u32>(&line, " x ").unwrap_err();
+        assert_eq!(
+            "Failed to parse and convert the text 'x' of element 'Line'",
+            error.to_string()
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{AttributeElementExt, Error, TextElementExt};
+use minidom::Element;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// A UUID, normalized before being parsed: surrounding whitespaces, braces
+/// and the `urn:uuid:` prefix are removed, whatever the case.
+struct XsUuid(Uuid);
+
+impl FromStr for XsUuid {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim();
+        let value = match value.get(.."urn:uuid:".len()) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("urn:uuid:") => &value[prefix.len()..],
+            _ => value,
+        };
+        let value = value
+            .strip_prefix('{')
+            .and_then(|value| value.strip_suffix('}'))
+            .unwrap_or(value);
+        Uuid::parse_str(value).map(XsUuid)
+    }
+}
+
+/// Parse UUID identifiers into [`Uuid`].
+///
+/// [`Uuid`]: ../uuid/struct.Uuid.html
+pub trait UuidElementExt {
+    /// Try to get a UUID attribute (like
+    /// `{67E55044-10B1-426F-9247-BB680E5FE0C8}`) and return a [`Result`].
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_attribute_uuid(&self, attr_name: &str) -> Result<Uuid, Error>;
+
+    /// Get a UUID attribute if present and valid and return an [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn attribute_uuid(&self, attr_name: &str) -> Option<Uuid> {
+        self.try_attribute_uuid(attr_name).ok()
+    }
+
+    /// Try to parse the text of the element as a UUID and return a
+    /// [`Result`].
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    fn try_text_uuid(&self) -> Result<Uuid, Error>;
+
+    /// Parse the text of the element as a UUID if valid and return an
+    /// [`Option`].
+    ///
+    /// [`Option`]:  https://doc.rust-lang.org/std/option/enum.Option.html
+    fn text_uuid(&self) -> Option<Uuid> {
+        self.try_text_uuid().ok()
+    }
+}
+
+impl UuidElementExt for Element {
+    /// Implementation of [`UuidElementExt`] for [`Element`] accepts the
+    /// hyphenated and simple forms, in upper or lower case, optionally
+    /// surrounded by braces or prefixed by `urn:uuid:`. The reason of a
+    /// failure is the source of the returned [`ParseError`] or
+    /// [`TextParseError`].
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::UuidElementExt;
+    /// use std::error::Error;
+    ///
+    /// let xml: &'static str = r#"<Operator id="{67E55044-10B1-426F-9247-BB680E5FE0C8}" ref="67e55044-10b1" />"#;
+    /// let operator: Element = xml.parse().unwrap();
+    /// let id = operator.try_attribute_uuid("id").unwrap();
+    /// assert_eq!("67e55044-10b1-426f-9247-bb680e5fe0c8", id.to_string());
+    /// let error = operator.try_attribute_uuid("ref").unwrap_err();
+    /// assert_eq!(
+    ///     "Failed to parse and convert the value '67e55044-10b1' of attribute 'ref' in element 'Operator'",
+    ///     error.to_string()
+    /// );
+    /// assert!(error.source().is_some());
+    /// ```
+    ///
+    /// [`UuidElementExt`]: trait.UuidElementExt.html
+    /// [`Element`]: ../minidom/element/struct.Element.html
+    /// [`ParseError`]: enum.Error.html#variant.ParseError
+    /// [`TextParseError`]: enum.Error.html#variant.TextParseError
+    fn try_attribute_uuid(&self, attr_name: &str) -> Result<Uuid, Error> {
+        self.try_attribute::<XsUuid>(attr_name).map(|uuid| uuid.0)
+    }
+
+    fn try_text_uuid(&self) -> Result<Uuid, Error> {
+        self.try_text::<XsUuid>().map(|uuid| uuid.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn normalization() {
+        let expected = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+        for value in &[
+            "67E55044-10B1-426F-9247-BB680E5FE0C8",
+            " {67e55044-10b1-426f-9247-bb680e5fe0c8} ",
+            "URN:UUID:67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "urn:uuid:{67e55044-10b1-426f-9247-bb680e5fe0c8}",
+            "67e5504410b1426f9247bb680e5fe0c8",
+        ] {
+            assert_eq!(
+                expected,
+                value.parse::<XsUuid>().unwrap().0.to_string(),
+                "{}",
+                value
+            );
+        }
+        for value in &["", "{}", "{67e5504410b1426f9247bb680e5fe0c8", "urn:uuid:"] {
+            assert!(value.parse::<XsUuid>().is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn text_error() {
+        let element: Element = r#"<Id>not-a-uuid</Id>"#.parse().unwrap();
+        match element.try_text_uuid().unwrap_err() {
+            Error::TextParseError { value, source, .. } => {
+                assert_eq!("not-a-uuid", value);
+                assert_eq!("invalid character: found `n` at 0", source.to_string());
+            }
+            e => panic!("unexpected error {:?}", e),
+        }
+        assert_eq!(None, element.text_uuid());
+    }
+}
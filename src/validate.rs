@@ -0,0 +1,364 @@
+use crate::{validator::cap_findings, AttrValue, ElementCursor, Finding, Severity};
+use minidom::Element;
+use std::collections::BTreeMap;
+
+/// Check of the type of a value, returning the parsing error.
+pub(crate) type TypeCheck = fn(&str) -> Result<(), String>;
+
+/// Check that a value can be parsed into `T`.
+pub(crate) fn parses<T: AttrValue>(value: &str) -> Result<(), String> {
+    T::parse_attr(value).map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// A child declared by [`ElementRules::with_child`].
+///
+/// [`ElementRules::with_child`]: struct.ElementRules.html#method.with_child
+#[derive(Debug, Clone)]
+struct ChildRule {
+    name: String,
+    min: usize,
+    max: Option<usize>,
+}
+
+impl ChildRule {
+    fn expected(&self) -> String {
+        match (self.min, self.max) {
+            (min, Some(max)) if min == max => format!("exactly {}", min),
+            (0, Some(max)) => format!("at most {}", max),
+            (min, Some(max)) => format!("between {} and {}", min, max),
+            (min, None) => format!("at least {}", min),
+        }
+    }
+}
+
+/// The rules of an element, declared with a builder, see [`ValidationRules`].
+///
+/// [`ValidationRules`]: struct.ValidationRules.html
+#[derive(Debug, Clone, Default)]
+pub struct ElementRules {
+    required: Vec<String>,
+    children: Vec<ChildRule>,
+    closed: bool,
+    values: Vec<(String, Vec<String>)>,
+    types: Vec<(String, TypeCheck)>,
+    text_values: Option<Vec<String>>,
+    text_type: Option<TypeCheck>,
+}
+
+impl ElementRules {
+    /// Create rules accepting any element.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require an attribute.
+    pub fn with_required(mut self, attribute: &str) -> Self {
+        self.required.push(attribute.to_owned());
+        self
+    }
+
+    /// Declare a child and how many times it occurs, from `min` to `max`
+    /// (unbounded if `None`).
+    pub fn with_child(mut self, name: &str, min: usize, max: Option<usize>) -> Self {
+        self.children.push(ChildRule {
+            name: name.to_owned(),
+            min,
+            max,
+        });
+        self
+    }
+
+    /// Reject the children which are not declared with [`with_child`].
+    ///
+    /// [`with_child`]: #method.with_child
+    pub fn closed(mut self) -> Self {
+        self.closed = true;
+        self
+    }
+
+    /// Restrict the values of an attribute, when present, to an enumeration.
+    pub fn with_values(mut self, attribute: &str, values: &[&str]) -> Self {
+        self.values.push((
+            attribute.to_owned(),
+            values.iter().map(|value| (*value).to_owned()).collect(),
+        ));
+        self
+    }
+
+    /// Require the value of an attribute, when present, to be parsable into
+    /// `T`.
+    pub fn with_type<T: AttrValue>(self, attribute: &str) -> Self {
+        self.with_type_check(attribute, parses::<T>)
+    }
+
+    /// Restrict the text of the element (without leading and trailing
+    /// whitespaces) to an enumeration.
+    pub fn with_text_values(mut self, values: &[&str]) -> Self {
+        self.text_values = Some(values.iter().map(|value| (*value).to_owned()).collect());
+        self
+    }
+
+    /// Require the text of the element (without leading and trailing
+    /// whitespaces) to be parsable into `T`.
+    pub fn with_text_type<T: AttrValue>(self) -> Self {
+        self.with_text_check(parses::<T>)
+    }
+
+    pub(crate) fn with_type_check(mut self, attribute: &str, check: TypeCheck) -> Self {
+        self.types.push((attribute.to_owned(), check));
+        self
+    }
+
+    pub(crate) fn with_text_check(mut self, check: TypeCheck) -> Self {
+        self.text_type = Some(check);
+        self
+    }
+
+    pub(crate) fn check(&self, element: &Element, path: &str, findings: &mut Vec<Finding>) {
+        let mut violation = |rule: &str, attribute: Option<&str>, message: String| {
+            let finding = Finding::new(rule, Severity::Error, path, &message);
+            findings.push(match attribute {
+                Some(attribute) => finding.with_attribute(attribute),
+                None => finding,
+            });
+        };
+        for attribute in &self.required {
+            if element.attr(attribute).is_none() {
+                violation(
+                    "required-attribute",
+                    Some(attribute),
+                    format!("Missing required attribute '{}'", attribute),
+                );
+            }
+        }
+        for (attribute, allowed) in &self.values {
+            if let Some(value) = element.attr(attribute) {
+                if !allowed.iter().any(|allowed| allowed == value) {
+                    violation(
+                        "enumeration",
+                        Some(attribute),
+                        format!(
+                            "Value '{}' of attribute '{}' is not one of: {}",
+                            value,
+                            attribute,
+                            allowed.join(", ")
+                        ),
+                    );
+                }
+            }
+        }
+        for (attribute, parse) in &self.types {
+            if let Some(value) = element.attr(attribute) {
+                if let Err(e) = parse(value) {
+                    violation(
+                        "type",
+                        Some(attribute),
+                        format!(
+                            "Invalid value '{}' of attribute '{}': {}",
+                            value, attribute, e
+                        ),
+                    );
+                }
+            }
+        }
+        if self.text_values.is_some() || self.text_type.is_some() {
+            let text = element.text();
+            let text = text.trim();
+            if let Some(allowed) = &self.text_values {
+                if !allowed.iter().any(|allowed| allowed == text) {
+                    violation(
+                        "enumeration",
+                        None,
+                        format!("Text '{}' is not one of: {}", text, allowed.join(", ")),
+                    );
+                }
+            }
+            if let Some(Err(e)) = self.text_type.map(|parse| parse(text)) {
+                violation("type", None, format!("Invalid text '{}': {}", text, e));
+            }
+        }
+        for child in &self.children {
+            let count = element
+                .children()
+                .filter(|c| c.name() == child.name)
+                .count();
+            if count < child.min || child.max.is_some_and(|max| count > max) {
+                violation(
+                    "cardinality",
+                    None,
+                    format!(
+                        "Expected {} child '{}', found {}",
+                        child.expected(),
+                        child.name,
+                        count
+                    ),
+                );
+            }
+        }
+        if self.closed {
+            let mut unexpected: Vec<&str> = Vec::new();
+            for name in element.children().map(Element::name) {
+                if self.children.iter().all(|child| child.name != name)
+                    && !unexpected.contains(&name)
+                {
+                    unexpected.push(name);
+                }
+            }
+            for name in unexpected {
+                violation(
+                    "unexpected-child",
+                    None,
+                    format!("Unexpected child '{}'", name),
+                );
+            }
+        }
+    }
+}
+
+/// Declarative rules of a document, a lightweight alternative to a schema:
+/// required attributes, allowed children and their cardinalities, enumerated
+/// and typed attribute values, by element name.
+///
+/// [`validate`] checks the whole tree and returns all the violations as
+/// [`Finding`]s of [`Severity::Error`], located by their breadcrumb (see
+/// [`ElementCursor::breadcrumb`]). Elements without rules are accepted.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{ElementRules, ValidationRules};
+///
+/// let rules = ValidationRules::new().with_element(
+///     "Line",
+///     ElementRules::new()
+///         .with_required("id")
+///         .with_values("mode", &["bus", "tram"])
+///         .with_type::<u32>("order")
+///         .with_child("Name", 1, Some(1))
+///         .closed(),
+/// );
+/// let xml: &'static str = r#"<root>
+///         <Line id="1" mode="bus" order="1"><Name>A</Name></Line>
+///         <Line mode="metro" order="x"><Notes /></Line>
+///     </root>"#;
+/// let root: Element = xml.parse().unwrap();
+/// let violations: Vec<String> = rules
+///     .validate(&root)
+///     .iter()
+///     .map(ToString::to_string)
+///     .collect();
+/// assert_eq!(
+///     vec![
+///         "error[required-attribute] root > Line[2]: Missing required attribute 'id'",
+///         "error[enumeration] root > Line[2]: Value 'metro' of attribute 'mode' is not one of: bus, tram",
+///         "error[type] root > Line[2]: Invalid value 'x' of attribute 'order': invalid digit found in string",
+///         "error[cardinality] root > Line[2]: Expected exactly 1 child 'Name', found 0",
+///         "error[unexpected-child] root > Line[2]: Unexpected child 'Notes'",
+///     ],
+///     violations
+/// );
+/// ```
+///
+/// [`ElementCursor::breadcrumb`]: struct.ElementCursor.html#method.breadcrumb
+/// [`Finding`]: struct.Finding.html
+/// [`Severity::Error`]: enum.Severity.html#variant.Error
+/// [`validate`]: #method.validate
+#[derive(Debug, Clone, Default)]
+pub struct ValidationRules {
+    elements: BTreeMap<String, ElementRules>,
+    max_findings: Option<usize>,
+}
+
+impl ValidationRules {
+    /// Create rules accepting any document.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare the rules of the elements with the given (local) name.
+    pub fn with_element(mut self, name: &str, rules: ElementRules) -> Self {
+        self.elements.insert(name.to_owned(), rules);
+        self
+    }
+
+    /// Keep at most `max_findings` violations (at least one), the next ones
+    /// being replaced by a single finding counting them, like
+    /// [`Validator::with_max_findings`].
+    ///
+    /// [`Validator::with_max_findings`]: struct.Validator.html#method.with_max_findings
+    pub fn with_max_findings(mut self, max_findings: usize) -> Self {
+        self.max_findings = Some(max_findings.max(1));
+        self
+    }
+
+    /// Check a tree against the rules and return all the violations, in
+    /// document order.
+    pub fn validate(&self, root: &Element) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        self.validate_cursor(&ElementCursor::new(root), &mut findings);
+        cap_findings(&mut findings, self.max_findings);
+        findings
+    }
+
+    fn validate_cursor(&self, cursor: &ElementCursor<'_>, findings: &mut Vec<Finding>) {
+        if let Some(rules) = self.elements.get(cursor.element().name()) {
+            rules.check(cursor.element(), &cursor.breadcrumb(), findings);
+        }
+        for child in cursor.children() {
+            self.validate_cursor(&child, findings);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn cardinalities() {
+        let rules = ValidationRules::new().with_element(
+            "frame",
+            ElementRules::new()
+                .with_child("Line", 1, None)
+                .with_child("Note", 0, Some(1))
+                .with_child("Stop", 2, Some(3)),
+        );
+        let root: Element = r#"<frame><Note/><Note/><Stop/><x/></frame>"#.parse().unwrap();
+        let messages: Vec<String> = rules
+            .validate(&root)
+            .into_iter()
+            .map(|finding| finding.message)
+            .collect();
+        assert_eq!(
+            vec![
+                "Expected at least 1 child 'Line', found 0",
+                "Expected at most 1 child 'Note', found 2",
+                "Expected between 2 and 3 child 'Stop', found 1",
+            ],
+            messages
+        );
+        let valid: Element = r#"<frame><Line/><Stop/><Stop/></frame>"#.parse().unwrap();
+        assert!(rules.validate(&valid).is_empty());
+    }
+
+    #[test]
+    fn max_findings() {
+        let rules = ValidationRules::new()
+            .with_element("Line", ElementRules::new().with_required("id"))
+            .with_max_findings(2);
+        let root: Element = r#"<root><Line/><Line/><Line/><Line/></root>"#.parse().unwrap();
+        let findings: Vec<String> = rules
+            .validate(&root)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            vec![
+                "error[required-attribute] root > Line[1]: Missing required attribute 'id'",
+                "error[required-attribute] root > Line[2]: Missing required attribute 'id'",
+                "info[omitted] 2 more findings omitted",
+            ],
+            findings
+        );
+    }
+}
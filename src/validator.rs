@@ -0,0 +1,322 @@
+use crate::{Error, ErrorReport, Finding, Modification, Severity};
+use minidom::Element;
+
+/// Collect the errors of many extractions instead of failing at the first
+/// one, to report all the problems of a document at once.
+///
+/// Each extraction is passed to [`check`], which gives back the value on
+/// success and records the error otherwise, so the validation can go on with
+/// the other values. Errors can be located with [`ElemResultExt`] or
+/// [`ElementCursor::try_with`] beforehand. Issues which are not errors, or
+/// which can be fixed automatically, are [`report`]ed as [`Finding`]s.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{AttributeElementExt, ElemResultExt, Validator};
+///
+/// let xml: &'static str = r#"<root>
+///         <Line id="1" order="x" />
+///         <Line order="2" />
+///     </root>"#;
+/// let root: Element = xml.parse().unwrap();
+/// let mut validator = Validator::new();
+/// for line in root.children() {
+///     let id: Option<u32> = validator.check(line.try_attribute("id").ctx(line));
+///     let order: Option<u32> = validator.check(line.try_attribute("order").ctx(line));
+///     if let (Some(id), Some(order)) = (id, order) {
+///         println!("Line {} at {}", id, order);
+///     }
+/// }
+/// let errors = validator.finish(()).unwrap_err();
+/// assert_eq!(2, errors.len());
+/// assert_eq!(
+///     "In Line: Failed to find attribute 'id' in element 'Line' (available: order)",
+///     errors[1].to_string()
+/// );
+/// ```
+///
+/// [`check`]: #method.check
+/// [`ElemResultExt`]: trait.ElemResultExt.html
+/// [`ElementCursor::try_with`]: struct.ElementCursor.html#method.try_with
+/// [`Finding`]: struct.Finding.html
+/// [`report`]: #method.report
+#[derive(Debug, Default)]
+pub struct Validator {
+    errors: Vec<Error>,
+    findings: Vec<Finding>,
+    max_findings: Option<usize>,
+    omitted: usize,
+}
+
+impl Validator {
+    /// Create a validator without any error.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep at most `max_findings` errors and `max_findings` findings (at
+    /// least one of each), the next ones being only counted. Once some are
+    /// omitted, a [`summary`] finding is added after the findings, and an
+    /// [`Omitted`] error after the errors given back by [`into_errors`] and
+    /// [`finish`]. Bulk runs can check [`is_full`] to abort early on
+    /// pathological documents.
+    ///
+    /// ```
+    /// use minidom::Element;
+    /// use minidom_ext::{AttributeElementExt, Validator};
+    ///
+    /// let root: Element = r#"<root><Line /><Line /><Line /></root>"#.parse().unwrap();
+    /// let mut validator = Validator::new().with_max_findings(1);
+    /// for line in root.children() {
+    ///     validator.check(line.try_attribute::<u32>("id"));
+    /// }
+    /// assert!(validator.is_full());
+    /// assert_eq!(1, validator.errors().len());
+    /// assert_eq!(
+    ///     "info[omitted] 2 more findings omitted",
+    ///     validator.findings()[0].to_string()
+    /// );
+    /// let errors = validator.into_errors();
+    /// assert_eq!(2, errors.len());
+    /// assert_eq!("2 more findings omitted", errors[1].to_string());
+    /// ```
+    ///
+    /// [`finish`]: #method.finish
+    /// [`into_errors`]: #method.into_errors
+    /// [`is_full`]: #method.is_full
+    /// [`Omitted`]: enum.Error.html#variant.Omitted
+    /// [`summary`]: #method.summary
+    pub fn with_max_findings(mut self, max_findings: usize) -> Self {
+        self.max_findings = Some(max_findings.max(1));
+        self
+    }
+
+    /// Give back the value of a successful extraction, or record the error
+    /// and return [`None`].
+    ///
+    /// [`None`]:  https://doc.rust-lang.org/std/option/enum.Option.html#variant.None
+    pub fn check<T>(&mut self, result: Result<T, Error>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.push(error);
+                None
+            }
+        }
+    }
+
+    fn accepts(&self, count: usize) -> bool {
+        self.max_findings.is_none_or(|max| count < max)
+    }
+
+    /// Number of findings kept, without the summary.
+    fn kept_findings(&self) -> usize {
+        self.findings.len() - usize::from(self.omitted > 0)
+    }
+
+    /// Count an error or a finding which is not kept, and update the summary
+    /// following the findings.
+    fn omit(&mut self) {
+        if self.omitted > 0 {
+            self.findings.pop();
+        }
+        self.omitted += 1;
+        self.findings.push(omitted_finding(self.omitted));
+    }
+
+    /// Record an error.
+    pub fn push(&mut self, error: Error) {
+        if self.accepts(self.errors.len()) {
+            self.errors.push(error);
+        } else {
+            self.omit();
+        }
+    }
+
+    /// Record a finding, which doesn't make the validation fail.
+    pub fn report(&mut self, finding: Finding) {
+        let kept = self.kept_findings();
+        if self.accepts(kept) {
+            self.findings.insert(kept, finding);
+        } else {
+            self.omit();
+        }
+    }
+
+    /// Check if the maximum number of errors or findings is reached, see
+    /// [`with_max_findings`].
+    ///
+    /// [`with_max_findings`]: #method.with_max_findings
+    pub fn is_full(&self) -> bool {
+        !self.accepts(self.errors.len()) || !self.accepts(self.kept_findings())
+    }
+
+    /// Number of errors and findings counted but not kept, see
+    /// [`with_max_findings`].
+    ///
+    /// [`with_max_findings`]: #method.with_max_findings
+    pub fn omitted(&self) -> usize {
+        self.omitted
+    }
+
+    /// A finding summarizing the errors and findings which were not kept, if
+    /// any. It is also the last of the [`findings`].
+    ///
+    /// [`findings`]: #method.findings
+    pub fn summary(&self) -> Option<Finding> {
+        if self.omitted == 0 {
+            return None;
+        }
+        Some(omitted_finding(self.omitted))
+    }
+
+    /// The findings reported so far, in order, followed by the [`summary`] if
+    /// some errors or findings were not kept.
+    ///
+    /// [`summary`]: #method.summary
+    pub fn findings(&self) -> &[Finding] {
+        &self.findings
+    }
+
+    /// Apply the fixes of the findings on the tree, in order, and return the
+    /// modifications.
+    pub fn apply_fixes(&self, root: &mut Element) -> Vec<Modification> {
+        self.findings
+            .iter()
+            .flat_map(|finding| finding.apply_fix(root))
+            .collect()
+    }
+
+    /// The errors recorded so far, in order.
+    pub fn errors(&self) -> &[Error] {
+        &self.errors
+    }
+
+    /// Flat reports of the errors recorded so far, in order, to be emitted
+    /// as JSON with the `serde` feature.
+    pub fn reports(&self) -> Vec<ErrorReport> {
+        self.errors.iter().map(ErrorReport::from).collect()
+    }
+
+    /// Check if no error was recorded.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Give back the recorded errors, followed by an [`Omitted`] error if
+    /// some errors or findings were not kept.
+    ///
+    /// [`Omitted`]: enum.Error.html#variant.Omitted
+    pub fn into_errors(mut self) -> Vec<Error> {
+        if self.omitted > 0 {
+            self.errors.push(Error::Omitted {
+                count: self.omitted,
+            });
+        }
+        self.errors
+    }
+
+    /// Return `value` if no error was recorded, or all the recorded errors
+    /// (see [`into_errors`]).
+    ///
+    /// [`into_errors`]: #method.into_errors
+    pub fn finish<T>(self, value: T) -> Result<T, Vec<Error>> {
+        if self.errors.is_empty() {
+            Ok(value)
+        } else {
+            Err(self.into_errors())
+        }
+    }
+}
+
+/// The finding standing for the `count` errors and findings not kept.
+fn omitted_finding(count: usize) -> Finding {
+    let message = format!("{} more findings omitted", count);
+    Finding::new("omitted", Severity::Info, "", &message)
+}
+
+/// Keep at most `max_findings` findings, replacing the next ones by a summary
+/// finding, like [`Validator::with_max_findings`].
+///
+/// [`Validator::with_max_findings`]: struct.Validator.html#method.with_max_findings
+pub(crate) fn cap_findings(findings: &mut Vec<Finding>, max_findings: Option<usize>) {
+    if let Some(max) = max_findings {
+        if findings.len() > max {
+            let omitted = findings.len() - max;
+            findings.truncate(max);
+            findings.push(omitted_finding(omitted));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AttributeElementExt, Edit, TextElementExt};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn accumulate() {
+        let root: Element = r#"<root a="1" b="x">y</root>"#.parse().unwrap();
+        let mut validator = Validator::new();
+        assert_eq!(Some(1), validator.check(root.try_attribute::<u32>("a")));
+        assert!(validator.is_valid());
+        assert_eq!(None, validator.check(root.try_attribute::<u32>("b")));
+        assert_eq!(None, validator.check(root.try_text::<u32>()));
+        validator.push(Error::NilElement {
+            element_name: "root".to_owned(),
+        });
+        let errors = validator.into_errors();
+        assert_eq!(3, errors.len());
+        assert!(matches!(errors[0], Error::ParseError { .. }));
+        assert!(matches!(errors[1], Error::TextParseError { .. }));
+        assert_eq!(Ok(42), Validator::new().finish(42).map_err(|e| e.len()));
+    }
+
+    #[test]
+    fn fix_findings() {
+        let mut root: Element = r#"<root><Line /><Line version="2" /></root>"#.parse().unwrap();
+        let mut validator = Validator::new();
+        for (index, line) in root.children().enumerate() {
+            if line.attr("version").is_none() {
+                let selector = format!("Line[{}]", index + 1).parse().unwrap();
+                validator.report(
+                    Finding::new("missing-version", Severity::Warning, "root > Line", "")
+                        .with_fix(Edit::set_attribute(selector, "version", "1")),
+                );
+            }
+        }
+        validator.report(Finding::new("info", Severity::Info, "root", ""));
+        assert!(validator.is_valid());
+        let modifications = validator.apply_fixes(&mut root);
+        assert_eq!(1, modifications.len());
+        assert_eq!(
+            "/root/Line[1]/@version: added '1'",
+            modifications[0].to_string()
+        );
+    }
+
+    #[test]
+    fn omitted_findings_are_summarized() {
+        let mut validator = Validator::new().with_max_findings(2);
+        for _ in 0..3 {
+            validator.push(Error::NilElement {
+                element_name: "root".to_owned(),
+            });
+        }
+        validator.report(Finding::new("first", Severity::Warning, "root", ""));
+        validator.report(Finding::new("second", Severity::Warning, "root", ""));
+        validator.report(Finding::new("third", Severity::Warning, "root", ""));
+        let rules: Vec<&str> = validator
+            .findings()
+            .iter()
+            .map(|finding| finding.rule.as_str())
+            .collect();
+        assert_eq!(vec!["first", "second", "omitted"], rules);
+        assert_eq!(2, validator.omitted());
+        let errors = validator.finish(()).unwrap_err();
+        assert_eq!(3, errors.len());
+        assert_eq!("2 more findings omitted", errors[2].to_string());
+        assert_eq!("MDX0026", errors[2].code());
+    }
+}
@@ -0,0 +1,346 @@
+use std::{fmt, str::FromStr};
+
+/// Parse a value from the lexical representation of an XML attribute.
+///
+/// Every type implementing [`FromStr`] is an [`AttrValue`], and types
+/// defined in this crate can implement an XML-specific representation, like
+/// [`List`] for space-separated lists.
+///
+/// [`AttrValue`]: trait.AttrValue.html
+/// [`FromStr`]: https://doc.rust-lang.org/std/str/trait.FromStr.html
+/// [`List`]: struct.List.html
+pub trait AttrValue: Sized {
+    /// The error returned when the value cannot be parsed.
+    type Err: std::error::Error + Send + Sync + 'static;
+
+    /// Parse the value of an attribute.
+    fn parse_attr(value: &str) -> Result<Self, Self::Err>;
+}
+
+impl<F> AttrValue for F
+where
+    F: FromStr,
+    F::Err: std::error::Error + Send + Sync + 'static,
+{
+    type Err = F::Err;
+
+    fn parse_attr(value: &str) -> Result<Self, Self::Err> {
+        value.parse()
+    }
+}
+
+/// A whitespace-separated list of values, like `xs:list` or `xs:IDREFS`.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{AttributeElementExt, List};
+///
+/// let xml: &'static str = r#"<Line stops=" 1 2  3" />"#;
+/// let line: Element = xml.parse().unwrap();
+/// let List(stops) = line.try_attribute::<List<u32>>("stops").unwrap();
+/// assert_eq!(vec![1, 2, 3], stops);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct List<T>(pub Vec<T>);
+
+/// Error returned when a token of a whitespace-separated list cannot be
+/// parsed.
+#[derive(Debug, thiserror::Error)]
+#[error("Failed to parse token '{token}' at index {index} of the list")]
+pub struct ListError<E>
+where
+    E: std::error::Error + 'static,
+{
+    /// Index of the failing token, starting at 0
+    pub index: usize,
+    /// The failing token
+    pub token: String,
+    /// Error returned by the parser of the token
+    #[source]
+    pub source: E,
+}
+
+/// Split a value on XML whitespaces and parse each token.
+pub(crate) fn parse_list<T, E, P>(value: &str, parse: P) -> Result<Vec<T>, ListError<E>>
+where
+    E: std::error::Error + 'static,
+    P: Fn(&str) -> Result<T, E>,
+{
+    value
+        .split_ascii_whitespace()
+        .enumerate()
+        .map(|(index, token)| {
+            parse(token).map_err(|source| ListError {
+                index,
+                token: token.to_owned(),
+                source,
+            })
+        })
+        .collect()
+}
+
+impl<T> AttrValue for List<T>
+where
+    T: AttrValue,
+{
+    type Err = ListError<T::Err>;
+
+    fn parse_attr(value: &str) -> Result<Self, Self::Err> {
+        parse_list(value, T::parse_attr).map(List)
+    }
+}
+
+impl<T> fmt::Display for List<T>
+where
+    T: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, value) in self.0.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", value)?;
+        }
+        Ok(())
+    }
+}
+
+/// A value restricted to a fixed list of lexical representations (like an
+/// `xs:enumeration`), to be read with
+/// [`AttributeElementExt::try_attribute_variant`].
+///
+/// ```
+/// use minidom_ext::XsEnumeration;
+/// use std::str::FromStr;
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Mode {
+///     Bus,
+///     Tram,
+/// }
+///
+/// impl FromStr for Mode {
+///     type Err = std::fmt::Error;
+///
+///     fn from_str(s: &str) -> Result<Self, Self::Err> {
+///         match s {
+///             "bus" => Ok(Mode::Bus),
+///             "tram" => Ok(Mode::Tram),
+///             _ => Err(std::fmt::Error),
+///         }
+///     }
+/// }
+///
+/// impl XsEnumeration for Mode {
+///     const VALUES: &'static [&'static str] = &["bus", "tram"];
+/// }
+/// ```
+///
+/// [`AttributeElementExt::try_attribute_variant`]: trait.AttributeElementExt.html#method.try_attribute_variant
+pub trait XsEnumeration: AttrValue {
+    /// The lexical representations of all the values.
+    const VALUES: &'static [&'static str];
+}
+
+/// Error returned when a value is not a valid `xs:boolean`.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("'{0}' is not a valid boolean, expected 'true', 'false', '1' or '0'")]
+pub struct XsBooleanError(String);
+
+/// A boolean with the lexical representation of `xs:boolean`, which accepts
+/// `true`, `false`, `1` and `0`, unlike `bool::from_str`.
+///
+/// Only these exact values are accepted, the same as the [`VALUES`] checked
+/// by `try_attribute_variant`: surrounding whitespaces can be trimmed with
+/// [`ParseOptions`] beforehand.
+///
+/// [`ParseOptions`]: struct.ParseOptions.html
+/// [`VALUES`]: trait.XsEnumeration.html#associatedconstant.VALUES
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct XsBoolean(pub bool);
+
+/// The lexical representations of `xs:boolean` and their values, parsed by
+/// `XsBoolean::from_str` and listed by `XsBoolean::VALUES`.
+const XS_BOOLEAN_LEXICAL: [(&str, bool); 4] =
+    [("true", true), ("false", false), ("1", true), ("0", false)];
+
+impl FromStr for XsBoolean {
+    type Err = XsBooleanError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        XS_BOOLEAN_LEXICAL
+            .iter()
+            .find(|(lexical, _)| *lexical == s)
+            .map(|(_, value)| XsBoolean(*value))
+            .ok_or_else(|| XsBooleanError(s.to_owned()))
+    }
+}
+
+impl XsEnumeration for XsBoolean {
+    const VALUES: &'static [&'static str] = &[
+        XS_BOOLEAN_LEXICAL[0].0,
+        XS_BOOLEAN_LEXICAL[1].0,
+        XS_BOOLEAN_LEXICAL[2].0,
+        XS_BOOLEAN_LEXICAL[3].0,
+    ];
+}
+
+impl From<XsBoolean> for bool {
+    fn from(value: XsBoolean) -> bool {
+        value.0
+    }
+}
+
+impl fmt::Display for XsBoolean {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A value displayed with its lexical representation in XML Schema, to be
+/// written with [`MutateElementExt::set_attr_value`].
+///
+/// Floats are written like `xs:float` and `xs:double`, the infinities and
+/// the not-a-number value being `INF`, `-INF` and `NaN` (instead of `inf`,
+/// `-inf` and `NaN` for [`Display`]). With the feature `chrono`, dates and
+/// date-times are written like `xs:date` and `xs:dateTime`.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::{MutateElementExt, XsFormat};
+///
+/// let mut stop = Element::bare("Stop");
+/// stop.set_attr_value("distance", XsFormat(f64::INFINITY));
+/// stop.set_attr_value("ratio", XsFormat(0.5f32));
+/// assert_eq!(Some("INF"), stop.attr("distance"));
+/// assert_eq!(Some("0.5"), stop.attr("ratio"));
+/// ```
+///
+/// [`Display`]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+/// [`MutateElementExt::set_attr_value`]: trait.MutateElementExt.html#tymethod.set_attr_value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct XsFormat<T>(pub T);
+
+macro_rules! xs_float_format {
+    ($float:ty) => {
+        impl fmt::Display for XsFormat<$float> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self.0 {
+                    value if value.is_nan() => f.write_str("NaN"),
+                    value if value == <$float>::INFINITY => f.write_str("INF"),
+                    value if value == <$float>::NEG_INFINITY => f.write_str("-INF"),
+                    value => write!(f, "{}", value),
+                }
+            }
+        }
+    };
+}
+
+xs_float_format!(f32);
+xs_float_format!(f64);
+
+/// Error returned when a value is not a valid `xs:hexBinary`.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum HexError {
+    /// A character is not an hexadecimal digit.
+    #[error("Invalid hexadecimal character '{character}' at position {position}")]
+    InvalidCharacter {
+        /// The invalid character
+        character: char,
+        /// Position of the character (in characters), starting at 0
+        position: usize,
+    },
+    /// The number of digits is odd.
+    #[error("Odd number of hexadecimal digits ({0})")]
+    OddLength(usize),
+}
+
+/// Binary content encoded with `xs:hexBinary` (two hexadecimal digits per
+/// byte, in upper or lower case).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct HexBinary(pub Vec<u8>);
+
+impl FromStr for HexBinary {
+    type Err = HexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits =
+            s.trim()
+                .chars()
+                .enumerate()
+                .map(|(position, character)| {
+                    character.to_digit(16).map(|digit| digit as u8).ok_or(
+                        HexError::InvalidCharacter {
+                            character,
+                            position,
+                        },
+                    )
+                })
+                .collect::<Result<Vec<u8>, _>>()?;
+        if digits.len() % 2 != 0 {
+            return Err(HexError::OddLength(digits.len()));
+        }
+        Ok(HexBinary(
+            digits
+                .chunks(2)
+                .map(|pair| pair[0] << 4 | pair[1])
+                .collect(),
+        ))
+    }
+}
+
+impl fmt::Display for HexBinary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{:02X}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn list() {
+        assert_eq!(List(Vec::<u8>::new()), List::parse_attr("  ").unwrap());
+        let list = List::<String>::parse_attr("a\tb\nc").unwrap();
+        assert_eq!("a b c", list.to_string());
+        let error = List::<u8>::parse_attr("1 x").unwrap_err();
+        assert_eq!(1, error.index);
+        assert_eq!(
+            "Failed to parse token 'x' at index 1 of the list",
+            format!("{}", error)
+        );
+    }
+
+    #[test]
+    fn hex_binary() {
+        let binary: HexBinary = " 0fA1 ".parse().unwrap();
+        assert_eq!(vec![0x0f, 0xa1], binary.0);
+        assert_eq!("0FA1", binary.to_string());
+        assert_eq!(Err(HexError::OddLength(3)), "0fA".parse::<HexBinary>());
+        let error = "0g".parse::<HexBinary>().unwrap_err();
+        assert_eq!(
+            "Invalid hexadecimal character 'g' at position 1",
+            format!("{}", error)
+        );
+    }
+
+    #[test]
+    fn xs_boolean() {
+        assert_eq!(XsBoolean(true), "1".parse().unwrap());
+        assert!(" 1 ".parse::<XsBoolean>().is_err());
+        for value in XsBoolean::VALUES {
+            assert!(value.parse::<XsBoolean>().is_ok());
+        }
+        assert_eq!(XsBoolean(false), "false".parse().unwrap());
+        let error = "True".parse::<XsBoolean>().unwrap_err();
+        assert_eq!(
+            "'True' is not a valid boolean, expected 'true', 'false', '1' or '0'",
+            format!("{}", error)
+        );
+    }
+}
@@ -0,0 +1,143 @@
+//! Support of the [`xml_view!`] macro.
+//!
+//! [`xml_view!`]: ../macro.xml_view.html
+
+/// Generate a view: a struct borrowing an [`Element`] with a typed accessor
+/// per field, each value being parsed the first time it is read and
+/// memoized.
+///
+/// Each field is declared with its type and its source:
+/// - `@"name"`: the attribute `name`, see [`AttributeElementExt::try_attribute`]
+/// - `"Child"/text`: the text of the unique child `Child`, see
+///   [`OnlyChildElementExt::try_only_child`] and [`TextElementExt::try_text`]
+/// - `text`: the text of the element
+///
+/// An accessor returns a reference to the value, or the error of the
+/// extraction (which is not memoized, so a failing field is read again on
+/// the next call). The view is created with `new` and gives back its element
+/// with `element`, so a field cannot be named `element`. Unused accessors are
+/// not reported as dead code.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::xml_view;
+///
+/// xml_view! {
+///     /// A stop place, read lazily
+///     #[derive(Debug)]
+///     pub StopPlaceView {
+///         id: u64 = @"id",
+///         name: String = "Name"/text,
+///         /// Missing in most stop places
+///         short_name: String = "ShortName"/text,
+///     }
+/// }
+///
+/// let xml: &'static str = r#"<StopPlace id="42"><Name>Gare de Lyon</Name></StopPlace>"#;
+/// let stop_place: Element = xml.parse().unwrap();
+/// let view = StopPlaceView::new(&stop_place);
+/// assert_eq!(42, *view.id().unwrap());
+/// assert_eq!("Gare de Lyon", view.name().unwrap());
+/// assert!(view.short_name().is_err());
+/// assert_eq!("StopPlace", view.element().name());
+/// ```
+///
+/// [`AttributeElementExt::try_attribute`]: trait.AttributeElementExt.html#tymethod.try_attribute
+/// [`Element`]: ../minidom/element/struct.Element.html
+/// [`OnlyChildElementExt::try_only_child`]: trait.OnlyChildElementExt.html#tymethod.try_only_child
+/// [`TextElementExt::try_text`]: trait.TextElementExt.html#tymethod.try_text
+#[macro_export]
+macro_rules! xml_view {
+    ($(#[$meta:meta])* $vis:vis $name:ident { $($fields:tt)* }) => {
+        $crate::xml_view!(@parse [$(#[$meta])* $vis $name] [] $($fields)*);
+    };
+    (@parse $header:tt [$($parsed:tt)*]
+        $(#[$field_meta:meta])* $field:ident : $ty:ty = @ $attribute:literal $(, $($rest:tt)*)?) => {
+        $crate::xml_view!(@parse $header
+            [$($parsed)* [$(#[$field_meta])* $field $ty [attribute $attribute]]]
+            $($($rest)*)?);
+    };
+    (@parse $header:tt [$($parsed:tt)*]
+        $(#[$field_meta:meta])* $field:ident : $ty:ty = $child:literal / text $(, $($rest:tt)*)?) => {
+        $crate::xml_view!(@parse $header
+            [$($parsed)* [$(#[$field_meta])* $field $ty [child_text $child]]]
+            $($($rest)*)?);
+    };
+    (@parse $header:tt [$($parsed:tt)*]
+        $(#[$field_meta:meta])* $field:ident : $ty:ty = text $(, $($rest:tt)*)?) => {
+        $crate::xml_view!(@parse $header
+            [$($parsed)* [$(#[$field_meta])* $field $ty [text]]]
+            $($($rest)*)?);
+    };
+    (@parse [$(#[$meta:meta])* $vis:vis $name:ident]
+        [$([$(#[$field_meta:meta])* $field:ident $ty:ty [$($source:tt)*]])*]) => {
+        $(#[$meta])*
+        $vis struct $name<'a> {
+            element: &'a $crate::__view::Element,
+            $($field: ::std::cell::OnceCell<$ty>,)*
+        }
+
+        #[allow(dead_code)]
+        impl<'a> $name<'a> {
+            /// Create a view of the element, nothing being read yet.
+            $vis fn new(element: &'a $crate::__view::Element) -> Self {
+                $name {
+                    element,
+                    $($field: ::std::cell::OnceCell::new(),)*
+                }
+            }
+
+            /// The element of the view.
+            $vis fn element(&self) -> &'a $crate::__view::Element {
+                self.element
+            }
+
+            $(
+                $(#[$field_meta])*
+                $vis fn $field(&self) -> ::std::result::Result<&$ty, $crate::Error> {
+                    if let ::std::option::Option::Some(value) = self.$field.get() {
+                        return ::std::result::Result::Ok(value);
+                    }
+                    let value: $ty = $crate::xml_view!(@read self.element, $($source)*)?;
+                    ::std::result::Result::Ok(self.$field.get_or_init(|| value))
+                }
+            )*
+        }
+    };
+    (@read $element:expr, attribute $attribute:literal) => {
+        $crate::AttributeElementExt::try_attribute($element, $attribute)
+    };
+    (@read $element:expr, child_text $child:literal) => {
+        $crate::OnlyChildElementExt::try_only_child($element, $child)
+            .and_then($crate::TextElementExt::try_text)
+    };
+    (@read $element:expr, text) => {
+        $crate::TextElementExt::try_text($element)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ErrorKind;
+    use minidom::Element;
+    use pretty_assertions::assert_eq;
+
+    crate::xml_view! {
+        LineView {
+            id: String = @"id",
+            order: u32 = "Order"/text,
+            label: String = text,
+        }
+    }
+
+    #[test]
+    fn memoized_accessors() {
+        let line: Element = r#"<Line id="line:1">M1<Order>x</Order></Line>"#.parse().unwrap();
+        let view = LineView::new(&line);
+        let id = view.id().unwrap();
+        assert!(std::ptr::eq(id, view.id().unwrap()));
+        assert_eq!("M1", view.label().unwrap());
+        assert_eq!(ErrorKind::TextParseError, view.order().unwrap_err().kind());
+        assert!(view.order.get().is_none());
+    }
+}
@@ -0,0 +1,89 @@
+//! Support of the [`xml!`] macro.
+//!
+//! [`xml!`]: ../macro.xml.html
+
+/// Build an [`Element`] tree declaratively.
+///
+/// The root element is declared with its name, optionally preceded by its
+/// namespace, and followed by its content between braces, each item being
+/// separated by a `;`:
+/// - `"name" => value`: the attribute `name`
+/// - `"Child" => { ... }`: a child element, with its own content
+/// - `text: value`: a text node
+///
+/// Attribute values and texts can be anything implementing [`Display`].
+/// Children are in the namespace of their parent. A block used as an
+/// attribute value must be wrapped in parentheses, to not be read as the
+/// content of a child.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::xml;
+///
+/// const NS: &str = "http://www.netex.org.uk/netex";
+/// let (id, name) = (1, "Metro 1");
+/// let line: Element = xml!(NS, "Line" {
+///     "id" => id;
+///     "version" => "any";
+///     "Name" => { text: name };
+///     "Presentation" => {
+///         "Colour" => { text: "FFCE00" };
+///     };
+/// });
+/// assert_eq!(
+///     r#"<Line xmlns="http://www.netex.org.uk/netex" id="1" version="any"><Name>Metro 1</Name><Presentation><Colour>FFCE00</Colour></Presentation></Line>"#,
+///     String::from(&line)
+/// );
+/// assert_eq!(Some(NS), line.children().next().unwrap().ns().as_deref());
+/// ```
+///
+/// [`Display`]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+/// [`Element`]: ../minidom/element/struct.Element.html
+#[macro_export]
+macro_rules! xml {
+    ($ns:expr, $name:literal { $($content:tt)* }) => {
+        $crate::xml!(@content $crate::__xml::Element::builder($name).ns($ns), $($content)*)
+    };
+    ($name:literal { $($content:tt)* }) => {
+        $crate::xml!(@content $crate::__xml::Element::builder($name), $($content)*)
+    };
+    (@content $builder:expr, ) => {
+        $builder.build()
+    };
+    (@content $builder:expr, text : $text:expr $(; $($rest:tt)*)?) => {
+        $crate::xml!(@content
+            $builder.append(::std::string::ToString::to_string(&$text)),
+            $($($rest)*)?)
+    };
+    (@content $builder:expr, $child:literal => { $($content:tt)* } $(; $($rest:tt)*)?) => {
+        $crate::xml!(@content
+            $builder.append($crate::xml!($child { $($content)* })),
+            $($($rest)*)?)
+    };
+    (@content $builder:expr, $attribute:literal => $value:expr $(; $($rest:tt)*)?) => {
+        $crate::xml!(@content
+            $builder.attr($attribute, ::std::string::ToString::to_string(&$value)),
+            $($($rest)*)?)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use minidom::Element;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn content() {
+        let name = String::from("A & B");
+        let element: Element = crate::xml!("Line" {
+            "Notes" => {};
+            text: name;
+            "count" => ({ 1 + 1 })
+        });
+        assert_eq!(
+            r#"<Line count="2"><Notes/>A &amp; B</Line>"#,
+            String::from(&element)
+        );
+        assert_eq!("A & B", name);
+    }
+}
@@ -0,0 +1,695 @@
+use crate::{
+    namespace::split_qname,
+    validate::{parses, TypeCheck},
+    validator::cap_findings,
+    AttributeElementExt, Document, ElementCursor, ElementRules, Error, Finding, Severity,
+    XsBoolean, XsDuration,
+};
+use minidom::Element;
+use std::{cmp::Ordering, collections::HashMap, path::Path};
+
+/// Namespace of XML Schema definitions.
+pub const XS_NS: &str = "http://www.w3.org/2001/XMLSchema";
+
+/// Base types and derived types are resolved up to this depth, to stop on
+/// circular definitions.
+const MAX_DEPTH: usize = 32;
+
+/// Sign of an arbitrary-precision `xs:integer`.
+fn integer_sign(value: &str) -> Result<Ordering, String> {
+    let value = value.trim();
+    let (negative, digits) = match value.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err("invalid integer".to_owned());
+    }
+    Ok(if digits.bytes().all(|b| b == b'0') {
+        Ordering::Equal
+    } else if negative {
+        Ordering::Less
+    } else {
+        Ordering::Greater
+    })
+}
+
+fn integer_with_sign(value: &str, signs: &[Ordering], expected: &str) -> Result<(), String> {
+    if signs.contains(&integer_sign(value)?) {
+        Ok(())
+    } else {
+        Err(format!("expected a {} integer", expected))
+    }
+}
+
+fn integer(value: &str) -> Result<(), String> {
+    integer_sign(value).map(|_| ())
+}
+
+fn positive_integer(value: &str) -> Result<(), String> {
+    integer_with_sign(value, &[Ordering::Greater], "positive")
+}
+
+fn non_negative_integer(value: &str) -> Result<(), String> {
+    integer_with_sign(value, &[Ordering::Greater, Ordering::Equal], "non-negative")
+}
+
+fn negative_integer(value: &str) -> Result<(), String> {
+    integer_with_sign(value, &[Ordering::Less], "negative")
+}
+
+fn non_positive_integer(value: &str) -> Result<(), String> {
+    integer_with_sign(value, &[Ordering::Less, Ordering::Equal], "non-positive")
+}
+
+/// Check the lexical form of an `xs:decimal`: `[+-]?(\d+(\.\d*)?|\.\d+)`,
+/// without exponent nor special values, and of any precision.
+fn decimal(value: &str) -> Result<(), String> {
+    let value = value.trim();
+    let unsigned = value
+        .strip_prefix(|c| c == '+' || c == '-')
+        .unwrap_or(value);
+    let (integer_part, fraction) = match unsigned.find('.') {
+        Some(index) => (&unsigned[..index], &unsigned[index + 1..]),
+        None => (unsigned, ""),
+    };
+    let digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+    if (integer_part.is_empty() && fraction.is_empty())
+        || !digits(integer_part)
+        || !digits(fraction)
+    {
+        return Err("invalid decimal".to_owned());
+    }
+    Ok(())
+}
+
+/// The check of a built-in type, `None` for the types which are not checked
+/// (strings, URIs, dates, ...).
+fn builtin(name: &str) -> Option<TypeCheck> {
+    let check: TypeCheck = match name {
+        "boolean" => parses::<XsBoolean>,
+        "byte" => parses::<i8>,
+        "short" => parses::<i16>,
+        "int" => parses::<i32>,
+        "long" => parses::<i64>,
+        "integer" => integer,
+        "positiveInteger" => positive_integer,
+        "nonNegativeInteger" => non_negative_integer,
+        "negativeInteger" => negative_integer,
+        "nonPositiveInteger" => non_positive_integer,
+        "unsignedByte" => parses::<u8>,
+        "unsignedShort" => parses::<u16>,
+        "unsignedInt" => parses::<u32>,
+        "unsignedLong" => parses::<u64>,
+        "float" => parses::<f32>,
+        "decimal" => decimal,
+        "double" => parses::<f64>,
+        "duration" => parses::<XsDuration>,
+        _ => return None,
+    };
+    Some(check)
+}
+
+fn local_name(qname: &str) -> &str {
+    split_qname(qname).1
+}
+
+fn xs_children(element: &Element) -> impl Iterator<Item = &Element> {
+    element.children().filter(|child| child.has_ns(XS_NS))
+}
+
+/// Occurrences of a particle (`minOccurs` and `maxOccurs`).
+fn occurrences(particle: &Element) -> (usize, Option<usize>) {
+    let min = particle.attribute("minOccurs").unwrap_or(1);
+    let max = match particle.attr("maxOccurs") {
+        Some("unbounded") => None,
+        Some(max) => max.parse().ok(),
+        None => Some(1),
+    };
+    (min, max)
+}
+
+/// A simple type: the check of its base type and its enumeration, if any.
+#[derive(Default)]
+struct SimpleType {
+    check: Option<TypeCheck>,
+    values: Option<Vec<String>>,
+}
+
+/// Children declared by a model group, by name, with their occurrences and
+/// their declaration (if compiled).
+#[derive(Default)]
+struct Content {
+    children: Vec<(String, usize, Option<usize>)>,
+    declarations: HashMap<String, usize>,
+    open: bool,
+}
+
+impl Content {
+    fn add(&mut self, name: &str, min: usize, max: Option<usize>, declaration: Option<usize>) {
+        match self.children.iter_mut().find(|(child, _, _)| child == name) {
+            Some((_, child_min, child_max)) => {
+                *child_min += min;
+                *child_max = child_max.zip(max).map(|(a, b)| a + b);
+            }
+            None => self.children.push((name.to_owned(), min, max)),
+        }
+        if let Some(declaration) = declaration {
+            self.declarations
+                .entry(name.to_owned())
+                .or_insert(declaration);
+        }
+    }
+}
+
+/// The rules of an element declaration, with the declarations of its
+/// children.
+#[derive(Debug, Clone, Default)]
+struct Declaration {
+    rules: ElementRules,
+    /// Index of the declarations of the children, by local name
+    children: HashMap<String, usize>,
+}
+
+struct Compiler<'a> {
+    elements: HashMap<&'a str, &'a Element>,
+    complex_types: HashMap<&'a str, &'a Element>,
+    simple_types: HashMap<&'a str, &'a Element>,
+    groups: HashMap<&'a str, &'a Element>,
+    /// Index of the compiled declarations, by address of their `xs:element`
+    compiled: HashMap<*const Element, usize>,
+    declarations: Vec<Declaration>,
+}
+
+impl<'a> Compiler<'a> {
+    fn new(schema: &'a Element) -> Self {
+        let mut compiler = Compiler {
+            elements: HashMap::new(),
+            complex_types: HashMap::new(),
+            simple_types: HashMap::new(),
+            groups: HashMap::new(),
+            compiled: HashMap::new(),
+            declarations: Vec::new(),
+        };
+        for definition in xs_children(schema) {
+            let name = match definition.attr("name") {
+                Some(name) => name,
+                None => continue,
+            };
+            let definitions = match definition.name() {
+                "element" => &mut compiler.elements,
+                "complexType" => &mut compiler.complex_types,
+                "simpleType" => &mut compiler.simple_types,
+                "group" => &mut compiler.groups,
+                _ => continue,
+            };
+            definitions.insert(name, definition);
+        }
+        compiler
+    }
+
+    /// Compile the rules of a declared element, returning its name and the
+    /// index of its declaration (`None` for a reference to an unknown
+    /// element).
+    fn element(&mut self, declaration: &'a Element) -> Result<(String, Option<usize>), Error> {
+        let declaration = match declaration.attr("ref") {
+            Some(reference) => match self.elements.get(local_name(reference)) {
+                Some(global) => *global,
+                None => return Ok((local_name(reference).to_owned(), None)),
+            },
+            None => declaration,
+        };
+        let name: String = declaration.try_attribute("name")?;
+        if let Some(index) = self.compiled.get(&(declaration as *const Element)) {
+            return Ok((name, Some(*index)));
+        }
+        // Registered before compiling, for the recursive declarations
+        let index = self.declarations.len();
+        self.declarations.push(Declaration::default());
+        self.compiled.insert(declaration as *const Element, index);
+        let mut rules = ElementRules::new();
+        let mut children = HashMap::new();
+        if let Some(type_name) = declaration.attr("type").map(local_name) {
+            match self.complex_types.get(type_name) {
+                Some(complex_type) => {
+                    rules = self.complex_type(complex_type, rules, &mut children, 0)?
+                }
+                None => rules = with_text_type(rules, self.named_simple_type(type_name, 0)),
+            }
+        }
+        for definition in xs_children(declaration) {
+            match definition.name() {
+                "complexType" => rules = self.complex_type(definition, rules, &mut children, 0)?,
+                "simpleType" => {
+                    rules = with_text_type(rules, self.simple_type(definition, 0));
+                }
+                _ => {}
+            }
+        }
+        self.declarations[index] = Declaration { rules, children };
+        Ok((name, Some(index)))
+    }
+
+    fn complex_type(
+        &mut self,
+        definition: &'a Element,
+        mut rules: ElementRules,
+        children: &mut HashMap<String, usize>,
+        depth: usize,
+    ) -> Result<ElementRules, Error> {
+        if depth > MAX_DEPTH {
+            return Ok(rules);
+        }
+        for item in xs_children(definition) {
+            match item.name() {
+                "sequence" | "choice" | "all" | "group" => {
+                    let mut content = Content::default();
+                    self.model_group(item, 1, Some(1), &mut content, depth)?;
+                    for (name, min, max) in &content.children {
+                        rules = rules.with_child(name, *min, *max);
+                    }
+                    for (name, declaration) in content.declarations {
+                        children.entry(name).or_insert(declaration);
+                    }
+                    if !content.open {
+                        rules = rules.closed();
+                    }
+                }
+                "attribute" => rules = self.attribute(item, rules)?,
+                "complexContent" | "simpleContent" => {
+                    for derivation in xs_children(item) {
+                        let base = derivation.attr("base").map(local_name);
+                        if derivation.name() == "extension" {
+                            if let Some(base) = base {
+                                match self.complex_types.get(base) {
+                                    Some(base) => {
+                                        rules =
+                                            self.complex_type(base, rules, children, depth + 1)?
+                                    }
+                                    None => {
+                                        let base = self.named_simple_type(base, depth + 1);
+                                        rules = with_text_type(rules, base);
+                                    }
+                                }
+                            }
+                        } else if item.name() == "simpleContent" {
+                            let base = self.simple_type(derivation, depth + 1);
+                            rules = with_text_type(rules, base);
+                        }
+                        rules = self.complex_type(derivation, rules, children, depth + 1)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(rules)
+    }
+
+    /// Collect the children declared by a model group (or a particle of a
+    /// model group), whose occurrences are multiplied by `min` and `max`.
+    fn model_group(
+        &mut self,
+        particle: &'a Element,
+        min: usize,
+        max: Option<usize>,
+        content: &mut Content,
+        depth: usize,
+    ) -> Result<(), Error> {
+        let (particle_min, particle_max) = occurrences(particle);
+        let min = min * particle_min;
+        let max = max.zip(particle_max).map(|(a, b)| a * b);
+        match particle.name() {
+            "element" => {
+                let (name, declaration) = self.element(particle)?;
+                content.add(&name, min, max, declaration);
+            }
+            "any" => content.open = true,
+            "group" if depth <= MAX_DEPTH => {
+                let group = particle
+                    .attr("ref")
+                    .and_then(|reference| self.groups.get(local_name(reference)).copied());
+                for item in xs_children(group.unwrap_or(particle)) {
+                    self.model_group(item, min, max, content, depth + 1)?;
+                }
+            }
+            "sequence" | "all" => {
+                for item in xs_children(particle) {
+                    self.model_group(item, min, max, content, depth)?;
+                }
+            }
+            "choice" => {
+                for item in xs_children(particle) {
+                    self.model_group(item, 0, max, content, depth)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn attribute(&self, declaration: &Element, rules: ElementRules) -> Result<ElementRules, Error> {
+        let name: String = match declaration.attr("ref") {
+            Some(reference) => reference.to_owned(),
+            None => declaration.try_attribute("name")?,
+        };
+        let mut rules = match declaration.attr("use") {
+            Some("required") => rules.with_required(&name),
+            _ => rules,
+        };
+        let mut simple_type = match declaration.attr("type") {
+            Some(type_name) => self.named_simple_type(local_name(type_name), 0),
+            None => SimpleType::default(),
+        };
+        if let Some(definition) = xs_children(declaration).find(|d| d.name() == "simpleType") {
+            simple_type = self.simple_type(definition, 0);
+        }
+        if let Some(check) = simple_type.check {
+            rules = rules.with_type_check(&name, check);
+        }
+        if let Some(values) = simple_type.values {
+            let values: Vec<&str> = values.iter().map(String::as_str).collect();
+            rules = rules.with_values(&name, &values);
+        }
+        Ok(rules)
+    }
+
+    fn named_simple_type(&self, name: &str, depth: usize) -> SimpleType {
+        match self.simple_types.get(name) {
+            Some(definition) if depth <= MAX_DEPTH => self.simple_type(definition, depth + 1),
+            Some(_) => SimpleType::default(),
+            None => SimpleType {
+                check: builtin(name),
+                values: None,
+            },
+        }
+    }
+
+    /// Resolve a `simpleType` or a `restriction` of a simple type.
+    fn simple_type(&self, definition: &Element, depth: usize) -> SimpleType {
+        let restriction = if definition.name() == "restriction" {
+            Some(definition)
+        } else {
+            xs_children(definition).find(|item| item.name() == "restriction")
+        };
+        let restriction = match restriction {
+            Some(restriction) => restriction,
+            None => return SimpleType::default(),
+        };
+        let mut simple_type = match restriction.attr("base") {
+            Some(base) => self.named_simple_type(local_name(base), depth + 1),
+            None => xs_children(restriction)
+                .find(|item| item.name() == "simpleType")
+                .map(|base| self.simple_type(base, depth + 1))
+                .unwrap_or_default(),
+        };
+        let values: Vec<String> = xs_children(restriction)
+            .filter(|facet| facet.name() == "enumeration")
+            .filter_map(|facet| facet.attr("value").map(ToOwned::to_owned))
+            .collect();
+        if !values.is_empty() {
+            simple_type.values = Some(values);
+        }
+        simple_type
+    }
+}
+
+fn with_text_type(mut rules: ElementRules, simple_type: SimpleType) -> ElementRules {
+    if let Some(check) = simple_type.check {
+        rules = rules.with_text_check(check);
+    }
+    if let Some(values) = simple_type.values {
+        let values: Vec<&str> = values.iter().map(String::as_str).collect();
+        rules = rules.with_text_values(&values);
+    }
+    rules
+}
+
+/// An XML Schema compiled into [`ElementRules`], to validate documents before
+/// processing them (feature `xsd`).
+///
+/// The supported subset covers most data exchange schemas: global and local
+/// element declarations and references, named and anonymous complex types
+/// (with their extensions), model groups (`sequence`, `choice`, `all` and
+/// named groups) and their occurrences, attributes (required, typed or
+/// enumerated), and simple types restricting the built-in numeric, boolean
+/// and duration types or enumerating values.
+///
+/// The rules of an element come from its declaration: the global one for the
+/// root, and the one in the content of its parent for its children, so
+/// elements with the same local name can have different rules. Children
+/// without a declaration in their parent (like the ones matching `xs:any`)
+/// use the global declaration of their name, if any. The order of the
+/// children is not checked, nor are imported or included schemas and
+/// identity constraints.
+///
+/// The `xsd` feature only gates this code, it doesn't add any dependency.
+///
+/// ```
+/// use minidom::Element;
+/// use minidom_ext::XsdSchema;
+///
+/// let xsd: &'static str = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+///         <xs:element name="Line">
+///             <xs:complexType>
+///                 <xs:sequence>
+///                     <xs:element name="Name" type="xs:string" />
+///                     <xs:element name="Order" type="xs:unsignedInt" minOccurs="0" />
+///                 </xs:sequence>
+///                 <xs:attribute name="id" type="xs:string" use="required" />
+///             </xs:complexType>
+///         </xs:element>
+///     </xs:schema>"#;
+/// let schema = XsdSchema::from_schema_element(&xsd.parse().unwrap()).unwrap();
+/// let line: Element = r#"<Line><Order>x</Order></Line>"#.parse().unwrap();
+/// let violations: Vec<String> = schema
+///     .validate(&line)
+///     .iter()
+///     .map(ToString::to_string)
+///     .collect();
+/// assert_eq!(
+///     vec![
+///         "error[required-attribute] Line: Missing required attribute 'id'",
+///         "error[cardinality] Line: Expected exactly 1 child 'Name', found 0",
+///         "error[type] Line > Order[1]: Invalid text 'x': invalid digit found in string",
+///     ],
+///     violations
+/// );
+/// ```
+///
+/// [`ElementRules`]: struct.ElementRules.html
+#[derive(Debug, Clone)]
+pub struct XsdSchema {
+    /// Index of the declarations of the global elements, by name
+    roots: HashMap<String, usize>,
+    declarations: Vec<Declaration>,
+    max_findings: Option<usize>,
+}
+
+impl XsdSchema {
+    /// Compile a schema from its `xs:schema` element.
+    pub fn from_schema_element(schema: &Element) -> Result<Self, Error> {
+        if !schema.is("schema", XS_NS) {
+            return Err(Error::UnexpectedElement {
+                expected: "schema".to_owned(),
+                found: schema.name().to_owned(),
+            });
+        }
+        let mut compiler = Compiler::new(schema);
+        let mut roots = HashMap::new();
+        for declaration in xs_children(schema).filter(|item| item.name() == "element") {
+            if let (name, Some(index)) = compiler.element(declaration)? {
+                roots.entry(name).or_insert(index);
+            }
+        }
+        Ok(XsdSchema {
+            roots,
+            declarations: compiler.declarations,
+            max_findings: None,
+        })
+    }
+
+    /// Read and compile a schema file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let document = Document::open(path)?;
+        XsdSchema::from_schema_element(&document).map_err(|error| document.locate(error))
+    }
+
+    /// Keep at most `max_findings` violations (at least one), the next ones
+    /// being replaced by a single finding counting them, like
+    /// [`Validator::with_max_findings`].
+    ///
+    /// [`Validator::with_max_findings`]: struct.Validator.html#method.with_max_findings
+    pub fn with_max_findings(mut self, max_findings: usize) -> Self {
+        self.max_findings = Some(max_findings.max(1));
+        self
+    }
+
+    /// Validate a tree and return all the violations, the root having to be
+    /// declared as a global element of the schema.
+    pub fn validate(&self, root: &Element) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let declaration = self.roots.get(root.name()).copied();
+        if declaration.is_none() {
+            findings.push(Finding::new(
+                "undeclared-root",
+                Severity::Error,
+                root.name(),
+                &format!(
+                    "Element '{}' is not a global element of the schema",
+                    root.name()
+                ),
+            ));
+        }
+        self.validate_cursor(&ElementCursor::new(root), declaration, &mut findings);
+        cap_findings(&mut findings, self.max_findings);
+        findings
+    }
+
+    fn validate_cursor(
+        &self,
+        cursor: &ElementCursor<'_>,
+        declaration: Option<usize>,
+        findings: &mut Vec<Finding>,
+    ) {
+        let declaration = declaration.map(|index| &self.declarations[index]);
+        if let Some(declaration) = declaration {
+            declaration
+                .rules
+                .check(cursor.element(), &cursor.breadcrumb(), findings);
+        }
+        for child in cursor.children() {
+            let name = child.element().name();
+            let child_declaration = declaration
+                .and_then(|declaration| declaration.children.get(name))
+                .or_else(|| self.roots.get(name))
+                .copied();
+            self.validate_cursor(&child, child_declaration, findings);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn named_types() {
+        let xsd: &'static str = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+                <xs:element name="frame" type="FrameType" />
+                <xs:complexType name="BaseType">
+                    <xs:attribute name="version" type="VersionType" />
+                </xs:complexType>
+                <xs:complexType name="FrameType">
+                    <xs:complexContent>
+                        <xs:extension base="BaseType">
+                            <xs:choice maxOccurs="unbounded">
+                                <xs:element ref="Line" />
+                                <xs:element name="Mode" type="ModeType" maxOccurs="2" />
+                            </xs:choice>
+                        </xs:extension>
+                    </xs:complexContent>
+                </xs:complexType>
+                <xs:element name="Line">
+                    <xs:complexType>
+                        <xs:simpleContent>
+                            <xs:extension base="xs:int" />
+                        </xs:simpleContent>
+                    </xs:complexType>
+                </xs:element>
+                <xs:simpleType name="VersionType">
+                    <xs:restriction base="xs:unsignedShort" />
+                </xs:simpleType>
+                <xs:simpleType name="ModeType">
+                    <xs:restriction base="xs:string">
+                        <xs:enumeration value="bus" />
+                        <xs:enumeration value="tram" />
+                    </xs:restriction>
+                </xs:simpleType>
+            </xs:schema>"#;
+        let schema = XsdSchema::from_schema_element(&xsd.parse().unwrap()).unwrap();
+        let valid: Element =
+            r#"<frame version="2"><Line>1</Line><Mode>bus</Mode><Line>2</Line></frame>"#
+                .parse()
+                .unwrap();
+        assert_eq!(Vec::<Finding>::new(), schema.validate(&valid));
+        let invalid: Element =
+            r#"<frame version="-1"><Line>x</Line><Mode>metro</Mode><Stop /></frame>"#
+                .parse()
+                .unwrap();
+        let rules: Vec<String> = schema
+            .validate(&invalid)
+            .into_iter()
+            .map(|finding| finding.rule)
+            .collect();
+        assert_eq!(
+            vec!["type", "unexpected-child", "type", "enumeration"],
+            rules
+        );
+        let line: Element = "<Line>1</Line>".parse().unwrap();
+        assert!(schema.validate(&line).is_empty());
+        let mode: Element = "<Mode>bus</Mode>".parse().unwrap();
+        assert_eq!("undeclared-root", schema.validate(&mode)[0].rule);
+    }
+
+    #[test]
+    fn local_declarations() {
+        let xsd: &'static str = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+                <xs:element name="frame">
+                    <xs:complexType>
+                        <xs:sequence>
+                            <xs:element name="Line">
+                                <xs:complexType>
+                                    <xs:sequence>
+                                        <xs:element name="Name" type="xs:string" />
+                                    </xs:sequence>
+                                </xs:complexType>
+                            </xs:element>
+                            <xs:element name="Stop">
+                                <xs:complexType>
+                                    <xs:sequence>
+                                        <xs:element name="Name" type="xs:positiveInteger" />
+                                    </xs:sequence>
+                                    <xs:attribute name="order" type="xs:integer" />
+                                </xs:complexType>
+                            </xs:element>
+                        </xs:sequence>
+                    </xs:complexType>
+                </xs:element>
+            </xs:schema>"#;
+        let schema = XsdSchema::from_schema_element(&xsd.parse().unwrap()).unwrap();
+        let valid: Element = r#"<frame><Line><Name>A</Name></Line><Stop order="-123456789012345678901234567890"><Name>1</Name></Stop></frame>"#
+            .parse()
+            .unwrap();
+        assert_eq!(Vec::<Finding>::new(), schema.validate(&valid));
+        let invalid: Element =
+            r#"<frame><Line><Name>A</Name></Line><Stop order="1.5"><Name>0</Name></Stop></frame>"#
+                .parse()
+                .unwrap();
+        let violations: Vec<String> = schema
+            .validate(&invalid)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            vec![
+                "error[type] frame > Stop[1]: Invalid value '1.5' of attribute 'order': invalid integer",
+                "error[type] frame > Stop[1] > Name[1]: Invalid text '0': expected a positive integer",
+            ],
+            violations
+        );
+    }
+
+    #[test]
+    fn decimals() {
+        let check = builtin("decimal").unwrap();
+        for valid in &["1", "-1.5", "+.5", "1.", " 12345678901234567890.123 "] {
+            assert_eq!(Ok(()), check(valid), "{}", valid);
+        }
+        for invalid in &["", ".", "-", "1e3", "INF", "NaN", "1.2.3", "0x1"] {
+            assert!(check(invalid).is_err(), "{}", invalid);
+        }
+        let double = builtin("double").unwrap();
+        assert_eq!(Ok(()), double("1e3"));
+    }
+}